@@ -0,0 +1,355 @@
+// Apache 2.0 License
+
+pub(crate) mod postprocess;
+mod screen;
+mod sprite;
+
+pub use postprocess::load_palette;
+pub use screen::render_screens;
+pub use sprite::render_sprites;
+
+use crate::{error::ResultExt, picture::Picture};
+use std::{
+    ffi::OsStr,
+    fs::{self, File},
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Mutex,
+    thread,
+};
+
+/// The engine used when neither the settings nor the command line pick one. Cycles doesn't need a display, so it
+/// works on headless build machines.
+const DEFAULT_ENGINE: &str = "CYCLES";
+
+/// How many lines from the end of Blender's output are kept when a render fails.
+const BLENDER_LOG_LINES: usize = 20;
+
+/// How Blender should render a scene. Anything left unset keeps the value saved in the .blend file.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct RenderSettings {
+    /// The render engine, e.g. CYCLES or BLENDER_EEVEE.
+    #[serde(default)]
+    pub engine: Option<String>,
+    /// Width and height of the render, in pixels.
+    #[serde(default)]
+    pub resolution: Option<[u32; 2]>,
+    /// Samples per pixel.
+    #[serde(default)]
+    pub samples: Option<u32>,
+    /// The name of the camera object to render from.
+    #[serde(default)]
+    pub camera: Option<String>,
+}
+
+impl RenderSettings {
+    /// Load render settings from a YAML file.
+    #[inline]
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        serde_yaml::from_reader(BufReader::new(File::open(path).with_file(path)?)).with_file(path)
+    }
+
+    /// Fill in anything unset in `self` from `base`.
+    #[inline]
+    pub fn or(self, base: &RenderSettings) -> RenderSettings {
+        RenderSettings {
+            engine: self.engine.or_else(|| base.engine.clone()),
+            resolution: self.resolution.or(base.resolution),
+            samples: self.samples.or(base.samples),
+            camera: self.camera.or_else(|| base.camera.clone()),
+        }
+    }
+
+    /// Python that applies these settings to the scene.
+    #[inline]
+    fn script(&self) -> String {
+        let mut script = String::from("import bpy\nscene = bpy.context.scene\n");
+
+        if let Some([x, y]) = self.resolution {
+            script.push_str(&format!(
+                "scene.render.resolution_x = {}\nscene.render.resolution_y = {}\nscene.render.resolution_percentage = 100\n",
+                x, y
+            ));
+        }
+        if let Some(samples) = self.samples {
+            script.push_str(&format!(
+                "scene.cycles.samples = {0}\nscene.eevee.taa_render_samples = {0}\n",
+                samples
+            ));
+        }
+        if let Some(camera) = &self.camera {
+            script.push_str(&format!("scene.camera = bpy.data.objects[{:?}]\n", camera));
+        }
+
+        script
+    }
+}
+
+/// Something that can turn frames of a model into PNG files.
+pub trait Renderer: Sync {
+    /// Render the given frames of a model into `outdir`, optionally running a Python snippet against the scene
+    /// first. Returns the paths of the rendered images, in the same order as `frames`.
+    fn render(
+        &self,
+        model: &Path,
+        outdir: &Path,
+        frames: &[usize],
+        settings: &RenderSettings,
+        python: Option<&str>,
+    ) -> crate::Result<Vec<PathBuf>>;
+}
+
+/// Renders using the "blender" command line tool.
+pub struct Blender;
+
+impl Renderer for Blender {
+    #[inline]
+    fn render(
+        &self,
+        model: &Path,
+        outdir: &Path,
+        frames: &[usize],
+        settings: &RenderSettings,
+        python: Option<&str>,
+    ) -> crate::Result<Vec<PathBuf>> {
+        run_blender(model, outdir, frames, settings, python)
+    }
+}
+
+/// Stands in for Blender where it isn't installed. Every frame is a transparent image of the requested resolution
+/// with an opaque block in the middle, so the rest of the pipeline has something to crop and offset.
+pub struct Placeholder;
+
+/// The resolution placeholders are drawn at when the settings don't specify one.
+const PLACEHOLDER_RESOLUTION: [u32; 2] = [64, 64];
+
+impl Renderer for Placeholder {
+    #[inline]
+    fn render(
+        &self,
+        _model: &Path,
+        outdir: &Path,
+        frames: &[usize],
+        settings: &RenderSettings,
+        _python: Option<&str>,
+    ) -> crate::Result<Vec<PathBuf>> {
+        let [width, height] = settings.resolution.unwrap_or(PLACEHOLDER_RESOLUTION);
+        let (width, height) = (width as usize, height as usize);
+        let mut picture = Picture::new(width, height);
+        for y in height / 4..(height * 3) / 4 {
+            for x in width / 4..(width * 3) / 4 {
+                picture.set(x, y, [128, 128, 128, 255]);
+            }
+        }
+
+        frames
+            .iter()
+            .map(|f| {
+                let path = outdir.join(format!("render_{:04}.png", f));
+                picture.save(&path)?;
+                Ok(path)
+            })
+            .collect()
+    }
+}
+
+/// Make sure a frame range has exactly one output file per frame.
+#[inline]
+pub fn check_frames(framestart: usize, frameend: usize, outfiles: usize) -> crate::Result {
+    if frameend < framestart {
+        return Err(crate::Error::StaticMsg("End frame is before start frame"));
+    }
+    if (frameend - framestart + 1) != outfiles {
+        return Err(crate::Error::StaticMsg("Frames do not match outfiles"));
+    }
+    Ok(())
+}
+
+/// Render a range of frames and copy them to the given output files.
+#[inline]
+pub fn render_blender<'a, I: Iterator<Item = &'a OsStr>>(
+    renderer: &dyn Renderer,
+    model: &Path,
+    framestart: usize,
+    frameend: usize,
+    frameout: I,
+    settings: &RenderSettings,
+) -> crate::Result {
+    let workdir = tempfile::tempdir()?;
+    let frames: Vec<usize> = (framestart..=frameend).collect();
+
+    // the temporary directory may be on another filesystem, so copy instead of renaming
+    renderer
+        .render(model, workdir.path(), &frames, settings, None)?
+        .into_iter()
+        .zip(frameout)
+        .try_for_each::<_, io::Result<()>>(|(src, dst)| fs::copy(src, dst).map(|_| ()))?;
+
+    Ok(())
+}
+
+/// Run a job for each item on up to `jobs` threads at once, stopping at the first error.
+#[inline]
+pub fn run_parallel<T: Sync, F: Fn(&T) -> crate::Result + Sync>(
+    items: &[T],
+    jobs: usize,
+    job: F,
+) -> crate::Result {
+    let queue = Mutex::new(items.iter());
+    let failure = Mutex::new(None);
+
+    thread::scope(|s| {
+        (0..jobs.max(1)).for_each(|_| {
+            s.spawn(|| loop {
+                if failure.lock().unwrap().is_some() {
+                    return;
+                }
+
+                let item = match queue.lock().unwrap().next() {
+                    Some(item) => item,
+                    None => return,
+                };
+
+                if let Err(e) = job(item) {
+                    failure.lock().unwrap().get_or_insert(e);
+                }
+            });
+        })
+    });
+
+    match failure.into_inner().unwrap() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+#[inline]
+fn run_blender(
+    model: &Path,
+    outdir: &Path,
+    frames: &[usize],
+    settings: &RenderSettings,
+    python: Option<&str>,
+) -> crate::Result<Vec<PathBuf>> {
+    let mut command = Command::new("blender");
+    // render file model in background
+    command
+        .arg("-b")
+        .arg(model)
+        .arg("-E")
+        .arg(settings.engine.as_deref().unwrap_or(DEFAULT_ENGINE));
+
+    // arguments are processed in order, so the scripts have to come before the render
+    command.arg("--python-expr").arg(settings.script());
+    if let Some(python) = python {
+        command.arg("--python-expr").arg(python);
+    }
+
+    let framelist = frames
+        .iter()
+        .map(|f| f.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let output = command
+        .arg("-o")
+        .arg(outdir.join("render_####.png"))
+        .arg("-F")
+        .arg("PNG")
+        .arg("-f")
+        .arg(&framelist)
+        .output()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => crate::Error::StaticMsg(
+                "Blender isn't installed, or isn't on PATH; render placeholders to build without it",
+            ),
+            _ => e.into(),
+        })?;
+
+    if !output.status.success() {
+        // Blender reports most problems on stdout, so keep the end of both streams. They go in the error rather than
+        // straight to stderr, so renders running in parallel don't interleave their logs.
+        let log = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let lines: Vec<&str> = log.lines().collect();
+        return Err(crate::Error::Msg(format!(
+            "Blender failed to render frames {} of {} ({}):\n{}",
+            framelist,
+            model.display(),
+            output.status,
+            lines[lines.len().saturating_sub(BLENDER_LOG_LINES)..].join("\n")
+        )));
+    }
+
+    Ok(frames
+        .iter()
+        .map(|f| outdir.join(format!("render_{:04}.png", f)))
+        .collect())
+}
+
+#[inline]
+fn default_true() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_counts() {
+        assert!(check_frames(1, 4, 4).is_ok());
+        assert!(check_frames(1, 4, 3).is_err());
+        assert!(check_frames(4, 1, 4).is_err());
+    }
+
+    #[test]
+    fn renders_are_copied_to_outfiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let outfiles = ["first.png", "second.png", "third.png"]
+            .iter()
+            .map(|name| dir.path().join(name))
+            .collect::<Vec<_>>();
+        let settings = RenderSettings {
+            resolution: Some([320, 200]),
+            ..Default::default()
+        };
+
+        render_blender(
+            &Placeholder,
+            Path::new("title_screen.blend"),
+            10,
+            12,
+            outfiles.iter().map(|p| p.as_os_str()),
+            &settings,
+        )
+        .unwrap();
+
+        for outfile in &outfiles {
+            let picture = Picture::load(outfile).unwrap();
+            assert_eq!((picture.width, picture.height), (320, 200));
+        }
+    }
+
+    #[test]
+    fn settings_fall_back_to_base() {
+        let base = RenderSettings {
+            engine: Some("BLENDER_EEVEE".to_string()),
+            samples: Some(16),
+            ..Default::default()
+        };
+        let flags = RenderSettings {
+            samples: Some(128),
+            ..Default::default()
+        };
+
+        let settings = flags.or(&base);
+        assert_eq!(settings.engine.as_deref(), Some("BLENDER_EEVEE"));
+        assert_eq!(settings.samples, Some(128));
+        assert_eq!(settings.resolution, None);
+    }
+}
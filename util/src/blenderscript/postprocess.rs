@@ -0,0 +1,151 @@
+// Apache 2.0 License
+
+use crate::{
+    colormap,
+    error::ResultExt,
+    picture::{Picture, ALPHA_THRESHOLD},
+};
+use std::{fs, path::Path};
+
+/// Steps that turn a full-color render into a Doom-ready graphic.
+#[derive(serde::Deserialize)]
+pub struct PostProcess {
+    /// The height of the finished graphic, in pixels. If not set, the render is only cropped.
+    #[serde(default)]
+    height: Option<usize>,
+    /// Use Floyd-Steinberg dithering when matching to the palette.
+    #[serde(default)]
+    dither: bool,
+    /// Draw a one pixel border in the palette's darkest color around the graphic.
+    #[serde(default = "super::default_true")]
+    outline: bool,
+}
+
+impl PostProcess {
+    /// Crop, scale, quantize and outline a render.
+    #[inline]
+    pub fn apply(&self, picture: &Picture, palette: &[[u8; 3]]) -> crate::Result<Picture> {
+        let bounds = picture
+            .bounds()
+            .ok_or(crate::Error::StaticMsg("Rendered graphic is empty"))?;
+        let mut picture = picture.crop(bounds);
+
+        if let Some(height) = self.height {
+            if height == 0 {
+                return Err(crate::Error::StaticMsg(
+                    "Target height must be at least one pixel",
+                ));
+            }
+            picture = picture.scale_to_height(height);
+        }
+
+        quantize(&mut picture, palette, self.dither);
+
+        if self.outline {
+            picture = picture.outline(darkest(palette)?);
+        }
+
+        Ok(picture)
+    }
+}
+
+/// Load the first 256 colors of a PLAYPAL lump.
+#[inline]
+pub fn load_palette(path: &Path) -> crate::Result<Vec<[u8; 3]>> {
+    colormap::read_palette(&fs::read(path).with_file(path)?).with_file(path)
+}
+
+/// Replace every color with its closest palette entry. Alpha is reduced to either fully opaque or fully transparent,
+/// since Doom graphics can't be partially see-through.
+#[inline]
+pub fn quantize(picture: &mut Picture, palette: &[[u8; 3]], dither: bool) {
+    // accumulated dithering error for each pixel
+    let mut error = vec![[0.0f32; 3]; picture.pixels.len()];
+
+    for y in 0..picture.height {
+        for x in 0..picture.width {
+            let [r, g, b, a] = picture.get(x, y);
+            if a < ALPHA_THRESHOLD {
+                picture.set(x, y, [0, 0, 0, 0]);
+                continue;
+            }
+
+            let i = (y * picture.width) + x;
+            let wanted = [
+                r as f32 + error[i][0],
+                g as f32 + error[i][1],
+                b as f32 + error[i][2],
+            ];
+            let search = [
+                wanted[0].round().clamp(0.0, 255.0) as u8,
+                wanted[1].round().clamp(0.0, 255.0) as u8,
+                wanted[2].round().clamp(0.0, 255.0) as u8,
+            ];
+            let [pr, pg, pb] =
+                palette[colormap::search_for_closest(palette.iter().copied(), search) as usize];
+            picture.set(x, y, [pr, pg, pb, 255]);
+
+            if !dither {
+                continue;
+            }
+
+            let diff = [
+                wanted[0] - pr as f32,
+                wanted[1] - pg as f32,
+                wanted[2] - pb as f32,
+            ];
+
+            // spread the error to the neighbors that haven't been visited yet
+            let mut spread = |dx: isize, dy: usize, weight: f32| {
+                let nx = x as isize + dx;
+                let ny = y + dy;
+                if nx < 0 || nx as usize >= picture.width || ny >= picture.height {
+                    return;
+                }
+
+                let n = (ny * picture.width) + nx as usize;
+                for c in 0..3 {
+                    error[n][c] += diff[c] * weight;
+                }
+            };
+
+            spread(1, 0, 7.0 / 16.0);
+            spread(-1, 1, 3.0 / 16.0);
+            spread(0, 1, 5.0 / 16.0);
+            spread(1, 1, 1.0 / 16.0);
+        }
+    }
+}
+
+/// The palette entry with the lowest luminance.
+#[inline]
+pub(crate) fn darkest(palette: &[[u8; 3]]) -> crate::Result<[u8; 3]> {
+    palette
+        .iter()
+        .copied()
+        .min_by_key(|[r, g, b]| (*r as u32 * 2126) + (*g as u32 * 7152) + (*b as u32 * 722))
+        .ok_or(crate::Error::StaticMsg("Palette is empty"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PALETTE: [[u8; 3]; 3] = [[255, 255, 255], [10, 10, 10], [200, 0, 0]];
+
+    #[test]
+    fn quantize_thresholds_alpha() {
+        let mut picture = Picture::new(2, 1);
+        picture.set(0, 0, [250, 5, 5, ALPHA_THRESHOLD - 1]);
+        picture.set(1, 0, [250, 5, 5, ALPHA_THRESHOLD]);
+        quantize(&mut picture, &PALETTE, false);
+        assert_eq!(picture.get(0, 0), [0, 0, 0, 0]);
+        assert_eq!(picture.get(1, 0), [200, 0, 0, 255]);
+    }
+
+    #[test]
+    fn darkest_color() {
+        assert_eq!(darkest(&PALETTE).unwrap(), [10, 10, 10]);
+        assert!(darkest(&[]).is_err());
+    }
+}
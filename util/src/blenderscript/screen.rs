@@ -0,0 +1,249 @@
+// Apache 2.0 License
+
+use super::{postprocess, RenderSettings, Renderer};
+use crate::{error::ResultExt, picture::Picture};
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+/// Fullscreen graphics are drawn at 320x200, but shown on 4:3 screens with tall pixels. Scenes are rendered with
+/// square pixels at 320x240, then squashed.
+const RENDER_RESOLUTION: [u32; 2] = [320, 240];
+const SCREEN_WIDTH: usize = 320;
+const SCREEN_HEIGHT: usize = 200;
+
+/// Script run before rendering a screen. Fullscreen graphics can't have holes, so the world is drawn behind the scene.
+const SCREEN_SCRIPT: &str = r#"
+import bpy
+bpy.context.scene.render.film_transparent = False
+"#;
+
+/// Script run before rendering a logo. Only the named objects are drawn, over a transparent background.
+const LOGO_SCRIPT: &str = r#"
+import bpy
+scene = bpy.context.scene
+scene.render.film_transparent = True
+names = [{names}]
+for obj in scene.objects:
+    if obj.type not in {'CAMERA', 'LIGHT'}:
+        obj.hide_render = obj.name not in names
+"#;
+
+/// Describes the fullscreen graphics rendered from a scene.
+#[derive(serde::Deserialize)]
+struct ScreenSpec {
+    /// The .blend file to render, relative to the spec.
+    model: PathBuf,
+    screens: Vec<Screen>,
+    /// A graphic cut out of the scene, like the M_DOOM logo on the main menu.
+    #[serde(default)]
+    logo: Option<Logo>,
+    /// Use Floyd-Steinberg dithering when matching to the palette.
+    #[serde(default)]
+    dither: bool,
+    /// How the scene is rendered. The resolution is always 320x240; settings given on the command line take priority.
+    #[serde(default)]
+    render: RenderSettings,
+}
+
+#[derive(serde::Deserialize)]
+struct Screen {
+    /// The lump name, e.g. TITLEPIC.
+    lump: String,
+    /// The Blender frame to render.
+    frame: usize,
+    /// The camera to render from, if not the one in the render settings.
+    #[serde(default)]
+    camera: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct Logo {
+    #[serde(default = "default_logo_lump")]
+    lump: String,
+    frame: usize,
+    #[serde(default)]
+    camera: Option<String>,
+    /// The objects that make up the logo. Everything else is hidden.
+    objects: Vec<String>,
+}
+
+#[inline]
+fn default_logo_lump() -> String {
+    "M_DOOM".to_string()
+}
+
+impl ScreenSpec {
+    #[inline]
+    fn validate(&self) -> crate::Result {
+        let lumps = self
+            .screens
+            .iter()
+            .map(|screen| &screen.lump)
+            .chain(self.logo.iter().map(|logo| &logo.lump));
+        for lump in lumps {
+            if lump.is_empty() || lump.len() > 8 || !lump.is_ascii() {
+                return Err(crate::Error::Msg(format!(
+                    "Lump name {:?} must be 1 to 8 ASCII characters",
+                    lump
+                )));
+            }
+        }
+
+        match &self.logo {
+            Some(logo) if logo.objects.is_empty() => Err(crate::Error::StaticMsg(
+                "A logo needs the objects it's made of",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Render the fullscreen graphics in a spec, squashed to 320x200 and matched to the PLAYPAL lump in `palette`.
+/// Each is written to `outdir` as a PNG named after its lump, with offsets of (0, 0).
+#[inline]
+pub fn render_screens(
+    renderer: &dyn Renderer,
+    spec_path: &Path,
+    outdir: &Path,
+    palette: &Path,
+    settings: &RenderSettings,
+) -> crate::Result {
+    let spec: ScreenSpec =
+        serde_yaml::from_reader(BufReader::new(File::open(spec_path).with_file(spec_path)?))
+            .with_file(spec_path)?;
+    spec.validate().with_file(spec_path)?;
+
+    let model = spec_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(&spec.model);
+    let settings = RenderSettings {
+        resolution: Some(RENDER_RESOLUTION),
+        ..settings.clone().or(&spec.render)
+    };
+    let palette = postprocess::load_palette(palette)?;
+
+    let workdir = tempfile::tempdir().with_file(outdir)?;
+    fs::create_dir_all(outdir).with_file(outdir)?;
+
+    let render = |frame: usize, camera: &Option<String>, script: &str| -> crate::Result<Picture> {
+        let settings = RenderSettings {
+            camera: camera.clone().or_else(|| settings.camera.clone()),
+            ..settings.clone()
+        };
+        let renders = renderer.render(&model, workdir.path(), &[frame], &settings, Some(script))?;
+        let picture = Picture::load(&renders[0])?;
+        fs::remove_file(&renders[0])?;
+
+        let [width, height] = RENDER_RESOLUTION;
+        if (picture.width, picture.height) != (width as usize, height as usize) {
+            return Err(crate::Error::StaticMsg(
+                "Fullscreen renders must be 320x240",
+            ));
+        }
+        Ok(picture.resize(SCREEN_WIDTH, SCREEN_HEIGHT))
+    };
+    let save = |mut picture: Picture, lump: &str| {
+        postprocess::quantize(&mut picture, &palette, spec.dither);
+        picture.offset = Some((0, 0));
+        picture.save(&outdir.join(format!("{}.png", lump.to_lowercase())))
+    };
+
+    spec.screens.iter().try_for_each(|screen| {
+        let mut picture = render(screen.frame, &screen.camera, SCREEN_SCRIPT)?;
+        // anything Blender left see-through is shown as it is, rather than becoming a hole
+        picture.pixels.iter_mut().for_each(|pixel| pixel[3] = 255);
+        save(picture, &screen.lump)
+    })?;
+
+    if let Some(logo) = &spec.logo {
+        let names = logo
+            .objects
+            .iter()
+            .map(|name| format!("{:?}", name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let picture = render(
+            logo.frame,
+            &logo.camera,
+            &LOGO_SCRIPT.replace("{names}", &names),
+        )?;
+        let bounds = picture
+            .bounds()
+            .ok_or(crate::Error::StaticMsg("Rendered logo is empty"))?;
+        save(picture.crop(bounds), &logo.lump)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blenderscript::Placeholder;
+
+    #[inline]
+    fn render(spec: &str) -> crate::Result<tempfile::TempDir> {
+        let dir = tempfile::tempdir()?;
+        let spec_path = dir.path().join("title.yml");
+        fs::write(&spec_path, spec)?;
+        let palette = dir.path().join("playpal.lmp");
+        fs::write(
+            &palette,
+            (0..=255u8).flat_map(|i| [i, i, i]).collect::<Vec<_>>(),
+        )?;
+
+        render_screens(
+            &Placeholder,
+            &spec_path,
+            &dir.path().join("graphics"),
+            &palette,
+            &RenderSettings::default(),
+        )?;
+        Ok(dir)
+    }
+
+    #[inline]
+    fn offsets(path: &Path) -> Vec<u8> {
+        let png = fs::read(path).unwrap();
+        let grab = png.windows(4).position(|w| w == b"grAb").unwrap() + 4;
+        png[grab..grab + 8].to_vec()
+    }
+
+    #[test]
+    fn screens_are_squashed() {
+        let dir = render(
+            "model: x.blend\nscreens:\n  - lump: TITLEPIC\n    frame: 1\n  - lump: HELP1\n    frame: 2\n",
+        )
+        .unwrap();
+        let path = dir.path().join("graphics/titlepic.png");
+        let picture = Picture::load(&path).unwrap();
+        assert_eq!((picture.width, picture.height), (320, 200));
+        assert_eq!(offsets(&path), [0; 8]);
+        // the placeholder's transparent border is filled in rather than left as holes
+        assert!(picture.pixels.iter().all(|pixel| pixel[3] == 255));
+        assert!(dir.path().join("graphics/help1.png").exists());
+    }
+
+    #[test]
+    fn logo_is_cropped() {
+        let dir =
+            render("model: x.blend\nscreens: []\nlogo:\n  frame: 1\n  objects: [Logo]\n").unwrap();
+        // the placeholder block covers the middle half of the render
+        let path = dir.path().join("graphics/m_doom.png");
+        let picture = Picture::load(&path).unwrap();
+        assert_eq!((picture.width, picture.height), (160, 100));
+        assert_eq!(offsets(&path), [0; 8]);
+    }
+
+    #[test]
+    fn bad_specs_are_rejected() {
+        assert!(
+            render("model: x.blend\nscreens:\n  - lump: TITLEPICTURE\n    frame: 1\n").is_err()
+        );
+        assert!(render("model: x.blend\nscreens: []\nlogo:\n  frame: 1\n  objects: []\n").is_err());
+    }
+}
@@ -0,0 +1,348 @@
+// Apache 2.0 License
+
+use super::{
+    postprocess::{self, PostProcess},
+    RenderSettings, Renderer,
+};
+use crate::{error::ResultExt, picture::Picture};
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+/// Script run before rendering each rotation. It makes the background transparent and turns the model around the
+/// vertical axis. If no objects are named, every top-level object that isn't a camera or a light is turned.
+const ROTATE_SCRIPT: &str = r#"
+import bpy, math
+scene = bpy.context.scene
+scene.render.film_transparent = True
+names = [{names}]
+for obj in scene.objects:
+    if (obj.name in names) if names else (obj.parent is None and obj.type not in {'CAMERA', 'LIGHT'}):
+        obj.rotation_euler[2] += math.radians({angle})
+"#;
+
+/// Describes how a model is turned into a set of sprites.
+#[derive(serde::Deserialize)]
+struct SpriteSpec {
+    /// The .blend file to render, relative to the spec.
+    model: PathBuf,
+    /// The four-character sprite name, e.g. "POSS".
+    sprite: String,
+    /// The objects to turn for each rotation.
+    #[serde(default)]
+    objects: Vec<String>,
+    /// Whether rotations 2-4 can be mirrored to make rotations 6-8.
+    #[serde(default = "super::default_true")]
+    mirror: bool,
+    animations: Vec<Animation>,
+    /// Cleanup applied to each render before it is written out.
+    #[serde(default)]
+    postprocess: Option<PostProcess>,
+    /// How the model is rendered. Settings given on the command line take priority.
+    #[serde(default)]
+    render: RenderSettings,
+}
+
+#[derive(serde::Deserialize)]
+struct Animation {
+    /// The frame letters, e.g. "ABCD".
+    letters: String,
+    /// The Blender frame to render for each letter.
+    frames: Vec<usize>,
+    /// If false, the frame is drawn from the front only and used for every angle (e.g. death frames).
+    #[serde(default = "super::default_true")]
+    rotations: bool,
+    /// Overrides the sprite-wide mirror setting.
+    #[serde(default)]
+    mirror: Option<bool>,
+}
+
+impl SpriteSpec {
+    #[inline]
+    fn validate(&self) -> crate::Result {
+        if self.sprite.len() != 4 || !self.sprite.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(crate::Error::StaticMsg(
+                "Sprite name must be four alphanumeric characters",
+            ));
+        }
+
+        self.animations.iter().try_for_each(|anim| {
+            if anim.letters.chars().count() != anim.frames.len() {
+                return Err(crate::Error::StaticMsg(
+                    "Frame letters do not match frame numbers",
+                ));
+            }
+
+            // Doom accepts frame letters from A to ]
+            if !anim.letters.chars().all(|c| ('A'..=']').contains(&c)) {
+                return Err(crate::Error::StaticMsg(
+                    "Frame letters must be between A and ]",
+                ));
+            }
+
+            Ok(())
+        })
+    }
+
+    /// The rotations that need to be rendered to cover every animation.
+    #[inline]
+    fn rotations(&self) -> impl Iterator<Item = u8> {
+        let last = self
+            .animations
+            .iter()
+            .map(
+                |anim| match (anim.rotations, anim.mirror.unwrap_or(self.mirror)) {
+                    (false, _) => 1,
+                    (true, true) => 5,
+                    (true, false) => 8,
+                },
+            )
+            .max()
+            .unwrap_or(0);
+
+        1..=last
+    }
+}
+
+/// Render every rotation of every animation in a sprite spec, writing Doom-named PNGs with grAb offsets to `outdir`.
+/// If the spec asks for post-processing, `palette` is the PLAYPAL lump to match the sprites to.
+#[inline]
+pub fn render_sprites(
+    renderer: &dyn Renderer,
+    spec_path: &Path,
+    outdir: &Path,
+    palette: Option<&Path>,
+    settings: &RenderSettings,
+) -> crate::Result {
+    let spec: SpriteSpec =
+        serde_yaml::from_reader(BufReader::new(File::open(spec_path).with_file(spec_path)?))
+            .with_file(spec_path)?;
+    spec.validate().with_file(spec_path)?;
+
+    let model = spec_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(&spec.model);
+    let settings = settings.clone().or(&spec.render);
+
+    let palette = match (&spec.postprocess, palette) {
+        (None, _) => None,
+        (Some(_), Some(palette)) => Some(postprocess::load_palette(palette)?),
+        (Some(_), None) => {
+            return Err(crate::Error::StaticMsg(
+                "Post-processing sprites needs a palette",
+            ))
+        }
+    };
+
+    let workdir = tempfile::tempdir()?;
+    let frames: Vec<usize> = spec
+        .animations
+        .iter()
+        .flat_map(|anim| anim.frames.iter().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let names = spec
+        .objects
+        .iter()
+        .map(|name| format!("{:?}", name))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    fs::create_dir_all(outdir)?;
+
+    spec.rotations().try_for_each(|rotation| {
+        let script = ROTATE_SCRIPT
+            .replace("{names}", &names)
+            .replace("{angle}", &rotation_angle(rotation).to_string());
+        let renders = renderer.render(&model, workdir.path(), &frames, &settings, Some(&script))?;
+
+        spec.animations.iter().try_for_each(|anim| {
+            let mirror = anim.mirror.unwrap_or(spec.mirror);
+            let wanted = match (anim.rotations, mirror) {
+                (false, _) => rotation == 1,
+                (true, true) => rotation <= 5,
+                (true, false) => true,
+            };
+            if !wanted {
+                return Ok(());
+            }
+
+            anim.letters
+                .chars()
+                .zip(anim.frames.iter())
+                .try_for_each(|(letter, frame)| {
+                    let index = frames.binary_search(frame).unwrap();
+                    let mut picture = Picture::load(&renders[index])?;
+                    picture.offset = Some(sprite_offset(&picture)?);
+                    if let (Some(postprocess), Some(palette)) = (&spec.postprocess, &palette) {
+                        picture = postprocess.apply(&picture, palette)?;
+                    }
+
+                    let name = if anim.rotations {
+                        lump_name(&spec.sprite, letter, rotation, mirror)
+                    } else {
+                        lump_name(&spec.sprite, letter, 0, false)
+                    };
+                    picture.save(&outdir.join(format!("{}.png", name.to_lowercase())))
+                })
+        })?;
+
+        renders.iter().try_for_each(fs::remove_file)?;
+        Ok(())
+    })
+}
+
+/// How far the model is turned for a Doom rotation. Rotation 1 faces the camera, and each one after that moves the
+/// viewer 45 degrees counter-clockwise around the model, which is the same as turning the model clockwise.
+#[inline]
+fn rotation_angle(rotation: u8) -> f32 {
+    match rotation {
+        0 => 0.0,
+        r => -45.0 * (r - 1) as f32,
+    }
+}
+
+/// Build a sprite lump name such as `POSSA1` or, for mirrored rotations, `POSSA2A8`.
+#[inline]
+fn lump_name(sprite: &str, letter: char, rotation: u8, mirror: bool) -> String {
+    if mirror && (2..=4).contains(&rotation) {
+        format!(
+            "{}{}{}{}{}",
+            sprite,
+            letter,
+            rotation,
+            letter,
+            10 - rotation
+        )
+    } else {
+        format!("{}{}{}", sprite, letter, rotation)
+    }
+}
+
+/// The camera is expected to be centered on the model's origin, so the horizontal offset is the middle of the render
+/// and the vertical offset puts the lowest opaque pixel on the floor.
+#[inline]
+fn sprite_offset(picture: &Picture) -> crate::Result<(i32, i32)> {
+    let bounds = picture
+        .bounds()
+        .ok_or(crate::Error::StaticMsg("Rendered sprite frame is empty"))?;
+    Ok(((picture.width / 2) as i32, bounds.bottom as i32 + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blenderscript::Placeholder;
+    use std::{collections::BTreeSet, fs};
+
+    #[inline]
+    fn render(spec: &str, palette: Option<&Path>) -> crate::Result<BTreeSet<String>> {
+        let dir = tempfile::tempdir()?;
+        let spec_path = dir.path().join("model.yml");
+        fs::write(&spec_path, spec)?;
+
+        let outdir = dir.path().join("sprites");
+        render_sprites(
+            &Placeholder,
+            &spec_path,
+            &outdir,
+            palette,
+            &RenderSettings::default(),
+        )?;
+
+        Ok(fs::read_dir(&outdir)?
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect())
+    }
+
+    #[test]
+    fn lump_names() {
+        assert_eq!(lump_name("POSS", 'A', 1, true), "POSSA1");
+        assert_eq!(lump_name("POSS", 'B', 3, true), "POSSB3B7");
+        assert_eq!(lump_name("POSS", 'C', 5, true), "POSSC5");
+        assert_eq!(lump_name("POSS", 'C', 3, false), "POSSC3");
+        assert_eq!(lump_name("POSS", 'H', 0, false), "POSSH0");
+    }
+
+    #[test]
+    fn mirrored_rotations() {
+        let names = render(
+            "model: x.blend\nsprite: OFCR\nanimations:\n  - letters: AB\n    frames: [1, 5]\n",
+            None,
+        )
+        .unwrap();
+        let expected: BTreeSet<String> = [
+            "ofcra1", "ofcra2a8", "ofcra3a7", "ofcra4a6", "ofcra5", "ofcrb1", "ofcrb2b8",
+            "ofcrb3b7", "ofcrb4b6", "ofcrb5",
+        ]
+        .iter()
+        .map(|name| format!("{}.png", name))
+        .collect();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn unmirrored_and_rotationless() {
+        let names = render(
+            "model: x.blend\nsprite: OFCR\nmirror: false\nanimations:\n  - letters: A\n    frames: [1]\n  - letters: HI\n    frames: [20, 21]\n    rotations: false\n",
+            None,
+        )
+        .unwrap();
+        let mut expected: BTreeSet<String> = (1..=8).map(|r| format!("ofcra{}.png", r)).collect();
+        expected.insert("ofcrh0.png".to_string());
+        expected.insert("ofcri0.png".to_string());
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn offsets_are_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let spec_path = dir.path().join("model.yml");
+        fs::write(
+            &spec_path,
+            "model: x.blend\nsprite: OFCR\nanimations:\n  - letters: A\n    frames: [1]\n",
+        )
+        .unwrap();
+        render_sprites(
+            &Placeholder,
+            &spec_path,
+            dir.path(),
+            None,
+            &RenderSettings::default(),
+        )
+        .unwrap();
+
+        // the placeholder block covers rows 16 to 47 of a 64x64 render, so the feet are on row 48
+        let png = fs::read(dir.path().join("ofcra1.png")).unwrap();
+        let grab = png.windows(4).position(|w| w == b"grAb").unwrap() + 4;
+        assert_eq!(&png[grab..grab + 8], &[0, 0, 0, 32, 0, 0, 0, 48]);
+    }
+
+    #[test]
+    fn mismatched_frames_are_rejected() {
+        assert!(render(
+            "model: x.blend\nsprite: OFCR\nanimations:\n  - letters: ABC\n    frames: [1, 2]\n",
+            None
+        )
+        .is_err());
+        assert!(render(
+            "model: x.blend\nsprite: OFFICER\nanimations:\n  - letters: A\n    frames: [1]\n",
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn postprocess_needs_palette() {
+        assert!(render(
+            "model: x.blend\nsprite: OFCR\nanimations:\n  - letters: A\n    frames: [1]\npostprocess:\n  height: 16\n",
+            None
+        )
+        .is_err());
+    }
+}
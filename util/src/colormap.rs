@@ -1,29 +1,22 @@
 // Apache 2.0 License
 
-use std::{
-    cmp,
-    io::{self, prelude::*},
-    iter,
-};
+use crate::playpal::PALETTE_SIZE;
+use std::{cmp, io::prelude::*};
 
-/// Generate the COLORMAP lump.
+/// Generate the COLORMAP lump from the bytes of a palette.
 #[inline]
-pub fn generate_colormap(dark_color: [u8; 3]) -> crate::Result {
-    if atty::is(atty::Stream::Stdin) {
-        return Err(crate::Error::StaticMsg("Stdin needs to be a file"));
-    }
-
+pub fn generate_colormap<W: Write>(
+    palette: &[u8],
+    dark_color: [u8; 3],
+    mut cout: W,
+) -> crate::Result {
     // the input should be the palette
-    let stdin = io::stdin();
-    let cin = stdin.lock();
-    let palette = read_palette(cin)?.collect::<crate::Result<Vec<_>>>()?;
+    let palette = read_palette(palette)?;
 
     // create a list that's nothing but the dark color
-    let dark_color = iter::repeat(dark_color).take(256);
+    let dark_color = std::iter::repeat_n(dark_color, 256);
 
-    // create the color palette and write to the stdout
-    let stdout = io::stdout();
-    let mut cout = stdout.lock();
+    // create the color palette and write it out
     //  * the first 32 are a variety of darkened palettes
     write_output(
         &mut cout,
@@ -111,7 +104,7 @@ fn invert_colors<I: IntoIterator<Item = [u8; 3]>>(i: I) -> impl Iterator<Item =
 
 // given a palette and a color, look for the color with the least amount of difference
 #[inline]
-fn search_for_closest<I: IntoIterator<Item = [u8; 3]>>(i: I, search: [u8; 3]) -> u8 {
+pub fn search_for_closest<I: IntoIterator<Item = [u8; 3]>>(i: I, search: [u8; 3]) -> u8 {
     let [r2, g2, b2] = search;
     i.into_iter()
         .enumerate()
@@ -125,47 +118,21 @@ fn search_for_closest<I: IntoIterator<Item = [u8; 3]>>(i: I, search: [u8; 3]) ->
         .0 as u8
 }
 
+/// Read the first palette of a PLAYPAL lump. More palettes after it are fine, but less than 256 colors isn't.
 #[inline]
-fn read_palette<R: Read>(r: R) -> crate::Result<impl Iterator<Item = crate::Result<[u8; 3]>>> {
-    // custom iterator that groups elements into groups of 3 and is also result-aware
-    struct GroupByThrees<I> {
-        inner: I,
-    }
-
-    impl<T, E, I: Iterator<Item = std::result::Result<T, E>>> Iterator for GroupByThrees<I> {
-        type Item = std::result::Result<[T; 3], E>;
-
-        #[inline]
-        fn next(&mut self) -> Option<Self::Item> {
-            match self.inner.next() {
-                None => None,
-                Some(Err(e)) => Some(Err(e)),
-                Some(Ok(b1)) => match self.inner.next() {
-                    None => None,
-                    Some(Err(e)) => Some(Err(e)),
-                    Some(Ok(b2)) => match self.inner.next() {
-                        None => None,
-                        Some(Err(e)) => Some(Err(e)),
-                        Some(Ok(b3)) => Some(Ok([b1, b2, b3])),
-                    },
-                },
-            }
-        }
-
-        #[inline]
-        fn size_hint(&self) -> (usize, Option<usize>) {
-            let (l, h) = self.inner.size_hint();
-            (l / 3, h.map(|h| h / 3))
-        }
+pub fn read_palette(bytes: &[u8]) -> crate::Result<Vec<[u8; 3]>> {
+    if bytes.len() < PALETTE_SIZE {
+        return Err(crate::Error::Msg(format!(
+            "Palette is {} bytes, but needs at least {} for 256 colors",
+            bytes.len(),
+            PALETTE_SIZE
+        )));
     }
 
-    Ok(GroupByThrees {
-        inner: r.bytes().map(|b| {
-            let b = b?;
-            crate::Result::Ok(b)
-        }),
-    }
-    .take(256))
+    Ok(bytes[..PALETTE_SIZE]
+        .chunks(3)
+        .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+        .collect())
 }
 
 #[inline]
@@ -184,14 +151,97 @@ fn floatify<I: IntoIterator<Item = [u8; 3]>>(i: I) -> impl Iterator<Item = [f32;
 fn bytify<I: IntoIterator<Item = [f32; 3]>>(i: I) -> impl Iterator<Item = [u8; 3]> {
     #[inline]
     fn cvt(f: f32) -> u8 {
-        if f < 0.0 {
-            0
-        } else if f > 255.0 {
-            255
-        } else {
-            f as u8
-        }
+        // `as` truncates, so 254.9 would come out as 254; NaN comes out as 0
+        f.clamp(0.0, 255.0).round() as u8
     }
 
     i.into_iter().map(|[r, g, b]| [cvt(r), cvt(g), cvt(b)])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn rgb() -> impl Strategy<Value = [u8; 3]> {
+        any::<[u8; 3]>()
+    }
+
+    proptest! {
+        #[test]
+        fn abs_diff_is_the_distance(a in any::<u8>(), b in any::<u8>()) {
+            prop_assert_eq!(abs_diff(a, b) as i16, (a as i16 - b as i16).abs());
+            prop_assert_eq!(abs_diff(a, b), abs_diff(b, a));
+        }
+
+        #[test]
+        fn bytes_survive_the_trip_through_floats(color in rgb()) {
+            prop_assert_eq!(bytify(floatify(vec![color])).next().unwrap(), color);
+        }
+
+        #[test]
+        fn floats_are_saturated(color in any::<[f32; 3]>()) {
+            let [r, g, b] = bytify(vec![color]).next().unwrap();
+            for (float, byte) in color.iter().zip(&[r, g, b]) {
+                if *float >= 255.0 {
+                    prop_assert_eq!(*byte, 255);
+                } else if *float <= 0.0 || float.is_nan() {
+                    prop_assert_eq!(*byte, 0);
+                }
+            }
+        }
+
+        #[test]
+        fn blends_stay_between_the_colors(a in rgb(), b in rgb(), factor in 0.0f32..=1.0) {
+            let blended = blend_colors(vec![a], vec![b], factor).next().unwrap();
+            for i in 0..3 {
+                prop_assert!(blended[i] >= a[i].min(b[i]) && blended[i] <= a[i].max(b[i]));
+            }
+            prop_assert_eq!(blend_colors(vec![a], vec![b], 1.0).next().unwrap(), a);
+            prop_assert_eq!(blend_colors(vec![a], vec![b], 0.0).next().unwrap(), b);
+        }
+
+        #[test]
+        fn inverted_colors_are_grey(color in rgb()) {
+            let [r, g, b] = invert_colors(vec![color]).next().unwrap();
+            prop_assert!(r == g && g == b);
+        }
+
+        #[test]
+        fn colors_in_the_palette_are_found(
+            palette in proptest::collection::vec(rgb(), 1..256),
+            index in any::<prop::sample::Index>(),
+        ) {
+            let search = palette[index.index(palette.len())];
+            let found = search_for_closest(palette.iter().copied(), search) as usize;
+            prop_assert_eq!(palette[found], search);
+        }
+
+        #[test]
+        fn palettes_need_256_colors(bytes in proptest::collection::vec(any::<u8>(), 0..2000)) {
+            match read_palette(&bytes) {
+                Ok(palette) => {
+                    prop_assert!(bytes.len() >= PALETTE_SIZE);
+                    prop_assert_eq!(palette.len(), 256);
+                }
+                Err(_) => prop_assert!(bytes.len() < PALETTE_SIZE),
+            }
+        }
+    }
+
+    #[test]
+    fn black_inverts_to_white() {
+        assert_eq!(invert_colors(vec![[0, 0, 0]]).next().unwrap(), [255; 3]);
+        assert_eq!(invert_colors(vec![[255; 3]]).next().unwrap(), [0; 3]);
+    }
+
+    #[test]
+    fn colormaps_are_34_maps() {
+        let palette: Vec<u8> = (0..=255u8).flat_map(|i| vec![i, i, i]).collect();
+        let mut out = vec![];
+        generate_colormap(&palette, [0, 0, 0], &mut out).unwrap();
+        assert_eq!(out.len(), 34 * 256);
+        // the last map is nothing but the dark color
+        assert!(out[33 * 256..].iter().all(|c| *c == 0));
+    }
+}
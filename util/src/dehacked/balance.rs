@@ -0,0 +1,187 @@
+// Apache 2.0 License
+
+use super::{
+    parse_bits,
+    vanilla::{self, CLIP_AMMO, MOBJINFO, WEAPONINFO},
+    BlockKind, Patch, AMMO_NONE, THING_FLAGS,
+};
+use std::convert::TryFrom;
+
+/// The parts of the thing, weapon and ammo tables that decide how hard a map is, after a patch has changed them.
+#[derive(Debug, Clone)]
+pub struct Balance {
+    /// Every thing, in DeHackEd order, so thing N is at N - 1.
+    pub things: Vec<ThingType>,
+    /// The ammo type each weapon uses, which is `AMMO_NONE` for fists and the chainsaw.
+    pub weapon_ammo: Vec<i64>,
+    /// How much of each ammo type a clip gives.
+    pub clip_ammo: Vec<i64>,
+    pub soulsphere_health: i64,
+    pub megasphere_health: i64,
+    /// The armor class green armor gives. The player gets 100 armor points per class.
+    pub green_armor_class: i64,
+    pub blue_armor_class: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThingType {
+    /// The name the source gives the thing, like MT_POSSESSED.
+    pub name: &'static str,
+    /// The number maps use for the thing, or -1 if it can't be placed in a map.
+    pub doomednum: i64,
+    pub health: i64,
+    pub flags: u32,
+}
+
+impl Balance {
+    /// The tables as they are in vanilla Doom II.
+    #[inline]
+    pub fn vanilla() -> Self {
+        let misc = |name| {
+            vanilla::MISC
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map_or(0, |(_, value)| *value)
+        };
+
+        Self {
+            things: vanilla::THINGS
+                .iter()
+                .zip(MOBJINFO)
+                .map(|(&name, info)| ThingType {
+                    name,
+                    doomednum: info.doomednum,
+                    health: info.spawnhealth,
+                    flags: info.flags,
+                })
+                .collect(),
+            weapon_ammo: WEAPONINFO.iter().map(|w| w.ammo).collect(),
+            clip_ammo: CLIP_AMMO.to_vec(),
+            soulsphere_health: misc("Soulsphere Health"),
+            megasphere_health: misc("Megasphere Health"),
+            green_armor_class: misc("Green Armor Class"),
+            blue_armor_class: misc("Blue Armor Class"),
+        }
+    }
+
+    /// The tables after a patch. Fields that don't affect balance, or that can't be read, are left alone; the patch
+    /// checker is what reports those.
+    #[inline]
+    pub fn patched(patch: &Patch) -> Self {
+        let mut balance = Self::vanilla();
+
+        patch.blocks.iter().for_each(|block| {
+            block.fields.iter().for_each(|field| {
+                let name = field.name.to_ascii_lowercase();
+                let value = field.value.parse::<i64>().ok();
+                match (&block.kind, name.as_str(), value) {
+                    (BlockKind::Thing { number, .. }, "bits", _) => {
+                        if let (Some(thing), Some(bits)) = (
+                            slot(&mut balance.things, *number - 1),
+                            parse_bits(&field.value, THING_FLAGS),
+                        ) {
+                            thing.flags = bits;
+                        }
+                    }
+                    (BlockKind::Thing { number, .. }, "id #", Some(value)) => {
+                        if let Some(thing) = slot(&mut balance.things, *number - 1) {
+                            thing.doomednum = value;
+                        }
+                    }
+                    (BlockKind::Thing { number, .. }, "hit points", Some(value)) => {
+                        if let Some(thing) = slot(&mut balance.things, *number - 1) {
+                            thing.health = value;
+                        }
+                    }
+                    (BlockKind::Weapon { number, .. }, "ammo type", Some(value)) => {
+                        if let Some(ammo) = slot(&mut balance.weapon_ammo, *number) {
+                            *ammo = value;
+                        }
+                    }
+                    (BlockKind::Ammo { number, .. }, "per ammo", Some(value)) => {
+                        if let Some(clip) = slot(&mut balance.clip_ammo, *number) {
+                            *clip = value;
+                        }
+                    }
+                    (BlockKind::Misc, "soulsphere health", Some(value)) => {
+                        balance.soulsphere_health = value
+                    }
+                    (BlockKind::Misc, "megasphere health", Some(value)) => {
+                        balance.megasphere_health = value
+                    }
+                    (BlockKind::Misc, "green armor class", Some(value)) => {
+                        balance.green_armor_class = value
+                    }
+                    (BlockKind::Misc, "blue armor class", Some(value)) => {
+                        balance.blue_armor_class = value
+                    }
+                    _ => {}
+                }
+            })
+        });
+
+        balance
+    }
+
+    /// The thing a map places with this number. Like the game, the first thing with the number wins.
+    #[inline]
+    pub fn thing(&self, doomednum: i16) -> Option<&ThingType> {
+        self.things
+            .iter()
+            .find(|t| t.doomednum == i64::from(doomednum))
+    }
+
+    /// How much ammo a clip of a weapon's ammo type gives, or nothing for weapons that don't use ammo.
+    #[inline]
+    pub fn weapon_clip(&self, weapon: &str) -> Option<(usize, i64)> {
+        let ammo =
+            *self
+                .weapon_ammo
+                .get(vanilla::find(vanilla::WEAPONS.iter().copied(), "", weapon)?)?;
+        if ammo == AMMO_NONE {
+            return None;
+        }
+        let ammo = usize::try_from(ammo).ok()?;
+        self.clip_ammo.get(ammo).map(|&clip| (ammo, clip))
+    }
+}
+
+/// An entry in one of the tables, if the number is in range.
+#[inline]
+fn slot<T>(table: &mut [T], number: i64) -> Option<&mut T> {
+    if number < 0 {
+        None
+    } else {
+        table.get_mut(number as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dehacked::parse_patch;
+
+    #[test]
+    fn patches_change_the_balance() {
+        let vanilla = Balance::vanilla();
+        assert_eq!(vanilla.thing(3004).unwrap().name, "MT_POSSESSED");
+        assert_eq!(vanilla.thing(3004).unwrap().health, 20);
+        assert_eq!(vanilla.weapon_clip("bfg"), Some((2, 20)));
+        assert_eq!(vanilla.weapon_clip("fist"), None);
+
+        let patch = parse_patch(
+            "Patch File for DeHackEd v3.0\n\nDoom version = 21\nPatch format = 6\n\n\
+             Thing 2 (Trooper)\nHit points = 45\nID # = 3001\n\n\
+             Weapon 6 (BFG 9000)\nAmmo type = 0\n\n\
+             Ammo 0 (Bullets)\nPer ammo = 15\n\n\
+             Misc 0\nSoulsphere Health = 150\nBlue Armor Class = 3\n",
+        );
+        let patched = Balance::patched(&patch);
+        // the trooper now comes before the imp, so it takes its number
+        assert_eq!(patched.thing(3001).unwrap().name, "MT_POSSESSED");
+        assert_eq!(patched.thing(3001).unwrap().health, 45);
+        assert_eq!(patched.weapon_clip("bfg"), Some((0, 15)));
+        assert_eq!(patched.soulsphere_health, 150);
+        assert_eq!(patched.blue_armor_class, 3);
+    }
+}
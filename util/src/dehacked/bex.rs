@@ -0,0 +1,332 @@
+// Apache 2.0 License
+
+//! Names used by the Boom BEX extensions and the ports that build on them.
+
+use super::Complevel;
+
+/// Codepointers from vanilla Doom II, which BEX can assign to any frame by name. "NULL" removes a frame's action.
+static VANILLA_CODEPOINTERS: &[&str] = &[
+    "NULL",
+    "A_Light0",
+    "A_WeaponReady",
+    "A_Lower",
+    "A_Raise",
+    "A_Punch",
+    "A_ReFire",
+    "A_FirePistol",
+    "A_Light1",
+    "A_FireShotgun",
+    "A_Light2",
+    "A_FireShotgun2",
+    "A_CheckReload",
+    "A_OpenShotgun2",
+    "A_LoadShotgun2",
+    "A_CloseShotgun2",
+    "A_FireCGun",
+    "A_GunFlash",
+    "A_FireMissile",
+    "A_Saw",
+    "A_FirePlasma",
+    "A_BFGsound",
+    "A_FireBFG",
+    "A_BFGSpray",
+    "A_Explode",
+    "A_Pain",
+    "A_PlayerScream",
+    "A_Fall",
+    "A_XScream",
+    "A_Look",
+    "A_Chase",
+    "A_FaceTarget",
+    "A_PosAttack",
+    "A_Scream",
+    "A_SPosAttack",
+    "A_VileChase",
+    "A_VileStart",
+    "A_VileTarget",
+    "A_VileAttack",
+    "A_StartFire",
+    "A_Fire",
+    "A_FireCrackle",
+    "A_Tracer",
+    "A_SkelWhoosh",
+    "A_SkelFist",
+    "A_SkelMissile",
+    "A_FatRaise",
+    "A_FatAttack1",
+    "A_FatAttack2",
+    "A_FatAttack3",
+    "A_BossDeath",
+    "A_CPosAttack",
+    "A_CPosRefire",
+    "A_TroopAttack",
+    "A_SargAttack",
+    "A_HeadAttack",
+    "A_BruisAttack",
+    "A_SkullAttack",
+    "A_Metal",
+    "A_SpidRefire",
+    "A_BabyMetal",
+    "A_BspiAttack",
+    "A_Hoof",
+    "A_CyberAttack",
+    "A_PainAttack",
+    "A_PainDie",
+    "A_KeenDie",
+    "A_BrainPain",
+    "A_BrainScream",
+    "A_BrainDie",
+    "A_BrainAwake",
+    "A_BrainSpit",
+    "A_SpawnSound",
+    "A_SpawnFly",
+    "A_BrainExplode",
+];
+
+/// Codepointers added by MBF.
+static MBF_CODEPOINTERS: &[&str] = &[
+    "A_Detonate",
+    "A_Mushroom",
+    "A_Die",
+    "A_Spawn",
+    "A_Turn",
+    "A_Face",
+    "A_Scratch",
+    "A_PlaySound",
+    "A_RandomJump",
+    "A_LineEffect",
+    "A_FireOldBFG",
+    "A_BetaSkullAttack",
+    "A_Stop",
+];
+
+/// Codepointers added by MBF21. These take their parameters from the frame's Args fields.
+static MBF21_CODEPOINTERS: &[&str] = &[
+    "A_SpawnObject",
+    "A_MonsterProjectile",
+    "A_MonsterBulletAttack",
+    "A_MonsterMeleeAttack",
+    "A_RadiusDamage",
+    "A_NoiseAlert",
+    "A_HealChase",
+    "A_SeekTracer",
+    "A_FindTracer",
+    "A_ClearTracer",
+    "A_JumpIfHealthBelow",
+    "A_JumpIfTargetInSight",
+    "A_JumpIfTargetCloser",
+    "A_JumpIfTracerInSight",
+    "A_JumpIfTracerCloser",
+    "A_JumpIfFlagsSet",
+    "A_AddFlags",
+    "A_RemoveFlags",
+    "A_WeaponProjectile",
+    "A_WeaponBulletAttack",
+    "A_WeaponMeleeAttack",
+    "A_WeaponSound",
+    "A_WeaponAlert",
+    "A_WeaponJump",
+    "A_ConsumeAmmo",
+    "A_CheckAmmo",
+    "A_RefireTo",
+    "A_GunFlashTo",
+];
+
+/// Mnemonics that can be replaced in a [STRINGS] section. Numbered families such as HUSTR_12 are matched by
+/// `string_mnemonic` instead.
+static STRING_MNEMONICS: &[&str] = &[
+    "D_DEVSTR",
+    "D_CDROM",
+    "PRESSKEY",
+    "PRESSYN",
+    "QUITMSG",
+    "LOADNET",
+    "QLOADNET",
+    "QSAVESPOT",
+    "SAVEDEAD",
+    "QSPROMPT",
+    "QLPROMPT",
+    "NEWGAME",
+    "NIGHTMARE",
+    "SWSTRING",
+    "MSGOFF",
+    "MSGON",
+    "NETEND",
+    "ENDGAME",
+    "DOSY",
+    "DETAILHI",
+    "DETAILLO",
+    "EMPTYSTRING",
+    "GOTARMOR",
+    "GOTMEGA",
+    "GOTHTHBONUS",
+    "GOTARMBONUS",
+    "GOTSTIM",
+    "GOTMEDINEED",
+    "GOTMEDIKIT",
+    "GOTSUPER",
+    "GOTBLUECARD",
+    "GOTYELWCARD",
+    "GOTREDCARD",
+    "GOTBLUESKUL",
+    "GOTYELWSKUL",
+    "GOTREDSKULL",
+    "GOTINVUL",
+    "GOTBERSERK",
+    "GOTINVIS",
+    "GOTSUIT",
+    "GOTMAP",
+    "GOTVISOR",
+    "GOTMSPHERE",
+    "GOTCLIP",
+    "GOTCLIPBOX",
+    "GOTROCKET",
+    "GOTROCKBOX",
+    "GOTCELL",
+    "GOTCELLBOX",
+    "GOTSHELLS",
+    "GOTSHELLBOX",
+    "GOTBACKPACK",
+    "GOTBFG9000",
+    "GOTCHAINGUN",
+    "GOTCHAINSAW",
+    "GOTLAUNCHER",
+    "GOTPLASMA",
+    "GOTSHOTGUN",
+    "GOTSHOTGUN2",
+    "PD_BLUEO",
+    "PD_REDO",
+    "PD_YELLOWO",
+    "PD_BLUEK",
+    "PD_REDK",
+    "PD_YELLOWK",
+    "PD_BLUEC",
+    "PD_REDC",
+    "PD_YELLOWC",
+    "PD_BLUES",
+    "PD_REDS",
+    "PD_YELLOWS",
+    "PD_ANY",
+    "PD_ALL3",
+    "PD_ALL6",
+    "GGSAVED",
+    "HUSTR_MSGU",
+    "HUSTR_MESSAGESENT",
+    "HUSTR_PLRGREEN",
+    "HUSTR_PLRINDIGO",
+    "HUSTR_PLRBROWN",
+    "HUSTR_PLRRED",
+    "AMSTR_FOLLOWON",
+    "AMSTR_FOLLOWOFF",
+    "AMSTR_GRIDON",
+    "AMSTR_GRIDOFF",
+    "AMSTR_MARKEDSPOT",
+    "AMSTR_MARKSCLEARED",
+    "STSTR_MUS",
+    "STSTR_NOMUS",
+    "STSTR_DQDON",
+    "STSTR_DQDOFF",
+    "STSTR_KFAADDED",
+    "STSTR_FAADDED",
+    "STSTR_NCON",
+    "STSTR_NCOFF",
+    "STSTR_BEHOLD",
+    "STSTR_BEHOLDX",
+    "STSTR_CHOPPERS",
+    "STSTR_CLEV",
+    "STSTR_COMPON",
+    "STSTR_COMPOFF",
+    "CC_ZOMBIE",
+    "CC_SHOTGUN",
+    "CC_HEAVY",
+    "CC_IMP",
+    "CC_DEMON",
+    "CC_LOST",
+    "CC_CACO",
+    "CC_HELL",
+    "CC_BARON",
+    "CC_ARACH",
+    "CC_PAIN",
+    "CC_REVEN",
+    "CC_MANCU",
+    "CC_ARCH",
+    "CC_SPIDER",
+    "CC_CYBER",
+    "CC_HERO",
+    "BGFLATE1",
+    "BGFLATE2",
+    "BGFLATE3",
+    "BGFLATE4",
+    "BGFLAT06",
+    "BGFLAT11",
+    "BGFLAT20",
+    "BGFLAT30",
+    "BGFLAT15",
+    "BGFLAT31",
+    "BGCASTCALL",
+    "SAVEGAMENAME",
+];
+
+/// Numbered families of string mnemonics, with the range of numbers each one has.
+static NUMBERED_MNEMONICS: &[(&str, &str, u32, u32)] = &[
+    ("HUSTR_", "", 1, 32),
+    ("PHUSTR_", "", 1, 32),
+    ("THUSTR_", "", 1, 32),
+    ("HUSTR_CHATMACRO", "", 0, 9),
+    ("HUSTR_TALKTOSELF", "", 1, 5),
+    ("GAMMALVL", "", 0, 4),
+    ("STARTUP", "", 1, 5),
+    ("E", "TEXT", 1, 4),
+    ("C", "TEXT", 1, 6),
+    ("P", "TEXT", 1, 6),
+    ("T", "TEXT", 1, 6),
+];
+
+/// The first complevel that has a codepointer, or `None` if no port has one by that name.
+#[inline]
+pub fn codepointer(name: &str) -> Option<Complevel> {
+    // BEX allows the "A_" to be left off
+    let with_prefix = format!("A_{}", name);
+    let find = |table: &[&str]| {
+        table
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(name) || p.eq_ignore_ascii_case(&with_prefix))
+    };
+
+    if find(VANILLA_CODEPOINTERS) {
+        Some(Complevel::Boom)
+    } else if find(MBF_CODEPOINTERS) {
+        Some(Complevel::Mbf)
+    } else if find(MBF21_CODEPOINTERS) {
+        Some(Complevel::Mbf21)
+    } else {
+        None
+    }
+}
+
+/// Whether a [STRINGS] mnemonic is one that ports know about.
+#[inline]
+pub fn string_mnemonic(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    if STRING_MNEMONICS.contains(&name.as_str()) {
+        return true;
+    }
+
+    // HUSTR_E1M1 and friends
+    if let Some(map) = name.strip_prefix("HUSTR_E") {
+        let map = map.as_bytes();
+        return map.len() == 3
+            && (b'1'..=b'4').contains(&map[0])
+            && map[1] == b'M'
+            && (b'1'..=b'9').contains(&map[2]);
+    }
+
+    NUMBERED_MNEMONICS
+        .iter()
+        .any(|(prefix, suffix, first, last)| {
+            name.strip_prefix(prefix)
+                .and_then(|n| n.strip_suffix(suffix))
+                .and_then(|n| n.parse::<u32>().ok())
+                .is_some_and(|n| (*first..=*last).contains(&n))
+        })
+}
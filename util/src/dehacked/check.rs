@@ -0,0 +1,881 @@
+// Apache 2.0 License
+
+use super::*;
+use std::{collections::HashSet, fmt, ops::RangeInclusive};
+
+/// The tallest thing in vanilla Doom II is the Cyberdemon, at 110 units.
+const MAX_VANILLA_HEIGHT: i64 = 110;
+/// The widest thing in vanilla Doom II is the Spider Mastermind, with a radius of 128 units.
+const MAX_VANILLA_WIDTH: i64 = 128;
+
+/// Doom versions that DeHackEd patches can target.
+static DOOM_VERSIONS: &[i64] = &[12, 16, 17, 19, 20, 21, MBF21_DOOM_VERSION];
+
+/// The Doom version that marks a patch as MBF21.
+const MBF21_DOOM_VERSION: i64 = 2021;
+
+/// Par times can be given for Doom II maps up to MAP32, or Ultimate Doom maps up to E4M9.
+const MAX_PAR_MAP: i64 = 32;
+
+/// Something wrong, or possibly wrong, with a patch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Diagnostic {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}: {}", self.line, severity, self.message)
+    }
+}
+
+/// What a field's value means, which decides how it is checked.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Value {
+    /// A plain integer.
+    Plain,
+    /// A 16.16 fixed point number.
+    Fixed,
+    /// A plain integer for monsters, but fixed point for projectiles.
+    Speed,
+    /// A DeHackEd thing number.
+    Thing,
+    Frame,
+    Sound,
+    Sprite,
+    Ammo,
+    /// Flags, as a number or names from the given table.
+    Bits(&'static [(&'static str, u32)]),
+    /// A codepointer argument, which is fixed point or not depending on the codepointer.
+    Arg,
+}
+
+static THING_FIELDS: &[(&str, Value)] = &[
+    ("ID #", Value::Plain),
+    ("Initial frame", Value::Frame),
+    ("Hit points", Value::Plain),
+    ("First moving frame", Value::Frame),
+    ("Alert sound", Value::Sound),
+    ("Reaction time", Value::Plain),
+    ("Attack sound", Value::Sound),
+    ("Injury frame", Value::Frame),
+    ("Pain chance", Value::Plain),
+    ("Pain sound", Value::Sound),
+    ("Close attack frame", Value::Frame),
+    ("Far attack frame", Value::Frame),
+    ("Death frame", Value::Frame),
+    ("Exploding frame", Value::Frame),
+    ("Death sound", Value::Sound),
+    ("Speed", Value::Speed),
+    ("Width", Value::Fixed),
+    ("Height", Value::Fixed),
+    ("Mass", Value::Plain),
+    ("Missile damage", Value::Plain),
+    ("Action sound", Value::Sound),
+    ("Bits", Value::Bits(THING_FLAGS)),
+    ("Respawn frame", Value::Frame),
+];
+
+static MBF21_THING_FIELDS: &[(&str, Value)] = &[
+    ("Infighting group", Value::Plain),
+    ("Projectile group", Value::Plain),
+    ("Splash group", Value::Plain),
+    ("MBF21 Bits", Value::Bits(MBF21_THING_FLAGS)),
+    ("Rip sound", Value::Sound),
+    ("Fast speed", Value::Speed),
+    ("Melee range", Value::Fixed),
+];
+
+static FRAME_FIELDS: &[(&str, Value)] = &[
+    ("Sprite number", Value::Sprite),
+    ("Sprite subnumber", Value::Plain),
+    ("Duration", Value::Plain),
+    ("Next frame", Value::Frame),
+    ("Unknown 1", Value::Plain),
+    ("Unknown 2", Value::Plain),
+];
+
+static MBF21_FRAME_FIELDS: &[(&str, Value)] = &[
+    ("MBF21 Bits", Value::Bits(MBF21_FRAME_FLAGS)),
+    ("Args1", Value::Arg),
+    ("Args2", Value::Arg),
+    ("Args3", Value::Arg),
+    ("Args4", Value::Arg),
+    ("Args5", Value::Arg),
+    ("Args6", Value::Arg),
+    ("Args7", Value::Arg),
+    ("Args8", Value::Arg),
+];
+
+static WEAPON_FIELDS: &[(&str, Value)] = &[
+    ("Ammo type", Value::Ammo),
+    ("Deselect frame", Value::Frame),
+    ("Select frame", Value::Frame),
+    ("Bobbing frame", Value::Frame),
+    ("Shooting frame", Value::Frame),
+    ("Firing frame", Value::Frame),
+];
+
+static MBF21_WEAPON_FIELDS: &[(&str, Value)] = &[
+    ("MBF21 Bits", Value::Bits(MBF21_WEAPON_FLAGS)),
+    ("Ammo per shot", Value::Plain),
+];
+
+static AMMO_FIELDS: &[(&str, Value)] = &[("Max ammo", Value::Plain), ("Per ammo", Value::Plain)];
+
+static SOUND_FIELDS: &[(&str, Value)] = &[
+    ("Offset", Value::Plain),
+    ("Zero/One", Value::Plain),
+    ("Value", Value::Plain),
+    ("Zero 1", Value::Plain),
+    ("Zero 2", Value::Plain),
+    ("Zero 3", Value::Plain),
+    ("Zero 4", Value::Plain),
+    ("Neg. One 1", Value::Plain),
+    ("Neg. One 2", Value::Plain),
+];
+
+static SPRITE_FIELDS: &[(&str, Value)] = &[("Offset", Value::Plain)];
+
+static POINTER_FIELDS: &[(&str, Value)] = &[("Codep Frame", Value::Frame)];
+
+static HELPER_FIELDS: &[(&str, Value)] = &[("Type", Value::Thing)];
+
+static MISC_FIELDS: &[(&str, Value)] = &[
+    ("Initial Health", Value::Plain),
+    ("Initial Bullets", Value::Plain),
+    ("Max Health", Value::Plain),
+    ("Max Armor", Value::Plain),
+    ("Green Armor Class", Value::Plain),
+    ("Blue Armor Class", Value::Plain),
+    ("Max Soulsphere", Value::Plain),
+    ("Soulsphere Health", Value::Plain),
+    ("Megasphere Health", Value::Plain),
+    ("God Mode Health", Value::Plain),
+    ("IDFA Armor", Value::Plain),
+    ("IDFA Armor Class", Value::Plain),
+    ("IDKFA Armor", Value::Plain),
+    ("IDKFA Armor Class", Value::Plain),
+    ("BFG Cells/Shot", Value::Plain),
+    ("Monsters Infight", Value::Plain),
+];
+
+/// Cheat fields hold cheat codes rather than numbers, so they're only checked by name.
+static CHEAT_FIELDS: &[&str] = &[
+    "Change music",
+    "Chainsaw",
+    "God mode",
+    "Ammo & Keys",
+    "Ammo",
+    "No Clipping 1",
+    "No Clipping 2",
+    "Invincibility",
+    "Berserk",
+    "Invisibility",
+    "Radiation Suit",
+    "Auto-map",
+    "Lite-Amp Goggles",
+    "BEHOLD menu",
+    "Level Warp",
+    "Player Position",
+    "Map cheat",
+];
+
+/// Look for anything in a patch that the target complevel would reject or that looks like a mistake.
+#[inline]
+pub fn check_patch(patch: &Patch, complevel: Complevel) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut error = |line: usize, message: String| {
+        diagnostics.push(Diagnostic {
+            line,
+            severity: Severity::Error,
+            message,
+        })
+    };
+
+    patch
+        .unparsed
+        .iter()
+        .for_each(|(line, text)| error(*line, format!("could not understand {:?}", text.trim())));
+
+    if patch.doom_version == Some(MBF21_DOOM_VERSION) && complevel < Complevel::Mbf21 {
+        error(
+            1,
+            format!(
+                "Doom version {} marks an MBF21 patch, but the target is {}",
+                MBF21_DOOM_VERSION, complevel
+            ),
+        );
+    }
+
+    if patch.patch_format != Some(6) {
+        diagnostics.push(Diagnostic {
+            line: 1,
+            severity: Severity::Warning,
+            message: "patch should declare \"Patch format = 6\"".to_string(),
+        });
+    }
+    if let Some(version) = patch.doom_version {
+        if !DOOM_VERSIONS.contains(&version) {
+            diagnostics.push(Diagnostic {
+                line: 1,
+                severity: Severity::Warning,
+                message: format!("unknown Doom version {}", version),
+            });
+        }
+    }
+
+    let needed = patch
+        .blocks
+        .iter()
+        .map(|block| check_block(block, complevel, &mut diagnostics))
+        .max()
+        .unwrap_or(Complevel::Vanilla);
+
+    // ports use the version to decide how to read the rest of the patch
+    if needed == Complevel::Mbf21 && patch.doom_version != Some(MBF21_DOOM_VERSION) {
+        diagnostics.push(Diagnostic {
+            line: 1,
+            severity: Severity::Warning,
+            message: format!(
+                "patch uses MBF21 features, so it should declare \"Doom version = {}\"",
+                MBF21_DOOM_VERSION
+            ),
+        });
+    }
+
+    diagnostics.sort_by_key(|d| d.line);
+    diagnostics
+}
+
+/// Collects the diagnostics for one block, along with the lowest complevel that can use everything in it.
+struct Report<'a> {
+    name: String,
+    complevel: Complevel,
+    needed: Complevel,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Report<'_> {
+    #[inline]
+    fn push(&mut self, line: usize, severity: Severity, message: String) {
+        self.diagnostics.push(Diagnostic {
+            line,
+            severity,
+            message: format!("{}: {}", self.name, message),
+        })
+    }
+
+    /// Note that something in the block needs a complevel, and complain if the target is older.
+    #[inline]
+    fn need(&mut self, line: usize, level: Complevel, what: &str) {
+        self.needed = self.needed.max(level);
+        if level > self.complevel {
+            let message = format!(
+                "{} needs complevel {} or later, but the target is {}",
+                what, level, self.complevel
+            );
+            self.push(line, Severity::Error, message);
+        }
+    }
+
+    /// Warn about a field that has already been set in this block.
+    #[inline]
+    fn duplicate(&mut self, seen: &mut HashSet<String>, field: &Field) {
+        if !seen.insert(field.name.to_ascii_lowercase()) {
+            let message = format!("{:?} is set more than once", field.name);
+            self.push(field.line, Severity::Warning, message);
+        }
+    }
+}
+
+/// The valid numbers for a table with `limit` entries, or any number from `first` up if it has no limit.
+#[inline]
+fn numbers(first: i64, limit: Option<i64>) -> RangeInclusive<i64> {
+    first..=limit.map_or(i64::MAX, |limit| limit - 1 + first)
+}
+
+/// Describe a range of numbers for messages.
+#[inline]
+fn describe_range(range: &RangeInclusive<i64>) -> String {
+    if *range.end() == i64::MAX {
+        format!("{} or more", range.start())
+    } else {
+        format!("{} to {}", range.start(), range.end())
+    }
+}
+
+/// Check one block, returning the lowest complevel that can use everything in it.
+#[inline]
+fn check_block(
+    block: &Block,
+    complevel: Complevel,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Complevel {
+    let limits = complevel.limits();
+    let mut report = Report {
+        name: block.kind.describe(),
+        complevel,
+        needed: Complevel::Vanilla,
+        diagnostics,
+    };
+
+    type Fields = &'static [(&'static str, Value)];
+    let (number, range, fields, mbf21_fields): (Option<i64>, _, Fields, Fields) = match &block.kind
+    {
+        BlockKind::Thing { number, .. } => (
+            Some(*number),
+            numbers(1, limits.things),
+            THING_FIELDS,
+            MBF21_THING_FIELDS,
+        ),
+        BlockKind::Frame { number } => (
+            Some(*number),
+            numbers(0, limits.frames),
+            FRAME_FIELDS,
+            MBF21_FRAME_FIELDS,
+        ),
+        BlockKind::Weapon { number, .. } => (
+            Some(*number),
+            numbers(0, Some(NUM_WEAPONS)),
+            WEAPON_FIELDS,
+            MBF21_WEAPON_FIELDS,
+        ),
+        BlockKind::Ammo { number, .. } => {
+            (Some(*number), numbers(0, Some(NUM_AMMO)), AMMO_FIELDS, &[])
+        }
+        BlockKind::Sound { number } => {
+            (Some(*number), numbers(0, limits.sounds), SOUND_FIELDS, &[])
+        }
+        BlockKind::Sprite { number } => (
+            Some(*number),
+            numbers(0, limits.sprites),
+            SPRITE_FIELDS,
+            &[],
+        ),
+        BlockKind::Pointer { frame, .. } => {
+            if frame.is_none() {
+                report.push(
+                    block.line,
+                    Severity::Error,
+                    "pointer blocks need the frame in parentheses, e.g. \"(Frame 85)\"".to_string(),
+                );
+            }
+            (*frame, numbers(0, limits.frames), POINTER_FIELDS, &[])
+        }
+        BlockKind::Misc => (None, 0..=0, MISC_FIELDS, &[]),
+        BlockKind::Helper => {
+            report.need(block.line, Complevel::Mbf, "[HELPER]");
+            (None, 0..=0, HELPER_FIELDS, &[])
+        }
+        BlockKind::Cheat => {
+            block
+                .fields
+                .iter()
+                .filter(|f| !CHEAT_FIELDS.iter().any(|c| c.eq_ignore_ascii_case(&f.name)))
+                .for_each(|f| {
+                    report.push(
+                        f.line,
+                        Severity::Error,
+                        format!("unknown cheat {:?}", f.name),
+                    )
+                });
+            return report.needed;
+        }
+        BlockKind::Text { old, new } => {
+            // vanilla DeHackEd can only use the space the original string took up, padded to four bytes, but
+            // Boom and later allocate new strings
+            let room = ((old.len() + 4) & !3) - 1;
+            if complevel == Complevel::Vanilla && new.len() > room {
+                report.push(
+                    block.line,
+                    Severity::Warning,
+                    format!(
+                        "replacement is {} characters but vanilla only has room for {}",
+                        new.len(),
+                        room
+                    ),
+                );
+            }
+            return report.needed;
+        }
+        BlockKind::Include { .. } => {
+            report.need(block.line, Complevel::Boom, "INCLUDE");
+            return report.needed;
+        }
+        BlockKind::CodePointers => {
+            report.need(block.line, Complevel::Boom, "[CODEPTR]");
+            check_codepointers(block, &limits, &mut report);
+            return report.needed;
+        }
+        BlockKind::Strings => {
+            report.need(block.line, Complevel::Boom, "[STRINGS]");
+            check_strings(block, &mut report);
+            return report.needed;
+        }
+        BlockKind::Pars => {
+            report.need(block.line, Complevel::Boom, "[PARS]");
+            check_pars(block, &mut report);
+            return report.needed;
+        }
+        BlockKind::SpriteNames | BlockKind::SoundNames | BlockKind::MusicNames => {
+            report.need(block.line, Complevel::Boom, &block.kind.describe());
+            check_names(block, &limits, &mut report);
+            return report.needed;
+        }
+    };
+
+    if let Some(number) = number {
+        if !range.contains(&number) {
+            report.push(
+                block.line,
+                Severity::Error,
+                format!(
+                    "number {} is out of range (must be {})",
+                    number,
+                    describe_range(&range)
+                ),
+            );
+        }
+    }
+
+    // projectile speeds are fixed point, so find out if this thing is one
+    let missile = match &block.kind {
+        BlockKind::Thing { number, .. } => block
+            .fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case("Bits"))
+            .and_then(|f| parse_bits(&f.value, THING_FLAGS))
+            .map_or(VANILLA_MISSILES.contains(number), |bits| {
+                bits & MF_MISSILE != 0
+            }),
+        _ => false,
+    };
+
+    let mut seen = HashSet::new();
+    block.fields.iter().for_each(|field| {
+        let find = |fields: Fields| {
+            fields
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&field.name))
+                .map(|(_, kind)| *kind)
+        };
+        let kind = match (find(fields), find(mbf21_fields)) {
+            (Some(kind), _) => kind,
+            (None, Some(kind)) => {
+                report.need(field.line, Complevel::Mbf21, &format!("{:?}", field.name));
+                kind
+            }
+            (None, None) => {
+                report.push(
+                    field.line,
+                    Severity::Error,
+                    format!("unknown field {:?}", field.name),
+                );
+                return;
+            }
+        };
+
+        report.duplicate(&mut seen, field);
+
+        if let Value::Bits(flags) = kind {
+            let bits = match parse_bits(&field.value, flags) {
+                Some(bits) => bits,
+                None => {
+                    report.push(
+                        field.line,
+                        Severity::Error,
+                        format!("unknown flags in {:?}", field.value),
+                    );
+                    return;
+                }
+            };
+
+            // the top four thing flags came later
+            if field.name.eq_ignore_ascii_case("Bits") {
+                if bits & BOOM_THING_FLAGS != 0 {
+                    report.need(field.line, Complevel::Boom, "the TRANSLUCENT flag");
+                }
+                if bits & MBF_THING_FLAGS != 0 {
+                    report.need(
+                        field.line,
+                        Complevel::Mbf,
+                        "the TOUCHY, BOUNCES and FRIEND flags",
+                    );
+                }
+            }
+            return;
+        }
+
+        let value: i64 = match field.value.parse() {
+            Ok(value) => value,
+            Err(_) => {
+                report.push(
+                    field.line,
+                    Severity::Error,
+                    format!("{} = {:?} is not a number", field.name, field.value),
+                );
+                return;
+            }
+        };
+
+        let kind = match (kind, missile) {
+            (Value::Speed, true) => Value::Fixed,
+            (Value::Speed, false) => Value::Plain,
+            (kind, _) => kind,
+        };
+
+        if let Some((severity, message)) = check_value(&field.name, kind, value, &limits) {
+            report.push(field.line, severity, message);
+        }
+    });
+
+    report.needed
+}
+
+/// Check the "FRAME number = codepointer" lines of a [CODEPTR] section.
+#[inline]
+fn check_codepointers(block: &Block, limits: &Limits, report: &mut Report) {
+    let range = numbers(0, limits.frames);
+    let mut seen = HashSet::new();
+
+    block.fields.iter().for_each(|field| {
+        let mut words = field.name.split_whitespace();
+        let frame = match (words.next(), words.next(), words.next()) {
+            (Some(word), Some(frame), None) if word.eq_ignore_ascii_case("frame") => {
+                frame.parse().ok()
+            }
+            _ => None,
+        };
+
+        match frame {
+            None => report.push(
+                field.line,
+                Severity::Error,
+                format!(
+                    "expected \"FRAME number = codepointer\", not {:?}",
+                    field.name
+                ),
+            ),
+            Some(frame) if !range.contains(&frame) => report.push(
+                field.line,
+                Severity::Error,
+                format!(
+                    "frame {} is out of range (must be {})",
+                    frame,
+                    describe_range(&range)
+                ),
+            ),
+            Some(_) => report.duplicate(&mut seen, field),
+        }
+
+        // the section itself already needs Boom, so only later codepointers are worth mentioning
+        match bex::codepointer(&field.value) {
+            Some(level) if level > Complevel::Boom => {
+                report.need(field.line, level, &format!("codepointer {}", field.value))
+            }
+            Some(_) => (),
+            None => report.push(
+                field.line,
+                Severity::Error,
+                format!("unknown codepointer {:?}", field.value),
+            ),
+        }
+    });
+}
+
+/// Check the mnemonics of a [STRINGS] section. Ports ignore ones they don't know, so these are only warnings.
+#[inline]
+fn check_strings(block: &Block, report: &mut Report) {
+    let mut seen = HashSet::new();
+
+    block.fields.iter().for_each(|field| {
+        if bex::string_mnemonic(&field.name) {
+            report.duplicate(&mut seen, field);
+        } else {
+            report.push(
+                field.line,
+                Severity::Warning,
+                format!("unknown string mnemonic {:?}", field.name),
+            );
+        }
+    });
+}
+
+/// Check the "par episode map seconds" and "par map seconds" lines of a [PARS] section.
+#[inline]
+fn check_pars(block: &Block, report: &mut Report) {
+    block.fields.iter().for_each(|field| {
+        if !field.name.eq_ignore_ascii_case("par") {
+            report.push(
+                field.line,
+                Severity::Error,
+                format!("expected a par time, not {:?}", field.name),
+            );
+            return;
+        }
+
+        let numbers = field
+            .value
+            .split_whitespace()
+            .map(str::parse::<i64>)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_or_default();
+
+        let valid = match numbers[..] {
+            [episode, map, seconds] => {
+                (1..=4).contains(&episode) && (1..=9).contains(&map) && seconds >= 0
+            }
+            [map, seconds] => (1..=MAX_PAR_MAP).contains(&map) && seconds >= 0,
+            _ => false,
+        };
+        if !valid {
+            report.push(
+                field.line,
+                Severity::Error,
+                format!(
+                    "par {:?} should be \"par episode map seconds\" or \"par map seconds\"",
+                    field.value
+                ),
+            );
+        }
+    });
+}
+
+/// Check the renames in a [SPRITES], [SOUNDS] or [MUSIC] section. DSDHacked also allows new sprites and sounds
+/// to be named by number.
+#[inline]
+fn check_names(block: &Block, limits: &Limits, report: &mut Report) {
+    let (what, length, range) = match block.kind {
+        BlockKind::SpriteNames => ("sprite", 4..=4, Some(numbers(0, limits.sprites))),
+        BlockKind::SoundNames => ("sound", 1..=6, Some(numbers(0, limits.sounds))),
+        _ => ("music", 1..=6, None),
+    };
+    let mut seen = HashSet::new();
+
+    block.fields.iter().for_each(|field| {
+        report.duplicate(&mut seen, field);
+
+        if let Ok(number) = field.name.parse::<i64>() {
+            match &range {
+                Some(range) => {
+                    report.need(field.line, Complevel::Mbf21, &format!("numbered {}s", what));
+                    if !range.contains(&number) {
+                        report.push(
+                            field.line,
+                            Severity::Error,
+                            format!(
+                                "{} {} is out of range (must be {})",
+                                what,
+                                number,
+                                describe_range(range)
+                            ),
+                        );
+                    }
+                }
+                None => report.push(
+                    field.line,
+                    Severity::Error,
+                    format!("{} can't be renamed by number", what),
+                ),
+            }
+        }
+
+        if !length.contains(&field.value.len()) {
+            report.push(
+                field.line,
+                Severity::Error,
+                format!(
+                    "{} name {:?} must be {} characters long",
+                    what,
+                    field.value,
+                    if length.start() == length.end() {
+                        length.start().to_string()
+                    } else {
+                        format!("{} to {}", length.start(), length.end())
+                    }
+                ),
+            );
+        }
+    });
+}
+
+/// Check a single numeric value against what its field means.
+#[inline]
+fn check_value(name: &str, kind: Value, value: i64, limits: &Limits) -> Option<(Severity, String)> {
+    let in_range = |what: &str, range: RangeInclusive<i64>| {
+        if range.contains(&value) {
+            None
+        } else {
+            Some((
+                Severity::Error,
+                format!(
+                    "{} = {} is not a valid {} (must be {})",
+                    name,
+                    value,
+                    what,
+                    describe_range(&range)
+                ),
+            ))
+        }
+    };
+
+    match kind {
+        Value::Thing => in_range("thing", numbers(1, limits.things)),
+        Value::Frame => in_range("frame", numbers(0, limits.frames)),
+        Value::Sound => in_range("sound", numbers(0, limits.sounds)),
+        Value::Sprite => in_range("sprite", numbers(0, limits.sprites)),
+        Value::Ammo if value != AMMO_NONE => in_range("ammo type", numbers(0, Some(NUM_AMMO))),
+        Value::Fixed if value != 0 && value.abs() < FRACUNIT => Some((
+            Severity::Warning,
+            format!(
+                "{} = {} is fixed point, so this is only {:.4} units; did you mean {}?",
+                name,
+                value,
+                value as f64 / FRACUNIT as f64,
+                value * FRACUNIT
+            ),
+        )),
+        Value::Fixed => {
+            let limit = match name.to_ascii_lowercase().as_str() {
+                "height" => Some(MAX_VANILLA_HEIGHT),
+                "width" => Some(MAX_VANILLA_WIDTH),
+                _ => None,
+            }?;
+
+            if value > limit * FRACUNIT {
+                Some((
+                    Severity::Warning,
+                    format!(
+                        "{} = {} is {:.2} units, more than any vanilla thing ({}); check that it was converted to fixed point correctly",
+                        name,
+                        value,
+                        value as f64 / FRACUNIT as f64,
+                        limit
+                    ),
+                ))
+            } else {
+                None
+            }
+        }
+        // mass is often huge on purpose, so it's the one plain field that can't be checked this way
+        Value::Plain
+            if value >= FRACUNIT && value % FRACUNIT == 0 && !name.eq_ignore_ascii_case("Mass") =>
+        {
+            Some((
+                Severity::Warning,
+                format!(
+                    "{} = {} looks like a fixed point number; did you mean {}?",
+                    name,
+                    value,
+                    value / FRACUNIT
+                ),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dehacked::parse_patch;
+
+    fn messages(text: &str) -> Vec<(usize, Severity)> {
+        messages_at(text, Complevel::Vanilla)
+    }
+
+    fn messages_at(text: &str, complevel: Complevel) -> Vec<(usize, Severity)> {
+        check_patch(&parse_patch(text), complevel)
+            .into_iter()
+            .map(|d| (d.line, d.severity))
+            .collect()
+    }
+
+    #[test]
+    fn clean_patch() {
+        let text = "Patch File for DeHackEd v3.0\r\nDoom version = 19\r\nPatch format = 6\r\n\r\n\
+                    Thing 2 (Trooper)\r\nHit points = 30\r\nSpeed = 8\r\nHeight = 3670016\r\n\r\n\
+                    Text 5 5\r\nIMP  FOO  \r\n";
+        assert_eq!(messages(text), vec![]);
+    }
+
+    #[test]
+    fn problems_are_reported() {
+        let text = "Doom version = 19\nPatch format = 6\n\n\
+                    Thing 200\nWidth = 20\n\n\
+                    Thing 2\nColour = 3\nHit points = 1966080\n\n\
+                    Frame 5000\n";
+        assert_eq!(
+            messages(text),
+            vec![
+                (4, Severity::Error),
+                (5, Severity::Warning),
+                (8, Severity::Error),
+                (9, Severity::Warning),
+                (11, Severity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn bex_sections() {
+        let text = "Doom version = 21\nPatch format = 6\n\n\
+                    [CODEPTR]\nFRAME 12 = A_Look\nFRAME 13 = Chase\nFRAME 14 = A_Dance\n\n\
+                    [STRINGS]\nGOTARMOR = You got the \\\n  armor!\nNOTAMNEMONIC = Hi\n\n\
+                    [PARS]\npar 1 1 30\npar 33 100\n";
+        assert_eq!(
+            messages_at(text, Complevel::Boom),
+            vec![
+                (7, Severity::Error),
+                (12, Severity::Warning),
+                (16, Severity::Error),
+            ]
+        );
+
+        let errors = messages_at(text, Complevel::Vanilla)
+            .into_iter()
+            .filter(|(_, severity)| *severity == Severity::Error)
+            .count();
+        assert_eq!(errors, 5);
+
+        let patch = parse_patch(text);
+        assert_eq!(patch.blocks[1].fields[0].value, "You got the armor!");
+    }
+
+    #[test]
+    fn mbf21_features_need_mbf21() {
+        let text = "Doom version = 2021\nPatch format = 6\n\n\
+                    Thing 150\nMBF21 Bits = LOGRAV+BOSS\n\n\
+                    Frame 1200\nArgs1 = 65536\n\n\
+                    [CODEPTR]\nFRAME 1200 = A_MonsterProjectile\n";
+        assert_eq!(messages_at(text, Complevel::Mbf21), vec![]);
+
+        let mbf = messages_at(text, Complevel::Mbf);
+        assert_eq!(
+            mbf.iter().map(|(line, _)| *line).collect::<Vec<_>>(),
+            vec![1, 4, 5, 7, 8, 11, 11]
+        );
+        assert!(mbf.iter().all(|(_, severity)| *severity == Severity::Error));
+
+        // without the version, ports won't read the patch as MBF21
+        let text = text.replace("2021", "19");
+        assert_eq!(
+            messages_at(&text, Complevel::Mbf21),
+            vec![(1, Severity::Warning)]
+        );
+    }
+}
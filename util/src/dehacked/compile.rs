@@ -0,0 +1,663 @@
+// Apache 2.0 License
+
+use super::{
+    check_patch, parse_patch,
+    vanilla::{self, STATES},
+    Complevel, Severity, AMMO_NONE, FRACUNIT, MF_MISSILE, THING_FLAGS, VANILLA_MISSILES,
+};
+use crate::error::ResultExt;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write as _},
+    fs::File,
+    io::{BufReader, Write},
+    path::Path,
+};
+
+/// The Doom version patches are written for unless the source says otherwise.
+const DEFAULT_DOOM_VERSION: i64 = 19;
+
+/// A DeHackEd patch written with names instead of numbers, and with distances in map units instead of fixed point.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Source {
+    #[serde(default)]
+    doom_version: Option<i64>,
+    #[serde(default)]
+    things: BTreeMap<Ref, Thing>,
+    #[serde(default)]
+    states: BTreeMap<Ref, State>,
+    #[serde(default)]
+    weapons: BTreeMap<Ref, Weapon>,
+    #[serde(default)]
+    ammo: BTreeMap<Ref, Ammo>,
+    #[serde(default)]
+    misc: Misc,
+    #[serde(default)]
+    text: Vec<Text>,
+    /// BEX string replacements, by mnemonic.
+    #[serde(default)]
+    strings: BTreeMap<String, String>,
+}
+
+/// Something in one of the vanilla tables, either by name or by number.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+#[serde(untagged)]
+pub enum Ref {
+    Number(i64),
+    Name(String),
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Thing {
+    /// The editor number, "ID #" in DeHackEd.
+    id: Option<i64>,
+    initial_frame: Option<Ref>,
+    hit_points: Option<i64>,
+    first_moving_frame: Option<Ref>,
+    alert_sound: Option<Ref>,
+    reaction_time: Option<i64>,
+    attack_sound: Option<Ref>,
+    injury_frame: Option<Ref>,
+    pain_chance: Option<i64>,
+    pain_sound: Option<Ref>,
+    close_attack_frame: Option<Ref>,
+    far_attack_frame: Option<Ref>,
+    death_frame: Option<Ref>,
+    exploding_frame: Option<Ref>,
+    death_sound: Option<Ref>,
+    /// Units per tic for projectiles, or units per step for monsters, which must be whole.
+    speed: Option<f64>,
+    /// The radius, in units.
+    width: Option<f64>,
+    height: Option<f64>,
+    mass: Option<i64>,
+    missile_damage: Option<i64>,
+    action_sound: Option<Ref>,
+    bits: Option<Bits>,
+    respawn_frame: Option<Ref>,
+}
+
+/// Thing flags, as a number or a list of names.
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum Bits {
+    Number(u32),
+    Names(Vec<String>),
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct State {
+    sprite: Option<Ref>,
+    /// The sprite frame, as a letter or a number.
+    frame: Option<Ref>,
+    bright: Option<bool>,
+    duration: Option<i64>,
+    next: Option<Ref>,
+    /// The codepointer to call, e.g. A_Chase, or NULL for none.
+    action: Option<String>,
+    misc1: Option<i64>,
+    misc2: Option<i64>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Weapon {
+    ammo_type: Option<Ref>,
+    deselect_frame: Option<Ref>,
+    select_frame: Option<Ref>,
+    bobbing_frame: Option<Ref>,
+    shooting_frame: Option<Ref>,
+    firing_frame: Option<Ref>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Ammo {
+    max_ammo: Option<i64>,
+    per_ammo: Option<i64>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Misc {
+    initial_health: Option<i64>,
+    initial_bullets: Option<i64>,
+    max_health: Option<i64>,
+    max_armor: Option<i64>,
+    green_armor_class: Option<i64>,
+    blue_armor_class: Option<i64>,
+    max_soulsphere: Option<i64>,
+    soulsphere_health: Option<i64>,
+    megasphere_health: Option<i64>,
+    god_mode_health: Option<i64>,
+    idfa_armor: Option<i64>,
+    idfa_armor_class: Option<i64>,
+    idkfa_armor: Option<i64>,
+    idkfa_armor_class: Option<i64>,
+    bfg_cells_per_shot: Option<i64>,
+    monsters_infight: Option<i64>,
+}
+
+/// A replacement for one of the strings in the executable.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Text {
+    old: String,
+    new: String,
+}
+
+/// The fields of one block, in the order they're written.
+#[derive(Default)]
+struct Fields(Vec<(&'static str, i64)>);
+
+impl Fields {
+    #[inline]
+    fn plain(&mut self, name: &'static str, value: Option<i64>) {
+        if let Some(value) = value {
+            self.0.push((name, value));
+        }
+    }
+
+    #[inline]
+    fn fixed(&mut self, name: &'static str, value: Option<f64>) {
+        self.plain(name, value.map(to_fixed));
+    }
+
+    #[inline]
+    fn state(&mut self, name: &'static str, value: &Option<Ref>) -> crate::Result {
+        self.plain(name, value.as_ref().map(state_number).transpose()?);
+        Ok(())
+    }
+
+    #[inline]
+    fn sound(&mut self, name: &'static str, value: &Option<Ref>) -> crate::Result {
+        let number = value
+            .as_ref()
+            .map(|sound| sound.resolve("sound", vanilla::SOUNDS.iter().copied(), "sfx_", 0))
+            .transpose()?;
+        self.plain(name, number);
+        Ok(())
+    }
+}
+
+impl Ref {
+    /// The number of the entry this refers to, where the table is numbered from `first`.
+    #[inline]
+    fn resolve<I: IntoIterator<Item = &'static str>>(
+        &self,
+        what: &str,
+        table: I,
+        prefix: &str,
+        first: i64,
+    ) -> crate::Result<i64> {
+        match self {
+            Ref::Number(number) if *number < first => Err(crate::Error::Msg(format!(
+                "There is no {} {}; they're numbered from {}",
+                what, number, first
+            ))),
+            Ref::Number(number) => Ok(*number),
+            Ref::Name(name) => vanilla::find(table, prefix, name)
+                .map(|i| i as i64 + first)
+                .ok_or_else(|| unknown(what, name)),
+        }
+    }
+}
+
+impl fmt::Display for Ref {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ref::Number(number) => write!(f, "{}", number),
+            Ref::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+impl Source {
+    /// Load a source from a YAML file.
+    #[inline]
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        serde_yaml::from_reader(BufReader::new(File::open(path).with_file(path)?)).with_file(path)
+    }
+}
+
+/// Compile a DeHackEd source to a patch. The patch is checked against the complevel before it is written, and fails
+/// if the check finds errors.
+#[inline]
+pub fn compile<W: Write>(source: &Source, complevel: Complevel, mut cout: W) -> crate::Result {
+    let patch = compile_source(source, complevel)?;
+
+    cout.write_all(patch.as_bytes())?;
+    Ok(())
+}
+
+/// Turn a source into the text of a patch, with DOS line endings like DeHackEd writes.
+#[inline]
+pub fn compile_source(source: &Source, complevel: Complevel) -> crate::Result<String> {
+    let mut out = String::new();
+    // writing to a String can't fail
+    let _ = write!(
+        out,
+        "Patch File for DeHackEd v3.0\n# Compiled by klamath-util; edit the source instead of this file.\n\n\
+         Doom version = {}\nPatch format = 6\n",
+        source.doom_version.unwrap_or(DEFAULT_DOOM_VERSION)
+    );
+
+    let mut block = |header: String, fields: Fields| {
+        let _ = write!(out, "\n{}\n", header);
+        fields.0.iter().for_each(|(name, value)| {
+            let _ = writeln!(out, "{} = {}", name, value);
+        });
+    };
+
+    // sort everything by number so the patch comes out the same however the source is arranged
+    let things = resolve_all("Thing", &source.things, |r| {
+        r.resolve("thing", vanilla::THINGS.iter().copied(), "MT_", 1)
+    })?;
+    for (number, thing) in things {
+        let name = (number as usize)
+            .checked_sub(1)
+            .and_then(|i| vanilla::THINGS.get(i))
+            .map_or_else(
+                || format!("Thing {}", number),
+                |name| format!("Thing {} ({})", number, name),
+            );
+        block(name, thing.fields(number)?);
+    }
+
+    let states = resolve_all("Frame", &source.states, state_number)?;
+    let mut pointers = vec![];
+    let mut codeptrs = vec![];
+    for (number, state) in &states {
+        // states that only change their codepointer don't need a Frame block
+        let fields = state.fields(*number)?;
+        if !fields.0.is_empty() {
+            block(format!("Frame {}", number), fields);
+        }
+
+        if let Some(action) = &state.action {
+            match codepointer_source(action) {
+                // vanilla can only copy codepointers from one frame to another, and only onto frames that have one
+                Some(from) if pointer_number(*number).is_some() => {
+                    pointers.push((*number, from));
+                }
+                _ => codeptrs.push((*number, action)),
+            }
+        }
+    }
+
+    for (frame, from) in pointers {
+        let mut fields = Fields::default();
+        fields.plain("Codep Frame", Some(from));
+        block(
+            format!(
+                "Pointer {} (Frame {})",
+                pointer_number(frame).unwrap(),
+                frame
+            ),
+            fields,
+        );
+    }
+
+    let weapons = resolve_all("Weapon", &source.weapons, |r| {
+        r.resolve("weapon", vanilla::WEAPONS.iter().copied(), "wp_", 0)
+    })?;
+    for (number, weapon) in weapons {
+        let name = vanilla::WEAPONS.get(number as usize).map_or_else(
+            || format!("Weapon {}", number),
+            |name| format!("Weapon {} ({})", number, name),
+        );
+        block(name, weapon.fields()?);
+    }
+
+    let ammo = resolve_all("Ammo", &source.ammo, |r| {
+        r.resolve("ammo type", vanilla::AMMO.iter().copied(), "am_", 0)
+    })?;
+    for (number, ammo) in ammo {
+        let name = vanilla::AMMO.get(number as usize).map_or_else(
+            || format!("Ammo {}", number),
+            |name| format!("Ammo {} ({})", number, name),
+        );
+        let mut fields = Fields::default();
+        fields.plain("Max ammo", ammo.max_ammo);
+        fields.plain("Per ammo", ammo.per_ammo);
+        block(name, fields);
+    }
+
+    let misc = source.misc.fields();
+    if !misc.0.is_empty() {
+        block("Misc 0".to_string(), misc);
+    }
+
+    source.text.iter().for_each(|text| {
+        let _ = write!(
+            out,
+            "\nText {} {}\n{}{}\n",
+            text.old.chars().count(),
+            text.new.chars().count(),
+            text.old,
+            text.new
+        );
+    });
+
+    if !codeptrs.is_empty() {
+        out.push_str("\n[CODEPTR]\n");
+        codeptrs.iter().for_each(|(frame, action)| {
+            let _ = writeln!(out, "FRAME {} = {}", frame, action);
+        });
+    }
+
+    if !source.strings.is_empty() {
+        out.push_str("\n[STRINGS]\n");
+        source.strings.iter().for_each(|(mnemonic, value)| {
+            let _ = writeln!(out, "{} = {}", mnemonic, value.replace('\n', "\\n"));
+        });
+    }
+
+    // make sure what came out is something ports will accept; warnings are about values the source chose on purpose,
+    // and `dehacked check` reports them
+    let errors: Vec<String> = check_patch(&parse_patch(&out), complevel)
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| format!("compiled patch:{}", d))
+        .collect();
+    if !errors.is_empty() {
+        return Err(crate::Error::Msg(format!(
+            "Compiled patch has errors:\n{}",
+            errors.join("\n")
+        )));
+    }
+
+    Ok(out.replace('\n', "\r\n"))
+}
+
+impl Thing {
+    #[inline]
+    fn fields(&self, number: i64) -> crate::Result<Fields> {
+        let bits = match &self.bits {
+            None => None,
+            Some(Bits::Number(bits)) => Some(*bits),
+            Some(Bits::Names(names)) => Some(names.iter().try_fold(0, |bits, name| {
+                THING_FLAGS
+                    .iter()
+                    .find(|(flag, _)| flag.eq_ignore_ascii_case(name))
+                    .map(|(_, value)| bits | value)
+                    .ok_or_else(|| unknown("thing flag", name))
+            })?),
+        };
+
+        // projectiles move in fixed point, but monsters move in whole units
+        let missile = bits.map_or(VANILLA_MISSILES.contains(&number), |bits| {
+            bits & MF_MISSILE != 0
+        });
+        let speed = match self.speed {
+            Some(speed) if missile => Some(to_fixed(speed)),
+            Some(speed) if speed.fract() == 0.0 => Some(speed as i64),
+            Some(_) => {
+                return Err(crate::Error::StaticMsg(
+                    "Monster speeds must be whole numbers",
+                ))
+            }
+            None => None,
+        };
+
+        let mut fields = Fields::default();
+        fields.plain("ID #", self.id);
+        fields.state("Initial frame", &self.initial_frame)?;
+        fields.plain("Hit points", self.hit_points);
+        fields.state("First moving frame", &self.first_moving_frame)?;
+        fields.sound("Alert sound", &self.alert_sound)?;
+        fields.plain("Reaction time", self.reaction_time);
+        fields.sound("Attack sound", &self.attack_sound)?;
+        fields.state("Injury frame", &self.injury_frame)?;
+        fields.plain("Pain chance", self.pain_chance);
+        fields.sound("Pain sound", &self.pain_sound)?;
+        fields.state("Close attack frame", &self.close_attack_frame)?;
+        fields.state("Far attack frame", &self.far_attack_frame)?;
+        fields.state("Death frame", &self.death_frame)?;
+        fields.state("Exploding frame", &self.exploding_frame)?;
+        fields.sound("Death sound", &self.death_sound)?;
+        fields.plain("Speed", speed);
+        fields.fixed("Width", self.width);
+        fields.fixed("Height", self.height);
+        fields.plain("Mass", self.mass);
+        fields.plain("Missile damage", self.missile_damage);
+        fields.sound("Action sound", &self.action_sound)?;
+        fields.plain("Bits", bits.map(i64::from));
+        fields.state("Respawn frame", &self.respawn_frame)?;
+        Ok(fields)
+    }
+}
+
+impl State {
+    #[inline]
+    fn fields(&self, number: i64) -> crate::Result<Fields> {
+        let sprite = self
+            .sprite
+            .as_ref()
+            .map(|sprite| sprite.resolve("sprite", vanilla::SPRITES.iter().copied(), "SPR_", 0))
+            .transpose()?;
+
+        // changing only the letter or only the brightness keeps the other from the original frame
+        let subnumber = if self.frame.is_some() || self.bright.is_some() {
+            let original = STATES.get(number as usize).map_or(0, |s| s.frame);
+            let letter = match &self.frame {
+                None => original & !vanilla::FF_FULLBRIGHT,
+                Some(Ref::Number(frame)) => *frame as u32,
+                Some(Ref::Name(letter)) => match letter.as_bytes() {
+                    [c @ b'A'..=b'Z'] | [c @ b'a'..=b'z'] => (c.to_ascii_uppercase() - b'A') as u32,
+                    _ => return Err(unknown("sprite frame", letter)),
+                },
+            };
+            let bright = self
+                .bright
+                .unwrap_or(original & vanilla::FF_FULLBRIGHT != 0);
+            Some(if bright {
+                letter | vanilla::FF_FULLBRIGHT
+            } else {
+                letter
+            } as i64)
+        } else {
+            None
+        };
+
+        let mut fields = Fields::default();
+        fields.plain("Sprite number", sprite);
+        fields.plain("Sprite subnumber", subnumber);
+        fields.plain("Duration", self.duration);
+        fields.state("Next frame", &self.next)?;
+        fields.plain("Unknown 1", self.misc1);
+        fields.plain("Unknown 2", self.misc2);
+        Ok(fields)
+    }
+}
+
+impl Weapon {
+    #[inline]
+    fn fields(&self) -> crate::Result<Fields> {
+        let ammo = match &self.ammo_type {
+            Some(Ref::Name(name))
+                if name.eq_ignore_ascii_case("noammo")
+                    || name.eq_ignore_ascii_case("am_noammo") =>
+            {
+                Some(AMMO_NONE)
+            }
+            ammo => ammo
+                .as_ref()
+                .map(|ammo| ammo.resolve("ammo type", vanilla::AMMO.iter().copied(), "am_", 0))
+                .transpose()?,
+        };
+
+        let mut fields = Fields::default();
+        fields.plain("Ammo type", ammo);
+        fields.state("Deselect frame", &self.deselect_frame)?;
+        fields.state("Select frame", &self.select_frame)?;
+        fields.state("Bobbing frame", &self.bobbing_frame)?;
+        fields.state("Shooting frame", &self.shooting_frame)?;
+        fields.state("Firing frame", &self.firing_frame)?;
+        Ok(fields)
+    }
+}
+
+impl Misc {
+    #[inline]
+    fn fields(&self) -> Fields {
+        let mut fields = Fields::default();
+        fields.plain("Initial Health", self.initial_health);
+        fields.plain("Initial Bullets", self.initial_bullets);
+        fields.plain("Max Health", self.max_health);
+        fields.plain("Max Armor", self.max_armor);
+        fields.plain("Green Armor Class", self.green_armor_class);
+        fields.plain("Blue Armor Class", self.blue_armor_class);
+        fields.plain("Max Soulsphere", self.max_soulsphere);
+        fields.plain("Soulsphere Health", self.soulsphere_health);
+        fields.plain("Megasphere Health", self.megasphere_health);
+        fields.plain("God Mode Health", self.god_mode_health);
+        fields.plain("IDFA Armor", self.idfa_armor);
+        fields.plain("IDFA Armor Class", self.idfa_armor_class);
+        fields.plain("IDKFA Armor", self.idkfa_armor);
+        fields.plain("IDKFA Armor Class", self.idkfa_armor_class);
+        fields.plain("BFG Cells/Shot", self.bfg_cells_per_shot);
+        fields.plain("Monsters Infight", self.monsters_infight);
+        fields
+    }
+}
+
+/// Resolve the keys of a section to numbers, and sort by them.
+#[inline]
+fn resolve_all<'a, T, F: Fn(&Ref) -> crate::Result<i64>>(
+    what: &str,
+    section: &'a BTreeMap<Ref, T>,
+    resolve: F,
+) -> crate::Result<BTreeMap<i64, &'a T>> {
+    let mut resolved = BTreeMap::new();
+    for (key, value) in section {
+        if resolved.insert(resolve(key)?, value).is_some() {
+            return Err(crate::Error::Msg(format!(
+                "{} {} is listed more than once",
+                what, key
+            )));
+        }
+    }
+    Ok(resolved)
+}
+
+#[inline]
+fn state_number(state: &Ref) -> crate::Result<i64> {
+    state.resolve("state", STATES.iter().map(|s| s.name), "S_", 0)
+}
+
+/// The DeHackEd codepointer number of a vanilla frame, if it has a codepointer at all.
+#[inline]
+fn pointer_number(frame: i64) -> Option<usize> {
+    if frame < 0 {
+        return None;
+    }
+    STATES.get(frame as usize)?.action?;
+    Some(
+        STATES[..frame as usize]
+            .iter()
+            .filter(|s| s.action.is_some())
+            .count(),
+    )
+}
+
+/// The first vanilla frame that calls a codepointer, which other frames can copy it from. NULL comes from frame 0.
+#[inline]
+fn codepointer_source(action: &str) -> Option<i64> {
+    if action.eq_ignore_ascii_case("NULL") {
+        return Some(0);
+    }
+
+    STATES
+        .iter()
+        .position(|s| {
+            s.action.is_some_and(|a| {
+                a.eq_ignore_ascii_case(action) || a[2..].eq_ignore_ascii_case(action)
+            })
+        })
+        .map(|i| i as i64)
+}
+
+/// Convert map units to 16.16 fixed point.
+#[inline]
+fn to_fixed(units: f64) -> i64 {
+    (units * FRACUNIT as f64).round() as i64
+}
+
+#[inline]
+fn unknown(what: &str, name: &str) -> crate::Error {
+    crate::Error::Msg(format!("Unknown {} {:?}", what, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile_yaml(yaml: &str) -> crate::Result<String> {
+        let source: Source = serde_yaml::from_str(yaml)?;
+        compile_source(&source, Complevel::Vanilla).map(|patch| patch.replace("\r\n", "\n"))
+    }
+
+    #[test]
+    fn names_and_units_are_converted() {
+        let patch = compile_yaml(
+            "things:\n  MT_BFG:\n    speed: 150.0\n    width: 3\n  UNDEAD:\n    speed: 20\n    height: 122\n\
+             states:\n  S_PISTOL1:\n    duration: 3\n  S_DSGUN4:\n    next: S_DSGUN6\n    bright: true\n",
+        )
+        .unwrap();
+
+        // taller than any vanilla thing is only a warning, so it doesn't stop the compile
+        assert!(patch.contains("\nThing 6 (MT_UNDEAD)\nSpeed = 20\nHeight = 7995392\n"));
+        assert!(patch.contains("\nThing 36 (MT_BFG)\nSpeed = 9830400\nWidth = 196608\n"));
+        assert!(patch.contains("\nFrame 13\nDuration = 3\n"));
+        assert!(patch.contains("\nFrame 38\nSprite subnumber = 32770\nNext frame = 40\n"));
+        // things come before frames whatever order the source uses
+        assert!(patch.find("Thing 6").unwrap() < patch.find("Thing 36").unwrap());
+    }
+
+    #[test]
+    fn codepointers_are_copied_from_vanilla_frames() {
+        let patch = compile_yaml(
+            "states:\n  S_BFG2:\n    action: A_FirePistol\n  S_VILE_DIE3:\n    action: BrainDie\n",
+        )
+        .unwrap();
+
+        assert!(patch.contains("\nPointer 65 (Frame 85)\nCodep Frame = 14\n"));
+        assert!(patch.contains("\nPointer 137 (Frame 273)\nCodep Frame = 783\n"));
+        assert!(!patch.contains("\nFrame 273\n"));
+    }
+
+    #[test]
+    fn text_lengths_count_newlines() {
+        let patch = compile_yaml("text:\n  - old: \"IMP\"\n    new: \"B\\nPG\"\n").unwrap();
+        assert!(patch.contains("\nText 3 4\nIMPB\nPG\n"));
+    }
+
+    #[test]
+    fn mistakes_are_rejected() {
+        assert!(compile_yaml("things:\n  MT_NOTHING:\n    hit_points: 1\n").is_err());
+        assert!(compile_yaml("things:\n  MT_TROOP:\n    speed: 8.5\n").is_err());
+        assert!(compile_yaml("things:\n  MT_TROOP:\n    colour: red\n").is_err());
+        assert_eq!(
+            compile_yaml("things:\n  MT_TROOP: {}\n  TROOP: {}\n")
+                .unwrap_err()
+                .to_string(),
+            "Thing TROOP is listed more than once"
+        );
+        // things are numbered from 1, and everything else from 0
+        assert!(compile_yaml("things:\n  0:\n    hit_points: 5\n").is_err());
+        assert!(compile_yaml("states:\n  -1:\n    duration: 5\n").is_err());
+        // MBF codepointers need the BEX [CODEPTR] section, which vanilla can't read
+        let err = compile_yaml("states:\n  S_TROO_ATK3:\n    action: A_Mushroom\n").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Compiled patch has errors:\ncompiled patch:"),
+            "{}",
+            err
+        );
+    }
+}
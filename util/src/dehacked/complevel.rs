@@ -0,0 +1,103 @@
+// Apache 2.0 License
+
+use super::{NUM_FRAMES, NUM_SOUNDS, NUM_SPRITES, NUM_THINGS};
+use std::{fmt, str::FromStr};
+
+/// The compatibility level a patch is meant for. Each level accepts everything the ones before it do.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Complevel {
+    /// Doom II 1.9 and the DeHackEd executable patcher, complevels 2 to 4.
+    Vanilla,
+    /// Boom, complevel 9, which adds the BEX extensions.
+    Boom,
+    /// MBF, complevel 11, which adds helper dogs and new codepointers.
+    Mbf,
+    /// MBF21, complevel 21, along with the DSDHacked extended indices.
+    Mbf21,
+}
+
+/// How many of each table an engine has. `None` means the engine allocates new entries as patches use them.
+#[derive(Debug, Copy, Clone)]
+pub struct Limits {
+    /// The highest thing number, since DeHackEd numbers things from 1.
+    pub things: Option<i64>,
+    pub frames: Option<i64>,
+    pub sprites: Option<i64>,
+    pub sounds: Option<i64>,
+}
+
+impl Complevel {
+    /// The complevel number, as used by PrBoom+ and DSDA-Doom.
+    #[inline]
+    pub fn number(self) -> u32 {
+        match self {
+            Complevel::Vanilla => 2,
+            Complevel::Boom => 9,
+            Complevel::Mbf => 11,
+            Complevel::Mbf21 => 21,
+        }
+    }
+
+    /// The size of each table at this complevel. Boom adds the push and pull things and TNT1, and MBF adds the
+    /// helper dog, the beta items and their frames and sounds, as they are numbered in PrBoom+.
+    #[inline]
+    pub fn limits(self) -> Limits {
+        match self {
+            Complevel::Vanilla => Limits {
+                things: Some(NUM_THINGS),
+                frames: Some(NUM_FRAMES),
+                sprites: Some(NUM_SPRITES),
+                sounds: Some(NUM_SOUNDS),
+            },
+            Complevel::Boom => Limits {
+                things: Some(139),
+                frames: Some(968),
+                sprites: Some(139),
+                sounds: Some(NUM_SOUNDS),
+            },
+            Complevel::Mbf => Limits {
+                things: Some(146),
+                frames: Some(1089),
+                sprites: Some(144),
+                sounds: Some(114),
+            },
+            Complevel::Mbf21 => Limits {
+                things: None,
+                frames: None,
+                sprites: None,
+                sounds: None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Complevel {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Complevel::Vanilla => "vanilla",
+            Complevel::Boom => "boom",
+            Complevel::Mbf => "mbf",
+            Complevel::Mbf21 => "mbf21",
+        };
+        write!(f, "{} ({})", name, self.number())
+    }
+}
+
+impl FromStr for Complevel {
+    type Err = crate::Error;
+
+    /// Accepts either a name or a complevel number.
+    #[inline]
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "vanilla" | "doom2" | "2" | "3" | "4" => Ok(Complevel::Vanilla),
+            "boom" | "9" => Ok(Complevel::Boom),
+            "mbf" | "11" => Ok(Complevel::Mbf),
+            "mbf21" | "21" => Ok(Complevel::Mbf21),
+            _ => Err(crate::Error::StaticMsg(
+                "Complevel must be vanilla, boom, mbf, mbf21 or one of 2, 3, 4, 9, 11, 21",
+            )),
+        }
+    }
+}
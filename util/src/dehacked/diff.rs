@@ -0,0 +1,601 @@
+// Apache 2.0 License
+
+use super::{
+    vanilla::{self, MobjInfo, WeaponInfo, STATES},
+    *,
+};
+use std::io::Write;
+
+/// Doom runs at 35 tics per second.
+const TICRATE: f64 = 35.0;
+
+/// How much of a replaced string to show before cutting it off.
+const MAX_TEXT: usize = 30;
+
+/// What a field's value means, which decides how it is shown.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Kind {
+    Plain,
+    /// A 16.16 fixed point number of units.
+    Fixed,
+    /// Fixed point units per tic for projectiles, but whole units per step for monsters.
+    Speed,
+    /// A number of tics.
+    Tics,
+    Frame,
+    Sound,
+    Sprite,
+    /// A sprite frame letter, with `FF_FULLBRIGHT` for frames that ignore the light level.
+    Subnumber,
+    Ammo,
+    Bits,
+}
+
+/// Gets the value of a field from an entry in one of the vanilla tables.
+type Getter<T> = fn(&T) -> i64;
+
+/// DeHackEd's names for the fields of a table, along with how to get each one from the vanilla table.
+type Fields<T> = &'static [(&'static str, Kind, Getter<T>)];
+
+static THING_FIELDS: Fields<MobjInfo> = &[
+    ("ID #", Kind::Plain, |m| m.doomednum),
+    ("Initial frame", Kind::Frame, |m| state(m.spawnstate)),
+    ("Hit points", Kind::Plain, |m| m.spawnhealth),
+    ("First moving frame", Kind::Frame, |m| state(m.seestate)),
+    ("Alert sound", Kind::Sound, |m| sound(m.seesound)),
+    ("Reaction time", Kind::Plain, |m| m.reactiontime),
+    ("Attack sound", Kind::Sound, |m| sound(m.attacksound)),
+    ("Injury frame", Kind::Frame, |m| state(m.painstate)),
+    ("Pain chance", Kind::Plain, |m| m.painchance),
+    ("Pain sound", Kind::Sound, |m| sound(m.painsound)),
+    ("Close attack frame", Kind::Frame, |m| state(m.meleestate)),
+    ("Far attack frame", Kind::Frame, |m| state(m.missilestate)),
+    ("Death frame", Kind::Frame, |m| state(m.deathstate)),
+    ("Exploding frame", Kind::Frame, |m| state(m.xdeathstate)),
+    ("Death sound", Kind::Sound, |m| sound(m.deathsound)),
+    ("Speed", Kind::Speed, |m| m.speed),
+    ("Width", Kind::Fixed, |m| m.radius),
+    ("Height", Kind::Fixed, |m| m.height),
+    ("Mass", Kind::Plain, |m| m.mass),
+    ("Missile damage", Kind::Plain, |m| m.damage),
+    ("Action sound", Kind::Sound, |m| sound(m.activesound)),
+    ("Bits", Kind::Bits, |m| i64::from(m.flags)),
+    ("Respawn frame", Kind::Frame, |m| state(m.raisestate)),
+];
+
+static FRAME_FIELDS: Fields<vanilla::State> = &[
+    ("Sprite number", Kind::Sprite, |s| sprite(s.sprite)),
+    ("Sprite subnumber", Kind::Subnumber, |s| i64::from(s.frame)),
+    ("Duration", Kind::Tics, |s| i64::from(s.tics)),
+    ("Next frame", Kind::Frame, |s| state(s.next)),
+    ("Unknown 1", Kind::Plain, |_| 0),
+    ("Unknown 2", Kind::Plain, |_| 0),
+];
+
+static WEAPON_FIELDS: Fields<WeaponInfo> = &[
+    ("Ammo type", Kind::Ammo, |w| w.ammo),
+    ("Deselect frame", Kind::Frame, |w| state(w.upstate)),
+    ("Select frame", Kind::Frame, |w| state(w.downstate)),
+    ("Bobbing frame", Kind::Frame, |w| state(w.readystate)),
+    ("Shooting frame", Kind::Frame, |w| state(w.atkstate)),
+    ("Firing frame", Kind::Frame, |w| state(w.flashstate)),
+];
+
+/// One change a patch makes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// The thing, frame and so on that changed, e.g. "Thing 2 (MT_POSSESSED)".
+    pub what: String,
+    pub field: String,
+    /// The value in vanilla Doom II, or nothing if there isn't one, like for new frames or [STRINGS].
+    pub old: String,
+    pub new: String,
+    /// The values in units people think in, like map units or seconds, or which flags changed.
+    pub note: String,
+}
+
+/// Write a table of everything a patch, or a YAML source for one, changes from vanilla Doom II.
+#[inline]
+pub fn diff<W: Write>(path: &Path, mut out: W) -> crate::Result {
+    let patch = Patch::load_or_compile(path)?;
+
+    let mut rows = vec![Change {
+        what: "Changes".to_string(),
+        field: "Field".to_string(),
+        old: "Vanilla".to_string(),
+        new: "Patch".to_string(),
+        note: "Notes".to_string(),
+    }];
+    rows.extend(diff_patch(&patch));
+
+    let width = |column: fn(&Change) -> &String| {
+        rows.iter()
+            .map(|row| column(row).chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let widths = [
+        width(|c| &c.what),
+        width(|c| &c.field),
+        width(|c| &c.old),
+        width(|c| &c.new),
+    ];
+
+    for row in &rows {
+        let line = format!(
+            "{:w0$}  {:w1$}  {:w2$}  {:w3$}  {}",
+            row.what,
+            row.field,
+            row.old,
+            row.new,
+            row.note,
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3]
+        );
+        writeln!(out, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
+
+/// Everything a patch changes, in the order the patch changes it. Fields set to their vanilla values are left out.
+#[inline]
+pub fn diff_patch(patch: &Patch) -> Vec<Change> {
+    let mut changes = vec![];
+    patch
+        .blocks
+        .iter()
+        .for_each(|block| diff_block(block, &mut changes));
+    changes
+}
+
+#[inline]
+fn diff_block(block: &Block, changes: &mut Vec<Change>) {
+    match &block.kind {
+        BlockKind::Thing { number, .. } => {
+            let mobj = get(vanilla::MOBJINFO, *number - 1);
+
+            // projectile speeds are fixed point, so find out if this thing is one
+            let missile = block
+                .fields
+                .iter()
+                .find(|f| f.name.eq_ignore_ascii_case("Bits"))
+                .and_then(|f| parse_bits(&f.value, THING_FLAGS))
+                .unwrap_or_else(|| mobj.map_or(0, |m| m.flags))
+                & MF_MISSILE
+                != 0;
+
+            let what = name("Thing", *number, get(vanilla::THINGS, *number - 1));
+            block.fields.iter().for_each(|field| {
+                let (kind, old) = match lookup(THING_FIELDS, &field.name) {
+                    Some((kind, get)) => (kind, mobj.map(get)),
+                    None => (Kind::Plain, None),
+                };
+                let kind = match (kind, missile) {
+                    (Kind::Speed, true) => Kind::Fixed,
+                    (Kind::Speed, false) => Kind::Plain,
+                    (kind, _) => kind,
+                };
+                changes.extend(change(&what, field, kind, old));
+            });
+        }
+        BlockKind::Frame { number } => {
+            let state = vanilla_state(*number);
+            let what = name("Frame", *number, state.map(|s| &s.name));
+            block.fields.iter().for_each(|field| {
+                let (kind, old) = match lookup(FRAME_FIELDS, &field.name) {
+                    Some((kind, get)) => (kind, state.map(get)),
+                    None => (Kind::Plain, None),
+                };
+                changes.extend(change(&what, field, kind, old));
+            });
+        }
+        BlockKind::Weapon { number, .. } => {
+            let weapon = get(vanilla::WEAPONINFO, *number);
+            let what = name("Weapon", *number, get(vanilla::WEAPONS, *number));
+            block.fields.iter().for_each(|field| {
+                let (kind, old) = match lookup(WEAPON_FIELDS, &field.name) {
+                    Some((kind, get)) => (kind, weapon.map(get)),
+                    None => (Kind::Plain, None),
+                };
+                changes.extend(change(&what, field, kind, old));
+            });
+        }
+        BlockKind::Ammo { number, .. } => {
+            let what = name("Ammo", *number, get(vanilla::AMMO, *number));
+            block.fields.iter().for_each(|field| {
+                let table = if field.name.eq_ignore_ascii_case("Max ammo") {
+                    vanilla::MAX_AMMO
+                } else if field.name.eq_ignore_ascii_case("Per ammo") {
+                    vanilla::CLIP_AMMO
+                } else {
+                    &[]
+                };
+                let old = get(table, *number).copied();
+                changes.extend(change(&what, field, Kind::Plain, old));
+            });
+        }
+        BlockKind::Misc => block.fields.iter().for_each(|field| {
+            let old = vanilla::MISC
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&field.name))
+                .map(|(_, value)| *value);
+            changes.extend(change("Misc", field, Kind::Plain, old));
+        }),
+        BlockKind::Pointer {
+            frame: Some(frame), ..
+        } => block
+            .fields
+            .iter()
+            .filter(|f| f.name.eq_ignore_ascii_case("Codep Frame"))
+            .for_each(|field| {
+                let new = field
+                    .value
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(vanilla_state)
+                    .map_or_else(|| field.value.clone(), |s| action(s.action));
+                changes.extend(codepointer(*frame, new));
+            }),
+        BlockKind::CodePointers => block.fields.iter().for_each(|field| {
+            let frame = field
+                .name
+                .split_whitespace()
+                .nth(1)
+                .and_then(|n| n.parse().ok());
+            if let Some(frame) = frame {
+                changes.extend(codepointer(frame, field.value.clone()));
+            }
+        }),
+        BlockKind::Text { old, new } => changes.push(Change {
+            what: "Text".to_string(),
+            field: String::new(),
+            old: abbreviate(old),
+            new: abbreviate(new),
+            note: format!(
+                "{} -> {} characters",
+                old.chars().count(),
+                new.chars().count()
+            ),
+        }),
+        // there's nothing vanilla to compare everything else to
+        _ => block.fields.iter().for_each(|field| {
+            changes.push(Change {
+                what: block.kind.describe(),
+                field: field.name.clone(),
+                old: String::new(),
+                new: abbreviate(&field.value),
+                note: String::new(),
+            })
+        }),
+    }
+}
+
+/// The change one field makes, unless it sets the field to what it already was.
+#[inline]
+fn change(what: &str, field: &Field, kind: Kind, old: Option<i64>) -> Option<Change> {
+    let new = match field.value.parse::<i64>() {
+        Ok(new) => new,
+        // flags can be given by name
+        Err(_) if kind == Kind::Bits => match parse_bits(&field.value, THING_FLAGS) {
+            Some(bits) => i64::from(bits),
+            None => return Some(unparsed(what, field, old, kind)),
+        },
+        Err(_) => return Some(unparsed(what, field, old, kind)),
+    };
+    if old == Some(new) {
+        return None;
+    }
+
+    let note = match (kind, old) {
+        (Kind::Fixed, Some(old)) => format!("{} -> {} units", units(old), units(new)),
+        (Kind::Fixed, None) => format!("{} units", units(new)),
+        (Kind::Tics, Some(old)) => format!("{} -> {}", seconds(old), seconds(new)),
+        (Kind::Tics, None) => seconds(new),
+        (Kind::Bits, old) => flag_changes(old.unwrap_or(0), new),
+        _ => String::new(),
+    };
+
+    Some(Change {
+        what: what.to_string(),
+        field: field.name.clone(),
+        old: old.map_or_else(String::new, |old| show(kind, old)),
+        new: show(kind, new),
+        note,
+    })
+}
+
+#[inline]
+fn unparsed(what: &str, field: &Field, old: Option<i64>, kind: Kind) -> Change {
+    Change {
+        what: what.to_string(),
+        field: field.name.clone(),
+        old: old.map_or_else(String::new, |old| show(kind, old)),
+        new: field.value.clone(),
+        note: "not a number".to_string(),
+    }
+}
+
+/// A change to the codepointer a frame calls.
+#[inline]
+fn codepointer(frame: i64, new: String) -> Option<Change> {
+    let state = vanilla_state(frame);
+    let old = state.map_or_else(String::new, |s| action(s.action));
+
+    // BEX lets the A_ be left off
+    let same = old.eq_ignore_ascii_case(&new)
+        || old
+            .strip_prefix("A_")
+            .is_some_and(|old| old.eq_ignore_ascii_case(&new));
+    if same {
+        return None;
+    }
+
+    Some(Change {
+        what: name("Frame", frame, state.map(|s| &s.name)),
+        field: "Codepointer".to_string(),
+        old,
+        new,
+        note: String::new(),
+    })
+}
+
+/// A value along with what it refers to, like "287 (S_FIRE7)".
+#[inline]
+fn show(kind: Kind, value: i64) -> String {
+    let table_name = |table: &[&'static str]| {
+        get(table, value).map_or_else(|| value.to_string(), |name| format!("{} ({})", value, name))
+    };
+
+    match kind {
+        Kind::Frame => get(STATES, value)
+            .map_or_else(|| value.to_string(), |s| format!("{} ({})", value, s.name)),
+        Kind::Sound => table_name(vanilla::SOUNDS),
+        Kind::Sprite => table_name(vanilla::SPRITES),
+        Kind::Ammo if value == AMMO_NONE => format!("{} (none)", value),
+        Kind::Ammo => table_name(vanilla::AMMO),
+        Kind::Subnumber => {
+            let letter = (value & !(vanilla::FF_FULLBRIGHT as i64)) as u8;
+            let bright = if value & vanilla::FF_FULLBRIGHT as i64 != 0 {
+                ", bright"
+            } else {
+                ""
+            };
+            match letter {
+                0..=25 => format!("{} ({}{})", value, (b'A' + letter) as char, bright),
+                _ => value.to_string(),
+            }
+        }
+        Kind::Bits => format!("0x{:08X}", value as u32),
+        _ => value.to_string(),
+    }
+}
+
+/// Which flags a Bits change adds and removes, like "+NOGRAVITY -SOLID".
+#[inline]
+fn flag_changes(old: i64, new: i64) -> String {
+    let (old, new) = (old as u32, new as u32);
+    // TRANSLATION is both TRANSLATION1 and TRANSLATION2, which already get their own names
+    THING_FLAGS
+        .iter()
+        .filter(|(_, flag)| flag.count_ones() == 1)
+        .filter_map(|(name, flag)| match (old & flag != 0, new & flag != 0) {
+            (false, true) => Some(format!("+{}", name)),
+            (true, false) => Some(format!("-{}", name)),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A fixed point value in units, without a fraction if it's whole.
+#[inline]
+fn units(value: i64) -> String {
+    if value % FRACUNIT == 0 {
+        (value / FRACUNIT).to_string()
+    } else {
+        format!("{:.2}", value as f64 / FRACUNIT as f64)
+    }
+}
+
+#[inline]
+fn seconds(tics: i64) -> String {
+    if tics < 0 {
+        "forever".to_string()
+    } else {
+        format!("{:.2}s", tics as f64 / TICRATE)
+    }
+}
+
+/// A string shortened to fit in the table, with newlines shown as \n.
+#[inline]
+fn abbreviate(text: &str) -> String {
+    let escaped = text.replace('\n', "\\n");
+    if escaped.chars().count() > MAX_TEXT {
+        format!("{}...", escaped.chars().take(MAX_TEXT).collect::<String>())
+    } else {
+        escaped
+    }
+}
+
+#[inline]
+fn name(kind: &str, number: i64, name: Option<&&str>) -> String {
+    match name {
+        Some(name) if number >= 0 => format!("{} {} ({})", kind, number, name),
+        _ => format!("{} {}", kind, number),
+    }
+}
+
+#[inline]
+fn action(action: Option<&str>) -> String {
+    action.unwrap_or("NULL").to_string()
+}
+
+#[inline]
+fn lookup<T>(table: Fields<T>, field: &str) -> Option<(Kind, Getter<T>)> {
+    table
+        .iter()
+        .find(|(name, _, _)| name.eq_ignore_ascii_case(field))
+        .map(|(_, kind, get)| (*kind, *get))
+}
+
+#[inline]
+fn vanilla_state(number: i64) -> Option<&'static vanilla::State> {
+    get(STATES, number)
+}
+
+/// An entry in a table by DeHackEd number, which might be negative or past the end.
+#[inline]
+fn get<T>(table: &[T], index: i64) -> Option<&T> {
+    if index < 0 {
+        None
+    } else {
+        table.get(index as usize)
+    }
+}
+
+#[inline]
+fn state(name: &str) -> i64 {
+    table_index(STATES.iter().map(|s| s.name), name)
+}
+
+#[inline]
+fn sound(name: &str) -> i64 {
+    table_index(vanilla::SOUNDS.iter().copied(), name)
+}
+
+#[inline]
+fn sprite(name: &str) -> i64 {
+    table_index(vanilla::SPRITES.iter().copied(), name)
+}
+
+/// Where a name from the vanilla tables is. The tables are checked against each other by tests, so the name is always
+/// there.
+#[inline]
+fn table_index<I: IntoIterator<Item = &'static str>>(table: I, name: &str) -> i64 {
+    vanilla::find(table, "", name).map_or(-1, |i| i as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(text: &str) -> Vec<(String, String, String, String, String)> {
+        diff_patch(&parse_patch(text))
+            .into_iter()
+            .map(|c| (c.what, c.field, c.old, c.new, c.note))
+            .collect()
+    }
+
+    fn row(
+        what: &str,
+        field: &str,
+        old: &str,
+        new: &str,
+        note: &str,
+    ) -> (String, String, String, String, String) {
+        (
+            what.to_string(),
+            field.to_string(),
+            old.to_string(),
+            new.to_string(),
+            note.to_string(),
+        )
+    }
+
+    #[test]
+    fn things_are_compared_with_vanilla() {
+        let changes = changes(
+            "Doom version = 19\nPatch format = 6\n\n\
+             Thing 2 (Trooper)\nHit points = 45\nReaction time = 8\n\n\
+             Thing 36 (BFG projectile)\nSpeed = 9830400\nWidth = 196608\nMissile damage = 200\n\n\
+             Thing 6 (Revenant)\nSpeed = 20\nBits = 4194310\n",
+        );
+
+        assert_eq!(
+            changes,
+            vec![
+                row("Thing 2 (MT_POSSESSED)", "Hit points", "20", "45", ""),
+                row(
+                    "Thing 36 (MT_BFG)",
+                    "Speed",
+                    "1638400",
+                    "9830400",
+                    "25 -> 150 units"
+                ),
+                row(
+                    "Thing 36 (MT_BFG)",
+                    "Width",
+                    "851968",
+                    "196608",
+                    "13 -> 3 units"
+                ),
+                row("Thing 36 (MT_BFG)", "Missile damage", "100", "200", ""),
+                row("Thing 6 (MT_UNDEAD)", "Speed", "10", "20", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn frames_and_codepointers_are_named() {
+        let changes = changes(
+            "Frame 85\nSprite subnumber = 0\nDuration = 0\nNext frame = 287\n\n\
+             Pointer 65 (Frame 85)\nCodep Frame = 14\n\n\
+             [CODEPTR]\nFRAME 86 = FireBFG\nFRAME 87 = A_Mushroom\n",
+        );
+
+        assert_eq!(
+            changes,
+            vec![
+                row(
+                    "Frame 85 (S_BFG2)",
+                    "Sprite subnumber",
+                    "1 (B)",
+                    "0 (A)",
+                    ""
+                ),
+                row("Frame 85 (S_BFG2)", "Duration", "10", "0", "0.29s -> 0.00s"),
+                row(
+                    "Frame 85 (S_BFG2)",
+                    "Next frame",
+                    "86 (S_BFG3)",
+                    "287 (S_FIRE7)",
+                    ""
+                ),
+                row(
+                    "Frame 85 (S_BFG2)",
+                    "Codepointer",
+                    "A_GunFlash",
+                    "A_FirePistol",
+                    ""
+                ),
+                row(
+                    "Frame 87 (S_BFG4)",
+                    "Codepointer",
+                    "A_ReFire",
+                    "A_Mushroom",
+                    ""
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn everything_else() {
+        let changes = changes(
+            "Weapon 6 (BFG 9000)\nAmmo type = 0\n\nMisc 0\nBFG Cells/Shot = 21\n\n\
+             Text 3 3\nIMPBPG\n\n[STRINGS]\nGOTARMOR = Picked up the vest.\n",
+        );
+
+        assert_eq!(
+            changes,
+            vec![
+                row("Weapon 6 (bfg)", "Ammo type", "2 (cell)", "0 (clip)", ""),
+                row("Misc", "BFG Cells/Shot", "40", "21", ""),
+                row("Text", "", "IMP", "BPG", "3 -> 3 characters"),
+                row("[STRINGS]", "GOTARMOR", "", "Picked up the vest.", ""),
+            ]
+        );
+    }
+}
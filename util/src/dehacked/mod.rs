@@ -0,0 +1,349 @@
+// Apache 2.0 License
+
+mod balance;
+mod bex;
+mod check;
+mod compile;
+mod complevel;
+mod diff;
+mod parse;
+mod vanilla;
+
+pub use balance::Balance;
+pub use check::{check_patch, Diagnostic, Severity};
+pub use compile::{compile, compile_source, Source};
+pub use complevel::{Complevel, Limits};
+pub use diff::diff;
+pub use parse::parse_patch;
+
+use crate::error::ResultExt;
+use std::{ffi::OsStr, fs, io::Write, path::Path};
+
+/// Number of things in vanilla Doom II. DeHackEd numbers them from 1.
+pub const NUM_THINGS: i64 = 137;
+/// Number of frames (states) in vanilla Doom II.
+pub const NUM_FRAMES: i64 = 967;
+/// Number of sprite names in vanilla Doom II.
+pub const NUM_SPRITES: i64 = 138;
+/// Number of sound effects in vanilla Doom II, including the empty sound 0.
+pub const NUM_SOUNDS: i64 = 109;
+/// Number of weapons in vanilla Doom II.
+pub const NUM_WEAPONS: i64 = 9;
+/// Number of ammo types in vanilla Doom II. Weapons can also use ammo type 5, which means no ammo.
+pub const NUM_AMMO: i64 = 4;
+/// The ammo type for weapons that don't use any ammo.
+pub const AMMO_NONE: i64 = 5;
+
+/// One unit in Doom's 16.16 fixed point.
+pub const FRACUNIT: i64 = 1 << 16;
+
+/// The names of the thing flags that can be used in a "Bits" field, along with their values.
+pub static THING_FLAGS: &[(&str, u32)] = &[
+    ("SPECIAL", 0x0000_0001),
+    ("SOLID", 0x0000_0002),
+    ("SHOOTABLE", 0x0000_0004),
+    ("NOSECTOR", 0x0000_0008),
+    ("NOBLOCKMAP", 0x0000_0010),
+    ("AMBUSH", 0x0000_0020),
+    ("JUSTHIT", 0x0000_0040),
+    ("JUSTATTACKED", 0x0000_0080),
+    ("SPAWNCEILING", 0x0000_0100),
+    ("NOGRAVITY", 0x0000_0200),
+    ("DROPOFF", 0x0000_0400),
+    ("PICKUP", 0x0000_0800),
+    ("NOCLIP", 0x0000_1000),
+    ("SLIDE", 0x0000_2000),
+    ("FLOAT", 0x0000_4000),
+    ("TELEPORT", 0x0000_8000),
+    ("MISSILE", 0x0001_0000),
+    ("DROPPED", 0x0002_0000),
+    ("SHADOW", 0x0004_0000),
+    ("NOBLOOD", 0x0008_0000),
+    ("CORPSE", 0x0010_0000),
+    ("INFLOAT", 0x0020_0000),
+    ("COUNTKILL", 0x0040_0000),
+    ("COUNTITEM", 0x0080_0000),
+    ("SKULLFLY", 0x0100_0000),
+    ("NOTDMATCH", 0x0200_0000),
+    ("TRANSLATION1", 0x0400_0000),
+    ("TRANSLATION2", 0x0800_0000),
+    ("TRANSLATION", 0x0C00_0000),
+    ("TOUCHY", 0x1000_0000),
+    ("BOUNCES", 0x2000_0000),
+    ("FRIEND", 0x4000_0000),
+    ("TRANSLUCENT", 0x8000_0000),
+];
+
+/// Thing flags that Boom added.
+pub const BOOM_THING_FLAGS: u32 = 0x8000_0000;
+/// Thing flags that MBF added.
+pub const MBF_THING_FLAGS: u32 = 0x7000_0000;
+
+/// The names of the thing flags that can be used in an "MBF21 Bits" field.
+pub static MBF21_THING_FLAGS: &[(&str, u32)] = &[
+    ("LOGRAV", 0x0000_0001),
+    ("SHORTMRANGE", 0x0000_0002),
+    ("DMGIGNORED", 0x0000_0004),
+    ("NORADIUSDMG", 0x0000_0008),
+    ("FORCERADIUSDMG", 0x0000_0010),
+    ("HIGHERMPROB", 0x0000_0020),
+    ("RANGEHALF", 0x0000_0040),
+    ("NOTHRESHOLD", 0x0000_0080),
+    ("LONGMELEE", 0x0000_0100),
+    ("BOSS", 0x0000_0200),
+    ("MAP07BOSS1", 0x0000_0400),
+    ("MAP07BOSS2", 0x0000_0800),
+    ("E1M8BOSS", 0x0000_1000),
+    ("E2M8BOSS", 0x0000_2000),
+    ("E3M8BOSS", 0x0000_4000),
+    ("E4M6BOSS", 0x0000_8000),
+    ("E4M8BOSS", 0x0001_0000),
+    ("RIP", 0x0002_0000),
+    ("FULLVOLSOUNDS", 0x0004_0000),
+];
+
+/// The names of the frame flags that can be used in an "MBF21 Bits" field.
+pub static MBF21_FRAME_FLAGS: &[(&str, u32)] = &[("SKILL5FAST", 0x0000_0001)];
+
+/// The names of the weapon flags that can be used in an "MBF21 Bits" field.
+pub static MBF21_WEAPON_FLAGS: &[(&str, u32)] = &[
+    ("NOTHRUST", 0x0000_0001),
+    ("SILENT", 0x0000_0002),
+    ("NOAUTOFIRE", 0x0000_0004),
+    ("FLEEMELEE", 0x0000_0008),
+    ("AUTOSWITCHFROM", 0x0000_0010),
+    ("NOAUTOSWITCHTO", 0x0000_0020),
+];
+
+/// The flag that makes a thing a projectile, which changes the meaning of its speed.
+pub const MF_MISSILE: u32 = 0x0001_0000;
+
+/// Things that are projectiles in vanilla Doom II, by DeHackEd number.
+pub static VANILLA_MISSILES: &[i64] = &[7, 10, 17, 29, 32, 33, 34, 35, 36, 37];
+
+/// A parsed DeHackEd patch.
+#[derive(Debug, Default, Clone)]
+pub struct Patch {
+    pub doom_version: Option<i64>,
+    pub patch_format: Option<i64>,
+    pub blocks: Vec<Block>,
+    /// Lines that weren't part of any block, along with their line numbers.
+    pub unparsed: Vec<(usize, String)>,
+}
+
+/// A block of changes to one thing, frame, weapon and so on.
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub kind: BlockKind,
+    /// The line the block starts on.
+    pub line: usize,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockKind {
+    Thing {
+        number: i64,
+        name: Option<String>,
+    },
+    Frame {
+        number: i64,
+    },
+    Weapon {
+        number: i64,
+        name: Option<String>,
+    },
+    Ammo {
+        number: i64,
+        name: Option<String>,
+    },
+    Sound {
+        number: i64,
+    },
+    Sprite {
+        number: i64,
+    },
+    /// A codepointer change. The frame in parentheses is the one that gets the new action.
+    Pointer {
+        number: i64,
+        frame: Option<i64>,
+    },
+    Text {
+        old: String,
+        new: String,
+    },
+    Cheat,
+    Misc,
+    /// A BEX [CODEPTR] section, which gives frames codepointers by name.
+    CodePointers,
+    /// A BEX [STRINGS] section, which replaces strings by mnemonic.
+    Strings,
+    /// A BEX [PARS] section, with par times for each map.
+    Pars,
+    /// An MBF [HELPER] section, which picks the thing spawned for helper dogs.
+    Helper,
+    /// A BEX [SPRITES] section, which renames sprites.
+    SpriteNames,
+    /// A BEX [SOUNDS] section, which renames sound lumps.
+    SoundNames,
+    /// A BEX [MUSIC] section, which renames music lumps.
+    MusicNames,
+    /// A BEX INCLUDE of another patch, relative to this one. NOTEXT leaves out the other patch's text changes.
+    Include {
+        path: String,
+        notext: bool,
+    },
+}
+
+/// A "Name = value" line inside a block.
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub value: String,
+    pub line: usize,
+}
+
+impl Patch {
+    /// Load and parse a patch file.
+    #[inline]
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let bytes = fs::read(path).with_file(path)?;
+        Ok(parse_patch(&String::from_utf8_lossy(&bytes)))
+    }
+
+    /// Load a patch, or compile a YAML source into one first. Sources are compiled for the most permissive complevel,
+    /// since the patch is only being looked at.
+    #[inline]
+    pub fn load_or_compile(path: &Path) -> crate::Result<Self> {
+        let yaml = path
+            .extension()
+            .and_then(OsStr::to_str)
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml"));
+        if yaml {
+            let source = Source::load(path)?;
+            Ok(parse_patch(
+                &compile_source(&source, Complevel::Mbf21).with_file(path)?,
+            ))
+        } else {
+            Self::load(path)
+        }
+    }
+}
+
+impl BlockKind {
+    /// A description of the block for messages, e.g. "Thing 2 (Trooper)".
+    #[inline]
+    pub fn describe(&self) -> String {
+        match self {
+            BlockKind::Thing { number, name: None } => format!("Thing {}", number),
+            BlockKind::Thing {
+                number,
+                name: Some(name),
+            } => format!("Thing {} ({})", number, name),
+            BlockKind::Frame { number } => format!("Frame {}", number),
+            BlockKind::Weapon { number, name: None } => format!("Weapon {}", number),
+            BlockKind::Weapon {
+                number,
+                name: Some(name),
+            } => format!("Weapon {} ({})", number, name),
+            BlockKind::Ammo { number, name: None } => format!("Ammo {}", number),
+            BlockKind::Ammo {
+                number,
+                name: Some(name),
+            } => format!("Ammo {} ({})", number, name),
+            BlockKind::Sound { number } => format!("Sound {}", number),
+            BlockKind::Sprite { number } => format!("Sprite {}", number),
+            BlockKind::Pointer { number, .. } => format!("Pointer {}", number),
+            BlockKind::Text { old, .. } => format!("Text {:?}", old),
+            BlockKind::Cheat => "Cheat".to_string(),
+            BlockKind::Misc => "Misc".to_string(),
+            BlockKind::CodePointers => "[CODEPTR]".to_string(),
+            BlockKind::Strings => "[STRINGS]".to_string(),
+            BlockKind::Pars => "[PARS]".to_string(),
+            BlockKind::Helper => "[HELPER]".to_string(),
+            BlockKind::SpriteNames => "[SPRITES]".to_string(),
+            BlockKind::SoundNames => "[SOUNDS]".to_string(),
+            BlockKind::MusicNames => "[MUSIC]".to_string(),
+            BlockKind::Include { path, .. } => format!("INCLUDE {}", path),
+        }
+    }
+}
+
+/// Parse a "Bits" value, which is either a number or names from `flags` joined with '+' or '|'.
+#[inline]
+pub fn parse_bits(value: &str, flags: &[(&str, u32)]) -> Option<u32> {
+    if let Ok(bits) = value.parse::<i64>() {
+        return Some(bits as u32);
+    }
+
+    value
+        .split(['+', '|', ','])
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .try_fold(0, |bits, name| {
+            flags
+                .iter()
+                .find(|(flag, _)| flag.eq_ignore_ascii_case(name))
+                .map(|(_, value)| bits | value)
+        })
+}
+
+/// Check a patch file and everything it includes, and write everything that looks wrong to `out`. A YAML source is
+/// compiled first, which is where its warnings are reported. Fails if any problem is an error rather than a warning.
+#[inline]
+pub fn check<W: Write>(path: &Path, complevel: Complevel, mut out: W) -> crate::Result {
+    let patch = Patch::load_or_compile(path)?;
+    let mut reports = vec![(path.to_path_buf(), check_patch(&patch, complevel))];
+
+    // includes are relative to the patch that includes them
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    patch.blocks.iter().for_each(|block| {
+        if let BlockKind::Include { path: include, .. } = &block.kind {
+            let include = dir.join(include);
+            let included = match Patch::load(&include) {
+                Ok(included) => included,
+                Err(e) => {
+                    reports[0].1.push(Diagnostic {
+                        line: block.line,
+                        severity: Severity::Error,
+                        message: format!("could not read {}: {:?}", include.display(), e),
+                    });
+                    return;
+                }
+            };
+
+            // Boom only follows includes in the top level patch
+            let mut diagnostics = check_patch(&included, complevel);
+            included
+                .blocks
+                .iter()
+                .filter(|b| matches!(b.kind, BlockKind::Include { .. }))
+                .for_each(|b| {
+                    diagnostics.push(Diagnostic {
+                        line: b.line,
+                        severity: Severity::Error,
+                        message: "included patches can't include other patches".to_string(),
+                    })
+                });
+            diagnostics.sort_by_key(|d| d.line);
+
+            reports.push((include, diagnostics));
+        }
+    });
+
+    reports.iter().try_for_each(|(path, diagnostics)| {
+        diagnostics
+            .iter()
+            .try_for_each(|d| writeln!(out, "{}:{}", path.display(), d))
+    })?;
+
+    if reports
+        .iter()
+        .flat_map(|(_, diagnostics)| diagnostics)
+        .any(|d| d.severity == Severity::Error)
+    {
+        Err(crate::Error::StaticMsg("DeHackEd patch has errors"))
+    } else {
+        Ok(())
+    }
+}
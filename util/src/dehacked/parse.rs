@@ -0,0 +1,235 @@
+// Apache 2.0 License
+
+use super::{Block, BlockKind, Field, Patch};
+
+/// Parse the text of a DeHackEd patch. Parsing never fails; lines that can't be understood are kept in
+/// `Patch::unparsed` so the checker can report them.
+#[inline]
+pub fn parse_patch(text: &str) -> Patch {
+    // line numbers are kept for every line, so remove carriage returns without joining lines
+    let text = text.replace('\r', "");
+    let mut lines = text.split('\n').enumerate().map(|(i, line)| (i + 1, line));
+    let mut patch = Patch::default();
+
+    while let Some((lineno, line)) = lines.next() {
+        let trimmed = line.trim();
+
+        // a [STRINGS] value ending in a backslash carries on to the next line
+        if let Some(field) = continued_string(&mut patch) {
+            field.value.pop();
+            field.value.push_str(trimmed);
+            continue;
+        }
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        // the first line of a patch identifies it
+        if trimmed.starts_with("Patch File for DeHackEd") {
+            continue;
+        }
+
+        if let Some((name, value)) = split_field(trimmed) {
+            match (name.to_ascii_lowercase().as_str(), patch.blocks.last_mut()) {
+                ("doom version", None) => patch.doom_version = value.parse().ok(),
+                ("patch format", None) => patch.patch_format = value.parse().ok(),
+                (_, Some(block)) => block.fields.push(Field {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                    line: lineno,
+                }),
+                (_, None) => patch.unparsed.push((lineno, line.to_string())),
+            }
+            continue;
+        }
+
+        // par times don't have an equals sign, so they'd otherwise look like block headers
+        if let Some(block) = patch.blocks.last_mut() {
+            if block.kind == BlockKind::Pars {
+                let mut words = trimmed.splitn(2, char::is_whitespace);
+                if let (Some(word), Some(times)) = (words.next(), words.next()) {
+                    if word.eq_ignore_ascii_case("par") {
+                        block.fields.push(Field {
+                            name: word.to_string(),
+                            value: times.trim().to_string(),
+                            line: lineno,
+                        });
+                        continue;
+                    }
+                }
+            }
+        }
+
+        match parse_header(trimmed) {
+            Some(Header::Text(old, new)) => {
+                // text blocks are followed by exactly old + new characters, counting each newline as one
+                let wanted = old + new;
+                let mut body = String::new();
+                let mut last = lineno;
+
+                while body.chars().count() < wanted {
+                    match lines.next() {
+                        Some((next, line)) => {
+                            if last != lineno {
+                                body.push('\n');
+                            }
+                            body.push_str(line);
+                            last = next;
+                        }
+                        None => break,
+                    }
+                }
+
+                let count = body.chars().count();
+                if count < wanted {
+                    patch.unparsed.push((lineno, line.to_string()));
+                    continue;
+                }
+                if count > wanted {
+                    // the new text ended partway through a line
+                    patch
+                        .unparsed
+                        .push((last, body.chars().skip(wanted).collect()));
+                }
+
+                patch.blocks.push(Block {
+                    kind: BlockKind::Text {
+                        old: body.chars().take(old).collect(),
+                        new: body.chars().skip(old).take(new).collect(),
+                    },
+                    line: lineno,
+                    fields: vec![],
+                });
+            }
+            Some(Header::Block(kind)) => patch.blocks.push(Block {
+                kind,
+                line: lineno,
+                fields: vec![],
+            }),
+            None => patch.unparsed.push((lineno, line.to_string())),
+        }
+    }
+
+    patch
+}
+
+/// The last field of a [STRINGS] section, if it is waiting for a continuation line.
+#[inline]
+fn continued_string(patch: &mut Patch) -> Option<&mut Field> {
+    let block = patch.blocks.last_mut()?;
+    if block.kind != BlockKind::Strings {
+        return None;
+    }
+
+    block.fields.last_mut().filter(|f| f.value.ends_with('\\'))
+}
+
+/// Split a "Name = value" line.
+#[inline]
+fn split_field(line: &str) -> Option<(&str, &str)> {
+    let eq = line.find('=')?;
+    let name = line[..eq].trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    Some((name, line[eq + 1..].trim()))
+}
+
+/// The line that starts a block.
+enum Header {
+    Block(BlockKind),
+    /// A text block, with the lengths of the old and new text.
+    Text(usize, usize),
+}
+
+/// Parse the line that starts a block, such as "Thing 2 (Trooper)", "Pointer 65 (Frame 85)" or "[CODEPTR]".
+#[inline]
+fn parse_header(line: &str) -> Option<Header> {
+    let mut words = line.splitn(2, char::is_whitespace);
+    let keyword = words.next()?;
+    let rest = words.next().unwrap_or("").trim();
+
+    // everything after the number is an optional name in parentheses
+    let (number, name) = match rest.find(char::is_whitespace) {
+        Some(space) => {
+            let name = rest[space..].trim();
+            let name = name
+                .strip_prefix('(')
+                .and_then(|n| n.strip_suffix(')'))
+                .unwrap_or(name);
+            (&rest[..space], Some(name.to_string()))
+        }
+        None => (rest, None),
+    };
+
+    match keyword.to_ascii_lowercase().as_str() {
+        "thing" => Some(BlockKind::Thing {
+            number: number.parse().ok()?,
+            name,
+        }),
+        "frame" => Some(BlockKind::Frame {
+            number: number.parse().ok()?,
+        }),
+        "weapon" => Some(BlockKind::Weapon {
+            number: number.parse().ok()?,
+            name,
+        }),
+        "ammo" => Some(BlockKind::Ammo {
+            number: number.parse().ok()?,
+            name,
+        }),
+        "sound" => Some(BlockKind::Sound {
+            number: number.parse().ok()?,
+        }),
+        "sprite" => Some(BlockKind::Sprite {
+            number: number.parse().ok()?,
+        }),
+        "cheat" => Some(BlockKind::Cheat),
+        "misc" => Some(BlockKind::Misc),
+        "pointer" => {
+            // the frame in parentheses is what engines actually use to find the state
+            let frame = name.as_deref().and_then(|name| {
+                let mut words = name.split_whitespace();
+                match (words.next(), words.next()) {
+                    (Some(w), Some(frame)) if w.eq_ignore_ascii_case("frame") => frame.parse().ok(),
+                    _ => None,
+                }
+            });
+            Some(BlockKind::Pointer {
+                number: number.parse().ok()?,
+                frame,
+            })
+        }
+        "[codeptr]" => Some(BlockKind::CodePointers),
+        "[strings]" => Some(BlockKind::Strings),
+        "[pars]" => Some(BlockKind::Pars),
+        "[helper]" => Some(BlockKind::Helper),
+        "[sprites]" => Some(BlockKind::SpriteNames),
+        "[sounds]" => Some(BlockKind::SoundNames),
+        "[music]" => Some(BlockKind::MusicNames),
+        "include" => {
+            let (notext, path) = match rest.split_once(char::is_whitespace) {
+                Some((word, path)) if word.eq_ignore_ascii_case("notext") => (true, path.trim()),
+                _ => (false, rest),
+            };
+            if path.is_empty() {
+                return None;
+            }
+            Some(BlockKind::Include {
+                path: path.to_string(),
+                notext,
+            })
+        }
+        "text" => {
+            let mut lengths = rest.split_whitespace().map(str::parse::<usize>);
+            return match (lengths.next(), lengths.next()) {
+                (Some(Ok(old)), Some(Ok(new))) => Some(Header::Text(old, new)),
+                _ => None,
+            };
+        }
+        _ => None,
+    }
+    .map(Header::Block)
+}
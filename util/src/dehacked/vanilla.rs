@@ -0,0 +1,4915 @@
+// Apache 2.0 License
+
+//! Tables from vanilla Doom II 1.9, in the order the game and DeHackEd number them.
+
+use super::{AMMO_NONE, FRACUNIT, MF_MISSILE};
+
+/// Set in a state's frame number to draw it at full brightness.
+pub const FF_FULLBRIGHT: u32 = 0x8000;
+
+/// One entry in the state table. DeHackEd calls these frames.
+#[derive(Debug)]
+pub struct State {
+    pub name: &'static str,
+    pub sprite: &'static str,
+    /// The sprite frame, counting from 0 for A, with `FF_FULLBRIGHT` set for frames that ignore the light level.
+    pub frame: u32,
+    /// How long the state lasts, or -1 for forever.
+    pub tics: i32,
+    /// The codepointer called when the state is entered.
+    pub action: Option<&'static str>,
+    pub next: &'static str,
+}
+
+#[inline]
+const fn state(
+    name: &'static str,
+    sprite: &'static str,
+    frame: u32,
+    tics: i32,
+    action: Option<&'static str>,
+    next: &'static str,
+) -> State {
+    State {
+        name,
+        sprite,
+        frame,
+        tics,
+        action,
+        next,
+    }
+}
+
+/// Thing types, by their names in the source. DeHackEd numbers these from 1.
+pub static THINGS: &[&str] = &[
+    "MT_PLAYER",
+    "MT_POSSESSED",
+    "MT_SHOTGUY",
+    "MT_VILE",
+    "MT_FIRE",
+    "MT_UNDEAD",
+    "MT_TRACER",
+    "MT_SMOKE",
+    "MT_FATSO",
+    "MT_FATSHOT",
+    "MT_CHAINGUY",
+    "MT_TROOP",
+    "MT_SERGEANT",
+    "MT_SHADOWS",
+    "MT_HEAD",
+    "MT_BRUISER",
+    "MT_BRUISERSHOT",
+    "MT_KNIGHT",
+    "MT_SKULL",
+    "MT_SPIDER",
+    "MT_BABY",
+    "MT_CYBORG",
+    "MT_PAIN",
+    "MT_WOLFSS",
+    "MT_KEEN",
+    "MT_BOSSBRAIN",
+    "MT_BOSSSPIT",
+    "MT_BOSSTARGET",
+    "MT_SPAWNSHOT",
+    "MT_SPAWNFIRE",
+    "MT_BARREL",
+    "MT_TROOPSHOT",
+    "MT_HEADSHOT",
+    "MT_ROCKET",
+    "MT_PLASMA",
+    "MT_BFG",
+    "MT_ARACHPLAZ",
+    "MT_PUFF",
+    "MT_BLOOD",
+    "MT_TFOG",
+    "MT_IFOG",
+    "MT_TELEPORTMAN",
+    "MT_EXTRABFG",
+    "MT_MISC0",
+    "MT_MISC1",
+    "MT_MISC2",
+    "MT_MISC3",
+    "MT_MISC4",
+    "MT_MISC5",
+    "MT_MISC6",
+    "MT_MISC7",
+    "MT_MISC8",
+    "MT_MISC9",
+    "MT_MISC10",
+    "MT_MISC11",
+    "MT_MISC12",
+    "MT_INV",
+    "MT_MISC13",
+    "MT_INS",
+    "MT_MISC14",
+    "MT_MISC15",
+    "MT_MISC16",
+    "MT_MEGA",
+    "MT_CLIP",
+    "MT_MISC17",
+    "MT_MISC18",
+    "MT_MISC19",
+    "MT_MISC20",
+    "MT_MISC21",
+    "MT_MISC22",
+    "MT_MISC23",
+    "MT_MISC24",
+    "MT_MISC25",
+    "MT_CHAINGUN",
+    "MT_MISC26",
+    "MT_MISC27",
+    "MT_MISC28",
+    "MT_SHOTGUN",
+    "MT_SUPERSHOTGUN",
+    "MT_MISC29",
+    "MT_MISC30",
+    "MT_MISC31",
+    "MT_MISC32",
+    "MT_MISC33",
+    "MT_MISC34",
+    "MT_MISC35",
+    "MT_MISC36",
+    "MT_MISC37",
+    "MT_MISC38",
+    "MT_MISC39",
+    "MT_MISC40",
+    "MT_MISC41",
+    "MT_MISC42",
+    "MT_MISC43",
+    "MT_MISC44",
+    "MT_MISC45",
+    "MT_MISC46",
+    "MT_MISC47",
+    "MT_MISC48",
+    "MT_MISC49",
+    "MT_MISC50",
+    "MT_MISC51",
+    "MT_MISC52",
+    "MT_MISC53",
+    "MT_MISC54",
+    "MT_MISC55",
+    "MT_MISC56",
+    "MT_MISC57",
+    "MT_MISC58",
+    "MT_MISC59",
+    "MT_MISC60",
+    "MT_MISC61",
+    "MT_MISC62",
+    "MT_MISC63",
+    "MT_MISC64",
+    "MT_MISC65",
+    "MT_MISC66",
+    "MT_MISC67",
+    "MT_MISC68",
+    "MT_MISC69",
+    "MT_MISC70",
+    "MT_MISC71",
+    "MT_MISC72",
+    "MT_MISC73",
+    "MT_MISC74",
+    "MT_MISC75",
+    "MT_MISC76",
+    "MT_MISC77",
+    "MT_MISC78",
+    "MT_MISC79",
+    "MT_MISC80",
+    "MT_MISC81",
+    "MT_MISC82",
+    "MT_MISC83",
+    "MT_MISC84",
+    "MT_MISC85",
+    "MT_MISC86",
+];
+
+pub static SPRITES: &[&str] = &[
+    "TROO", "SHTG", "PUNG", "PISG", "PISF", "SHTF", "SHT2", "CHGG", "CHGF", "MISG", "MISF", "SAWG",
+    "PLSG", "PLSF", "BFGG", "BFGF", "BLUD", "PUFF", "BAL1", "BAL2", "PLSS", "PLSE", "MISL", "BFS1",
+    "BFE1", "BFE2", "TFOG", "IFOG", "PLAY", "POSS", "SPOS", "VILE", "FIRE", "FATB", "FBXP", "SKEL",
+    "MANF", "FATT", "CPOS", "SARG", "HEAD", "BAL7", "BOSS", "BOS2", "SKUL", "SPID", "BSPI", "APLS",
+    "APBX", "CYBR", "PAIN", "SSWV", "KEEN", "BBRN", "BOSF", "ARM1", "ARM2", "BAR1", "BEXP", "FCAN",
+    "BON1", "BON2", "BKEY", "RKEY", "YKEY", "BSKU", "RSKU", "YSKU", "STIM", "MEDI", "SOUL", "PINV",
+    "PSTR", "PINS", "MEGA", "SUIT", "PMAP", "PVIS", "CLIP", "AMMO", "ROCK", "BROK", "CELL", "CELP",
+    "SHEL", "SBOX", "BPAK", "BFUG", "MGUN", "CSAW", "LAUN", "PLAS", "SHOT", "SGN2", "COLU", "SMT2",
+    "GOR1", "POL2", "POL5", "POL4", "POL3", "POL1", "POL6", "GOR2", "GOR3", "GOR4", "GOR5", "SMIT",
+    "COL1", "COL2", "COL3", "COL4", "CAND", "CBRA", "COL6", "TRE1", "TRE2", "ELEC", "CEYE", "FSKU",
+    "COL5", "TBLU", "TGRN", "TRED", "SMBT", "SMGT", "SMRT", "HDB1", "HDB2", "HDB3", "HDB4", "HDB5",
+    "HDB6", "POB1", "POB2", "BRS1", "TLMP", "TLP2",
+];
+
+/// Sound effects, by their lump names without the DS prefix. Sound 0 is a placeholder for "no sound".
+pub static SOUNDS: &[&str] = &[
+    "none", "pistol", "shotgn", "sgcock", "dshtgn", "dbopn", "dbcls", "dbload", "plasma", "bfg",
+    "sawup", "sawidl", "sawful", "sawhit", "rlaunc", "rxplod", "firsht", "firxpl", "pstart",
+    "pstop", "doropn", "dorcls", "stnmov", "swtchn", "swtchx", "plpain", "dmpain", "popain",
+    "vipain", "mnpain", "pepain", "slop", "itemup", "wpnup", "oof", "telept", "posit1", "posit2",
+    "posit3", "bgsit1", "bgsit2", "sgtsit", "cacsit", "brssit", "cybsit", "spisit", "bspsit",
+    "kntsit", "vilsit", "mansit", "pesit", "sklatk", "sgtatk", "skepch", "vilatk", "claw",
+    "skeswg", "pldeth", "pdiehi", "podth1", "podth2", "podth3", "bgdth1", "bgdth2", "sgtdth",
+    "cacdth", "skldth", "brsdth", "cybdth", "spidth", "bspdth", "vildth", "kntdth", "pedth",
+    "skedth", "posact", "bgact", "dmact", "bspact", "bspwlk", "vilact", "noway", "barexp", "punch",
+    "hoof", "metal", "chgun", "tink", "bdopn", "bdcls", "itmbk", "flame", "flamst", "getpow",
+    "bospit", "boscub", "bossit", "bospn", "bosdth", "manatk", "mandth", "sssit", "ssdth",
+    "keenpn", "keendt", "skeact", "skesit", "skeatk", "radio",
+];
+
+pub static WEAPONS: &[&str] = &[
+    "fist",
+    "pistol",
+    "shotgun",
+    "chaingun",
+    "missile",
+    "plasma",
+    "bfg",
+    "chainsaw",
+    "supershotgun",
+];
+
+/// Ammo types. Weapons that don't use ammo have type `AMMO_NONE` instead.
+pub static AMMO: &[&str] = &["clip", "shell", "cell", "misl"];
+
+pub static STATES: &[State] = &[
+    state("S_NULL", "TROO", 0, -1, None, "S_NULL"),
+    state("S_LIGHTDONE", "SHTG", 4, 0, Some("A_Light0"), "S_NULL"),
+    state("S_PUNCH", "PUNG", 0, 1, Some("A_WeaponReady"), "S_PUNCH"),
+    state("S_PUNCHDOWN", "PUNG", 0, 1, Some("A_Lower"), "S_PUNCHDOWN"),
+    state("S_PUNCHUP", "PUNG", 0, 1, Some("A_Raise"), "S_PUNCHUP"),
+    state("S_PUNCH1", "PUNG", 1, 4, None, "S_PUNCH2"),
+    state("S_PUNCH2", "PUNG", 2, 4, Some("A_Punch"), "S_PUNCH3"),
+    state("S_PUNCH3", "PUNG", 3, 5, None, "S_PUNCH4"),
+    state("S_PUNCH4", "PUNG", 2, 4, None, "S_PUNCH5"),
+    state("S_PUNCH5", "PUNG", 1, 5, Some("A_ReFire"), "S_PUNCH"),
+    state("S_PISTOL", "PISG", 0, 1, Some("A_WeaponReady"), "S_PISTOL"),
+    state(
+        "S_PISTOLDOWN",
+        "PISG",
+        0,
+        1,
+        Some("A_Lower"),
+        "S_PISTOLDOWN",
+    ),
+    state("S_PISTOLUP", "PISG", 0, 1, Some("A_Raise"), "S_PISTOLUP"),
+    state("S_PISTOL1", "PISG", 0, 4, None, "S_PISTOL2"),
+    state("S_PISTOL2", "PISG", 1, 6, Some("A_FirePistol"), "S_PISTOL3"),
+    state("S_PISTOL3", "PISG", 2, 4, None, "S_PISTOL4"),
+    state("S_PISTOL4", "PISG", 1, 5, Some("A_ReFire"), "S_PISTOL"),
+    state(
+        "S_PISTOLFLASH",
+        "PISF",
+        FF_FULLBRIGHT,
+        7,
+        Some("A_Light1"),
+        "S_LIGHTDONE",
+    ),
+    state("S_SGUN", "SHTG", 0, 1, Some("A_WeaponReady"), "S_SGUN"),
+    state("S_SGUNDOWN", "SHTG", 0, 1, Some("A_Lower"), "S_SGUNDOWN"),
+    state("S_SGUNUP", "SHTG", 0, 1, Some("A_Raise"), "S_SGUNUP"),
+    state("S_SGUN1", "SHTG", 0, 3, None, "S_SGUN2"),
+    state("S_SGUN2", "SHTG", 0, 7, Some("A_FireShotgun"), "S_SGUN3"),
+    state("S_SGUN3", "SHTG", 1, 5, None, "S_SGUN4"),
+    state("S_SGUN4", "SHTG", 2, 5, None, "S_SGUN5"),
+    state("S_SGUN5", "SHTG", 3, 4, None, "S_SGUN6"),
+    state("S_SGUN6", "SHTG", 2, 5, None, "S_SGUN7"),
+    state("S_SGUN7", "SHTG", 1, 5, None, "S_SGUN8"),
+    state("S_SGUN8", "SHTG", 0, 3, None, "S_SGUN9"),
+    state("S_SGUN9", "SHTG", 0, 7, Some("A_ReFire"), "S_SGUN"),
+    state(
+        "S_SGUNFLASH1",
+        "SHTF",
+        FF_FULLBRIGHT,
+        4,
+        Some("A_Light1"),
+        "S_SGUNFLASH2",
+    ),
+    state(
+        "S_SGUNFLASH2",
+        "SHTF",
+        FF_FULLBRIGHT | 1,
+        3,
+        Some("A_Light2"),
+        "S_LIGHTDONE",
+    ),
+    state("S_DSGUN", "SHT2", 0, 1, Some("A_WeaponReady"), "S_DSGUN"),
+    state("S_DSGUNDOWN", "SHT2", 0, 1, Some("A_Lower"), "S_DSGUNDOWN"),
+    state("S_DSGUNUP", "SHT2", 0, 1, Some("A_Raise"), "S_DSGUNUP"),
+    state("S_DSGUN1", "SHT2", 0, 3, None, "S_DSGUN2"),
+    state("S_DSGUN2", "SHT2", 0, 7, Some("A_FireShotgun2"), "S_DSGUN3"),
+    state("S_DSGUN3", "SHT2", 1, 7, None, "S_DSGUN4"),
+    state("S_DSGUN4", "SHT2", 2, 7, Some("A_CheckReload"), "S_DSGUN5"),
+    state("S_DSGUN5", "SHT2", 3, 7, Some("A_OpenShotgun2"), "S_DSGUN6"),
+    state("S_DSGUN6", "SHT2", 4, 7, None, "S_DSGUN7"),
+    state("S_DSGUN7", "SHT2", 5, 7, Some("A_LoadShotgun2"), "S_DSGUN8"),
+    state("S_DSGUN8", "SHT2", 6, 6, None, "S_DSGUN9"),
+    state(
+        "S_DSGUN9",
+        "SHT2",
+        7,
+        6,
+        Some("A_CloseShotgun2"),
+        "S_DSGUN10",
+    ),
+    state("S_DSGUN10", "SHT2", 0, 5, Some("A_ReFire"), "S_DSGUN"),
+    state("S_DSNR1", "SHT2", 1, 7, None, "S_DSNR2"),
+    state("S_DSNR2", "SHT2", 0, 3, None, "S_DSGUNDOWN"),
+    state(
+        "S_DSGUNFLASH1",
+        "SHT2",
+        FF_FULLBRIGHT | 8,
+        5,
+        Some("A_Light1"),
+        "S_DSGUNFLASH2",
+    ),
+    state(
+        "S_DSGUNFLASH2",
+        "SHT2",
+        FF_FULLBRIGHT | 9,
+        4,
+        Some("A_Light2"),
+        "S_LIGHTDONE",
+    ),
+    state("S_CHAIN", "CHGG", 0, 1, Some("A_WeaponReady"), "S_CHAIN"),
+    state("S_CHAINDOWN", "CHGG", 0, 1, Some("A_Lower"), "S_CHAINDOWN"),
+    state("S_CHAINUP", "CHGG", 0, 1, Some("A_Raise"), "S_CHAINUP"),
+    state("S_CHAIN1", "CHGG", 0, 4, Some("A_FireCGun"), "S_CHAIN2"),
+    state("S_CHAIN2", "CHGG", 1, 4, Some("A_FireCGun"), "S_CHAIN3"),
+    state("S_CHAIN3", "CHGG", 1, 0, Some("A_ReFire"), "S_CHAIN"),
+    state(
+        "S_CHAINFLASH1",
+        "CHGF",
+        FF_FULLBRIGHT,
+        5,
+        Some("A_Light1"),
+        "S_LIGHTDONE",
+    ),
+    state(
+        "S_CHAINFLASH2",
+        "CHGF",
+        FF_FULLBRIGHT | 1,
+        5,
+        Some("A_Light2"),
+        "S_LIGHTDONE",
+    ),
+    state(
+        "S_MISSILE",
+        "MISG",
+        0,
+        1,
+        Some("A_WeaponReady"),
+        "S_MISSILE",
+    ),
+    state(
+        "S_MISSILEDOWN",
+        "MISG",
+        0,
+        1,
+        Some("A_Lower"),
+        "S_MISSILEDOWN",
+    ),
+    state("S_MISSILEUP", "MISG", 0, 1, Some("A_Raise"), "S_MISSILEUP"),
+    state("S_MISSILE1", "MISG", 1, 8, Some("A_GunFlash"), "S_MISSILE2"),
+    state(
+        "S_MISSILE2",
+        "MISG",
+        1,
+        12,
+        Some("A_FireMissile"),
+        "S_MISSILE3",
+    ),
+    state("S_MISSILE3", "MISG", 1, 0, Some("A_ReFire"), "S_MISSILE"),
+    state(
+        "S_MISSILEFLASH1",
+        "MISF",
+        FF_FULLBRIGHT,
+        3,
+        Some("A_Light1"),
+        "S_MISSILEFLASH2",
+    ),
+    state(
+        "S_MISSILEFLASH2",
+        "MISF",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_MISSILEFLASH3",
+    ),
+    state(
+        "S_MISSILEFLASH3",
+        "MISF",
+        FF_FULLBRIGHT | 2,
+        4,
+        Some("A_Light2"),
+        "S_MISSILEFLASH4",
+    ),
+    state(
+        "S_MISSILEFLASH4",
+        "MISF",
+        FF_FULLBRIGHT | 3,
+        4,
+        Some("A_Light2"),
+        "S_LIGHTDONE",
+    ),
+    state("S_SAW", "SAWG", 2, 4, Some("A_WeaponReady"), "S_SAWB"),
+    state("S_SAWB", "SAWG", 3, 4, Some("A_WeaponReady"), "S_SAW"),
+    state("S_SAWDOWN", "SAWG", 2, 1, Some("A_Lower"), "S_SAWDOWN"),
+    state("S_SAWUP", "SAWG", 2, 1, Some("A_Raise"), "S_SAWUP"),
+    state("S_SAW1", "SAWG", 0, 4, Some("A_Saw"), "S_SAW2"),
+    state("S_SAW2", "SAWG", 1, 4, Some("A_Saw"), "S_SAW3"),
+    state("S_SAW3", "SAWG", 1, 0, Some("A_ReFire"), "S_SAW"),
+    state("S_PLASMA", "PLSG", 0, 1, Some("A_WeaponReady"), "S_PLASMA"),
+    state(
+        "S_PLASMADOWN",
+        "PLSG",
+        0,
+        1,
+        Some("A_Lower"),
+        "S_PLASMADOWN",
+    ),
+    state("S_PLASMAUP", "PLSG", 0, 1, Some("A_Raise"), "S_PLASMAUP"),
+    state("S_PLASMA1", "PLSG", 0, 3, Some("A_FirePlasma"), "S_PLASMA2"),
+    state("S_PLASMA2", "PLSG", 1, 20, Some("A_ReFire"), "S_PLASMA"),
+    state(
+        "S_PLASMAFLASH1",
+        "PLSF",
+        FF_FULLBRIGHT,
+        4,
+        Some("A_Light1"),
+        "S_LIGHTDONE",
+    ),
+    state(
+        "S_PLASMAFLASH2",
+        "PLSF",
+        FF_FULLBRIGHT | 1,
+        4,
+        Some("A_Light1"),
+        "S_LIGHTDONE",
+    ),
+    state("S_BFG", "BFGG", 0, 1, Some("A_WeaponReady"), "S_BFG"),
+    state("S_BFGDOWN", "BFGG", 0, 1, Some("A_Lower"), "S_BFGDOWN"),
+    state("S_BFGUP", "BFGG", 0, 1, Some("A_Raise"), "S_BFGUP"),
+    state("S_BFG1", "BFGG", 0, 20, Some("A_BFGsound"), "S_BFG2"),
+    state("S_BFG2", "BFGG", 1, 10, Some("A_GunFlash"), "S_BFG3"),
+    state("S_BFG3", "BFGG", 1, 10, Some("A_FireBFG"), "S_BFG4"),
+    state("S_BFG4", "BFGG", 1, 20, Some("A_ReFire"), "S_BFG"),
+    state(
+        "S_BFGFLASH1",
+        "BFGF",
+        FF_FULLBRIGHT,
+        11,
+        Some("A_Light1"),
+        "S_BFGFLASH2",
+    ),
+    state(
+        "S_BFGFLASH2",
+        "BFGF",
+        FF_FULLBRIGHT | 1,
+        6,
+        Some("A_Light2"),
+        "S_LIGHTDONE",
+    ),
+    state("S_BLOOD1", "BLUD", 2, 8, None, "S_BLOOD2"),
+    state("S_BLOOD2", "BLUD", 1, 8, None, "S_BLOOD3"),
+    state("S_BLOOD3", "BLUD", 0, 8, None, "S_NULL"),
+    state("S_PUFF1", "PUFF", FF_FULLBRIGHT, 4, None, "S_PUFF2"),
+    state("S_PUFF2", "PUFF", 1, 4, None, "S_PUFF3"),
+    state("S_PUFF3", "PUFF", 2, 4, None, "S_PUFF4"),
+    state("S_PUFF4", "PUFF", 3, 4, None, "S_NULL"),
+    state("S_TBALL1", "BAL1", FF_FULLBRIGHT, 4, None, "S_TBALL2"),
+    state("S_TBALL2", "BAL1", FF_FULLBRIGHT | 1, 4, None, "S_TBALL1"),
+    state("S_TBALLX1", "BAL1", FF_FULLBRIGHT | 2, 6, None, "S_TBALLX2"),
+    state("S_TBALLX2", "BAL1", FF_FULLBRIGHT | 3, 6, None, "S_TBALLX3"),
+    state("S_TBALLX3", "BAL1", FF_FULLBRIGHT | 4, 6, None, "S_NULL"),
+    state("S_RBALL1", "BAL2", FF_FULLBRIGHT, 4, None, "S_RBALL2"),
+    state("S_RBALL2", "BAL2", FF_FULLBRIGHT | 1, 4, None, "S_RBALL1"),
+    state("S_RBALLX1", "BAL2", FF_FULLBRIGHT | 2, 6, None, "S_RBALLX2"),
+    state("S_RBALLX2", "BAL2", FF_FULLBRIGHT | 3, 6, None, "S_RBALLX3"),
+    state("S_RBALLX3", "BAL2", FF_FULLBRIGHT | 4, 6, None, "S_NULL"),
+    state("S_PLASBALL", "PLSS", FF_FULLBRIGHT, 6, None, "S_PLASBALL2"),
+    state(
+        "S_PLASBALL2",
+        "PLSS",
+        FF_FULLBRIGHT | 1,
+        6,
+        None,
+        "S_PLASBALL",
+    ),
+    state("S_PLASEXP", "PLSE", FF_FULLBRIGHT, 4, None, "S_PLASEXP2"),
+    state(
+        "S_PLASEXP2",
+        "PLSE",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_PLASEXP3",
+    ),
+    state(
+        "S_PLASEXP3",
+        "PLSE",
+        FF_FULLBRIGHT | 2,
+        4,
+        None,
+        "S_PLASEXP4",
+    ),
+    state(
+        "S_PLASEXP4",
+        "PLSE",
+        FF_FULLBRIGHT | 3,
+        4,
+        None,
+        "S_PLASEXP5",
+    ),
+    state("S_PLASEXP5", "PLSE", FF_FULLBRIGHT | 4, 4, None, "S_NULL"),
+    state("S_ROCKET", "MISL", FF_FULLBRIGHT, 1, None, "S_ROCKET"),
+    state("S_BFGSHOT", "BFS1", FF_FULLBRIGHT, 4, None, "S_BFGSHOT2"),
+    state(
+        "S_BFGSHOT2",
+        "BFS1",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_BFGSHOT",
+    ),
+    state("S_BFGLAND", "BFE1", FF_FULLBRIGHT, 8, None, "S_BFGLAND2"),
+    state(
+        "S_BFGLAND2",
+        "BFE1",
+        FF_FULLBRIGHT | 1,
+        8,
+        None,
+        "S_BFGLAND3",
+    ),
+    state(
+        "S_BFGLAND3",
+        "BFE1",
+        FF_FULLBRIGHT | 2,
+        8,
+        Some("A_BFGSpray"),
+        "S_BFGLAND4",
+    ),
+    state(
+        "S_BFGLAND4",
+        "BFE1",
+        FF_FULLBRIGHT | 3,
+        8,
+        None,
+        "S_BFGLAND5",
+    ),
+    state(
+        "S_BFGLAND5",
+        "BFE1",
+        FF_FULLBRIGHT | 4,
+        8,
+        None,
+        "S_BFGLAND6",
+    ),
+    state("S_BFGLAND6", "BFE1", FF_FULLBRIGHT | 5, 8, None, "S_NULL"),
+    state("S_BFGEXP", "BFE2", FF_FULLBRIGHT, 8, None, "S_BFGEXP2"),
+    state("S_BFGEXP2", "BFE2", FF_FULLBRIGHT | 1, 8, None, "S_BFGEXP3"),
+    state("S_BFGEXP3", "BFE2", FF_FULLBRIGHT | 2, 8, None, "S_BFGEXP4"),
+    state("S_BFGEXP4", "BFE2", FF_FULLBRIGHT | 3, 8, None, "S_NULL"),
+    state(
+        "S_EXPLODE1",
+        "MISL",
+        FF_FULLBRIGHT | 1,
+        8,
+        Some("A_Explode"),
+        "S_EXPLODE2",
+    ),
+    state(
+        "S_EXPLODE2",
+        "MISL",
+        FF_FULLBRIGHT | 2,
+        6,
+        None,
+        "S_EXPLODE3",
+    ),
+    state("S_EXPLODE3", "MISL", FF_FULLBRIGHT | 3, 4, None, "S_NULL"),
+    state("S_TFOG", "TFOG", FF_FULLBRIGHT, 6, None, "S_TFOG01"),
+    state("S_TFOG01", "TFOG", FF_FULLBRIGHT | 1, 6, None, "S_TFOG02"),
+    state("S_TFOG02", "TFOG", FF_FULLBRIGHT, 6, None, "S_TFOG2"),
+    state("S_TFOG2", "TFOG", FF_FULLBRIGHT | 1, 6, None, "S_TFOG3"),
+    state("S_TFOG3", "TFOG", FF_FULLBRIGHT | 2, 6, None, "S_TFOG4"),
+    state("S_TFOG4", "TFOG", FF_FULLBRIGHT | 3, 6, None, "S_TFOG5"),
+    state("S_TFOG5", "TFOG", FF_FULLBRIGHT | 4, 6, None, "S_TFOG6"),
+    state("S_TFOG6", "TFOG", FF_FULLBRIGHT | 5, 6, None, "S_TFOG7"),
+    state("S_TFOG7", "TFOG", FF_FULLBRIGHT | 6, 6, None, "S_TFOG8"),
+    state("S_TFOG8", "TFOG", FF_FULLBRIGHT | 7, 6, None, "S_TFOG9"),
+    state("S_TFOG9", "TFOG", FF_FULLBRIGHT | 8, 6, None, "S_TFOG10"),
+    state("S_TFOG10", "TFOG", FF_FULLBRIGHT | 9, 6, None, "S_NULL"),
+    state("S_IFOG", "IFOG", FF_FULLBRIGHT, 6, None, "S_IFOG01"),
+    state("S_IFOG01", "IFOG", FF_FULLBRIGHT | 1, 6, None, "S_IFOG02"),
+    state("S_IFOG02", "IFOG", FF_FULLBRIGHT, 6, None, "S_IFOG2"),
+    state("S_IFOG2", "IFOG", FF_FULLBRIGHT | 1, 6, None, "S_IFOG3"),
+    state("S_IFOG3", "IFOG", FF_FULLBRIGHT | 2, 6, None, "S_IFOG4"),
+    state("S_IFOG4", "IFOG", FF_FULLBRIGHT | 3, 6, None, "S_IFOG5"),
+    state("S_IFOG5", "IFOG", FF_FULLBRIGHT | 4, 6, None, "S_NULL"),
+    state("S_PLAY", "PLAY", 0, -1, None, "S_NULL"),
+    state("S_PLAY_RUN1", "PLAY", 0, 4, None, "S_PLAY_RUN2"),
+    state("S_PLAY_RUN2", "PLAY", 1, 4, None, "S_PLAY_RUN3"),
+    state("S_PLAY_RUN3", "PLAY", 2, 4, None, "S_PLAY_RUN4"),
+    state("S_PLAY_RUN4", "PLAY", 3, 4, None, "S_PLAY_RUN1"),
+    state("S_PLAY_ATK1", "PLAY", 4, 12, None, "S_PLAY"),
+    state(
+        "S_PLAY_ATK2",
+        "PLAY",
+        FF_FULLBRIGHT | 5,
+        6,
+        None,
+        "S_PLAY_ATK1",
+    ),
+    state("S_PLAY_PAIN", "PLAY", 6, 4, None, "S_PLAY_PAIN2"),
+    state("S_PLAY_PAIN2", "PLAY", 6, 4, Some("A_Pain"), "S_PLAY"),
+    state("S_PLAY_DIE1", "PLAY", 7, 10, None, "S_PLAY_DIE2"),
+    state(
+        "S_PLAY_DIE2",
+        "PLAY",
+        8,
+        10,
+        Some("A_PlayerScream"),
+        "S_PLAY_DIE3",
+    ),
+    state("S_PLAY_DIE3", "PLAY", 9, 10, Some("A_Fall"), "S_PLAY_DIE4"),
+    state("S_PLAY_DIE4", "PLAY", 10, 10, None, "S_PLAY_DIE5"),
+    state("S_PLAY_DIE5", "PLAY", 11, 10, None, "S_PLAY_DIE6"),
+    state("S_PLAY_DIE6", "PLAY", 12, 10, None, "S_PLAY_DIE7"),
+    state("S_PLAY_DIE7", "PLAY", 13, -1, None, "S_NULL"),
+    state("S_PLAY_XDIE1", "PLAY", 14, 5, None, "S_PLAY_XDIE2"),
+    state(
+        "S_PLAY_XDIE2",
+        "PLAY",
+        15,
+        5,
+        Some("A_XScream"),
+        "S_PLAY_XDIE3",
+    ),
+    state(
+        "S_PLAY_XDIE3",
+        "PLAY",
+        16,
+        5,
+        Some("A_Fall"),
+        "S_PLAY_XDIE4",
+    ),
+    state("S_PLAY_XDIE4", "PLAY", 17, 5, None, "S_PLAY_XDIE5"),
+    state("S_PLAY_XDIE5", "PLAY", 18, 5, None, "S_PLAY_XDIE6"),
+    state("S_PLAY_XDIE6", "PLAY", 19, 5, None, "S_PLAY_XDIE7"),
+    state("S_PLAY_XDIE7", "PLAY", 20, 5, None, "S_PLAY_XDIE8"),
+    state("S_PLAY_XDIE8", "PLAY", 21, 5, None, "S_PLAY_XDIE9"),
+    state("S_PLAY_XDIE9", "PLAY", 22, -1, None, "S_NULL"),
+    state("S_POSS_STND", "POSS", 0, 10, Some("A_Look"), "S_POSS_STND2"),
+    state("S_POSS_STND2", "POSS", 1, 10, Some("A_Look"), "S_POSS_STND"),
+    state("S_POSS_RUN1", "POSS", 0, 4, Some("A_Chase"), "S_POSS_RUN2"),
+    state("S_POSS_RUN2", "POSS", 0, 4, Some("A_Chase"), "S_POSS_RUN3"),
+    state("S_POSS_RUN3", "POSS", 1, 4, Some("A_Chase"), "S_POSS_RUN4"),
+    state("S_POSS_RUN4", "POSS", 1, 4, Some("A_Chase"), "S_POSS_RUN5"),
+    state("S_POSS_RUN5", "POSS", 2, 4, Some("A_Chase"), "S_POSS_RUN6"),
+    state("S_POSS_RUN6", "POSS", 2, 4, Some("A_Chase"), "S_POSS_RUN7"),
+    state("S_POSS_RUN7", "POSS", 3, 4, Some("A_Chase"), "S_POSS_RUN8"),
+    state("S_POSS_RUN8", "POSS", 3, 4, Some("A_Chase"), "S_POSS_RUN1"),
+    state(
+        "S_POSS_ATK1",
+        "POSS",
+        4,
+        10,
+        Some("A_FaceTarget"),
+        "S_POSS_ATK2",
+    ),
+    state(
+        "S_POSS_ATK2",
+        "POSS",
+        5,
+        8,
+        Some("A_PosAttack"),
+        "S_POSS_ATK3",
+    ),
+    state("S_POSS_ATK3", "POSS", 4, 8, None, "S_POSS_RUN1"),
+    state("S_POSS_PAIN", "POSS", 6, 3, None, "S_POSS_PAIN2"),
+    state("S_POSS_PAIN2", "POSS", 6, 3, Some("A_Pain"), "S_POSS_RUN1"),
+    state("S_POSS_DIE1", "POSS", 7, 5, None, "S_POSS_DIE2"),
+    state("S_POSS_DIE2", "POSS", 8, 5, Some("A_Scream"), "S_POSS_DIE3"),
+    state("S_POSS_DIE3", "POSS", 9, 5, Some("A_Fall"), "S_POSS_DIE4"),
+    state("S_POSS_DIE4", "POSS", 10, 5, None, "S_POSS_DIE5"),
+    state("S_POSS_DIE5", "POSS", 11, -1, None, "S_NULL"),
+    state("S_POSS_XDIE1", "POSS", 12, 5, None, "S_POSS_XDIE2"),
+    state(
+        "S_POSS_XDIE2",
+        "POSS",
+        13,
+        5,
+        Some("A_XScream"),
+        "S_POSS_XDIE3",
+    ),
+    state(
+        "S_POSS_XDIE3",
+        "POSS",
+        14,
+        5,
+        Some("A_Fall"),
+        "S_POSS_XDIE4",
+    ),
+    state("S_POSS_XDIE4", "POSS", 15, 5, None, "S_POSS_XDIE5"),
+    state("S_POSS_XDIE5", "POSS", 16, 5, None, "S_POSS_XDIE6"),
+    state("S_POSS_XDIE6", "POSS", 17, 5, None, "S_POSS_XDIE7"),
+    state("S_POSS_XDIE7", "POSS", 18, 5, None, "S_POSS_XDIE8"),
+    state("S_POSS_XDIE8", "POSS", 19, 5, None, "S_POSS_XDIE9"),
+    state("S_POSS_XDIE9", "POSS", 20, -1, None, "S_NULL"),
+    state("S_POSS_RAISE1", "POSS", 10, 5, None, "S_POSS_RAISE2"),
+    state("S_POSS_RAISE2", "POSS", 9, 5, None, "S_POSS_RAISE3"),
+    state("S_POSS_RAISE3", "POSS", 8, 5, None, "S_POSS_RAISE4"),
+    state("S_POSS_RAISE4", "POSS", 7, 5, None, "S_POSS_RUN1"),
+    state("S_SPOS_STND", "SPOS", 0, 10, Some("A_Look"), "S_SPOS_STND2"),
+    state("S_SPOS_STND2", "SPOS", 1, 10, Some("A_Look"), "S_SPOS_STND"),
+    state("S_SPOS_RUN1", "SPOS", 0, 3, Some("A_Chase"), "S_SPOS_RUN2"),
+    state("S_SPOS_RUN2", "SPOS", 0, 3, Some("A_Chase"), "S_SPOS_RUN3"),
+    state("S_SPOS_RUN3", "SPOS", 1, 3, Some("A_Chase"), "S_SPOS_RUN4"),
+    state("S_SPOS_RUN4", "SPOS", 1, 3, Some("A_Chase"), "S_SPOS_RUN5"),
+    state("S_SPOS_RUN5", "SPOS", 2, 3, Some("A_Chase"), "S_SPOS_RUN6"),
+    state("S_SPOS_RUN6", "SPOS", 2, 3, Some("A_Chase"), "S_SPOS_RUN7"),
+    state("S_SPOS_RUN7", "SPOS", 3, 3, Some("A_Chase"), "S_SPOS_RUN8"),
+    state("S_SPOS_RUN8", "SPOS", 3, 3, Some("A_Chase"), "S_SPOS_RUN1"),
+    state(
+        "S_SPOS_ATK1",
+        "SPOS",
+        4,
+        10,
+        Some("A_FaceTarget"),
+        "S_SPOS_ATK2",
+    ),
+    state(
+        "S_SPOS_ATK2",
+        "SPOS",
+        FF_FULLBRIGHT | 5,
+        10,
+        Some("A_SPosAttack"),
+        "S_SPOS_ATK3",
+    ),
+    state("S_SPOS_ATK3", "SPOS", 4, 10, None, "S_SPOS_RUN1"),
+    state("S_SPOS_PAIN", "SPOS", 6, 3, None, "S_SPOS_PAIN2"),
+    state("S_SPOS_PAIN2", "SPOS", 6, 3, Some("A_Pain"), "S_SPOS_RUN1"),
+    state("S_SPOS_DIE1", "SPOS", 7, 5, None, "S_SPOS_DIE2"),
+    state("S_SPOS_DIE2", "SPOS", 8, 5, Some("A_Scream"), "S_SPOS_DIE3"),
+    state("S_SPOS_DIE3", "SPOS", 9, 5, Some("A_Fall"), "S_SPOS_DIE4"),
+    state("S_SPOS_DIE4", "SPOS", 10, 5, None, "S_SPOS_DIE5"),
+    state("S_SPOS_DIE5", "SPOS", 11, -1, None, "S_NULL"),
+    state("S_SPOS_XDIE1", "SPOS", 12, 5, None, "S_SPOS_XDIE2"),
+    state(
+        "S_SPOS_XDIE2",
+        "SPOS",
+        13,
+        5,
+        Some("A_XScream"),
+        "S_SPOS_XDIE3",
+    ),
+    state(
+        "S_SPOS_XDIE3",
+        "SPOS",
+        14,
+        5,
+        Some("A_Fall"),
+        "S_SPOS_XDIE4",
+    ),
+    state("S_SPOS_XDIE4", "SPOS", 15, 5, None, "S_SPOS_XDIE5"),
+    state("S_SPOS_XDIE5", "SPOS", 16, 5, None, "S_SPOS_XDIE6"),
+    state("S_SPOS_XDIE6", "SPOS", 17, 5, None, "S_SPOS_XDIE7"),
+    state("S_SPOS_XDIE7", "SPOS", 18, 5, None, "S_SPOS_XDIE8"),
+    state("S_SPOS_XDIE8", "SPOS", 19, 5, None, "S_SPOS_XDIE9"),
+    state("S_SPOS_XDIE9", "SPOS", 20, -1, None, "S_NULL"),
+    state("S_SPOS_RAISE1", "SPOS", 11, 5, None, "S_SPOS_RAISE2"),
+    state("S_SPOS_RAISE2", "SPOS", 10, 5, None, "S_SPOS_RAISE3"),
+    state("S_SPOS_RAISE3", "SPOS", 9, 5, None, "S_SPOS_RAISE4"),
+    state("S_SPOS_RAISE4", "SPOS", 8, 5, None, "S_SPOS_RAISE5"),
+    state("S_SPOS_RAISE5", "SPOS", 7, 5, None, "S_SPOS_RUN1"),
+    state("S_VILE_STND", "VILE", 0, 10, Some("A_Look"), "S_VILE_STND2"),
+    state("S_VILE_STND2", "VILE", 1, 10, Some("A_Look"), "S_VILE_STND"),
+    state(
+        "S_VILE_RUN1",
+        "VILE",
+        0,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN2",
+    ),
+    state(
+        "S_VILE_RUN2",
+        "VILE",
+        0,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN3",
+    ),
+    state(
+        "S_VILE_RUN3",
+        "VILE",
+        1,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN4",
+    ),
+    state(
+        "S_VILE_RUN4",
+        "VILE",
+        1,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN5",
+    ),
+    state(
+        "S_VILE_RUN5",
+        "VILE",
+        2,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN6",
+    ),
+    state(
+        "S_VILE_RUN6",
+        "VILE",
+        2,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN7",
+    ),
+    state(
+        "S_VILE_RUN7",
+        "VILE",
+        3,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN8",
+    ),
+    state(
+        "S_VILE_RUN8",
+        "VILE",
+        3,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN9",
+    ),
+    state(
+        "S_VILE_RUN9",
+        "VILE",
+        4,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN10",
+    ),
+    state(
+        "S_VILE_RUN10",
+        "VILE",
+        4,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN11",
+    ),
+    state(
+        "S_VILE_RUN11",
+        "VILE",
+        5,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN12",
+    ),
+    state(
+        "S_VILE_RUN12",
+        "VILE",
+        5,
+        2,
+        Some("A_VileChase"),
+        "S_VILE_RUN1",
+    ),
+    state(
+        "S_VILE_ATK1",
+        "VILE",
+        FF_FULLBRIGHT | 6,
+        0,
+        Some("A_VileStart"),
+        "S_VILE_ATK2",
+    ),
+    state(
+        "S_VILE_ATK2",
+        "VILE",
+        FF_FULLBRIGHT | 6,
+        10,
+        Some("A_FaceTarget"),
+        "S_VILE_ATK3",
+    ),
+    state(
+        "S_VILE_ATK3",
+        "VILE",
+        FF_FULLBRIGHT | 7,
+        8,
+        Some("A_VileTarget"),
+        "S_VILE_ATK4",
+    ),
+    state(
+        "S_VILE_ATK4",
+        "VILE",
+        FF_FULLBRIGHT | 8,
+        8,
+        Some("A_FaceTarget"),
+        "S_VILE_ATK5",
+    ),
+    state(
+        "S_VILE_ATK5",
+        "VILE",
+        FF_FULLBRIGHT | 9,
+        8,
+        Some("A_FaceTarget"),
+        "S_VILE_ATK6",
+    ),
+    state(
+        "S_VILE_ATK6",
+        "VILE",
+        FF_FULLBRIGHT | 10,
+        8,
+        Some("A_FaceTarget"),
+        "S_VILE_ATK7",
+    ),
+    state(
+        "S_VILE_ATK7",
+        "VILE",
+        FF_FULLBRIGHT | 11,
+        8,
+        Some("A_FaceTarget"),
+        "S_VILE_ATK8",
+    ),
+    state(
+        "S_VILE_ATK8",
+        "VILE",
+        FF_FULLBRIGHT | 12,
+        8,
+        Some("A_FaceTarget"),
+        "S_VILE_ATK9",
+    ),
+    state(
+        "S_VILE_ATK9",
+        "VILE",
+        FF_FULLBRIGHT | 13,
+        8,
+        Some("A_FaceTarget"),
+        "S_VILE_ATK10",
+    ),
+    state(
+        "S_VILE_ATK10",
+        "VILE",
+        FF_FULLBRIGHT | 14,
+        8,
+        Some("A_VileAttack"),
+        "S_VILE_ATK11",
+    ),
+    state(
+        "S_VILE_ATK11",
+        "VILE",
+        FF_FULLBRIGHT | 15,
+        20,
+        None,
+        "S_VILE_RUN1",
+    ),
+    state(
+        "S_VILE_HEAL1",
+        "VILE",
+        FF_FULLBRIGHT | 26,
+        10,
+        None,
+        "S_VILE_HEAL2",
+    ),
+    state(
+        "S_VILE_HEAL2",
+        "VILE",
+        FF_FULLBRIGHT | 27,
+        10,
+        None,
+        "S_VILE_HEAL3",
+    ),
+    state(
+        "S_VILE_HEAL3",
+        "VILE",
+        FF_FULLBRIGHT | 28,
+        10,
+        None,
+        "S_VILE_RUN1",
+    ),
+    state("S_VILE_PAIN", "VILE", 16, 5, None, "S_VILE_PAIN2"),
+    state("S_VILE_PAIN2", "VILE", 16, 5, Some("A_Pain"), "S_VILE_RUN1"),
+    state("S_VILE_DIE1", "VILE", 16, 7, None, "S_VILE_DIE2"),
+    state(
+        "S_VILE_DIE2",
+        "VILE",
+        17,
+        7,
+        Some("A_Scream"),
+        "S_VILE_DIE3",
+    ),
+    state("S_VILE_DIE3", "VILE", 18, 7, Some("A_Fall"), "S_VILE_DIE4"),
+    state("S_VILE_DIE4", "VILE", 19, 7, None, "S_VILE_DIE5"),
+    state("S_VILE_DIE5", "VILE", 20, 7, None, "S_VILE_DIE6"),
+    state("S_VILE_DIE6", "VILE", 21, 7, None, "S_VILE_DIE7"),
+    state("S_VILE_DIE7", "VILE", 22, 7, None, "S_VILE_DIE8"),
+    state("S_VILE_DIE8", "VILE", 23, 7, None, "S_VILE_DIE9"),
+    state("S_VILE_DIE9", "VILE", 24, 7, None, "S_VILE_DIE10"),
+    state("S_VILE_DIE10", "VILE", 25, -1, None, "S_NULL"),
+    state(
+        "S_FIRE1",
+        "FIRE",
+        FF_FULLBRIGHT,
+        2,
+        Some("A_StartFire"),
+        "S_FIRE2",
+    ),
+    state(
+        "S_FIRE2",
+        "FIRE",
+        FF_FULLBRIGHT | 1,
+        2,
+        Some("A_Fire"),
+        "S_FIRE3",
+    ),
+    state(
+        "S_FIRE3",
+        "FIRE",
+        FF_FULLBRIGHT,
+        2,
+        Some("A_Fire"),
+        "S_FIRE4",
+    ),
+    state(
+        "S_FIRE4",
+        "FIRE",
+        FF_FULLBRIGHT | 1,
+        2,
+        Some("A_Fire"),
+        "S_FIRE5",
+    ),
+    state(
+        "S_FIRE5",
+        "FIRE",
+        FF_FULLBRIGHT | 2,
+        2,
+        Some("A_FireCrackle"),
+        "S_FIRE6",
+    ),
+    state(
+        "S_FIRE6",
+        "FIRE",
+        FF_FULLBRIGHT | 1,
+        2,
+        Some("A_Fire"),
+        "S_FIRE7",
+    ),
+    state(
+        "S_FIRE7",
+        "FIRE",
+        FF_FULLBRIGHT | 2,
+        2,
+        Some("A_Fire"),
+        "S_FIRE8",
+    ),
+    state(
+        "S_FIRE8",
+        "FIRE",
+        FF_FULLBRIGHT | 1,
+        2,
+        Some("A_Fire"),
+        "S_FIRE9",
+    ),
+    state(
+        "S_FIRE9",
+        "FIRE",
+        FF_FULLBRIGHT | 2,
+        2,
+        Some("A_Fire"),
+        "S_FIRE10",
+    ),
+    state(
+        "S_FIRE10",
+        "FIRE",
+        FF_FULLBRIGHT | 3,
+        2,
+        Some("A_Fire"),
+        "S_FIRE11",
+    ),
+    state(
+        "S_FIRE11",
+        "FIRE",
+        FF_FULLBRIGHT | 2,
+        2,
+        Some("A_Fire"),
+        "S_FIRE12",
+    ),
+    state(
+        "S_FIRE12",
+        "FIRE",
+        FF_FULLBRIGHT | 3,
+        2,
+        Some("A_Fire"),
+        "S_FIRE13",
+    ),
+    state(
+        "S_FIRE13",
+        "FIRE",
+        FF_FULLBRIGHT | 2,
+        2,
+        Some("A_Fire"),
+        "S_FIRE14",
+    ),
+    state(
+        "S_FIRE14",
+        "FIRE",
+        FF_FULLBRIGHT | 3,
+        2,
+        Some("A_Fire"),
+        "S_FIRE15",
+    ),
+    state(
+        "S_FIRE15",
+        "FIRE",
+        FF_FULLBRIGHT | 4,
+        2,
+        Some("A_Fire"),
+        "S_FIRE16",
+    ),
+    state(
+        "S_FIRE16",
+        "FIRE",
+        FF_FULLBRIGHT | 3,
+        2,
+        Some("A_Fire"),
+        "S_FIRE17",
+    ),
+    state(
+        "S_FIRE17",
+        "FIRE",
+        FF_FULLBRIGHT | 4,
+        2,
+        Some("A_Fire"),
+        "S_FIRE18",
+    ),
+    state(
+        "S_FIRE18",
+        "FIRE",
+        FF_FULLBRIGHT | 3,
+        2,
+        Some("A_Fire"),
+        "S_FIRE19",
+    ),
+    state(
+        "S_FIRE19",
+        "FIRE",
+        FF_FULLBRIGHT | 4,
+        2,
+        Some("A_FireCrackle"),
+        "S_FIRE20",
+    ),
+    state(
+        "S_FIRE20",
+        "FIRE",
+        FF_FULLBRIGHT | 5,
+        2,
+        Some("A_Fire"),
+        "S_FIRE21",
+    ),
+    state(
+        "S_FIRE21",
+        "FIRE",
+        FF_FULLBRIGHT | 4,
+        2,
+        Some("A_Fire"),
+        "S_FIRE22",
+    ),
+    state(
+        "S_FIRE22",
+        "FIRE",
+        FF_FULLBRIGHT | 5,
+        2,
+        Some("A_Fire"),
+        "S_FIRE23",
+    ),
+    state(
+        "S_FIRE23",
+        "FIRE",
+        FF_FULLBRIGHT | 4,
+        2,
+        Some("A_Fire"),
+        "S_FIRE24",
+    ),
+    state(
+        "S_FIRE24",
+        "FIRE",
+        FF_FULLBRIGHT | 5,
+        2,
+        Some("A_Fire"),
+        "S_FIRE25",
+    ),
+    state(
+        "S_FIRE25",
+        "FIRE",
+        FF_FULLBRIGHT | 6,
+        2,
+        Some("A_Fire"),
+        "S_FIRE26",
+    ),
+    state(
+        "S_FIRE26",
+        "FIRE",
+        FF_FULLBRIGHT | 7,
+        2,
+        Some("A_Fire"),
+        "S_FIRE27",
+    ),
+    state(
+        "S_FIRE27",
+        "FIRE",
+        FF_FULLBRIGHT | 6,
+        2,
+        Some("A_Fire"),
+        "S_FIRE28",
+    ),
+    state(
+        "S_FIRE28",
+        "FIRE",
+        FF_FULLBRIGHT | 7,
+        2,
+        Some("A_Fire"),
+        "S_FIRE29",
+    ),
+    state(
+        "S_FIRE29",
+        "FIRE",
+        FF_FULLBRIGHT | 6,
+        2,
+        Some("A_Fire"),
+        "S_FIRE30",
+    ),
+    state(
+        "S_FIRE30",
+        "FIRE",
+        FF_FULLBRIGHT | 7,
+        2,
+        Some("A_Fire"),
+        "S_NULL",
+    ),
+    state("S_SMOKE1", "PUFF", 1, 4, None, "S_SMOKE2"),
+    state("S_SMOKE2", "PUFF", 2, 4, None, "S_SMOKE3"),
+    state("S_SMOKE3", "PUFF", 1, 4, None, "S_SMOKE4"),
+    state("S_SMOKE4", "PUFF", 2, 4, None, "S_SMOKE5"),
+    state("S_SMOKE5", "PUFF", 3, 4, None, "S_NULL"),
+    state(
+        "S_TRACER",
+        "FATB",
+        FF_FULLBRIGHT,
+        2,
+        Some("A_Tracer"),
+        "S_TRACER2",
+    ),
+    state(
+        "S_TRACER2",
+        "FATB",
+        FF_FULLBRIGHT | 1,
+        2,
+        Some("A_Tracer"),
+        "S_TRACER",
+    ),
+    state("S_TRACEEXP1", "FBXP", FF_FULLBRIGHT, 8, None, "S_TRACEEXP2"),
+    state(
+        "S_TRACEEXP2",
+        "FBXP",
+        FF_FULLBRIGHT | 1,
+        6,
+        None,
+        "S_TRACEEXP3",
+    ),
+    state("S_TRACEEXP3", "FBXP", FF_FULLBRIGHT | 2, 4, None, "S_NULL"),
+    state("S_SKEL_STND", "SKEL", 0, 10, Some("A_Look"), "S_SKEL_STND2"),
+    state("S_SKEL_STND2", "SKEL", 1, 10, Some("A_Look"), "S_SKEL_STND"),
+    state("S_SKEL_RUN1", "SKEL", 0, 2, Some("A_Chase"), "S_SKEL_RUN2"),
+    state("S_SKEL_RUN2", "SKEL", 0, 2, Some("A_Chase"), "S_SKEL_RUN3"),
+    state("S_SKEL_RUN3", "SKEL", 1, 2, Some("A_Chase"), "S_SKEL_RUN4"),
+    state("S_SKEL_RUN4", "SKEL", 1, 2, Some("A_Chase"), "S_SKEL_RUN5"),
+    state("S_SKEL_RUN5", "SKEL", 2, 2, Some("A_Chase"), "S_SKEL_RUN6"),
+    state("S_SKEL_RUN6", "SKEL", 2, 2, Some("A_Chase"), "S_SKEL_RUN7"),
+    state("S_SKEL_RUN7", "SKEL", 3, 2, Some("A_Chase"), "S_SKEL_RUN8"),
+    state("S_SKEL_RUN8", "SKEL", 3, 2, Some("A_Chase"), "S_SKEL_RUN9"),
+    state("S_SKEL_RUN9", "SKEL", 4, 2, Some("A_Chase"), "S_SKEL_RUN10"),
+    state(
+        "S_SKEL_RUN10",
+        "SKEL",
+        4,
+        2,
+        Some("A_Chase"),
+        "S_SKEL_RUN11",
+    ),
+    state(
+        "S_SKEL_RUN11",
+        "SKEL",
+        5,
+        2,
+        Some("A_Chase"),
+        "S_SKEL_RUN12",
+    ),
+    state("S_SKEL_RUN12", "SKEL", 5, 2, Some("A_Chase"), "S_SKEL_RUN1"),
+    state(
+        "S_SKEL_FIST1",
+        "SKEL",
+        6,
+        0,
+        Some("A_FaceTarget"),
+        "S_SKEL_FIST2",
+    ),
+    state(
+        "S_SKEL_FIST2",
+        "SKEL",
+        6,
+        6,
+        Some("A_SkelWhoosh"),
+        "S_SKEL_FIST3",
+    ),
+    state(
+        "S_SKEL_FIST3",
+        "SKEL",
+        7,
+        6,
+        Some("A_FaceTarget"),
+        "S_SKEL_FIST4",
+    ),
+    state(
+        "S_SKEL_FIST4",
+        "SKEL",
+        8,
+        6,
+        Some("A_SkelFist"),
+        "S_SKEL_RUN1",
+    ),
+    state(
+        "S_SKEL_MISS1",
+        "SKEL",
+        FF_FULLBRIGHT | 9,
+        0,
+        Some("A_FaceTarget"),
+        "S_SKEL_MISS2",
+    ),
+    state(
+        "S_SKEL_MISS2",
+        "SKEL",
+        FF_FULLBRIGHT | 9,
+        10,
+        Some("A_FaceTarget"),
+        "S_SKEL_MISS3",
+    ),
+    state(
+        "S_SKEL_MISS3",
+        "SKEL",
+        10,
+        10,
+        Some("A_SkelMissile"),
+        "S_SKEL_MISS4",
+    ),
+    state(
+        "S_SKEL_MISS4",
+        "SKEL",
+        10,
+        10,
+        Some("A_FaceTarget"),
+        "S_SKEL_RUN1",
+    ),
+    state("S_SKEL_PAIN", "SKEL", 11, 5, None, "S_SKEL_PAIN2"),
+    state("S_SKEL_PAIN2", "SKEL", 11, 5, Some("A_Pain"), "S_SKEL_RUN1"),
+    state("S_SKEL_DIE1", "SKEL", 11, 7, None, "S_SKEL_DIE2"),
+    state("S_SKEL_DIE2", "SKEL", 12, 7, None, "S_SKEL_DIE3"),
+    state(
+        "S_SKEL_DIE3",
+        "SKEL",
+        13,
+        7,
+        Some("A_Scream"),
+        "S_SKEL_DIE4",
+    ),
+    state("S_SKEL_DIE4", "SKEL", 14, 7, Some("A_Fall"), "S_SKEL_DIE5"),
+    state("S_SKEL_DIE5", "SKEL", 15, 7, None, "S_SKEL_DIE6"),
+    state("S_SKEL_DIE6", "SKEL", 16, -1, None, "S_NULL"),
+    state("S_SKEL_RAISE1", "SKEL", 16, 5, None, "S_SKEL_RAISE2"),
+    state("S_SKEL_RAISE2", "SKEL", 15, 5, None, "S_SKEL_RAISE3"),
+    state("S_SKEL_RAISE3", "SKEL", 14, 5, None, "S_SKEL_RAISE4"),
+    state("S_SKEL_RAISE4", "SKEL", 13, 5, None, "S_SKEL_RAISE5"),
+    state("S_SKEL_RAISE5", "SKEL", 12, 5, None, "S_SKEL_RAISE6"),
+    state("S_SKEL_RAISE6", "SKEL", 11, 5, None, "S_SKEL_RUN1"),
+    state("S_FATSHOT1", "MANF", FF_FULLBRIGHT, 4, None, "S_FATSHOT2"),
+    state(
+        "S_FATSHOT2",
+        "MANF",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_FATSHOT1",
+    ),
+    state(
+        "S_FATSHOTX1",
+        "MISL",
+        FF_FULLBRIGHT | 1,
+        8,
+        None,
+        "S_FATSHOTX2",
+    ),
+    state(
+        "S_FATSHOTX2",
+        "MISL",
+        FF_FULLBRIGHT | 2,
+        6,
+        None,
+        "S_FATSHOTX3",
+    ),
+    state("S_FATSHOTX3", "MISL", FF_FULLBRIGHT | 3, 4, None, "S_NULL"),
+    state("S_FATT_STND", "FATT", 0, 15, Some("A_Look"), "S_FATT_STND2"),
+    state("S_FATT_STND2", "FATT", 1, 15, Some("A_Look"), "S_FATT_STND"),
+    state("S_FATT_RUN1", "FATT", 0, 4, Some("A_Chase"), "S_FATT_RUN2"),
+    state("S_FATT_RUN2", "FATT", 0, 4, Some("A_Chase"), "S_FATT_RUN3"),
+    state("S_FATT_RUN3", "FATT", 1, 4, Some("A_Chase"), "S_FATT_RUN4"),
+    state("S_FATT_RUN4", "FATT", 1, 4, Some("A_Chase"), "S_FATT_RUN5"),
+    state("S_FATT_RUN5", "FATT", 2, 4, Some("A_Chase"), "S_FATT_RUN6"),
+    state("S_FATT_RUN6", "FATT", 2, 4, Some("A_Chase"), "S_FATT_RUN7"),
+    state("S_FATT_RUN7", "FATT", 3, 4, Some("A_Chase"), "S_FATT_RUN8"),
+    state("S_FATT_RUN8", "FATT", 3, 4, Some("A_Chase"), "S_FATT_RUN9"),
+    state("S_FATT_RUN9", "FATT", 4, 4, Some("A_Chase"), "S_FATT_RUN10"),
+    state(
+        "S_FATT_RUN10",
+        "FATT",
+        4,
+        4,
+        Some("A_Chase"),
+        "S_FATT_RUN11",
+    ),
+    state(
+        "S_FATT_RUN11",
+        "FATT",
+        5,
+        4,
+        Some("A_Chase"),
+        "S_FATT_RUN12",
+    ),
+    state("S_FATT_RUN12", "FATT", 5, 4, Some("A_Chase"), "S_FATT_RUN1"),
+    state(
+        "S_FATT_ATK1",
+        "FATT",
+        6,
+        20,
+        Some("A_FatRaise"),
+        "S_FATT_ATK2",
+    ),
+    state(
+        "S_FATT_ATK2",
+        "FATT",
+        FF_FULLBRIGHT | 7,
+        10,
+        Some("A_FatAttack1"),
+        "S_FATT_ATK3",
+    ),
+    state(
+        "S_FATT_ATK3",
+        "FATT",
+        6,
+        5,
+        Some("A_FaceTarget"),
+        "S_FATT_ATK4",
+    ),
+    state(
+        "S_FATT_ATK4",
+        "FATT",
+        6,
+        5,
+        Some("A_FaceTarget"),
+        "S_FATT_ATK5",
+    ),
+    state(
+        "S_FATT_ATK5",
+        "FATT",
+        FF_FULLBRIGHT | 7,
+        10,
+        Some("A_FatAttack2"),
+        "S_FATT_ATK6",
+    ),
+    state(
+        "S_FATT_ATK6",
+        "FATT",
+        6,
+        5,
+        Some("A_FaceTarget"),
+        "S_FATT_ATK7",
+    ),
+    state(
+        "S_FATT_ATK7",
+        "FATT",
+        6,
+        5,
+        Some("A_FaceTarget"),
+        "S_FATT_ATK8",
+    ),
+    state(
+        "S_FATT_ATK8",
+        "FATT",
+        FF_FULLBRIGHT | 7,
+        10,
+        Some("A_FatAttack3"),
+        "S_FATT_ATK9",
+    ),
+    state(
+        "S_FATT_ATK9",
+        "FATT",
+        6,
+        5,
+        Some("A_FaceTarget"),
+        "S_FATT_ATK10",
+    ),
+    state(
+        "S_FATT_ATK10",
+        "FATT",
+        6,
+        5,
+        Some("A_FaceTarget"),
+        "S_FATT_RUN1",
+    ),
+    state("S_FATT_PAIN", "FATT", 9, 3, None, "S_FATT_PAIN2"),
+    state("S_FATT_PAIN2", "FATT", 9, 3, Some("A_Pain"), "S_FATT_RUN1"),
+    state("S_FATT_DIE1", "FATT", 10, 6, None, "S_FATT_DIE2"),
+    state(
+        "S_FATT_DIE2",
+        "FATT",
+        11,
+        6,
+        Some("A_Scream"),
+        "S_FATT_DIE3",
+    ),
+    state("S_FATT_DIE3", "FATT", 12, 6, Some("A_Fall"), "S_FATT_DIE4"),
+    state("S_FATT_DIE4", "FATT", 13, 6, None, "S_FATT_DIE5"),
+    state("S_FATT_DIE5", "FATT", 14, 6, None, "S_FATT_DIE6"),
+    state("S_FATT_DIE6", "FATT", 15, 6, None, "S_FATT_DIE7"),
+    state("S_FATT_DIE7", "FATT", 16, 6, None, "S_FATT_DIE8"),
+    state("S_FATT_DIE8", "FATT", 17, 6, None, "S_FATT_DIE9"),
+    state("S_FATT_DIE9", "FATT", 18, 6, None, "S_FATT_DIE10"),
+    state(
+        "S_FATT_DIE10",
+        "FATT",
+        19,
+        -1,
+        Some("A_BossDeath"),
+        "S_NULL",
+    ),
+    state("S_FATT_RAISE1", "FATT", 17, 5, None, "S_FATT_RAISE2"),
+    state("S_FATT_RAISE2", "FATT", 16, 5, None, "S_FATT_RAISE3"),
+    state("S_FATT_RAISE3", "FATT", 15, 5, None, "S_FATT_RAISE4"),
+    state("S_FATT_RAISE4", "FATT", 14, 5, None, "S_FATT_RAISE5"),
+    state("S_FATT_RAISE5", "FATT", 13, 5, None, "S_FATT_RAISE6"),
+    state("S_FATT_RAISE6", "FATT", 12, 5, None, "S_FATT_RAISE7"),
+    state("S_FATT_RAISE7", "FATT", 11, 5, None, "S_FATT_RAISE8"),
+    state("S_FATT_RAISE8", "FATT", 10, 5, None, "S_FATT_RUN1"),
+    state("S_CPOS_STND", "CPOS", 0, 10, Some("A_Look"), "S_CPOS_STND2"),
+    state("S_CPOS_STND2", "CPOS", 1, 10, Some("A_Look"), "S_CPOS_STND"),
+    state("S_CPOS_RUN1", "CPOS", 0, 3, Some("A_Chase"), "S_CPOS_RUN2"),
+    state("S_CPOS_RUN2", "CPOS", 0, 3, Some("A_Chase"), "S_CPOS_RUN3"),
+    state("S_CPOS_RUN3", "CPOS", 1, 3, Some("A_Chase"), "S_CPOS_RUN4"),
+    state("S_CPOS_RUN4", "CPOS", 1, 3, Some("A_Chase"), "S_CPOS_RUN5"),
+    state("S_CPOS_RUN5", "CPOS", 2, 3, Some("A_Chase"), "S_CPOS_RUN6"),
+    state("S_CPOS_RUN6", "CPOS", 2, 3, Some("A_Chase"), "S_CPOS_RUN7"),
+    state("S_CPOS_RUN7", "CPOS", 3, 3, Some("A_Chase"), "S_CPOS_RUN8"),
+    state("S_CPOS_RUN8", "CPOS", 3, 3, Some("A_Chase"), "S_CPOS_RUN1"),
+    state(
+        "S_CPOS_ATK1",
+        "CPOS",
+        4,
+        10,
+        Some("A_FaceTarget"),
+        "S_CPOS_ATK2",
+    ),
+    state(
+        "S_CPOS_ATK2",
+        "CPOS",
+        FF_FULLBRIGHT | 5,
+        4,
+        Some("A_CPosAttack"),
+        "S_CPOS_ATK3",
+    ),
+    state(
+        "S_CPOS_ATK3",
+        "CPOS",
+        FF_FULLBRIGHT | 4,
+        4,
+        Some("A_CPosAttack"),
+        "S_CPOS_ATK4",
+    ),
+    state(
+        "S_CPOS_ATK4",
+        "CPOS",
+        5,
+        1,
+        Some("A_CPosRefire"),
+        "S_CPOS_ATK2",
+    ),
+    state("S_CPOS_PAIN", "CPOS", 6, 3, None, "S_CPOS_PAIN2"),
+    state("S_CPOS_PAIN2", "CPOS", 6, 3, Some("A_Pain"), "S_CPOS_RUN1"),
+    state("S_CPOS_DIE1", "CPOS", 7, 5, None, "S_CPOS_DIE2"),
+    state("S_CPOS_DIE2", "CPOS", 8, 5, Some("A_Scream"), "S_CPOS_DIE3"),
+    state("S_CPOS_DIE3", "CPOS", 9, 5, Some("A_Fall"), "S_CPOS_DIE4"),
+    state("S_CPOS_DIE4", "CPOS", 10, 5, None, "S_CPOS_DIE5"),
+    state("S_CPOS_DIE5", "CPOS", 11, 5, None, "S_CPOS_DIE6"),
+    state("S_CPOS_DIE6", "CPOS", 12, 5, None, "S_CPOS_DIE7"),
+    state("S_CPOS_DIE7", "CPOS", 13, -1, None, "S_NULL"),
+    state("S_CPOS_XDIE1", "CPOS", 14, 5, None, "S_CPOS_XDIE2"),
+    state(
+        "S_CPOS_XDIE2",
+        "CPOS",
+        15,
+        5,
+        Some("A_XScream"),
+        "S_CPOS_XDIE3",
+    ),
+    state(
+        "S_CPOS_XDIE3",
+        "CPOS",
+        16,
+        5,
+        Some("A_Fall"),
+        "S_CPOS_XDIE4",
+    ),
+    state("S_CPOS_XDIE4", "CPOS", 17, 5, None, "S_CPOS_XDIE5"),
+    state("S_CPOS_XDIE5", "CPOS", 18, 5, None, "S_CPOS_XDIE6"),
+    state("S_CPOS_XDIE6", "CPOS", 19, -1, None, "S_NULL"),
+    state("S_CPOS_RAISE1", "CPOS", 13, 5, None, "S_CPOS_RAISE2"),
+    state("S_CPOS_RAISE2", "CPOS", 12, 5, None, "S_CPOS_RAISE3"),
+    state("S_CPOS_RAISE3", "CPOS", 11, 5, None, "S_CPOS_RAISE4"),
+    state("S_CPOS_RAISE4", "CPOS", 10, 5, None, "S_CPOS_RAISE5"),
+    state("S_CPOS_RAISE5", "CPOS", 9, 5, None, "S_CPOS_RAISE6"),
+    state("S_CPOS_RAISE6", "CPOS", 8, 5, None, "S_CPOS_RAISE7"),
+    state("S_CPOS_RAISE7", "CPOS", 7, 5, None, "S_CPOS_RUN1"),
+    state("S_TROO_STND", "TROO", 0, 10, Some("A_Look"), "S_TROO_STND2"),
+    state("S_TROO_STND2", "TROO", 1, 10, Some("A_Look"), "S_TROO_STND"),
+    state("S_TROO_RUN1", "TROO", 0, 3, Some("A_Chase"), "S_TROO_RUN2"),
+    state("S_TROO_RUN2", "TROO", 0, 3, Some("A_Chase"), "S_TROO_RUN3"),
+    state("S_TROO_RUN3", "TROO", 1, 3, Some("A_Chase"), "S_TROO_RUN4"),
+    state("S_TROO_RUN4", "TROO", 1, 3, Some("A_Chase"), "S_TROO_RUN5"),
+    state("S_TROO_RUN5", "TROO", 2, 3, Some("A_Chase"), "S_TROO_RUN6"),
+    state("S_TROO_RUN6", "TROO", 2, 3, Some("A_Chase"), "S_TROO_RUN7"),
+    state("S_TROO_RUN7", "TROO", 3, 3, Some("A_Chase"), "S_TROO_RUN8"),
+    state("S_TROO_RUN8", "TROO", 3, 3, Some("A_Chase"), "S_TROO_RUN1"),
+    state(
+        "S_TROO_ATK1",
+        "TROO",
+        4,
+        8,
+        Some("A_FaceTarget"),
+        "S_TROO_ATK2",
+    ),
+    state(
+        "S_TROO_ATK2",
+        "TROO",
+        5,
+        8,
+        Some("A_FaceTarget"),
+        "S_TROO_ATK3",
+    ),
+    state(
+        "S_TROO_ATK3",
+        "TROO",
+        6,
+        6,
+        Some("A_TroopAttack"),
+        "S_TROO_RUN1",
+    ),
+    state("S_TROO_PAIN", "TROO", 7, 2, None, "S_TROO_PAIN2"),
+    state("S_TROO_PAIN2", "TROO", 7, 2, Some("A_Pain"), "S_TROO_RUN1"),
+    state("S_TROO_DIE1", "TROO", 8, 8, None, "S_TROO_DIE2"),
+    state("S_TROO_DIE2", "TROO", 9, 8, Some("A_Scream"), "S_TROO_DIE3"),
+    state("S_TROO_DIE3", "TROO", 10, 6, None, "S_TROO_DIE4"),
+    state("S_TROO_DIE4", "TROO", 11, 6, Some("A_Fall"), "S_TROO_DIE5"),
+    state("S_TROO_DIE5", "TROO", 12, -1, None, "S_NULL"),
+    state("S_TROO_XDIE1", "TROO", 13, 5, None, "S_TROO_XDIE2"),
+    state(
+        "S_TROO_XDIE2",
+        "TROO",
+        14,
+        5,
+        Some("A_XScream"),
+        "S_TROO_XDIE3",
+    ),
+    state("S_TROO_XDIE3", "TROO", 15, 5, None, "S_TROO_XDIE4"),
+    state(
+        "S_TROO_XDIE4",
+        "TROO",
+        16,
+        5,
+        Some("A_Fall"),
+        "S_TROO_XDIE5",
+    ),
+    state("S_TROO_XDIE5", "TROO", 17, 5, None, "S_TROO_XDIE6"),
+    state("S_TROO_XDIE6", "TROO", 18, 5, None, "S_TROO_XDIE7"),
+    state("S_TROO_XDIE7", "TROO", 19, 5, None, "S_TROO_XDIE8"),
+    state("S_TROO_XDIE8", "TROO", 20, -1, None, "S_NULL"),
+    state("S_TROO_RAISE1", "TROO", 12, 8, None, "S_TROO_RAISE2"),
+    state("S_TROO_RAISE2", "TROO", 11, 8, None, "S_TROO_RAISE3"),
+    state("S_TROO_RAISE3", "TROO", 10, 6, None, "S_TROO_RAISE4"),
+    state("S_TROO_RAISE4", "TROO", 9, 6, None, "S_TROO_RAISE5"),
+    state("S_TROO_RAISE5", "TROO", 8, 6, None, "S_TROO_RUN1"),
+    state("S_SARG_STND", "SARG", 0, 10, Some("A_Look"), "S_SARG_STND2"),
+    state("S_SARG_STND2", "SARG", 1, 10, Some("A_Look"), "S_SARG_STND"),
+    state("S_SARG_RUN1", "SARG", 0, 2, Some("A_Chase"), "S_SARG_RUN2"),
+    state("S_SARG_RUN2", "SARG", 0, 2, Some("A_Chase"), "S_SARG_RUN3"),
+    state("S_SARG_RUN3", "SARG", 1, 2, Some("A_Chase"), "S_SARG_RUN4"),
+    state("S_SARG_RUN4", "SARG", 1, 2, Some("A_Chase"), "S_SARG_RUN5"),
+    state("S_SARG_RUN5", "SARG", 2, 2, Some("A_Chase"), "S_SARG_RUN6"),
+    state("S_SARG_RUN6", "SARG", 2, 2, Some("A_Chase"), "S_SARG_RUN7"),
+    state("S_SARG_RUN7", "SARG", 3, 2, Some("A_Chase"), "S_SARG_RUN8"),
+    state("S_SARG_RUN8", "SARG", 3, 2, Some("A_Chase"), "S_SARG_RUN1"),
+    state(
+        "S_SARG_ATK1",
+        "SARG",
+        4,
+        8,
+        Some("A_FaceTarget"),
+        "S_SARG_ATK2",
+    ),
+    state(
+        "S_SARG_ATK2",
+        "SARG",
+        5,
+        8,
+        Some("A_FaceTarget"),
+        "S_SARG_ATK3",
+    ),
+    state(
+        "S_SARG_ATK3",
+        "SARG",
+        6,
+        8,
+        Some("A_SargAttack"),
+        "S_SARG_RUN1",
+    ),
+    state("S_SARG_PAIN", "SARG", 7, 2, None, "S_SARG_PAIN2"),
+    state("S_SARG_PAIN2", "SARG", 7, 2, Some("A_Pain"), "S_SARG_RUN1"),
+    state("S_SARG_DIE1", "SARG", 8, 8, None, "S_SARG_DIE2"),
+    state("S_SARG_DIE2", "SARG", 9, 8, Some("A_Scream"), "S_SARG_DIE3"),
+    state("S_SARG_DIE3", "SARG", 10, 4, None, "S_SARG_DIE4"),
+    state("S_SARG_DIE4", "SARG", 11, 4, Some("A_Fall"), "S_SARG_DIE5"),
+    state("S_SARG_DIE5", "SARG", 12, 4, None, "S_SARG_DIE6"),
+    state("S_SARG_DIE6", "SARG", 13, -1, None, "S_NULL"),
+    state("S_SARG_RAISE1", "SARG", 13, 5, None, "S_SARG_RAISE2"),
+    state("S_SARG_RAISE2", "SARG", 12, 5, None, "S_SARG_RAISE3"),
+    state("S_SARG_RAISE3", "SARG", 11, 5, None, "S_SARG_RAISE4"),
+    state("S_SARG_RAISE4", "SARG", 10, 5, None, "S_SARG_RAISE5"),
+    state("S_SARG_RAISE5", "SARG", 9, 5, None, "S_SARG_RAISE6"),
+    state("S_SARG_RAISE6", "SARG", 8, 5, None, "S_SARG_RUN1"),
+    state("S_HEAD_STND", "HEAD", 0, 10, Some("A_Look"), "S_HEAD_STND"),
+    state("S_HEAD_RUN1", "HEAD", 0, 3, Some("A_Chase"), "S_HEAD_RUN1"),
+    state(
+        "S_HEAD_ATK1",
+        "HEAD",
+        1,
+        5,
+        Some("A_FaceTarget"),
+        "S_HEAD_ATK2",
+    ),
+    state(
+        "S_HEAD_ATK2",
+        "HEAD",
+        2,
+        5,
+        Some("A_FaceTarget"),
+        "S_HEAD_ATK3",
+    ),
+    state(
+        "S_HEAD_ATK3",
+        "HEAD",
+        FF_FULLBRIGHT | 3,
+        5,
+        Some("A_HeadAttack"),
+        "S_HEAD_RUN1",
+    ),
+    state("S_HEAD_PAIN", "HEAD", 4, 3, None, "S_HEAD_PAIN2"),
+    state("S_HEAD_PAIN2", "HEAD", 4, 3, Some("A_Pain"), "S_HEAD_PAIN3"),
+    state("S_HEAD_PAIN3", "HEAD", 5, 6, None, "S_HEAD_RUN1"),
+    state("S_HEAD_DIE1", "HEAD", 6, 8, None, "S_HEAD_DIE2"),
+    state("S_HEAD_DIE2", "HEAD", 7, 8, Some("A_Scream"), "S_HEAD_DIE3"),
+    state("S_HEAD_DIE3", "HEAD", 8, 8, None, "S_HEAD_DIE4"),
+    state("S_HEAD_DIE4", "HEAD", 9, 8, None, "S_HEAD_DIE5"),
+    state("S_HEAD_DIE5", "HEAD", 10, 8, Some("A_Fall"), "S_HEAD_DIE6"),
+    state("S_HEAD_DIE6", "HEAD", 11, -1, None, "S_NULL"),
+    state("S_HEAD_RAISE1", "HEAD", 11, 8, None, "S_HEAD_RAISE2"),
+    state("S_HEAD_RAISE2", "HEAD", 10, 8, None, "S_HEAD_RAISE3"),
+    state("S_HEAD_RAISE3", "HEAD", 9, 8, None, "S_HEAD_RAISE4"),
+    state("S_HEAD_RAISE4", "HEAD", 8, 8, None, "S_HEAD_RAISE5"),
+    state("S_HEAD_RAISE5", "HEAD", 7, 8, None, "S_HEAD_RAISE6"),
+    state("S_HEAD_RAISE6", "HEAD", 6, 8, None, "S_HEAD_RUN1"),
+    state("S_BRBALL1", "BAL7", FF_FULLBRIGHT, 4, None, "S_BRBALL2"),
+    state("S_BRBALL2", "BAL7", FF_FULLBRIGHT | 1, 4, None, "S_BRBALL1"),
+    state(
+        "S_BRBALLX1",
+        "BAL7",
+        FF_FULLBRIGHT | 2,
+        6,
+        None,
+        "S_BRBALLX2",
+    ),
+    state(
+        "S_BRBALLX2",
+        "BAL7",
+        FF_FULLBRIGHT | 3,
+        6,
+        None,
+        "S_BRBALLX3",
+    ),
+    state("S_BRBALLX3", "BAL7", FF_FULLBRIGHT | 4, 6, None, "S_NULL"),
+    state("S_BOSS_STND", "BOSS", 0, 10, Some("A_Look"), "S_BOSS_STND2"),
+    state("S_BOSS_STND2", "BOSS", 1, 10, Some("A_Look"), "S_BOSS_STND"),
+    state("S_BOSS_RUN1", "BOSS", 0, 3, Some("A_Chase"), "S_BOSS_RUN2"),
+    state("S_BOSS_RUN2", "BOSS", 0, 3, Some("A_Chase"), "S_BOSS_RUN3"),
+    state("S_BOSS_RUN3", "BOSS", 1, 3, Some("A_Chase"), "S_BOSS_RUN4"),
+    state("S_BOSS_RUN4", "BOSS", 1, 3, Some("A_Chase"), "S_BOSS_RUN5"),
+    state("S_BOSS_RUN5", "BOSS", 2, 3, Some("A_Chase"), "S_BOSS_RUN6"),
+    state("S_BOSS_RUN6", "BOSS", 2, 3, Some("A_Chase"), "S_BOSS_RUN7"),
+    state("S_BOSS_RUN7", "BOSS", 3, 3, Some("A_Chase"), "S_BOSS_RUN8"),
+    state("S_BOSS_RUN8", "BOSS", 3, 3, Some("A_Chase"), "S_BOSS_RUN1"),
+    state(
+        "S_BOSS_ATK1",
+        "BOSS",
+        4,
+        8,
+        Some("A_FaceTarget"),
+        "S_BOSS_ATK2",
+    ),
+    state(
+        "S_BOSS_ATK2",
+        "BOSS",
+        5,
+        8,
+        Some("A_FaceTarget"),
+        "S_BOSS_ATK3",
+    ),
+    state(
+        "S_BOSS_ATK3",
+        "BOSS",
+        6,
+        8,
+        Some("A_BruisAttack"),
+        "S_BOSS_RUN1",
+    ),
+    state("S_BOSS_PAIN", "BOSS", 7, 2, None, "S_BOSS_PAIN2"),
+    state("S_BOSS_PAIN2", "BOSS", 7, 2, Some("A_Pain"), "S_BOSS_RUN1"),
+    state("S_BOSS_DIE1", "BOSS", 8, 8, None, "S_BOSS_DIE2"),
+    state("S_BOSS_DIE2", "BOSS", 9, 8, Some("A_Scream"), "S_BOSS_DIE3"),
+    state("S_BOSS_DIE3", "BOSS", 10, 8, None, "S_BOSS_DIE4"),
+    state("S_BOSS_DIE4", "BOSS", 11, 8, Some("A_Fall"), "S_BOSS_DIE5"),
+    state("S_BOSS_DIE5", "BOSS", 12, 8, None, "S_BOSS_DIE6"),
+    state("S_BOSS_DIE6", "BOSS", 13, 8, None, "S_BOSS_DIE7"),
+    state("S_BOSS_DIE7", "BOSS", 14, -1, Some("A_BossDeath"), "S_NULL"),
+    state("S_BOSS_RAISE1", "BOSS", 14, 8, None, "S_BOSS_RAISE2"),
+    state("S_BOSS_RAISE2", "BOSS", 13, 8, None, "S_BOSS_RAISE3"),
+    state("S_BOSS_RAISE3", "BOSS", 12, 8, None, "S_BOSS_RAISE4"),
+    state("S_BOSS_RAISE4", "BOSS", 11, 8, None, "S_BOSS_RAISE5"),
+    state("S_BOSS_RAISE5", "BOSS", 10, 8, None, "S_BOSS_RAISE6"),
+    state("S_BOSS_RAISE6", "BOSS", 9, 8, None, "S_BOSS_RAISE7"),
+    state("S_BOSS_RAISE7", "BOSS", 8, 8, None, "S_BOSS_RUN1"),
+    state("S_BOS2_STND", "BOS2", 0, 10, Some("A_Look"), "S_BOS2_STND2"),
+    state("S_BOS2_STND2", "BOS2", 1, 10, Some("A_Look"), "S_BOS2_STND"),
+    state("S_BOS2_RUN1", "BOS2", 0, 3, Some("A_Chase"), "S_BOS2_RUN2"),
+    state("S_BOS2_RUN2", "BOS2", 0, 3, Some("A_Chase"), "S_BOS2_RUN3"),
+    state("S_BOS2_RUN3", "BOS2", 1, 3, Some("A_Chase"), "S_BOS2_RUN4"),
+    state("S_BOS2_RUN4", "BOS2", 1, 3, Some("A_Chase"), "S_BOS2_RUN5"),
+    state("S_BOS2_RUN5", "BOS2", 2, 3, Some("A_Chase"), "S_BOS2_RUN6"),
+    state("S_BOS2_RUN6", "BOS2", 2, 3, Some("A_Chase"), "S_BOS2_RUN7"),
+    state("S_BOS2_RUN7", "BOS2", 3, 3, Some("A_Chase"), "S_BOS2_RUN8"),
+    state("S_BOS2_RUN8", "BOS2", 3, 3, Some("A_Chase"), "S_BOS2_RUN1"),
+    state(
+        "S_BOS2_ATK1",
+        "BOS2",
+        4,
+        8,
+        Some("A_FaceTarget"),
+        "S_BOS2_ATK2",
+    ),
+    state(
+        "S_BOS2_ATK2",
+        "BOS2",
+        5,
+        8,
+        Some("A_FaceTarget"),
+        "S_BOS2_ATK3",
+    ),
+    state(
+        "S_BOS2_ATK3",
+        "BOS2",
+        6,
+        8,
+        Some("A_BruisAttack"),
+        "S_BOS2_RUN1",
+    ),
+    state("S_BOS2_PAIN", "BOS2", 7, 2, None, "S_BOS2_PAIN2"),
+    state("S_BOS2_PAIN2", "BOS2", 7, 2, Some("A_Pain"), "S_BOS2_RUN1"),
+    state("S_BOS2_DIE1", "BOS2", 8, 8, None, "S_BOS2_DIE2"),
+    state("S_BOS2_DIE2", "BOS2", 9, 8, Some("A_Scream"), "S_BOS2_DIE3"),
+    state("S_BOS2_DIE3", "BOS2", 10, 8, None, "S_BOS2_DIE4"),
+    state("S_BOS2_DIE4", "BOS2", 11, 8, Some("A_Fall"), "S_BOS2_DIE5"),
+    state("S_BOS2_DIE5", "BOS2", 12, 8, None, "S_BOS2_DIE6"),
+    state("S_BOS2_DIE6", "BOS2", 13, 8, None, "S_BOS2_DIE7"),
+    state("S_BOS2_DIE7", "BOS2", 14, -1, None, "S_NULL"),
+    state("S_BOS2_RAISE1", "BOS2", 14, 8, None, "S_BOS2_RAISE2"),
+    state("S_BOS2_RAISE2", "BOS2", 13, 8, None, "S_BOS2_RAISE3"),
+    state("S_BOS2_RAISE3", "BOS2", 12, 8, None, "S_BOS2_RAISE4"),
+    state("S_BOS2_RAISE4", "BOS2", 11, 8, None, "S_BOS2_RAISE5"),
+    state("S_BOS2_RAISE5", "BOS2", 10, 8, None, "S_BOS2_RAISE6"),
+    state("S_BOS2_RAISE6", "BOS2", 9, 8, None, "S_BOS2_RAISE7"),
+    state("S_BOS2_RAISE7", "BOS2", 8, 8, None, "S_BOS2_RUN1"),
+    state(
+        "S_SKULL_STND",
+        "SKUL",
+        FF_FULLBRIGHT,
+        10,
+        Some("A_Look"),
+        "S_SKULL_STND2",
+    ),
+    state(
+        "S_SKULL_STND2",
+        "SKUL",
+        FF_FULLBRIGHT | 1,
+        10,
+        Some("A_Look"),
+        "S_SKULL_STND",
+    ),
+    state(
+        "S_SKULL_RUN1",
+        "SKUL",
+        FF_FULLBRIGHT,
+        6,
+        Some("A_Chase"),
+        "S_SKULL_RUN2",
+    ),
+    state(
+        "S_SKULL_RUN2",
+        "SKUL",
+        FF_FULLBRIGHT | 1,
+        6,
+        Some("A_Chase"),
+        "S_SKULL_RUN1",
+    ),
+    state(
+        "S_SKULL_ATK1",
+        "SKUL",
+        FF_FULLBRIGHT | 2,
+        10,
+        Some("A_FaceTarget"),
+        "S_SKULL_ATK2",
+    ),
+    state(
+        "S_SKULL_ATK2",
+        "SKUL",
+        FF_FULLBRIGHT | 3,
+        4,
+        Some("A_SkullAttack"),
+        "S_SKULL_ATK3",
+    ),
+    state(
+        "S_SKULL_ATK3",
+        "SKUL",
+        FF_FULLBRIGHT | 2,
+        4,
+        None,
+        "S_SKULL_ATK4",
+    ),
+    state(
+        "S_SKULL_ATK4",
+        "SKUL",
+        FF_FULLBRIGHT | 3,
+        4,
+        None,
+        "S_SKULL_ATK3",
+    ),
+    state(
+        "S_SKULL_PAIN",
+        "SKUL",
+        FF_FULLBRIGHT | 4,
+        3,
+        None,
+        "S_SKULL_PAIN2",
+    ),
+    state(
+        "S_SKULL_PAIN2",
+        "SKUL",
+        FF_FULLBRIGHT | 4,
+        3,
+        Some("A_Pain"),
+        "S_SKULL_RUN1",
+    ),
+    state(
+        "S_SKULL_DIE1",
+        "SKUL",
+        FF_FULLBRIGHT | 5,
+        6,
+        None,
+        "S_SKULL_DIE2",
+    ),
+    state(
+        "S_SKULL_DIE2",
+        "SKUL",
+        FF_FULLBRIGHT | 6,
+        6,
+        Some("A_Scream"),
+        "S_SKULL_DIE3",
+    ),
+    state(
+        "S_SKULL_DIE3",
+        "SKUL",
+        FF_FULLBRIGHT | 7,
+        6,
+        None,
+        "S_SKULL_DIE4",
+    ),
+    state(
+        "S_SKULL_DIE4",
+        "SKUL",
+        FF_FULLBRIGHT | 8,
+        6,
+        Some("A_Fall"),
+        "S_SKULL_DIE5",
+    ),
+    state("S_SKULL_DIE5", "SKUL", 9, 6, None, "S_SKULL_DIE6"),
+    state("S_SKULL_DIE6", "SKUL", 10, 6, None, "S_NULL"),
+    state("S_SPID_STND", "SPID", 0, 10, Some("A_Look"), "S_SPID_STND2"),
+    state("S_SPID_STND2", "SPID", 1, 10, Some("A_Look"), "S_SPID_STND"),
+    state("S_SPID_RUN1", "SPID", 0, 3, Some("A_Metal"), "S_SPID_RUN2"),
+    state("S_SPID_RUN2", "SPID", 0, 3, Some("A_Chase"), "S_SPID_RUN3"),
+    state("S_SPID_RUN3", "SPID", 1, 3, Some("A_Chase"), "S_SPID_RUN4"),
+    state("S_SPID_RUN4", "SPID", 1, 3, Some("A_Chase"), "S_SPID_RUN5"),
+    state("S_SPID_RUN5", "SPID", 2, 3, Some("A_Metal"), "S_SPID_RUN6"),
+    state("S_SPID_RUN6", "SPID", 2, 3, Some("A_Chase"), "S_SPID_RUN7"),
+    state("S_SPID_RUN7", "SPID", 3, 3, Some("A_Chase"), "S_SPID_RUN8"),
+    state("S_SPID_RUN8", "SPID", 3, 3, Some("A_Chase"), "S_SPID_RUN9"),
+    state("S_SPID_RUN9", "SPID", 4, 3, Some("A_Metal"), "S_SPID_RUN10"),
+    state(
+        "S_SPID_RUN10",
+        "SPID",
+        4,
+        3,
+        Some("A_Chase"),
+        "S_SPID_RUN11",
+    ),
+    state(
+        "S_SPID_RUN11",
+        "SPID",
+        5,
+        3,
+        Some("A_Chase"),
+        "S_SPID_RUN12",
+    ),
+    state("S_SPID_RUN12", "SPID", 5, 3, Some("A_Chase"), "S_SPID_RUN1"),
+    state(
+        "S_SPID_ATK1",
+        "SPID",
+        FF_FULLBRIGHT,
+        20,
+        Some("A_FaceTarget"),
+        "S_SPID_ATK2",
+    ),
+    state(
+        "S_SPID_ATK2",
+        "SPID",
+        FF_FULLBRIGHT | 6,
+        4,
+        Some("A_SPosAttack"),
+        "S_SPID_ATK3",
+    ),
+    state(
+        "S_SPID_ATK3",
+        "SPID",
+        FF_FULLBRIGHT | 7,
+        4,
+        Some("A_SPosAttack"),
+        "S_SPID_ATK4",
+    ),
+    state(
+        "S_SPID_ATK4",
+        "SPID",
+        FF_FULLBRIGHT | 7,
+        1,
+        Some("A_SpidRefire"),
+        "S_SPID_ATK2",
+    ),
+    state("S_SPID_PAIN", "SPID", 8, 3, None, "S_SPID_PAIN2"),
+    state("S_SPID_PAIN2", "SPID", 8, 3, Some("A_Pain"), "S_SPID_RUN1"),
+    state(
+        "S_SPID_DIE1",
+        "SPID",
+        9,
+        20,
+        Some("A_Scream"),
+        "S_SPID_DIE2",
+    ),
+    state("S_SPID_DIE2", "SPID", 10, 10, Some("A_Fall"), "S_SPID_DIE3"),
+    state("S_SPID_DIE3", "SPID", 11, 10, None, "S_SPID_DIE4"),
+    state("S_SPID_DIE4", "SPID", 12, 10, None, "S_SPID_DIE5"),
+    state("S_SPID_DIE5", "SPID", 13, 10, None, "S_SPID_DIE6"),
+    state("S_SPID_DIE6", "SPID", 14, 10, None, "S_SPID_DIE7"),
+    state("S_SPID_DIE7", "SPID", 15, 10, None, "S_SPID_DIE8"),
+    state("S_SPID_DIE8", "SPID", 16, 10, None, "S_SPID_DIE9"),
+    state("S_SPID_DIE9", "SPID", 17, 10, None, "S_SPID_DIE10"),
+    state("S_SPID_DIE10", "SPID", 18, 30, None, "S_SPID_DIE11"),
+    state(
+        "S_SPID_DIE11",
+        "SPID",
+        18,
+        -1,
+        Some("A_BossDeath"),
+        "S_NULL",
+    ),
+    state("S_BSPI_STND", "BSPI", 0, 10, Some("A_Look"), "S_BSPI_STND2"),
+    state("S_BSPI_STND2", "BSPI", 1, 10, Some("A_Look"), "S_BSPI_STND"),
+    state("S_BSPI_SIGHT", "BSPI", 0, 20, None, "S_BSPI_RUN1"),
+    state(
+        "S_BSPI_RUN1",
+        "BSPI",
+        0,
+        3,
+        Some("A_BabyMetal"),
+        "S_BSPI_RUN2",
+    ),
+    state("S_BSPI_RUN2", "BSPI", 0, 3, Some("A_Chase"), "S_BSPI_RUN3"),
+    state("S_BSPI_RUN3", "BSPI", 1, 3, Some("A_Chase"), "S_BSPI_RUN4"),
+    state("S_BSPI_RUN4", "BSPI", 1, 3, Some("A_Chase"), "S_BSPI_RUN5"),
+    state("S_BSPI_RUN5", "BSPI", 2, 3, Some("A_Chase"), "S_BSPI_RUN6"),
+    state("S_BSPI_RUN6", "BSPI", 2, 3, Some("A_Chase"), "S_BSPI_RUN7"),
+    state(
+        "S_BSPI_RUN7",
+        "BSPI",
+        3,
+        3,
+        Some("A_BabyMetal"),
+        "S_BSPI_RUN8",
+    ),
+    state("S_BSPI_RUN8", "BSPI", 3, 3, Some("A_Chase"), "S_BSPI_RUN9"),
+    state("S_BSPI_RUN9", "BSPI", 4, 3, Some("A_Chase"), "S_BSPI_RUN10"),
+    state(
+        "S_BSPI_RUN10",
+        "BSPI",
+        4,
+        3,
+        Some("A_Chase"),
+        "S_BSPI_RUN11",
+    ),
+    state(
+        "S_BSPI_RUN11",
+        "BSPI",
+        5,
+        3,
+        Some("A_Chase"),
+        "S_BSPI_RUN12",
+    ),
+    state("S_BSPI_RUN12", "BSPI", 5, 3, Some("A_Chase"), "S_BSPI_RUN1"),
+    state(
+        "S_BSPI_ATK1",
+        "BSPI",
+        FF_FULLBRIGHT,
+        20,
+        Some("A_FaceTarget"),
+        "S_BSPI_ATK2",
+    ),
+    state(
+        "S_BSPI_ATK2",
+        "BSPI",
+        FF_FULLBRIGHT | 6,
+        4,
+        Some("A_BspiAttack"),
+        "S_BSPI_ATK3",
+    ),
+    state(
+        "S_BSPI_ATK3",
+        "BSPI",
+        FF_FULLBRIGHT | 7,
+        4,
+        None,
+        "S_BSPI_ATK4",
+    ),
+    state(
+        "S_BSPI_ATK4",
+        "BSPI",
+        FF_FULLBRIGHT | 7,
+        20,
+        Some("A_SpidRefire"),
+        "S_BSPI_ATK2",
+    ),
+    state("S_BSPI_PAIN", "BSPI", 8, 3, None, "S_BSPI_PAIN2"),
+    state("S_BSPI_PAIN2", "BSPI", 8, 3, Some("A_Pain"), "S_BSPI_RUN1"),
+    state(
+        "S_BSPI_DIE1",
+        "BSPI",
+        9,
+        20,
+        Some("A_Scream"),
+        "S_BSPI_DIE2",
+    ),
+    state("S_BSPI_DIE2", "BSPI", 10, 7, Some("A_Fall"), "S_BSPI_DIE3"),
+    state("S_BSPI_DIE3", "BSPI", 11, 7, None, "S_BSPI_DIE4"),
+    state("S_BSPI_DIE4", "BSPI", 12, 7, None, "S_BSPI_DIE5"),
+    state("S_BSPI_DIE5", "BSPI", 13, 7, None, "S_BSPI_DIE6"),
+    state("S_BSPI_DIE6", "BSPI", 14, 7, None, "S_BSPI_DIE7"),
+    state("S_BSPI_DIE7", "BSPI", 15, -1, Some("A_BossDeath"), "S_NULL"),
+    state("S_BSPI_RAISE1", "BSPI", 15, 5, None, "S_BSPI_RAISE2"),
+    state("S_BSPI_RAISE2", "BSPI", 14, 5, None, "S_BSPI_RAISE3"),
+    state("S_BSPI_RAISE3", "BSPI", 13, 5, None, "S_BSPI_RAISE4"),
+    state("S_BSPI_RAISE4", "BSPI", 12, 5, None, "S_BSPI_RAISE5"),
+    state("S_BSPI_RAISE5", "BSPI", 11, 5, None, "S_BSPI_RAISE6"),
+    state("S_BSPI_RAISE6", "BSPI", 10, 5, None, "S_BSPI_RAISE7"),
+    state("S_BSPI_RAISE7", "BSPI", 9, 5, None, "S_BSPI_RUN1"),
+    state(
+        "S_ARACH_PLAZ",
+        "APLS",
+        FF_FULLBRIGHT,
+        5,
+        None,
+        "S_ARACH_PLAZ2",
+    ),
+    state(
+        "S_ARACH_PLAZ2",
+        "APLS",
+        FF_FULLBRIGHT | 1,
+        5,
+        None,
+        "S_ARACH_PLAZ",
+    ),
+    state(
+        "S_ARACH_PLEX",
+        "APBX",
+        FF_FULLBRIGHT,
+        5,
+        None,
+        "S_ARACH_PLEX2",
+    ),
+    state(
+        "S_ARACH_PLEX2",
+        "APBX",
+        FF_FULLBRIGHT | 1,
+        5,
+        None,
+        "S_ARACH_PLEX3",
+    ),
+    state(
+        "S_ARACH_PLEX3",
+        "APBX",
+        FF_FULLBRIGHT | 2,
+        5,
+        None,
+        "S_ARACH_PLEX4",
+    ),
+    state(
+        "S_ARACH_PLEX4",
+        "APBX",
+        FF_FULLBRIGHT | 3,
+        5,
+        None,
+        "S_ARACH_PLEX5",
+    ),
+    state(
+        "S_ARACH_PLEX5",
+        "APBX",
+        FF_FULLBRIGHT | 4,
+        5,
+        None,
+        "S_NULL",
+    ),
+    state(
+        "S_CYBER_STND",
+        "CYBR",
+        0,
+        10,
+        Some("A_Look"),
+        "S_CYBER_STND2",
+    ),
+    state(
+        "S_CYBER_STND2",
+        "CYBR",
+        1,
+        10,
+        Some("A_Look"),
+        "S_CYBER_STND",
+    ),
+    state("S_CYBER_RUN1", "CYBR", 0, 3, Some("A_Hoof"), "S_CYBER_RUN2"),
+    state(
+        "S_CYBER_RUN2",
+        "CYBR",
+        0,
+        3,
+        Some("A_Chase"),
+        "S_CYBER_RUN3",
+    ),
+    state(
+        "S_CYBER_RUN3",
+        "CYBR",
+        1,
+        3,
+        Some("A_Chase"),
+        "S_CYBER_RUN4",
+    ),
+    state(
+        "S_CYBER_RUN4",
+        "CYBR",
+        1,
+        3,
+        Some("A_Chase"),
+        "S_CYBER_RUN5",
+    ),
+    state(
+        "S_CYBER_RUN5",
+        "CYBR",
+        2,
+        3,
+        Some("A_Chase"),
+        "S_CYBER_RUN6",
+    ),
+    state(
+        "S_CYBER_RUN6",
+        "CYBR",
+        2,
+        3,
+        Some("A_Chase"),
+        "S_CYBER_RUN7",
+    ),
+    state(
+        "S_CYBER_RUN7",
+        "CYBR",
+        3,
+        3,
+        Some("A_Metal"),
+        "S_CYBER_RUN8",
+    ),
+    state(
+        "S_CYBER_RUN8",
+        "CYBR",
+        3,
+        3,
+        Some("A_Chase"),
+        "S_CYBER_RUN1",
+    ),
+    state(
+        "S_CYBER_ATK1",
+        "CYBR",
+        4,
+        6,
+        Some("A_FaceTarget"),
+        "S_CYBER_ATK2",
+    ),
+    state(
+        "S_CYBER_ATK2",
+        "CYBR",
+        5,
+        12,
+        Some("A_CyberAttack"),
+        "S_CYBER_ATK3",
+    ),
+    state(
+        "S_CYBER_ATK3",
+        "CYBR",
+        4,
+        12,
+        Some("A_FaceTarget"),
+        "S_CYBER_ATK4",
+    ),
+    state(
+        "S_CYBER_ATK4",
+        "CYBR",
+        5,
+        12,
+        Some("A_CyberAttack"),
+        "S_CYBER_ATK5",
+    ),
+    state(
+        "S_CYBER_ATK5",
+        "CYBR",
+        4,
+        12,
+        Some("A_FaceTarget"),
+        "S_CYBER_ATK6",
+    ),
+    state(
+        "S_CYBER_ATK6",
+        "CYBR",
+        5,
+        12,
+        Some("A_CyberAttack"),
+        "S_CYBER_RUN1",
+    ),
+    state(
+        "S_CYBER_PAIN",
+        "CYBR",
+        6,
+        10,
+        Some("A_Pain"),
+        "S_CYBER_RUN1",
+    ),
+    state("S_CYBER_DIE1", "CYBR", 7, 10, None, "S_CYBER_DIE2"),
+    state(
+        "S_CYBER_DIE2",
+        "CYBR",
+        8,
+        10,
+        Some("A_Scream"),
+        "S_CYBER_DIE3",
+    ),
+    state("S_CYBER_DIE3", "CYBR", 9, 10, None, "S_CYBER_DIE4"),
+    state("S_CYBER_DIE4", "CYBR", 10, 10, None, "S_CYBER_DIE5"),
+    state("S_CYBER_DIE5", "CYBR", 11, 10, None, "S_CYBER_DIE6"),
+    state(
+        "S_CYBER_DIE6",
+        "CYBR",
+        12,
+        10,
+        Some("A_Fall"),
+        "S_CYBER_DIE7",
+    ),
+    state("S_CYBER_DIE7", "CYBR", 13, 10, None, "S_CYBER_DIE8"),
+    state("S_CYBER_DIE8", "CYBR", 14, 10, None, "S_CYBER_DIE9"),
+    state("S_CYBER_DIE9", "CYBR", 15, 30, None, "S_CYBER_DIE10"),
+    state(
+        "S_CYBER_DIE10",
+        "CYBR",
+        15,
+        -1,
+        Some("A_BossDeath"),
+        "S_NULL",
+    ),
+    state("S_PAIN_STND", "PAIN", 0, 10, Some("A_Look"), "S_PAIN_STND"),
+    state("S_PAIN_RUN1", "PAIN", 0, 3, Some("A_Chase"), "S_PAIN_RUN2"),
+    state("S_PAIN_RUN2", "PAIN", 0, 3, Some("A_Chase"), "S_PAIN_RUN3"),
+    state("S_PAIN_RUN3", "PAIN", 1, 3, Some("A_Chase"), "S_PAIN_RUN4"),
+    state("S_PAIN_RUN4", "PAIN", 1, 3, Some("A_Chase"), "S_PAIN_RUN5"),
+    state("S_PAIN_RUN5", "PAIN", 2, 3, Some("A_Chase"), "S_PAIN_RUN6"),
+    state("S_PAIN_RUN6", "PAIN", 2, 3, Some("A_Chase"), "S_PAIN_RUN1"),
+    state(
+        "S_PAIN_ATK1",
+        "PAIN",
+        3,
+        5,
+        Some("A_FaceTarget"),
+        "S_PAIN_ATK2",
+    ),
+    state(
+        "S_PAIN_ATK2",
+        "PAIN",
+        4,
+        5,
+        Some("A_FaceTarget"),
+        "S_PAIN_ATK3",
+    ),
+    state(
+        "S_PAIN_ATK3",
+        "PAIN",
+        FF_FULLBRIGHT | 5,
+        5,
+        Some("A_FaceTarget"),
+        "S_PAIN_ATK4",
+    ),
+    state(
+        "S_PAIN_ATK4",
+        "PAIN",
+        FF_FULLBRIGHT | 5,
+        0,
+        Some("A_PainAttack"),
+        "S_PAIN_RUN1",
+    ),
+    state("S_PAIN_PAIN", "PAIN", 6, 6, None, "S_PAIN_PAIN2"),
+    state("S_PAIN_PAIN2", "PAIN", 6, 6, Some("A_Pain"), "S_PAIN_RUN1"),
+    state(
+        "S_PAIN_DIE1",
+        "PAIN",
+        FF_FULLBRIGHT | 7,
+        8,
+        None,
+        "S_PAIN_DIE2",
+    ),
+    state(
+        "S_PAIN_DIE2",
+        "PAIN",
+        FF_FULLBRIGHT | 8,
+        8,
+        Some("A_Scream"),
+        "S_PAIN_DIE3",
+    ),
+    state(
+        "S_PAIN_DIE3",
+        "PAIN",
+        FF_FULLBRIGHT | 9,
+        8,
+        None,
+        "S_PAIN_DIE4",
+    ),
+    state(
+        "S_PAIN_DIE4",
+        "PAIN",
+        FF_FULLBRIGHT | 10,
+        8,
+        None,
+        "S_PAIN_DIE5",
+    ),
+    state(
+        "S_PAIN_DIE5",
+        "PAIN",
+        FF_FULLBRIGHT | 11,
+        8,
+        Some("A_PainDie"),
+        "S_PAIN_DIE6",
+    ),
+    state("S_PAIN_DIE6", "PAIN", FF_FULLBRIGHT | 12, 8, None, "S_NULL"),
+    state("S_PAIN_RAISE1", "PAIN", 12, 8, None, "S_PAIN_RAISE2"),
+    state("S_PAIN_RAISE2", "PAIN", 11, 8, None, "S_PAIN_RAISE3"),
+    state("S_PAIN_RAISE3", "PAIN", 10, 8, None, "S_PAIN_RAISE4"),
+    state("S_PAIN_RAISE4", "PAIN", 9, 8, None, "S_PAIN_RAISE5"),
+    state("S_PAIN_RAISE5", "PAIN", 8, 8, None, "S_PAIN_RAISE6"),
+    state("S_PAIN_RAISE6", "PAIN", 7, 8, None, "S_PAIN_RUN1"),
+    state("S_SSWV_STND", "SSWV", 0, 10, Some("A_Look"), "S_SSWV_STND2"),
+    state("S_SSWV_STND2", "SSWV", 1, 10, Some("A_Look"), "S_SSWV_STND"),
+    state("S_SSWV_RUN1", "SSWV", 0, 3, Some("A_Chase"), "S_SSWV_RUN2"),
+    state("S_SSWV_RUN2", "SSWV", 0, 3, Some("A_Chase"), "S_SSWV_RUN3"),
+    state("S_SSWV_RUN3", "SSWV", 1, 3, Some("A_Chase"), "S_SSWV_RUN4"),
+    state("S_SSWV_RUN4", "SSWV", 1, 3, Some("A_Chase"), "S_SSWV_RUN5"),
+    state("S_SSWV_RUN5", "SSWV", 2, 3, Some("A_Chase"), "S_SSWV_RUN6"),
+    state("S_SSWV_RUN6", "SSWV", 2, 3, Some("A_Chase"), "S_SSWV_RUN7"),
+    state("S_SSWV_RUN7", "SSWV", 3, 3, Some("A_Chase"), "S_SSWV_RUN8"),
+    state("S_SSWV_RUN8", "SSWV", 3, 3, Some("A_Chase"), "S_SSWV_RUN1"),
+    state(
+        "S_SSWV_ATK1",
+        "SSWV",
+        4,
+        10,
+        Some("A_FaceTarget"),
+        "S_SSWV_ATK2",
+    ),
+    state(
+        "S_SSWV_ATK2",
+        "SSWV",
+        5,
+        10,
+        Some("A_FaceTarget"),
+        "S_SSWV_ATK3",
+    ),
+    state(
+        "S_SSWV_ATK3",
+        "SSWV",
+        FF_FULLBRIGHT | 6,
+        4,
+        Some("A_CPosAttack"),
+        "S_SSWV_ATK4",
+    ),
+    state(
+        "S_SSWV_ATK4",
+        "SSWV",
+        5,
+        6,
+        Some("A_FaceTarget"),
+        "S_SSWV_ATK5",
+    ),
+    state(
+        "S_SSWV_ATK5",
+        "SSWV",
+        FF_FULLBRIGHT | 6,
+        4,
+        Some("A_CPosAttack"),
+        "S_SSWV_ATK6",
+    ),
+    state(
+        "S_SSWV_ATK6",
+        "SSWV",
+        5,
+        1,
+        Some("A_CPosRefire"),
+        "S_SSWV_ATK2",
+    ),
+    state("S_SSWV_PAIN", "SSWV", 7, 3, None, "S_SSWV_PAIN2"),
+    state("S_SSWV_PAIN2", "SSWV", 7, 3, Some("A_Pain"), "S_SSWV_RUN1"),
+    state("S_SSWV_DIE1", "SSWV", 8, 5, None, "S_SSWV_DIE2"),
+    state("S_SSWV_DIE2", "SSWV", 9, 5, Some("A_Scream"), "S_SSWV_DIE3"),
+    state("S_SSWV_DIE3", "SSWV", 10, 5, Some("A_Fall"), "S_SSWV_DIE4"),
+    state("S_SSWV_DIE4", "SSWV", 11, 5, None, "S_SSWV_DIE5"),
+    state("S_SSWV_DIE5", "SSWV", 12, -1, None, "S_NULL"),
+    state("S_SSWV_XDIE1", "SSWV", 13, 5, None, "S_SSWV_XDIE2"),
+    state(
+        "S_SSWV_XDIE2",
+        "SSWV",
+        14,
+        5,
+        Some("A_XScream"),
+        "S_SSWV_XDIE3",
+    ),
+    state(
+        "S_SSWV_XDIE3",
+        "SSWV",
+        15,
+        5,
+        Some("A_Fall"),
+        "S_SSWV_XDIE4",
+    ),
+    state("S_SSWV_XDIE4", "SSWV", 16, 5, None, "S_SSWV_XDIE5"),
+    state("S_SSWV_XDIE5", "SSWV", 17, 5, None, "S_SSWV_XDIE6"),
+    state("S_SSWV_XDIE6", "SSWV", 18, 5, None, "S_SSWV_XDIE7"),
+    state("S_SSWV_XDIE7", "SSWV", 19, 5, None, "S_SSWV_XDIE8"),
+    state("S_SSWV_XDIE8", "SSWV", 20, 5, None, "S_SSWV_XDIE9"),
+    state("S_SSWV_XDIE9", "SSWV", 21, -1, None, "S_NULL"),
+    state("S_SSWV_RAISE1", "SSWV", 12, 5, None, "S_SSWV_RAISE2"),
+    state("S_SSWV_RAISE2", "SSWV", 11, 5, None, "S_SSWV_RAISE3"),
+    state("S_SSWV_RAISE3", "SSWV", 10, 5, None, "S_SSWV_RAISE4"),
+    state("S_SSWV_RAISE4", "SSWV", 9, 5, None, "S_SSWV_RAISE5"),
+    state("S_SSWV_RAISE5", "SSWV", 8, 5, None, "S_SSWV_RUN1"),
+    state("S_KEENSTND", "KEEN", 0, -1, None, "S_KEENSTND"),
+    state("S_COMMKEEN", "KEEN", 0, 6, None, "S_COMMKEEN2"),
+    state("S_COMMKEEN2", "KEEN", 1, 6, None, "S_COMMKEEN3"),
+    state("S_COMMKEEN3", "KEEN", 2, 6, Some("A_Scream"), "S_COMMKEEN4"),
+    state("S_COMMKEEN4", "KEEN", 3, 6, None, "S_COMMKEEN5"),
+    state("S_COMMKEEN5", "KEEN", 4, 6, None, "S_COMMKEEN6"),
+    state("S_COMMKEEN6", "KEEN", 5, 6, None, "S_COMMKEEN7"),
+    state("S_COMMKEEN7", "KEEN", 6, 6, None, "S_COMMKEEN8"),
+    state("S_COMMKEEN8", "KEEN", 7, 6, None, "S_COMMKEEN9"),
+    state("S_COMMKEEN9", "KEEN", 8, 6, None, "S_COMMKEEN10"),
+    state("S_COMMKEEN10", "KEEN", 9, 6, None, "S_COMMKEEN11"),
+    state(
+        "S_COMMKEEN11",
+        "KEEN",
+        10,
+        6,
+        Some("A_KeenDie"),
+        "S_COMMKEEN12",
+    ),
+    state("S_COMMKEEN12", "KEEN", 11, -1, None, "S_NULL"),
+    state("S_KEENPAIN", "KEEN", 12, 4, None, "S_KEENPAIN2"),
+    state("S_KEENPAIN2", "KEEN", 12, 8, Some("A_Pain"), "S_KEENSTND"),
+    state("S_BRAIN", "BBRN", 0, -1, None, "S_NULL"),
+    state(
+        "S_BRAIN_PAIN",
+        "BBRN",
+        1,
+        36,
+        Some("A_BrainPain"),
+        "S_BRAIN",
+    ),
+    state(
+        "S_BRAIN_DIE1",
+        "BBRN",
+        0,
+        100,
+        Some("A_BrainScream"),
+        "S_BRAIN_DIE2",
+    ),
+    state("S_BRAIN_DIE2", "BBRN", 0, 10, None, "S_BRAIN_DIE3"),
+    state("S_BRAIN_DIE3", "BBRN", 0, 10, None, "S_BRAIN_DIE4"),
+    state("S_BRAIN_DIE4", "BBRN", 0, -1, Some("A_BrainDie"), "S_NULL"),
+    state("S_BRAINEYE", "SSWV", 0, 10, Some("A_Look"), "S_BRAINEYE"),
+    state(
+        "S_BRAINEYESEE",
+        "SSWV",
+        0,
+        181,
+        Some("A_BrainAwake"),
+        "S_BRAINEYE1",
+    ),
+    state(
+        "S_BRAINEYE1",
+        "SSWV",
+        0,
+        150,
+        Some("A_BrainSpit"),
+        "S_BRAINEYE1",
+    ),
+    state(
+        "S_SPAWN1",
+        "BOSF",
+        FF_FULLBRIGHT,
+        3,
+        Some("A_SpawnSound"),
+        "S_SPAWN2",
+    ),
+    state(
+        "S_SPAWN2",
+        "BOSF",
+        FF_FULLBRIGHT | 1,
+        3,
+        Some("A_SpawnFly"),
+        "S_SPAWN3",
+    ),
+    state(
+        "S_SPAWN3",
+        "BOSF",
+        FF_FULLBRIGHT | 2,
+        3,
+        Some("A_SpawnFly"),
+        "S_SPAWN4",
+    ),
+    state(
+        "S_SPAWN4",
+        "BOSF",
+        FF_FULLBRIGHT | 3,
+        3,
+        Some("A_SpawnFly"),
+        "S_SPAWN1",
+    ),
+    state(
+        "S_SPAWNFIRE1",
+        "FIRE",
+        FF_FULLBRIGHT,
+        4,
+        Some("A_Fire"),
+        "S_SPAWNFIRE2",
+    ),
+    state(
+        "S_SPAWNFIRE2",
+        "FIRE",
+        FF_FULLBRIGHT | 1,
+        4,
+        Some("A_Fire"),
+        "S_SPAWNFIRE3",
+    ),
+    state(
+        "S_SPAWNFIRE3",
+        "FIRE",
+        FF_FULLBRIGHT | 2,
+        4,
+        Some("A_Fire"),
+        "S_SPAWNFIRE4",
+    ),
+    state(
+        "S_SPAWNFIRE4",
+        "FIRE",
+        FF_FULLBRIGHT | 3,
+        4,
+        Some("A_Fire"),
+        "S_SPAWNFIRE5",
+    ),
+    state(
+        "S_SPAWNFIRE5",
+        "FIRE",
+        FF_FULLBRIGHT | 4,
+        4,
+        Some("A_Fire"),
+        "S_SPAWNFIRE6",
+    ),
+    state(
+        "S_SPAWNFIRE6",
+        "FIRE",
+        FF_FULLBRIGHT | 5,
+        4,
+        Some("A_Fire"),
+        "S_SPAWNFIRE7",
+    ),
+    state(
+        "S_SPAWNFIRE7",
+        "FIRE",
+        FF_FULLBRIGHT | 6,
+        4,
+        Some("A_Fire"),
+        "S_SPAWNFIRE8",
+    ),
+    state(
+        "S_SPAWNFIRE8",
+        "FIRE",
+        FF_FULLBRIGHT | 7,
+        4,
+        Some("A_Fire"),
+        "S_NULL",
+    ),
+    state(
+        "S_BRAINEXPLODE1",
+        "MISL",
+        FF_FULLBRIGHT | 1,
+        10,
+        None,
+        "S_BRAINEXPLODE2",
+    ),
+    state(
+        "S_BRAINEXPLODE2",
+        "MISL",
+        FF_FULLBRIGHT | 2,
+        10,
+        None,
+        "S_BRAINEXPLODE3",
+    ),
+    state(
+        "S_BRAINEXPLODE3",
+        "MISL",
+        FF_FULLBRIGHT | 3,
+        10,
+        Some("A_BrainExplode"),
+        "S_NULL",
+    ),
+    state("S_ARM1", "ARM1", 0, 6, None, "S_ARM1A"),
+    state("S_ARM1A", "ARM1", FF_FULLBRIGHT | 1, 7, None, "S_ARM1"),
+    state("S_ARM2", "ARM2", 0, 6, None, "S_ARM2A"),
+    state("S_ARM2A", "ARM2", FF_FULLBRIGHT | 1, 6, None, "S_ARM2"),
+    state("S_BAR1", "BAR1", 0, 6, None, "S_BAR2"),
+    state("S_BAR2", "BAR1", 1, 6, None, "S_BAR1"),
+    state("S_BEXP", "BEXP", FF_FULLBRIGHT, 5, None, "S_BEXP2"),
+    state(
+        "S_BEXP2",
+        "BEXP",
+        FF_FULLBRIGHT | 1,
+        5,
+        Some("A_Scream"),
+        "S_BEXP3",
+    ),
+    state("S_BEXP3", "BEXP", FF_FULLBRIGHT | 2, 5, None, "S_BEXP4"),
+    state(
+        "S_BEXP4",
+        "BEXP",
+        FF_FULLBRIGHT | 3,
+        10,
+        Some("A_Explode"),
+        "S_BEXP5",
+    ),
+    state("S_BEXP5", "BEXP", FF_FULLBRIGHT | 4, 10, None, "S_NULL"),
+    state("S_BBAR1", "FCAN", FF_FULLBRIGHT, 4, None, "S_BBAR2"),
+    state("S_BBAR2", "FCAN", FF_FULLBRIGHT | 1, 4, None, "S_BBAR3"),
+    state("S_BBAR3", "FCAN", FF_FULLBRIGHT | 2, 4, None, "S_BBAR1"),
+    state("S_BON1", "BON1", 0, 6, None, "S_BON1A"),
+    state("S_BON1A", "BON1", 1, 6, None, "S_BON1B"),
+    state("S_BON1B", "BON1", 2, 6, None, "S_BON1C"),
+    state("S_BON1C", "BON1", 3, 6, None, "S_BON1D"),
+    state("S_BON1D", "BON1", 2, 6, None, "S_BON1E"),
+    state("S_BON1E", "BON1", 1, 6, None, "S_BON1"),
+    state("S_BON2", "BON2", 0, 6, None, "S_BON2A"),
+    state("S_BON2A", "BON2", 1, 6, None, "S_BON2B"),
+    state("S_BON2B", "BON2", 2, 6, None, "S_BON2C"),
+    state("S_BON2C", "BON2", 3, 6, None, "S_BON2D"),
+    state("S_BON2D", "BON2", 2, 6, None, "S_BON2E"),
+    state("S_BON2E", "BON2", 1, 6, None, "S_BON2"),
+    state("S_BKEY", "BKEY", 0, 10, None, "S_BKEY2"),
+    state("S_BKEY2", "BKEY", FF_FULLBRIGHT | 1, 10, None, "S_BKEY"),
+    state("S_RKEY", "RKEY", 0, 10, None, "S_RKEY2"),
+    state("S_RKEY2", "RKEY", FF_FULLBRIGHT | 1, 10, None, "S_RKEY"),
+    state("S_YKEY", "YKEY", 0, 10, None, "S_YKEY2"),
+    state("S_YKEY2", "YKEY", FF_FULLBRIGHT | 1, 10, None, "S_YKEY"),
+    state("S_BSKULL", "BSKU", 0, 10, None, "S_BSKULL2"),
+    state("S_BSKULL2", "BSKU", FF_FULLBRIGHT | 1, 10, None, "S_BSKULL"),
+    state("S_RSKULL", "RSKU", 0, 10, None, "S_RSKULL2"),
+    state("S_RSKULL2", "RSKU", FF_FULLBRIGHT | 1, 10, None, "S_RSKULL"),
+    state("S_YSKULL", "YSKU", 0, 10, None, "S_YSKULL2"),
+    state("S_YSKULL2", "YSKU", FF_FULLBRIGHT | 1, 10, None, "S_YSKULL"),
+    state("S_STIM", "STIM", 0, -1, None, "S_NULL"),
+    state("S_MEDI", "MEDI", 0, -1, None, "S_NULL"),
+    state("S_SOUL", "SOUL", FF_FULLBRIGHT, 6, None, "S_SOUL2"),
+    state("S_SOUL2", "SOUL", FF_FULLBRIGHT | 1, 6, None, "S_SOUL3"),
+    state("S_SOUL3", "SOUL", FF_FULLBRIGHT | 2, 6, None, "S_SOUL4"),
+    state("S_SOUL4", "SOUL", FF_FULLBRIGHT | 3, 6, None, "S_SOUL5"),
+    state("S_SOUL5", "SOUL", FF_FULLBRIGHT | 2, 6, None, "S_SOUL6"),
+    state("S_SOUL6", "SOUL", FF_FULLBRIGHT | 1, 6, None, "S_SOUL"),
+    state("S_PINV", "PINV", FF_FULLBRIGHT, 6, None, "S_PINV2"),
+    state("S_PINV2", "PINV", FF_FULLBRIGHT | 1, 6, None, "S_PINV3"),
+    state("S_PINV3", "PINV", FF_FULLBRIGHT | 2, 6, None, "S_PINV4"),
+    state("S_PINV4", "PINV", FF_FULLBRIGHT | 3, 6, None, "S_PINV"),
+    state("S_PSTR", "PSTR", FF_FULLBRIGHT, -1, None, "S_NULL"),
+    state("S_PINS", "PINS", FF_FULLBRIGHT, 6, None, "S_PINS2"),
+    state("S_PINS2", "PINS", FF_FULLBRIGHT | 1, 6, None, "S_PINS3"),
+    state("S_PINS3", "PINS", FF_FULLBRIGHT | 2, 6, None, "S_PINS4"),
+    state("S_PINS4", "PINS", FF_FULLBRIGHT | 3, 6, None, "S_PINS"),
+    state("S_MEGA", "MEGA", FF_FULLBRIGHT, 6, None, "S_MEGA2"),
+    state("S_MEGA2", "MEGA", FF_FULLBRIGHT | 1, 6, None, "S_MEGA3"),
+    state("S_MEGA3", "MEGA", FF_FULLBRIGHT | 2, 6, None, "S_MEGA4"),
+    state("S_MEGA4", "MEGA", FF_FULLBRIGHT | 3, 6, None, "S_MEGA"),
+    state("S_SUIT", "SUIT", FF_FULLBRIGHT, -1, None, "S_NULL"),
+    state("S_PMAP", "PMAP", FF_FULLBRIGHT, 6, None, "S_PMAP2"),
+    state("S_PMAP2", "PMAP", FF_FULLBRIGHT | 1, 6, None, "S_PMAP3"),
+    state("S_PMAP3", "PMAP", FF_FULLBRIGHT | 2, 6, None, "S_PMAP4"),
+    state("S_PMAP4", "PMAP", FF_FULLBRIGHT | 3, 6, None, "S_PMAP5"),
+    state("S_PMAP5", "PMAP", FF_FULLBRIGHT | 2, 6, None, "S_PMAP6"),
+    state("S_PMAP6", "PMAP", FF_FULLBRIGHT | 1, 6, None, "S_PMAP"),
+    state("S_PVIS", "PVIS", FF_FULLBRIGHT, 6, None, "S_PVIS2"),
+    state("S_PVIS2", "PVIS", 1, 6, None, "S_PVIS"),
+    state("S_CLIP", "CLIP", 0, -1, None, "S_NULL"),
+    state("S_AMMO", "AMMO", 0, -1, None, "S_NULL"),
+    state("S_ROCK", "ROCK", 0, -1, None, "S_NULL"),
+    state("S_BROK", "BROK", 0, -1, None, "S_NULL"),
+    state("S_CELL", "CELL", 0, -1, None, "S_NULL"),
+    state("S_CELP", "CELP", 0, -1, None, "S_NULL"),
+    state("S_SHEL", "SHEL", 0, -1, None, "S_NULL"),
+    state("S_SBOX", "SBOX", 0, -1, None, "S_NULL"),
+    state("S_BPAK", "BPAK", 0, -1, None, "S_NULL"),
+    state("S_BFUG", "BFUG", 0, -1, None, "S_NULL"),
+    state("S_MGUN", "MGUN", 0, -1, None, "S_NULL"),
+    state("S_CSAW", "CSAW", 0, -1, None, "S_NULL"),
+    state("S_LAUN", "LAUN", 0, -1, None, "S_NULL"),
+    state("S_PLAS", "PLAS", 0, -1, None, "S_NULL"),
+    state("S_SHOT", "SHOT", 0, -1, None, "S_NULL"),
+    state("S_SHOT2", "SGN2", 0, -1, None, "S_NULL"),
+    state("S_COLU", "COLU", FF_FULLBRIGHT, -1, None, "S_NULL"),
+    state("S_STALAG", "SMT2", 0, -1, None, "S_NULL"),
+    state("S_BLOODYTWITCH", "GOR1", 0, 10, None, "S_BLOODYTWITCH2"),
+    state("S_BLOODYTWITCH2", "GOR1", 1, 15, None, "S_BLOODYTWITCH3"),
+    state("S_BLOODYTWITCH3", "GOR1", 2, 8, None, "S_BLOODYTWITCH4"),
+    state("S_BLOODYTWITCH4", "GOR1", 1, 6, None, "S_BLOODYTWITCH"),
+    state("S_DEADTORSO", "PLAY", 13, -1, None, "S_NULL"),
+    state("S_DEADBOTTOM", "PLAY", 18, -1, None, "S_NULL"),
+    state("S_HEADSONSTICK", "POL2", 0, -1, None, "S_NULL"),
+    state("S_GIBS", "POL5", 0, -1, None, "S_NULL"),
+    state("S_HEADONASTICK", "POL4", 0, -1, None, "S_NULL"),
+    state(
+        "S_HEADCANDLES",
+        "POL3",
+        FF_FULLBRIGHT,
+        6,
+        None,
+        "S_HEADCANDLES2",
+    ),
+    state(
+        "S_HEADCANDLES2",
+        "POL3",
+        FF_FULLBRIGHT | 1,
+        6,
+        None,
+        "S_HEADCANDLES",
+    ),
+    state("S_DEADSTICK", "POL1", 0, -1, None, "S_NULL"),
+    state("S_LIVESTICK", "POL6", 0, 6, None, "S_LIVESTICK2"),
+    state("S_LIVESTICK2", "POL6", 1, 8, None, "S_LIVESTICK"),
+    state("S_MEAT2", "GOR2", 0, -1, None, "S_NULL"),
+    state("S_MEAT3", "GOR3", 0, -1, None, "S_NULL"),
+    state("S_MEAT4", "GOR4", 0, -1, None, "S_NULL"),
+    state("S_MEAT5", "GOR5", 0, -1, None, "S_NULL"),
+    state("S_STALAGTITE", "SMIT", 0, -1, None, "S_NULL"),
+    state("S_TALLGRNCOL", "COL1", 0, -1, None, "S_NULL"),
+    state("S_SHRTGRNCOL", "COL2", 0, -1, None, "S_NULL"),
+    state("S_TALLREDCOL", "COL3", 0, -1, None, "S_NULL"),
+    state("S_SHRTREDCOL", "COL4", 0, -1, None, "S_NULL"),
+    state("S_CANDLESTIK", "CAND", FF_FULLBRIGHT, -1, None, "S_NULL"),
+    state("S_CANDELABRA", "CBRA", FF_FULLBRIGHT, -1, None, "S_NULL"),
+    state("S_SKULLCOL", "COL6", 0, -1, None, "S_NULL"),
+    state("S_TORCHTREE", "TRE1", 0, -1, None, "S_NULL"),
+    state("S_BIGTREE", "TRE2", 0, -1, None, "S_NULL"),
+    state("S_TECHPILLAR", "ELEC", 0, -1, None, "S_NULL"),
+    state("S_EVILEYE", "CEYE", FF_FULLBRIGHT, 6, None, "S_EVILEYE2"),
+    state(
+        "S_EVILEYE2",
+        "CEYE",
+        FF_FULLBRIGHT | 1,
+        6,
+        None,
+        "S_EVILEYE3",
+    ),
+    state(
+        "S_EVILEYE3",
+        "CEYE",
+        FF_FULLBRIGHT | 2,
+        6,
+        None,
+        "S_EVILEYE4",
+    ),
+    state(
+        "S_EVILEYE4",
+        "CEYE",
+        FF_FULLBRIGHT | 1,
+        6,
+        None,
+        "S_EVILEYE",
+    ),
+    state(
+        "S_FLOATSKULL",
+        "FSKU",
+        FF_FULLBRIGHT,
+        6,
+        None,
+        "S_FLOATSKULL2",
+    ),
+    state(
+        "S_FLOATSKULL2",
+        "FSKU",
+        FF_FULLBRIGHT | 1,
+        6,
+        None,
+        "S_FLOATSKULL3",
+    ),
+    state(
+        "S_FLOATSKULL3",
+        "FSKU",
+        FF_FULLBRIGHT | 2,
+        6,
+        None,
+        "S_FLOATSKULL",
+    ),
+    state("S_HEARTCOL", "COL5", 0, 14, None, "S_HEARTCOL2"),
+    state("S_HEARTCOL2", "COL5", 1, 14, None, "S_HEARTCOL"),
+    state(
+        "S_BLUETORCH",
+        "TBLU",
+        FF_FULLBRIGHT,
+        4,
+        None,
+        "S_BLUETORCH2",
+    ),
+    state(
+        "S_BLUETORCH2",
+        "TBLU",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_BLUETORCH3",
+    ),
+    state(
+        "S_BLUETORCH3",
+        "TBLU",
+        FF_FULLBRIGHT | 2,
+        4,
+        None,
+        "S_BLUETORCH4",
+    ),
+    state(
+        "S_BLUETORCH4",
+        "TBLU",
+        FF_FULLBRIGHT | 3,
+        4,
+        None,
+        "S_BLUETORCH",
+    ),
+    state(
+        "S_GREENTORCH",
+        "TGRN",
+        FF_FULLBRIGHT,
+        4,
+        None,
+        "S_GREENTORCH2",
+    ),
+    state(
+        "S_GREENTORCH2",
+        "TGRN",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_GREENTORCH3",
+    ),
+    state(
+        "S_GREENTORCH3",
+        "TGRN",
+        FF_FULLBRIGHT | 2,
+        4,
+        None,
+        "S_GREENTORCH4",
+    ),
+    state(
+        "S_GREENTORCH4",
+        "TGRN",
+        FF_FULLBRIGHT | 3,
+        4,
+        None,
+        "S_GREENTORCH",
+    ),
+    state("S_REDTORCH", "TRED", FF_FULLBRIGHT, 4, None, "S_REDTORCH2"),
+    state(
+        "S_REDTORCH2",
+        "TRED",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_REDTORCH3",
+    ),
+    state(
+        "S_REDTORCH3",
+        "TRED",
+        FF_FULLBRIGHT | 2,
+        4,
+        None,
+        "S_REDTORCH4",
+    ),
+    state(
+        "S_REDTORCH4",
+        "TRED",
+        FF_FULLBRIGHT | 3,
+        4,
+        None,
+        "S_REDTORCH",
+    ),
+    state(
+        "S_BTORCHSHRT",
+        "SMBT",
+        FF_FULLBRIGHT,
+        4,
+        None,
+        "S_BTORCHSHRT2",
+    ),
+    state(
+        "S_BTORCHSHRT2",
+        "SMBT",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_BTORCHSHRT3",
+    ),
+    state(
+        "S_BTORCHSHRT3",
+        "SMBT",
+        FF_FULLBRIGHT | 2,
+        4,
+        None,
+        "S_BTORCHSHRT4",
+    ),
+    state(
+        "S_BTORCHSHRT4",
+        "SMBT",
+        FF_FULLBRIGHT | 3,
+        4,
+        None,
+        "S_BTORCHSHRT",
+    ),
+    state(
+        "S_GTORCHSHRT",
+        "SMGT",
+        FF_FULLBRIGHT,
+        4,
+        None,
+        "S_GTORCHSHRT2",
+    ),
+    state(
+        "S_GTORCHSHRT2",
+        "SMGT",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_GTORCHSHRT3",
+    ),
+    state(
+        "S_GTORCHSHRT3",
+        "SMGT",
+        FF_FULLBRIGHT | 2,
+        4,
+        None,
+        "S_GTORCHSHRT4",
+    ),
+    state(
+        "S_GTORCHSHRT4",
+        "SMGT",
+        FF_FULLBRIGHT | 3,
+        4,
+        None,
+        "S_GTORCHSHRT",
+    ),
+    state(
+        "S_RTORCHSHRT",
+        "SMRT",
+        FF_FULLBRIGHT,
+        4,
+        None,
+        "S_RTORCHSHRT2",
+    ),
+    state(
+        "S_RTORCHSHRT2",
+        "SMRT",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_RTORCHSHRT3",
+    ),
+    state(
+        "S_RTORCHSHRT3",
+        "SMRT",
+        FF_FULLBRIGHT | 2,
+        4,
+        None,
+        "S_RTORCHSHRT4",
+    ),
+    state(
+        "S_RTORCHSHRT4",
+        "SMRT",
+        FF_FULLBRIGHT | 3,
+        4,
+        None,
+        "S_RTORCHSHRT",
+    ),
+    state("S_HANGNOGUTS", "HDB1", 0, -1, None, "S_NULL"),
+    state("S_HANGBNOBRAIN", "HDB2", 0, -1, None, "S_NULL"),
+    state("S_HANGTLOOKDN", "HDB3", 0, -1, None, "S_NULL"),
+    state("S_HANGTSKULL", "HDB4", 0, -1, None, "S_NULL"),
+    state("S_HANGTLOOKUP", "HDB5", 0, -1, None, "S_NULL"),
+    state("S_HANGTNOBRAIN", "HDB6", 0, -1, None, "S_NULL"),
+    state("S_COLONGIBS", "POB1", 0, -1, None, "S_NULL"),
+    state("S_SMALLPOOL", "POB2", 0, -1, None, "S_NULL"),
+    state("S_BRAINSTEM", "BRS1", 0, -1, None, "S_NULL"),
+    state("S_TECHLAMP", "TLMP", FF_FULLBRIGHT, 4, None, "S_TECHLAMP2"),
+    state(
+        "S_TECHLAMP2",
+        "TLMP",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_TECHLAMP3",
+    ),
+    state(
+        "S_TECHLAMP3",
+        "TLMP",
+        FF_FULLBRIGHT | 2,
+        4,
+        None,
+        "S_TECHLAMP4",
+    ),
+    state(
+        "S_TECHLAMP4",
+        "TLMP",
+        FF_FULLBRIGHT | 3,
+        4,
+        None,
+        "S_TECHLAMP",
+    ),
+    state(
+        "S_TECH2LAMP",
+        "TLP2",
+        FF_FULLBRIGHT,
+        4,
+        None,
+        "S_TECH2LAMP2",
+    ),
+    state(
+        "S_TECH2LAMP2",
+        "TLP2",
+        FF_FULLBRIGHT | 1,
+        4,
+        None,
+        "S_TECH2LAMP3",
+    ),
+    state(
+        "S_TECH2LAMP3",
+        "TLP2",
+        FF_FULLBRIGHT | 2,
+        4,
+        None,
+        "S_TECH2LAMP4",
+    ),
+    state(
+        "S_TECH2LAMP4",
+        "TLP2",
+        FF_FULLBRIGHT | 3,
+        4,
+        None,
+        "S_TECH2LAMP",
+    ),
+];
+
+// Thing flags used by the vanilla tables. `THING_FLAGS` has the full list by name.
+const MF_SPECIAL: u32 = 0x0000_0001;
+const MF_SOLID: u32 = 0x0000_0002;
+const MF_SHOOTABLE: u32 = 0x0000_0004;
+const MF_NOSECTOR: u32 = 0x0000_0008;
+const MF_NOBLOCKMAP: u32 = 0x0000_0010;
+const MF_SPAWNCEILING: u32 = 0x0000_0100;
+const MF_NOGRAVITY: u32 = 0x0000_0200;
+const MF_DROPOFF: u32 = 0x0000_0400;
+const MF_PICKUP: u32 = 0x0000_0800;
+const MF_NOCLIP: u32 = 0x0000_1000;
+const MF_FLOAT: u32 = 0x0000_4000;
+const MF_SHADOW: u32 = 0x0004_0000;
+const MF_NOBLOOD: u32 = 0x0008_0000;
+const MF_COUNTKILL: u32 = 0x0040_0000;
+const MF_COUNTITEM: u32 = 0x0080_0000;
+const MF_NOTDMATCH: u32 = 0x0200_0000;
+
+/// One entry in the thing table, which the source calls mobjinfo. States and sounds are by name, and distances are
+/// in fixed point, as DeHackEd writes them.
+#[derive(Debug)]
+pub struct MobjInfo {
+    pub doomednum: i64,
+    pub spawnstate: &'static str,
+    pub spawnhealth: i64,
+    pub seestate: &'static str,
+    pub seesound: &'static str,
+    pub reactiontime: i64,
+    pub attacksound: &'static str,
+    pub painstate: &'static str,
+    pub painchance: i64,
+    pub painsound: &'static str,
+    pub meleestate: &'static str,
+    pub missilestate: &'static str,
+    pub deathstate: &'static str,
+    pub xdeathstate: &'static str,
+    pub deathsound: &'static str,
+    /// Fixed point for projectiles, but whole units for monsters.
+    pub speed: i64,
+    pub radius: i64,
+    pub height: i64,
+    pub mass: i64,
+    pub damage: i64,
+    pub activesound: &'static str,
+    pub flags: u32,
+    pub raisestate: &'static str,
+}
+
+/// What most of the table leaves alone: a thing with no states or sounds that doesn't interact with anything.
+const NOTHING: MobjInfo = MobjInfo {
+    doomednum: -1,
+    spawnstate: "S_NULL",
+    spawnhealth: 1000,
+    seestate: "S_NULL",
+    seesound: "none",
+    reactiontime: 8,
+    attacksound: "none",
+    painstate: "S_NULL",
+    painchance: 0,
+    painsound: "none",
+    meleestate: "S_NULL",
+    missilestate: "S_NULL",
+    deathstate: "S_NULL",
+    xdeathstate: "S_NULL",
+    deathsound: "none",
+    speed: 0,
+    radius: 20 * FRACUNIT,
+    height: 16 * FRACUNIT,
+    mass: 100,
+    damage: 0,
+    activesound: "none",
+    flags: 0,
+    raisestate: "S_NULL",
+};
+
+/// The thing table, in the same order as `THINGS`.
+pub static MOBJINFO: &[MobjInfo] = &[
+    // MT_PLAYER
+    MobjInfo {
+        spawnstate: "S_PLAY",
+        spawnhealth: 100,
+        seestate: "S_PLAY_RUN1",
+        painstate: "S_PLAY_PAIN",
+        painchance: 255,
+        painsound: "plpain",
+        missilestate: "S_PLAY_ATK1",
+        deathstate: "S_PLAY_DIE1",
+        xdeathstate: "S_PLAY_XDIE1",
+        deathsound: "pldeth",
+        radius: 16 * FRACUNIT,
+        height: 56 * FRACUNIT,
+        flags: MF_SOLID | MF_SHOOTABLE | MF_DROPOFF | MF_PICKUP | MF_NOTDMATCH,
+        ..NOTHING
+    },
+    // MT_POSSESSED
+    MobjInfo {
+        doomednum: 3004,
+        spawnstate: "S_POSS_STND",
+        spawnhealth: 20,
+        seestate: "S_POSS_RUN1",
+        seesound: "posit1",
+        attacksound: "pistol",
+        painstate: "S_POSS_PAIN",
+        painchance: 200,
+        painsound: "popain",
+        missilestate: "S_POSS_ATK1",
+        deathstate: "S_POSS_DIE1",
+        xdeathstate: "S_POSS_XDIE1",
+        deathsound: "podth1",
+        speed: 8,
+        height: 56 * FRACUNIT,
+        activesound: "posact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_POSS_RAISE1",
+        ..NOTHING
+    },
+    // MT_SHOTGUY
+    MobjInfo {
+        doomednum: 9,
+        spawnstate: "S_SPOS_STND",
+        spawnhealth: 30,
+        seestate: "S_SPOS_RUN1",
+        seesound: "posit2",
+        painstate: "S_SPOS_PAIN",
+        painchance: 170,
+        painsound: "popain",
+        missilestate: "S_SPOS_ATK1",
+        deathstate: "S_SPOS_DIE1",
+        xdeathstate: "S_SPOS_XDIE1",
+        deathsound: "podth2",
+        speed: 8,
+        height: 56 * FRACUNIT,
+        activesound: "posact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_SPOS_RAISE1",
+        ..NOTHING
+    },
+    // MT_VILE
+    MobjInfo {
+        doomednum: 64,
+        spawnstate: "S_VILE_STND",
+        spawnhealth: 700,
+        seestate: "S_VILE_RUN1",
+        seesound: "vilsit",
+        painstate: "S_VILE_PAIN",
+        painchance: 10,
+        painsound: "vipain",
+        missilestate: "S_VILE_ATK1",
+        deathstate: "S_VILE_DIE1",
+        deathsound: "vildth",
+        speed: 15,
+        height: 56 * FRACUNIT,
+        mass: 500,
+        activesound: "vilact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        ..NOTHING
+    },
+    // MT_FIRE
+    MobjInfo {
+        spawnstate: "S_FIRE1",
+        flags: MF_NOBLOCKMAP | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_UNDEAD
+    MobjInfo {
+        doomednum: 66,
+        spawnstate: "S_SKEL_STND",
+        spawnhealth: 300,
+        seestate: "S_SKEL_RUN1",
+        seesound: "skesit",
+        painstate: "S_SKEL_PAIN",
+        painchance: 100,
+        painsound: "popain",
+        meleestate: "S_SKEL_FIST1",
+        missilestate: "S_SKEL_MISS1",
+        deathstate: "S_SKEL_DIE1",
+        deathsound: "skedth",
+        speed: 10,
+        height: 56 * FRACUNIT,
+        mass: 500,
+        activesound: "skeact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_SKEL_RAISE1",
+        ..NOTHING
+    },
+    // MT_TRACER
+    MobjInfo {
+        spawnstate: "S_TRACER",
+        seesound: "skeatk",
+        deathstate: "S_TRACEEXP1",
+        deathsound: "barexp",
+        speed: 10 * FRACUNIT,
+        radius: 11 * FRACUNIT,
+        height: 8 * FRACUNIT,
+        damage: 10,
+        flags: MF_NOBLOCKMAP | MF_MISSILE | MF_DROPOFF | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_SMOKE
+    MobjInfo {
+        spawnstate: "S_SMOKE1",
+        flags: MF_NOBLOCKMAP | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_FATSO
+    MobjInfo {
+        doomednum: 67,
+        spawnstate: "S_FATT_STND",
+        spawnhealth: 600,
+        seestate: "S_FATT_RUN1",
+        seesound: "mansit",
+        painstate: "S_FATT_PAIN",
+        painchance: 80,
+        painsound: "mnpain",
+        missilestate: "S_FATT_ATK1",
+        deathstate: "S_FATT_DIE1",
+        deathsound: "mandth",
+        speed: 8,
+        radius: 48 * FRACUNIT,
+        height: 64 * FRACUNIT,
+        mass: 1000,
+        activesound: "posact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_FATT_RAISE1",
+        ..NOTHING
+    },
+    // MT_FATSHOT
+    MobjInfo {
+        spawnstate: "S_FATSHOT1",
+        seesound: "firsht",
+        deathstate: "S_FATSHOTX1",
+        deathsound: "firxpl",
+        speed: 20 * FRACUNIT,
+        radius: 6 * FRACUNIT,
+        height: 8 * FRACUNIT,
+        damage: 8,
+        flags: MF_NOBLOCKMAP | MF_MISSILE | MF_DROPOFF | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_CHAINGUY
+    MobjInfo {
+        doomednum: 65,
+        spawnstate: "S_CPOS_STND",
+        spawnhealth: 70,
+        seestate: "S_CPOS_RUN1",
+        seesound: "posit2",
+        painstate: "S_CPOS_PAIN",
+        painchance: 170,
+        painsound: "popain",
+        missilestate: "S_CPOS_ATK1",
+        deathstate: "S_CPOS_DIE1",
+        xdeathstate: "S_CPOS_XDIE1",
+        deathsound: "podth2",
+        speed: 8,
+        height: 56 * FRACUNIT,
+        activesound: "posact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_CPOS_RAISE1",
+        ..NOTHING
+    },
+    // MT_TROOP
+    MobjInfo {
+        doomednum: 3001,
+        spawnstate: "S_TROO_STND",
+        spawnhealth: 60,
+        seestate: "S_TROO_RUN1",
+        seesound: "bgsit1",
+        painstate: "S_TROO_PAIN",
+        painchance: 200,
+        painsound: "popain",
+        meleestate: "S_TROO_ATK1",
+        missilestate: "S_TROO_ATK1",
+        deathstate: "S_TROO_DIE1",
+        xdeathstate: "S_TROO_XDIE1",
+        deathsound: "bgdth1",
+        speed: 8,
+        height: 56 * FRACUNIT,
+        activesound: "bgact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_TROO_RAISE1",
+        ..NOTHING
+    },
+    // MT_SERGEANT
+    MobjInfo {
+        doomednum: 3002,
+        spawnstate: "S_SARG_STND",
+        spawnhealth: 150,
+        seestate: "S_SARG_RUN1",
+        seesound: "sgtsit",
+        attacksound: "sgtatk",
+        painstate: "S_SARG_PAIN",
+        painchance: 180,
+        painsound: "dmpain",
+        meleestate: "S_SARG_ATK1",
+        deathstate: "S_SARG_DIE1",
+        deathsound: "sgtdth",
+        speed: 10,
+        radius: 30 * FRACUNIT,
+        height: 56 * FRACUNIT,
+        mass: 400,
+        activesound: "dmact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_SARG_RAISE1",
+        ..NOTHING
+    },
+    // MT_SHADOWS
+    MobjInfo {
+        doomednum: 58,
+        spawnstate: "S_SARG_STND",
+        spawnhealth: 150,
+        seestate: "S_SARG_RUN1",
+        seesound: "sgtsit",
+        attacksound: "sgtatk",
+        painstate: "S_SARG_PAIN",
+        painchance: 180,
+        painsound: "dmpain",
+        meleestate: "S_SARG_ATK1",
+        deathstate: "S_SARG_DIE1",
+        deathsound: "sgtdth",
+        speed: 10,
+        radius: 30 * FRACUNIT,
+        height: 56 * FRACUNIT,
+        mass: 400,
+        activesound: "dmact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_SHADOW | MF_COUNTKILL,
+        raisestate: "S_SARG_RAISE1",
+        ..NOTHING
+    },
+    // MT_HEAD
+    MobjInfo {
+        doomednum: 3005,
+        spawnstate: "S_HEAD_STND",
+        spawnhealth: 400,
+        seestate: "S_HEAD_RUN1",
+        seesound: "cacsit",
+        painstate: "S_HEAD_PAIN",
+        painchance: 128,
+        painsound: "dmpain",
+        missilestate: "S_HEAD_ATK1",
+        deathstate: "S_HEAD_DIE1",
+        deathsound: "cacdth",
+        speed: 8,
+        radius: 31 * FRACUNIT,
+        height: 56 * FRACUNIT,
+        mass: 400,
+        activesound: "dmact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_FLOAT | MF_NOGRAVITY | MF_COUNTKILL,
+        raisestate: "S_HEAD_RAISE1",
+        ..NOTHING
+    },
+    // MT_BRUISER
+    MobjInfo {
+        doomednum: 3003,
+        spawnstate: "S_BOSS_STND",
+        seestate: "S_BOSS_RUN1",
+        seesound: "brssit",
+        painstate: "S_BOSS_PAIN",
+        painchance: 50,
+        painsound: "dmpain",
+        meleestate: "S_BOSS_ATK1",
+        missilestate: "S_BOSS_ATK1",
+        deathstate: "S_BOSS_DIE1",
+        deathsound: "brsdth",
+        speed: 8,
+        radius: 24 * FRACUNIT,
+        height: 64 * FRACUNIT,
+        mass: 1000,
+        activesound: "dmact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_BOSS_RAISE1",
+        ..NOTHING
+    },
+    // MT_BRUISERSHOT
+    MobjInfo {
+        spawnstate: "S_BRBALL1",
+        seesound: "firsht",
+        deathstate: "S_BRBALLX1",
+        deathsound: "firxpl",
+        speed: 15 * FRACUNIT,
+        radius: 6 * FRACUNIT,
+        height: 8 * FRACUNIT,
+        damage: 8,
+        flags: MF_NOBLOCKMAP | MF_MISSILE | MF_DROPOFF | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_KNIGHT
+    MobjInfo {
+        doomednum: 69,
+        spawnstate: "S_BOS2_STND",
+        spawnhealth: 500,
+        seestate: "S_BOS2_RUN1",
+        seesound: "kntsit",
+        painstate: "S_BOS2_PAIN",
+        painchance: 50,
+        painsound: "dmpain",
+        meleestate: "S_BOS2_ATK1",
+        missilestate: "S_BOS2_ATK1",
+        deathstate: "S_BOS2_DIE1",
+        deathsound: "kntdth",
+        speed: 8,
+        radius: 24 * FRACUNIT,
+        height: 64 * FRACUNIT,
+        mass: 1000,
+        activesound: "dmact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_BOS2_RAISE1",
+        ..NOTHING
+    },
+    // MT_SKULL
+    MobjInfo {
+        doomednum: 3006,
+        spawnstate: "S_SKULL_STND",
+        spawnhealth: 100,
+        seestate: "S_SKULL_RUN1",
+        attacksound: "sklatk",
+        painstate: "S_SKULL_PAIN",
+        painchance: 256,
+        painsound: "dmpain",
+        missilestate: "S_SKULL_ATK1",
+        deathstate: "S_SKULL_DIE1",
+        deathsound: "firxpl",
+        speed: 8,
+        radius: 16 * FRACUNIT,
+        height: 56 * FRACUNIT,
+        mass: 50,
+        damage: 3,
+        activesound: "dmact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_FLOAT | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_SPIDER
+    MobjInfo {
+        doomednum: 7,
+        spawnstate: "S_SPID_STND",
+        spawnhealth: 3000,
+        seestate: "S_SPID_RUN1",
+        seesound: "spisit",
+        attacksound: "shotgn",
+        painstate: "S_SPID_PAIN",
+        painchance: 40,
+        painsound: "dmpain",
+        missilestate: "S_SPID_ATK1",
+        deathstate: "S_SPID_DIE1",
+        deathsound: "spidth",
+        speed: 12,
+        radius: 128 * FRACUNIT,
+        height: 100 * FRACUNIT,
+        mass: 1000,
+        activesound: "dmact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        ..NOTHING
+    },
+    // MT_BABY
+    MobjInfo {
+        doomednum: 68,
+        spawnstate: "S_BSPI_STND",
+        spawnhealth: 500,
+        seestate: "S_BSPI_SIGHT",
+        seesound: "bspsit",
+        painstate: "S_BSPI_PAIN",
+        painchance: 128,
+        painsound: "dmpain",
+        missilestate: "S_BSPI_ATK1",
+        deathstate: "S_BSPI_DIE1",
+        deathsound: "bspdth",
+        speed: 12,
+        radius: 64 * FRACUNIT,
+        height: 64 * FRACUNIT,
+        mass: 600,
+        activesound: "bspact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_BSPI_RAISE1",
+        ..NOTHING
+    },
+    // MT_CYBORG
+    MobjInfo {
+        doomednum: 16,
+        spawnstate: "S_CYBER_STND",
+        spawnhealth: 4000,
+        seestate: "S_CYBER_RUN1",
+        seesound: "cybsit",
+        painstate: "S_CYBER_PAIN",
+        painchance: 20,
+        painsound: "dmpain",
+        missilestate: "S_CYBER_ATK1",
+        deathstate: "S_CYBER_DIE1",
+        deathsound: "cybdth",
+        speed: 16,
+        radius: 40 * FRACUNIT,
+        height: 110 * FRACUNIT,
+        mass: 1000,
+        activesound: "dmact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        ..NOTHING
+    },
+    // MT_PAIN
+    MobjInfo {
+        doomednum: 71,
+        spawnstate: "S_PAIN_STND",
+        spawnhealth: 400,
+        seestate: "S_PAIN_RUN1",
+        seesound: "pesit",
+        painstate: "S_PAIN_PAIN",
+        painchance: 128,
+        painsound: "pepain",
+        missilestate: "S_PAIN_ATK1",
+        deathstate: "S_PAIN_DIE1",
+        deathsound: "pedth",
+        speed: 8,
+        radius: 31 * FRACUNIT,
+        height: 56 * FRACUNIT,
+        mass: 400,
+        activesound: "dmact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_FLOAT | MF_NOGRAVITY | MF_COUNTKILL,
+        raisestate: "S_PAIN_RAISE1",
+        ..NOTHING
+    },
+    // MT_WOLFSS
+    MobjInfo {
+        doomednum: 84,
+        spawnstate: "S_SSWV_STND",
+        spawnhealth: 50,
+        seestate: "S_SSWV_RUN1",
+        seesound: "sssit",
+        painstate: "S_SSWV_PAIN",
+        painchance: 170,
+        painsound: "popain",
+        missilestate: "S_SSWV_ATK1",
+        deathstate: "S_SSWV_DIE1",
+        xdeathstate: "S_SSWV_XDIE1",
+        deathsound: "ssdth",
+        speed: 8,
+        height: 56 * FRACUNIT,
+        activesound: "posact",
+        flags: MF_SOLID | MF_SHOOTABLE | MF_COUNTKILL,
+        raisestate: "S_SSWV_RAISE1",
+        ..NOTHING
+    },
+    // MT_KEEN
+    MobjInfo {
+        doomednum: 72,
+        spawnstate: "S_KEENSTND",
+        spawnhealth: 100,
+        painstate: "S_KEENPAIN",
+        painchance: 256,
+        painsound: "keenpn",
+        deathstate: "S_COMMKEEN",
+        deathsound: "keendt",
+        radius: 16 * FRACUNIT,
+        height: 72 * FRACUNIT,
+        mass: 10000000,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY | MF_SHOOTABLE | MF_COUNTKILL,
+        ..NOTHING
+    },
+    // MT_BOSSBRAIN
+    MobjInfo {
+        doomednum: 88,
+        spawnstate: "S_BRAIN",
+        spawnhealth: 250,
+        painstate: "S_BRAIN_PAIN",
+        painchance: 255,
+        painsound: "bospn",
+        deathstate: "S_BRAIN_DIE1",
+        deathsound: "bosdth",
+        radius: 16 * FRACUNIT,
+        mass: 10000000,
+        flags: MF_SOLID | MF_SHOOTABLE,
+        ..NOTHING
+    },
+    // MT_BOSSSPIT
+    MobjInfo {
+        doomednum: 89,
+        spawnstate: "S_BRAINEYE",
+        seestate: "S_BRAINEYESEE",
+        height: 32 * FRACUNIT,
+        flags: MF_NOBLOCKMAP | MF_NOSECTOR,
+        ..NOTHING
+    },
+    // MT_BOSSTARGET
+    MobjInfo {
+        doomednum: 87,
+        height: 32 * FRACUNIT,
+        flags: MF_NOBLOCKMAP | MF_NOSECTOR,
+        ..NOTHING
+    },
+    // MT_SPAWNSHOT
+    MobjInfo {
+        spawnstate: "S_SPAWN1",
+        seesound: "bospit",
+        deathsound: "firxpl",
+        speed: 10 * FRACUNIT,
+        radius: 6 * FRACUNIT,
+        height: 32 * FRACUNIT,
+        damage: 3,
+        flags: MF_NOBLOCKMAP | MF_MISSILE | MF_DROPOFF | MF_NOGRAVITY | MF_NOCLIP,
+        ..NOTHING
+    },
+    // MT_SPAWNFIRE
+    MobjInfo {
+        spawnstate: "S_SPAWNFIRE1",
+        flags: MF_NOBLOCKMAP | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_BARREL
+    MobjInfo {
+        doomednum: 2035,
+        spawnstate: "S_BAR1",
+        spawnhealth: 20,
+        deathstate: "S_BEXP",
+        deathsound: "barexp",
+        radius: 10 * FRACUNIT,
+        height: 42 * FRACUNIT,
+        flags: MF_SOLID | MF_SHOOTABLE | MF_NOBLOOD,
+        ..NOTHING
+    },
+    // MT_TROOPSHOT
+    MobjInfo {
+        spawnstate: "S_TBALL1",
+        seesound: "firsht",
+        deathstate: "S_TBALLX1",
+        deathsound: "firxpl",
+        speed: 10 * FRACUNIT,
+        radius: 6 * FRACUNIT,
+        height: 8 * FRACUNIT,
+        damage: 3,
+        flags: MF_NOBLOCKMAP | MF_MISSILE | MF_DROPOFF | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_HEADSHOT
+    MobjInfo {
+        spawnstate: "S_RBALL1",
+        seesound: "firsht",
+        deathstate: "S_RBALLX1",
+        deathsound: "firxpl",
+        speed: 10 * FRACUNIT,
+        radius: 6 * FRACUNIT,
+        height: 8 * FRACUNIT,
+        damage: 5,
+        flags: MF_NOBLOCKMAP | MF_MISSILE | MF_DROPOFF | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_ROCKET
+    MobjInfo {
+        spawnstate: "S_ROCKET",
+        seesound: "rlaunc",
+        deathstate: "S_EXPLODE1",
+        deathsound: "barexp",
+        speed: 20 * FRACUNIT,
+        radius: 11 * FRACUNIT,
+        height: 8 * FRACUNIT,
+        damage: 20,
+        flags: MF_NOBLOCKMAP | MF_MISSILE | MF_DROPOFF | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_PLASMA
+    MobjInfo {
+        spawnstate: "S_PLASBALL",
+        seesound: "plasma",
+        deathstate: "S_PLASEXP",
+        deathsound: "firxpl",
+        speed: 25 * FRACUNIT,
+        radius: 13 * FRACUNIT,
+        height: 8 * FRACUNIT,
+        damage: 5,
+        flags: MF_NOBLOCKMAP | MF_MISSILE | MF_DROPOFF | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_BFG
+    MobjInfo {
+        spawnstate: "S_BFGSHOT",
+        deathstate: "S_BFGLAND",
+        deathsound: "rxplod",
+        speed: 25 * FRACUNIT,
+        radius: 13 * FRACUNIT,
+        height: 8 * FRACUNIT,
+        damage: 100,
+        flags: MF_NOBLOCKMAP | MF_MISSILE | MF_DROPOFF | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_ARACHPLAZ
+    MobjInfo {
+        spawnstate: "S_ARACH_PLAZ",
+        seesound: "plasma",
+        deathstate: "S_ARACH_PLEX",
+        deathsound: "firxpl",
+        speed: 25 * FRACUNIT,
+        radius: 13 * FRACUNIT,
+        height: 8 * FRACUNIT,
+        damage: 5,
+        flags: MF_NOBLOCKMAP | MF_MISSILE | MF_DROPOFF | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_PUFF
+    MobjInfo {
+        spawnstate: "S_PUFF1",
+        flags: MF_NOBLOCKMAP | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_BLOOD
+    MobjInfo {
+        spawnstate: "S_BLOOD1",
+        flags: MF_NOBLOCKMAP,
+        ..NOTHING
+    },
+    // MT_TFOG
+    MobjInfo {
+        spawnstate: "S_TFOG",
+        flags: MF_NOBLOCKMAP | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_IFOG
+    MobjInfo {
+        spawnstate: "S_IFOG",
+        flags: MF_NOBLOCKMAP | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_TELEPORTMAN
+    MobjInfo {
+        doomednum: 14,
+        flags: MF_NOBLOCKMAP | MF_NOSECTOR,
+        ..NOTHING
+    },
+    // MT_EXTRABFG
+    MobjInfo {
+        spawnstate: "S_BFGEXP",
+        flags: MF_NOBLOCKMAP | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC0
+    MobjInfo {
+        doomednum: 2018,
+        spawnstate: "S_ARM1",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC1
+    MobjInfo {
+        doomednum: 2019,
+        spawnstate: "S_ARM2",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC2
+    MobjInfo {
+        doomednum: 2014,
+        spawnstate: "S_BON1",
+        flags: MF_SPECIAL | MF_COUNTITEM,
+        ..NOTHING
+    },
+    // MT_MISC3
+    MobjInfo {
+        doomednum: 2015,
+        spawnstate: "S_BON2",
+        flags: MF_SPECIAL | MF_COUNTITEM,
+        ..NOTHING
+    },
+    // MT_MISC4
+    MobjInfo {
+        doomednum: 5,
+        spawnstate: "S_BKEY",
+        flags: MF_SPECIAL | MF_NOTDMATCH,
+        ..NOTHING
+    },
+    // MT_MISC5
+    MobjInfo {
+        doomednum: 13,
+        spawnstate: "S_RKEY",
+        flags: MF_SPECIAL | MF_NOTDMATCH,
+        ..NOTHING
+    },
+    // MT_MISC6
+    MobjInfo {
+        doomednum: 6,
+        spawnstate: "S_YKEY",
+        flags: MF_SPECIAL | MF_NOTDMATCH,
+        ..NOTHING
+    },
+    // MT_MISC7
+    MobjInfo {
+        doomednum: 39,
+        spawnstate: "S_YSKULL",
+        flags: MF_SPECIAL | MF_NOTDMATCH,
+        ..NOTHING
+    },
+    // MT_MISC8
+    MobjInfo {
+        doomednum: 38,
+        spawnstate: "S_RSKULL",
+        flags: MF_SPECIAL | MF_NOTDMATCH,
+        ..NOTHING
+    },
+    // MT_MISC9
+    MobjInfo {
+        doomednum: 40,
+        spawnstate: "S_BSKULL",
+        flags: MF_SPECIAL | MF_NOTDMATCH,
+        ..NOTHING
+    },
+    // MT_MISC10
+    MobjInfo {
+        doomednum: 2011,
+        spawnstate: "S_STIM",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC11
+    MobjInfo {
+        doomednum: 2012,
+        spawnstate: "S_MEDI",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC12
+    MobjInfo {
+        doomednum: 2013,
+        spawnstate: "S_SOUL",
+        flags: MF_SPECIAL | MF_COUNTITEM,
+        ..NOTHING
+    },
+    // MT_INV
+    MobjInfo {
+        doomednum: 2022,
+        spawnstate: "S_PINV",
+        flags: MF_SPECIAL | MF_COUNTITEM,
+        ..NOTHING
+    },
+    // MT_MISC13
+    MobjInfo {
+        doomednum: 2023,
+        spawnstate: "S_PSTR",
+        flags: MF_SPECIAL | MF_COUNTITEM,
+        ..NOTHING
+    },
+    // MT_INS
+    MobjInfo {
+        doomednum: 2024,
+        spawnstate: "S_PINS",
+        flags: MF_SPECIAL | MF_COUNTITEM,
+        ..NOTHING
+    },
+    // MT_MISC14
+    MobjInfo {
+        doomednum: 2025,
+        spawnstate: "S_SUIT",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC15
+    MobjInfo {
+        doomednum: 2026,
+        spawnstate: "S_PMAP",
+        flags: MF_SPECIAL | MF_COUNTITEM,
+        ..NOTHING
+    },
+    // MT_MISC16
+    MobjInfo {
+        doomednum: 2045,
+        spawnstate: "S_PVIS",
+        flags: MF_SPECIAL | MF_COUNTITEM,
+        ..NOTHING
+    },
+    // MT_MEGA
+    MobjInfo {
+        doomednum: 83,
+        spawnstate: "S_MEGA",
+        flags: MF_SPECIAL | MF_COUNTITEM,
+        ..NOTHING
+    },
+    // MT_CLIP
+    MobjInfo {
+        doomednum: 2007,
+        spawnstate: "S_CLIP",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC17
+    MobjInfo {
+        doomednum: 2048,
+        spawnstate: "S_AMMO",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC18
+    MobjInfo {
+        doomednum: 2010,
+        spawnstate: "S_ROCK",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC19
+    MobjInfo {
+        doomednum: 2046,
+        spawnstate: "S_BROK",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC20
+    MobjInfo {
+        doomednum: 2047,
+        spawnstate: "S_CELL",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC21
+    MobjInfo {
+        doomednum: 17,
+        spawnstate: "S_CELP",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC22
+    MobjInfo {
+        doomednum: 2008,
+        spawnstate: "S_SHEL",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC23
+    MobjInfo {
+        doomednum: 2049,
+        spawnstate: "S_SBOX",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC24
+    MobjInfo {
+        doomednum: 8,
+        spawnstate: "S_BPAK",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC25
+    MobjInfo {
+        doomednum: 2006,
+        spawnstate: "S_BFUG",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_CHAINGUN
+    MobjInfo {
+        doomednum: 2002,
+        spawnstate: "S_MGUN",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC26
+    MobjInfo {
+        doomednum: 2005,
+        spawnstate: "S_CSAW",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC27
+    MobjInfo {
+        doomednum: 2003,
+        spawnstate: "S_LAUN",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC28
+    MobjInfo {
+        doomednum: 2004,
+        spawnstate: "S_PLAS",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_SHOTGUN
+    MobjInfo {
+        doomednum: 2001,
+        spawnstate: "S_SHOT",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_SUPERSHOTGUN
+    MobjInfo {
+        doomednum: 82,
+        spawnstate: "S_SHOT2",
+        flags: MF_SPECIAL,
+        ..NOTHING
+    },
+    // MT_MISC29
+    MobjInfo {
+        doomednum: 85,
+        spawnstate: "S_TECHLAMP",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC30
+    MobjInfo {
+        doomednum: 86,
+        spawnstate: "S_TECH2LAMP",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC31
+    MobjInfo {
+        doomednum: 2028,
+        spawnstate: "S_COLU",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC32
+    MobjInfo {
+        doomednum: 30,
+        spawnstate: "S_TALLGRNCOL",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC33
+    MobjInfo {
+        doomednum: 31,
+        spawnstate: "S_SHRTGRNCOL",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC34
+    MobjInfo {
+        doomednum: 32,
+        spawnstate: "S_TALLREDCOL",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC35
+    MobjInfo {
+        doomednum: 33,
+        spawnstate: "S_SHRTREDCOL",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC36
+    MobjInfo {
+        doomednum: 37,
+        spawnstate: "S_SKULLCOL",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC37
+    MobjInfo {
+        doomednum: 36,
+        spawnstate: "S_HEARTCOL",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC38
+    MobjInfo {
+        doomednum: 41,
+        spawnstate: "S_EVILEYE",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC39
+    MobjInfo {
+        doomednum: 42,
+        spawnstate: "S_FLOATSKULL",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC40
+    MobjInfo {
+        doomednum: 43,
+        spawnstate: "S_TORCHTREE",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC41
+    MobjInfo {
+        doomednum: 44,
+        spawnstate: "S_BLUETORCH",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC42
+    MobjInfo {
+        doomednum: 45,
+        spawnstate: "S_GREENTORCH",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC43
+    MobjInfo {
+        doomednum: 46,
+        spawnstate: "S_REDTORCH",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC44
+    MobjInfo {
+        doomednum: 55,
+        spawnstate: "S_BTORCHSHRT",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC45
+    MobjInfo {
+        doomednum: 56,
+        spawnstate: "S_GTORCHSHRT",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC46
+    MobjInfo {
+        doomednum: 57,
+        spawnstate: "S_RTORCHSHRT",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC47
+    MobjInfo {
+        doomednum: 47,
+        spawnstate: "S_STALAGTITE",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC48
+    MobjInfo {
+        doomednum: 48,
+        spawnstate: "S_TECHPILLAR",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC49
+    MobjInfo {
+        doomednum: 34,
+        spawnstate: "S_CANDLESTIK",
+        ..NOTHING
+    },
+    // MT_MISC50
+    MobjInfo {
+        doomednum: 35,
+        spawnstate: "S_CANDELABRA",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC51
+    MobjInfo {
+        doomednum: 49,
+        spawnstate: "S_BLOODYTWITCH",
+        radius: 16 * FRACUNIT,
+        height: 68 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC52
+    MobjInfo {
+        doomednum: 50,
+        spawnstate: "S_MEAT2",
+        radius: 16 * FRACUNIT,
+        height: 84 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC53
+    MobjInfo {
+        doomednum: 51,
+        spawnstate: "S_MEAT3",
+        radius: 16 * FRACUNIT,
+        height: 84 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC54
+    MobjInfo {
+        doomednum: 52,
+        spawnstate: "S_MEAT4",
+        radius: 16 * FRACUNIT,
+        height: 68 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC55
+    MobjInfo {
+        doomednum: 53,
+        spawnstate: "S_MEAT5",
+        radius: 16 * FRACUNIT,
+        height: 52 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC56
+    MobjInfo {
+        doomednum: 59,
+        spawnstate: "S_MEAT2",
+        height: 84 * FRACUNIT,
+        flags: MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC57
+    MobjInfo {
+        doomednum: 60,
+        spawnstate: "S_MEAT4",
+        height: 68 * FRACUNIT,
+        flags: MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC58
+    MobjInfo {
+        doomednum: 61,
+        spawnstate: "S_MEAT3",
+        height: 52 * FRACUNIT,
+        flags: MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC59
+    MobjInfo {
+        doomednum: 62,
+        spawnstate: "S_MEAT5",
+        height: 52 * FRACUNIT,
+        flags: MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC60
+    MobjInfo {
+        doomednum: 63,
+        spawnstate: "S_BLOODYTWITCH",
+        height: 68 * FRACUNIT,
+        flags: MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC61
+    MobjInfo {
+        doomednum: 22,
+        spawnstate: "S_HEAD_DIE6",
+        ..NOTHING
+    },
+    // MT_MISC62
+    MobjInfo {
+        doomednum: 15,
+        spawnstate: "S_PLAY_DIE7",
+        ..NOTHING
+    },
+    // MT_MISC63
+    MobjInfo {
+        doomednum: 18,
+        spawnstate: "S_POSS_DIE5",
+        ..NOTHING
+    },
+    // MT_MISC64
+    MobjInfo {
+        doomednum: 21,
+        spawnstate: "S_SARG_DIE6",
+        ..NOTHING
+    },
+    // MT_MISC65
+    MobjInfo {
+        doomednum: 23,
+        spawnstate: "S_SKULL_DIE6",
+        ..NOTHING
+    },
+    // MT_MISC66
+    MobjInfo {
+        doomednum: 20,
+        spawnstate: "S_TROO_DIE5",
+        ..NOTHING
+    },
+    // MT_MISC67
+    MobjInfo {
+        doomednum: 19,
+        spawnstate: "S_SPOS_DIE5",
+        ..NOTHING
+    },
+    // MT_MISC68
+    MobjInfo {
+        doomednum: 10,
+        spawnstate: "S_PLAY_XDIE9",
+        ..NOTHING
+    },
+    // MT_MISC69
+    MobjInfo {
+        doomednum: 12,
+        spawnstate: "S_PLAY_XDIE9",
+        ..NOTHING
+    },
+    // MT_MISC70
+    MobjInfo {
+        doomednum: 28,
+        spawnstate: "S_HEADSONSTICK",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC71
+    MobjInfo {
+        doomednum: 24,
+        spawnstate: "S_GIBS",
+        ..NOTHING
+    },
+    // MT_MISC72
+    MobjInfo {
+        doomednum: 27,
+        spawnstate: "S_HEADONASTICK",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC73
+    MobjInfo {
+        doomednum: 29,
+        spawnstate: "S_HEADCANDLES",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC74
+    MobjInfo {
+        doomednum: 25,
+        spawnstate: "S_DEADSTICK",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC75
+    MobjInfo {
+        doomednum: 26,
+        spawnstate: "S_LIVESTICK",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC76
+    MobjInfo {
+        doomednum: 54,
+        spawnstate: "S_BIGTREE",
+        radius: 32 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC77
+    MobjInfo {
+        doomednum: 70,
+        spawnstate: "S_BBAR1",
+        radius: 16 * FRACUNIT,
+        flags: MF_SOLID,
+        ..NOTHING
+    },
+    // MT_MISC78
+    MobjInfo {
+        doomednum: 73,
+        spawnstate: "S_HANGNOGUTS",
+        radius: 16 * FRACUNIT,
+        height: 88 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC79
+    MobjInfo {
+        doomednum: 74,
+        spawnstate: "S_HANGBNOBRAIN",
+        radius: 16 * FRACUNIT,
+        height: 88 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC80
+    MobjInfo {
+        doomednum: 75,
+        spawnstate: "S_HANGTLOOKDN",
+        radius: 16 * FRACUNIT,
+        height: 64 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC81
+    MobjInfo {
+        doomednum: 76,
+        spawnstate: "S_HANGTSKULL",
+        radius: 16 * FRACUNIT,
+        height: 64 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC82
+    MobjInfo {
+        doomednum: 77,
+        spawnstate: "S_HANGTLOOKUP",
+        radius: 16 * FRACUNIT,
+        height: 64 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC83
+    MobjInfo {
+        doomednum: 78,
+        spawnstate: "S_HANGTNOBRAIN",
+        radius: 16 * FRACUNIT,
+        height: 64 * FRACUNIT,
+        flags: MF_SOLID | MF_SPAWNCEILING | MF_NOGRAVITY,
+        ..NOTHING
+    },
+    // MT_MISC84
+    MobjInfo {
+        doomednum: 79,
+        spawnstate: "S_COLONGIBS",
+        flags: MF_NOBLOCKMAP,
+        ..NOTHING
+    },
+    // MT_MISC85
+    MobjInfo {
+        doomednum: 80,
+        spawnstate: "S_SMALLPOOL",
+        flags: MF_NOBLOCKMAP,
+        ..NOTHING
+    },
+    // MT_MISC86
+    MobjInfo {
+        doomednum: 81,
+        spawnstate: "S_BRAINSTEM",
+        flags: MF_NOBLOCKMAP,
+        ..NOTHING
+    },
+];
+
+/// One entry in the weapon table, with DeHackEd's names for each state.
+#[derive(Debug)]
+pub struct WeaponInfo {
+    pub ammo: i64,
+    /// The state the weapon starts in while being raised, which DeHackEd calls the deselect frame.
+    pub upstate: &'static str,
+    /// The state the weapon starts in while being lowered, which DeHackEd calls the select frame.
+    pub downstate: &'static str,
+    pub readystate: &'static str,
+    pub atkstate: &'static str,
+    pub flashstate: &'static str,
+}
+
+#[inline]
+const fn weapon(
+    ammo: i64,
+    upstate: &'static str,
+    downstate: &'static str,
+    readystate: &'static str,
+    atkstate: &'static str,
+    flashstate: &'static str,
+) -> WeaponInfo {
+    WeaponInfo {
+        ammo,
+        upstate,
+        downstate,
+        readystate,
+        atkstate,
+        flashstate,
+    }
+}
+
+/// The weapon table, in the same order as `WEAPONS`.
+pub static WEAPONINFO: &[WeaponInfo] = &[
+    weapon(
+        AMMO_NONE,
+        "S_PUNCHUP",
+        "S_PUNCHDOWN",
+        "S_PUNCH",
+        "S_PUNCH1",
+        "S_NULL",
+    ),
+    weapon(
+        0,
+        "S_PISTOLUP",
+        "S_PISTOLDOWN",
+        "S_PISTOL",
+        "S_PISTOL1",
+        "S_PISTOLFLASH",
+    ),
+    weapon(
+        1,
+        "S_SGUNUP",
+        "S_SGUNDOWN",
+        "S_SGUN",
+        "S_SGUN1",
+        "S_SGUNFLASH1",
+    ),
+    weapon(
+        0,
+        "S_CHAINUP",
+        "S_CHAINDOWN",
+        "S_CHAIN",
+        "S_CHAIN1",
+        "S_CHAINFLASH1",
+    ),
+    weapon(
+        3,
+        "S_MISSILEUP",
+        "S_MISSILEDOWN",
+        "S_MISSILE",
+        "S_MISSILE1",
+        "S_MISSILEFLASH1",
+    ),
+    weapon(
+        2,
+        "S_PLASMAUP",
+        "S_PLASMADOWN",
+        "S_PLASMA",
+        "S_PLASMA1",
+        "S_PLASMAFLASH1",
+    ),
+    weapon(2, "S_BFGUP", "S_BFGDOWN", "S_BFG", "S_BFG1", "S_BFGFLASH1"),
+    weapon(
+        AMMO_NONE,
+        "S_SAWUP",
+        "S_SAWDOWN",
+        "S_SAW",
+        "S_SAW1",
+        "S_NULL",
+    ),
+    weapon(
+        1,
+        "S_DSGUNUP",
+        "S_DSGUNDOWN",
+        "S_DSGUN",
+        "S_DSGUN1",
+        "S_DSGUNFLASH1",
+    ),
+];
+
+/// The most of each ammo type the player can carry without a backpack, in the same order as `AMMO`.
+pub static MAX_AMMO: &[i64] = &[200, 50, 300, 50];
+
+/// How much of each ammo type a clip gives, which DeHackEd calls "Per ammo".
+pub static CLIP_AMMO: &[i64] = &[10, 4, 20, 1];
+
+/// The settings in DeHackEd's Misc block, by their DeHackEd names.
+pub static MISC: &[(&str, i64)] = &[
+    ("Initial Health", 100),
+    ("Initial Bullets", 50),
+    ("Max Health", 200),
+    ("Max Armor", 200),
+    ("Green Armor Class", 1),
+    ("Blue Armor Class", 2),
+    ("Max Soulsphere", 200),
+    ("Soulsphere Health", 100),
+    ("Megasphere Health", 200),
+    ("God Mode Health", 100),
+    ("IDFA Armor", 200),
+    ("IDFA Armor Class", 2),
+    ("IDKFA Armor", 200),
+    ("IDKFA Armor Class", 2),
+    ("BFG Cells/Shot", 40),
+    // 221 lets monsters fight each other, and anything else doesn't
+    ("Monsters Infight", 202),
+];
+
+/// Find a name in one of these tables, ignoring case. The prefix the source gives the names, like "MT_" for things,
+/// can be left off.
+#[inline]
+pub fn find<I: IntoIterator<Item = &'static str>>(
+    table: I,
+    prefix: &str,
+    name: &str,
+) -> Option<usize> {
+    table.into_iter().position(|entry| {
+        entry.eq_ignore_ascii_case(name)
+            || (entry.len() == prefix.len() + name.len()
+                && entry[..prefix.len()].eq_ignore_ascii_case(prefix)
+                && entry[prefix.len()..].eq_ignore_ascii_case(name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dehacked::{
+        NUM_AMMO, NUM_FRAMES, NUM_SOUNDS, NUM_SPRITES, NUM_THINGS, NUM_WEAPONS, VANILLA_MISSILES,
+    };
+
+    #[test]
+    fn table_sizes() {
+        assert_eq!(THINGS.len() as i64, NUM_THINGS);
+        assert_eq!(MOBJINFO.len() as i64, NUM_THINGS);
+        assert_eq!(STATES.len() as i64, NUM_FRAMES);
+        assert_eq!(SPRITES.len() as i64, NUM_SPRITES);
+        assert_eq!(SOUNDS.len() as i64, NUM_SOUNDS);
+        assert_eq!(WEAPONS.len() as i64, NUM_WEAPONS);
+        assert_eq!(WEAPONINFO.len() as i64, NUM_WEAPONS);
+        assert_eq!(AMMO.len() as i64, NUM_AMMO);
+        assert_eq!(MAX_AMMO.len() as i64, NUM_AMMO);
+        assert_eq!(CLIP_AMMO.len() as i64, NUM_AMMO);
+    }
+
+    #[test]
+    fn things_and_weapons_refer_to_real_states_and_sounds() {
+        let state = |name| find(STATES.iter().map(|s| s.name), "", name).is_some();
+        let sound = |name| SOUNDS.contains(&name);
+
+        for mobj in MOBJINFO {
+            let states = [
+                mobj.spawnstate,
+                mobj.seestate,
+                mobj.painstate,
+                mobj.meleestate,
+                mobj.missilestate,
+                mobj.deathstate,
+                mobj.xdeathstate,
+                mobj.raisestate,
+            ];
+            assert!(states.iter().all(|s| state(s)), "{:?}", mobj);
+
+            let sounds = [
+                mobj.seesound,
+                mobj.attacksound,
+                mobj.painsound,
+                mobj.deathsound,
+                mobj.activesound,
+            ];
+            assert!(sounds.iter().all(|s| sound(s)), "{:?}", mobj);
+        }
+
+        for weapon in WEAPONINFO {
+            let states = [
+                weapon.upstate,
+                weapon.downstate,
+                weapon.readystate,
+                weapon.atkstate,
+                weapon.flashstate,
+            ];
+            assert!(states.iter().all(|s| state(s)), "{:?}", weapon);
+        }
+    }
+
+    #[test]
+    fn missiles_match_the_dehacked_list() {
+        let missiles: Vec<i64> = (1..)
+            .zip(MOBJINFO)
+            .filter(|(_, mobj)| mobj.flags & MF_MISSILE != 0)
+            .map(|(number, _)| number)
+            .collect();
+        assert_eq!(missiles, VANILLA_MISSILES);
+    }
+
+    #[test]
+    fn states_refer_to_real_states_and_sprites() {
+        for state in STATES {
+            assert!(find(STATES.iter().map(|s| s.name), "", state.next).is_some());
+            assert!(SPRITES.contains(&state.sprite));
+        }
+    }
+
+    #[test]
+    fn codepointers_are_numbered_like_dehacked() {
+        // DeHackEd numbers codepointers by counting the states that have one
+        let pointer = |frame: usize| {
+            STATES[..frame]
+                .iter()
+                .filter(|s| s.action.is_some())
+                .count()
+        };
+        assert_eq!(pointer(85), 65);
+        assert_eq!(pointer(273), 137);
+        assert_eq!(pointer(685), 380);
+        assert_eq!(STATES.iter().filter(|s| s.action.is_some()).count(), 448);
+    }
+
+    #[test]
+    fn names_are_found_with_or_without_prefix() {
+        assert_eq!(find(THINGS.iter().copied(), "MT_", "MT_POSSESSED"), Some(1));
+        assert_eq!(find(THINGS.iter().copied(), "MT_", "possessed"), Some(1));
+        assert_eq!(find(STATES.iter().map(|s| s.name), "S_", "PLAY"), Some(149));
+        assert_eq!(find(SPRITES.iter().copied(), "", "TROO"), Some(0));
+        assert_eq!(find(THINGS.iter().copied(), "MT_", "MT_NOPE"), None);
+    }
+}
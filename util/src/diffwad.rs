@@ -0,0 +1,395 @@
+// Apache 2.0 License
+
+use crate::{
+    error::ResultExt,
+    map::MAP_LUMPS,
+    wad::{Lump, Wad},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// Where a lump is in a WAD. Lumps with the same name in different namespaces are different lumps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Namespace {
+    Global,
+    /// A map marker and the lumps after it.
+    Map,
+    Sprites,
+    Flats,
+    Patches,
+}
+
+impl Namespace {
+    /// The markers the namespace is put between in a patch. PWADs use the doubled names, so vanilla tools that merge
+    /// them don't mistake them for the IWAD's.
+    #[inline]
+    fn markers(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Namespace::Global | Namespace::Map => None,
+            Namespace::Sprites => Some(("SS_START", "SS_END")),
+            Namespace::Flats => Some(("FF_START", "FF_END")),
+            Namespace::Patches => Some(("PP_START", "PP_END")),
+        }
+    }
+}
+
+impl fmt::Display for Namespace {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Namespace::Global => "lump",
+            Namespace::Map => "map",
+            Namespace::Sprites => "sprite",
+            Namespace::Flats => "flat",
+            Namespace::Patches => "patch",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Changed,
+    /// A PWAD can't take a lump out, so the old one stays until the next full download.
+    Removed,
+}
+
+/// A lump, or a whole map, that's different between two builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub namespace: Namespace,
+    pub name: String,
+    /// The size in the new build, or the old one if it was removed. Maps count every lump in them.
+    pub size: usize,
+    /// For maps, which of their lumps changed.
+    pub lumps: Vec<String>,
+}
+
+impl fmt::Display for Change {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            ChangeKind::Added => "added",
+            ChangeKind::Changed => "changed",
+            ChangeKind::Removed => "removed",
+        };
+        write!(
+            f,
+            "{:7} {} {} ({} bytes)",
+            kind, self.namespace, self.name, self.size
+        )?;
+        if !self.lumps.is_empty() {
+            write!(f, ": {}", self.lumps.join(", "))?;
+        }
+        if self.kind == ChangeKind::Removed {
+            f.write_str("; a patch can't remove it")?;
+        }
+        Ok(())
+    }
+}
+
+/// A lump, or a map marker with its lumps, with the namespace it's in.
+struct Entry<'a> {
+    namespace: Namespace,
+    lumps: &'a [Lump],
+}
+
+impl Entry<'_> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.lumps[0].name
+    }
+
+    #[inline]
+    fn size(&self) -> usize {
+        self.lumps.iter().map(|l| l.data.len()).sum()
+    }
+}
+
+/// Compare two builds lump by lump. Returns a PWAD with every lump that was added or changed, and what changed. A map
+/// is put in the patch whole if any of its lumps changed, since ports replace maps by their markers.
+#[inline]
+pub fn diff_wads(old: &Wad, new: &Wad) -> (Wad, Vec<Change>) {
+    let old_entries = entries(old);
+    let new_entries = entries(new);
+    // ports use the last lump with a name, so a later one wins here too
+    let old_by_name: HashMap<(Namespace, &str), &Entry<'_>> = old_entries
+        .iter()
+        .map(|entry| ((entry.namespace, entry.name()), entry))
+        .collect();
+
+    let mut changes = vec![];
+    let mut patch: Vec<&Entry<'_>> = vec![];
+    for entry in &new_entries {
+        let change = |kind, lumps| Change {
+            kind,
+            namespace: entry.namespace,
+            name: entry.name().to_string(),
+            size: entry.size(),
+            lumps,
+        };
+        match old_by_name.get(&(entry.namespace, entry.name())) {
+            None => changes.push(change(ChangeKind::Added, vec![])),
+            Some(old) if old.lumps != entry.lumps => {
+                let lumps = match entry.namespace {
+                    Namespace::Map => changed_map_lumps(old.lumps, entry.lumps),
+                    _ => vec![],
+                };
+                changes.push(change(ChangeKind::Changed, lumps))
+            }
+            Some(_) => continue,
+        }
+        patch.push(entry);
+    }
+
+    let mut seen: HashSet<(Namespace, &str)> = new_entries
+        .iter()
+        .map(|entry| (entry.namespace, entry.name()))
+        .collect();
+    let removed = old_entries
+        .iter()
+        .filter(|entry| seen.insert((entry.namespace, entry.name())));
+    changes.extend(removed.map(|entry| Change {
+        kind: ChangeKind::Removed,
+        namespace: entry.namespace,
+        name: entry.name().to_string(),
+        size: entry.size(),
+        lumps: vec![],
+    }));
+
+    // lumps and maps first, then each namespace between its markers
+    patch.sort_by_key(|entry| match entry.namespace {
+        Namespace::Global | Namespace::Map => Namespace::Global,
+        namespace => namespace,
+    });
+    let mut lumps = vec![];
+    let mut last = None;
+    for entry in patch {
+        if last != Some(entry.namespace) {
+            if let Some((_, end)) = last.and_then(Namespace::markers) {
+                lumps.push(marker(end));
+            }
+            if let Some((start, _)) = entry.namespace.markers() {
+                lumps.push(marker(start));
+            }
+            last = Some(entry.namespace);
+        }
+        lumps.extend(entry.lumps.iter().cloned());
+    }
+    if let Some((_, end)) = last.and_then(Namespace::markers) {
+        lumps.push(marker(end));
+    }
+
+    (Wad { lumps }, changes)
+}
+
+/// Write a patch from `old` to `new`, and write what's in it to `out`.
+#[inline]
+pub fn diff_wad<W: Write>(old: &Path, new: &Path, output: &Path, mut out: W) -> crate::Result {
+    let (patch, changes) = diff_wads(&Wad::load(old)?, &Wad::load(new)?);
+    patch
+        .write(BufWriter::new(File::create(output).with_file(output)?))
+        .with_file(output)?;
+
+    changes
+        .iter()
+        .try_for_each(|change| writeln!(out, "{}", change))?;
+    let count = |kind| changes.iter().filter(|c| c.kind == kind).count();
+    let size: usize = patch.lumps.iter().map(|l| l.data.len()).sum();
+    writeln!(
+        out,
+        "{} added, {} changed, {} removed; {} is {} lumps and {} bytes",
+        count(ChangeKind::Added),
+        count(ChangeKind::Changed),
+        count(ChangeKind::Removed),
+        output.display(),
+        patch.lumps.len(),
+        size
+    )?;
+    Ok(())
+}
+
+/// Split a WAD into lumps and maps, and find the namespace each is in. Markers are left out, since patches get their
+/// own.
+#[inline]
+fn entries(wad: &Wad) -> Vec<Entry<'_>> {
+    let mut entries = vec![];
+    let mut namespace = Namespace::Global;
+    let mut i = 0;
+    while i < wad.lumps.len() {
+        let name = wad.lumps[i].name.as_str();
+        let is_map_lump = |lump: &Lump| MAP_LUMPS.contains(&lump.name.as_str());
+        match (namespace, name) {
+            (_, "S_START") | (_, "SS_START") => namespace = Namespace::Sprites,
+            (_, "F_START") | (_, "FF_START") => namespace = Namespace::Flats,
+            (_, "P_START") | (_, "PP_START") => namespace = Namespace::Patches,
+            (_, "S_END")
+            | (_, "SS_END")
+            | (_, "F_END")
+            | (_, "FF_END")
+            | (_, "P_END")
+            | (_, "PP_END") => namespace = Namespace::Global,
+            // the IWAD splits its flats and patches with F1_START, F1_END and so on
+            (Namespace::Flats, _) | (Namespace::Patches, _)
+                if name.ends_with("_START") || name.ends_with("_END") => {}
+            (Namespace::Global, _) if wad.lumps.get(i + 1).is_some_and(is_map_lump) => {
+                let len = 1 + wad.lumps[i + 1..]
+                    .iter()
+                    .take_while(|lump| is_map_lump(lump))
+                    .count();
+                entries.push(Entry {
+                    namespace: Namespace::Map,
+                    lumps: &wad.lumps[i..i + len],
+                });
+                i += len;
+                continue;
+            }
+            _ => entries.push(Entry {
+                namespace,
+                lumps: &wad.lumps[i..i + 1],
+            }),
+        }
+        i += 1;
+    }
+    entries
+}
+
+/// The lumps of a map that are different, or only in one version of it.
+#[inline]
+fn changed_map_lumps(old: &[Lump], new: &[Lump]) -> Vec<String> {
+    let mut names: Vec<String> = new[1..]
+        .iter()
+        .filter(|lump| !old[1..].contains(lump))
+        .map(|lump| lump.name.clone())
+        .collect();
+    names.extend(
+        old[1..]
+            .iter()
+            .filter(|lump| !new[1..].iter().any(|l| l.name == lump.name))
+            .map(|lump| lump.name.clone()),
+    );
+    names
+}
+
+#[inline]
+fn marker(name: &str) -> Lump {
+    Lump {
+        name: name.to_string(),
+        data: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wad(lumps: &[(&str, &[u8])]) -> Wad {
+        Wad {
+            lumps: lumps
+                .iter()
+                .map(|(name, data)| Lump {
+                    name: name.to_string(),
+                    data: data.to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    fn names(wad: &Wad) -> Vec<&str> {
+        wad.lumps.iter().map(|l| l.name.as_str()).collect()
+    }
+
+    #[test]
+    fn identical_wads_make_an_empty_patch() {
+        let old = wad(&[
+            ("PLAYPAL", b"pal"),
+            ("S_START", b""),
+            ("TROOA1", b"imp"),
+            ("S_END", b""),
+        ]);
+        let (patch, changes) = diff_wads(&old, &old.clone());
+        assert!(patch.lumps.is_empty());
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn patches_have_changed_lumps_in_namespaces() {
+        let old = wad(&[
+            ("PLAYPAL", b"pal"),
+            ("COLORMAP", b"map"),
+            ("S_START", b""),
+            ("TROOA1", b"imp"),
+            ("POSSA1", b"zombie"),
+            ("S_END", b""),
+            ("F_START", b""),
+            ("F1_START", b""),
+            ("FLOOR0_1", b"floor"),
+            ("F1_END", b""),
+            ("F_END", b""),
+        ]);
+        let new = wad(&[
+            ("PLAYPAL", b"pal"),
+            ("COLORMAP", b"new map"),
+            ("S_START", b""),
+            ("TROOA1", b"imp"),
+            ("SARGA1", b"demon"),
+            ("S_END", b""),
+            ("F_START", b""),
+            ("F1_START", b""),
+            ("FLOOR0_1", b"new floor"),
+            ("F1_END", b""),
+            ("F_END", b""),
+        ]);
+        let (patch, changes) = diff_wads(&old, &new);
+        assert_eq!(
+            names(&patch),
+            ["COLORMAP", "SS_START", "SARGA1", "SS_END", "FF_START", "FLOOR0_1", "FF_END"]
+        );
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|c| (c.kind, c.namespace, c.name.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (ChangeKind::Changed, Namespace::Global, "COLORMAP"),
+                (ChangeKind::Added, Namespace::Sprites, "SARGA1"),
+                (ChangeKind::Changed, Namespace::Flats, "FLOOR0_1"),
+                (ChangeKind::Removed, Namespace::Sprites, "POSSA1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_are_patched_whole() {
+        let old = wad(&[
+            ("MAP01", b""),
+            ("THINGS", b"things"),
+            ("LINEDEFS", b"lines"),
+            ("MAP02", b""),
+            ("THINGS", b"things"),
+        ]);
+        let new = wad(&[
+            ("MAP01", b""),
+            ("THINGS", b"more things"),
+            ("LINEDEFS", b"lines"),
+            ("MAP02", b""),
+            ("THINGS", b"things"),
+        ]);
+        let (patch, changes) = diff_wads(&old, &new);
+        assert_eq!(names(&patch), ["MAP01", "THINGS", "LINEDEFS"]);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].namespace, Namespace::Map);
+        assert_eq!(changes[0].lumps, ["THINGS"]);
+        assert_eq!(
+            changes[0].to_string(),
+            "changed map MAP01 (16 bytes): THINGS"
+        );
+    }
+}
@@ -1,35 +1,33 @@
 // Apache 2.0 License
 
+use crate::error::ResultExt;
 use std::{
+    cmp,
     collections::HashMap,
+    convert::TryFrom,
     fs::File,
-    io::{self, prelude::*, BufReader},
+    io::{prelude::*, BufReader},
     path::Path,
     rc::Rc,
 };
 use tinyvec::TinyVec;
 
+/// The amounts of GUS memory there's a patch set for, in kilobytes.
+pub const GUS_MEMORY_KB: [usize; 4] = [256, 512, 768, 1024];
+
 /// Generate the DMXGUS lump for the WAD.
 #[inline]
-pub fn generate_dmxgus(config: &Path) -> crate::Result {
-    // load the config file
-    let mut config = BufReader::new(File::open(config)?);
-    let config: Dmxgus = serde_yaml::from_reader(&mut config)?;
-
+pub fn generate_dmxgus<W: Write>(config: Config, mut cout: W) -> crate::Result {
     // convert to instrument stats
-    let stats: InstrumentStats = config.into();
+    let stats = InstrumentStats::try_from(config)?;
 
     // get patchsets
-    let patchsets = vec![
-        patchset(256 * 1024, &stats),
-        patchset(512 * 1024, &stats),
-        patchset(768 * 1024, &stats),
-        patchset(1024 * 1024, &stats),
-    ];
+    let patchsets = GUS_MEMORY_KB
+        .iter()
+        .map(|kb| patchset(kb * 1024, &stats))
+        .collect::<crate::Result<Vec<_>>>()?;
 
     // write out patchsets
-    let stdout = io::stdout();
-    let mut cout = stdout.lock();
     write_patchsets(&mut cout, patchsets, stats)
 }
 
@@ -45,7 +43,7 @@ fn write_patchsets<W: Write>(
         .instrument_groups
         .into_iter()
         .flat_map(|g| g.members)
-        .filter_map(std::convert::identity)
+        .flatten()
         .map(|instrument| (instrument.midi_id, instrument.patch_name.clone()))
         .try_for_each::<_, crate::Result>(|(midi_id, patch_name)| {
             writeln!(
@@ -66,9 +64,11 @@ fn write_patchsets<W: Write>(
 
 /// Calculate a patch set for the specified size
 #[inline]
-fn patchset(mut size: usize, stats: &InstrumentStats) -> HashMap<u16, u16> {
+fn patchset(mut size: usize, stats: &InstrumentStats) -> crate::Result<HashMap<u16, u16>> {
     // gus wants us to reserve 8K + 32 bytes for other stuff
-    size = size.checked_sub((32 * 1024) + 8).expect("Can't fit it!");
+    size = size
+        .checked_sub((32 * 1024) + 8)
+        .ok_or(crate::Error::StaticMsg("GUS memory size is too small"))?;
 
     // create a patchset that patches every possible sound with a potential replacement
     let mut patchset: HashMap<u16, u16> = stats
@@ -87,19 +87,21 @@ fn patchset(mut size: usize, stats: &InstrumentStats) -> HashMap<u16, u16> {
     // go through the patchset and replace midi instruments with their equivalents as long as we can keep it under
     // the desired size
     let mut current_size = patch_size(
-        (&patchset)
-            .into_iter()
+        patchset
+            .iter()
             .map(|(i1, i2)| (stats.lookup(*i1).clone(), stats.lookup(*i2).clone())),
     );
-    assert!(
-        current_size < size,
-        "Minimal patch set won't fit in {} bytes!",
-        size
-    );
+    if current_size >= size {
+        return Err(crate::Error::Msg(format!(
+            "Minimal patch set needs {} bytes, but only {} fit",
+            current_size, size
+        )));
+    }
 
     // instruments are already sorted by decreasing priority
+    // every instrument is in a group, so it's already in the patch set
     stats.instruments.iter().for_each(|instrument| {
-        if patchset.get(&instrument.midi_id).copied().unwrap() != instrument.midi_id
+        if patchset.get(&instrument.midi_id) != Some(&instrument.midi_id)
             && instrument.patch_file_size + current_size < size
         {
             patchset.insert(instrument.midi_id, instrument.midi_id);
@@ -107,7 +109,7 @@ fn patchset(mut size: usize, stats: &InstrumentStats) -> HashMap<u16, u16> {
         }
     });
 
-    patchset
+    Ok(patchset)
 }
 
 // Calculate the size of an image-to-image mapping
@@ -119,18 +121,34 @@ fn patch_size<I: IntoIterator<Item = (Rc<Instrument>, Rc<Instrument>)>>(i: I) ->
         .sum()
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
-struct Dmxgus {
-    gus_instr_patches: HashMap<u16, String>,
-    patch_file_sizes: HashMap<String, usize>,
-    similar_groups: Vec<Vec<String>>,
-    instrument_stats: Vec<u16>,
+/// The patches a GUS has for each MIDI instrument, and how to choose between them.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Config {
+    /// The patch for each MIDI instrument. Percussion is numbered by its note plus 128.
+    pub gus_instr_patches: HashMap<u16, String>,
+    /// The size of each patch file, in bytes.
+    pub patch_file_sizes: HashMap<String, usize>,
+    /// Patches that can stand in for each other. The first in a group is used when the others don't fit.
+    pub similar_groups: Vec<Vec<String>>,
+    /// How often each instrument is used, by MIDI number.
+    pub instrument_stats: Vec<u16>,
 }
 
-impl From<Dmxgus> for InstrumentStats {
+impl Config {
+    /// Load a config from a YAML file.
     #[inline]
-    fn from(d: Dmxgus) -> InstrumentStats {
-        let Dmxgus {
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let config = BufReader::new(File::open(path).with_file(path)?);
+        serde_yaml::from_reader(config).with_file(path)
+    }
+}
+
+impl TryFrom<Config> for InstrumentStats {
+    type Error = crate::Error;
+
+    #[inline]
+    fn try_from(d: Config) -> crate::Result<InstrumentStats> {
+        let Config {
             gus_instr_patches,
             patch_file_sizes,
             similar_groups,
@@ -142,15 +160,17 @@ impl From<Dmxgus> for InstrumentStats {
         let instruments = gus_instr_patches
             .into_iter()
             .map(|(midi_id, patch_name)| {
-                let patch_file_size =
-                    patch_file_sizes
-                        .get(&patch_name)
-                        .cloned()
-                        .unwrap_or_else(|| {
-                            panic!("Cannot find patch file size for patch {}", &patch_name)
-                        });
-                let usage_score = instrument_stats[midi_id as usize];
-                (
+                let patch_file_size = patch_file_sizes
+                    .get(&patch_name)
+                    .cloned()
+                    .ok_or(crate::Error::StaticMsg("Patch has no file size"))
+                    .with_instrument(&patch_name)?;
+                let usage_score = instrument_stats
+                    .get(midi_id as usize)
+                    .copied()
+                    .ok_or(crate::Error::StaticMsg("Instrument has no usage stats"))
+                    .with_instrument(&patch_name)?;
+                Ok((
                     patch_name.clone(),
                     Rc::new(Instrument {
                         midi_id,
@@ -158,39 +178,58 @@ impl From<Dmxgus> for InstrumentStats {
                         patch_file_size,
                         usage_score,
                     }),
-                )
+                ))
             })
-            .collect::<HashMap<String, Rc<Instrument>>>();
+            .collect::<crate::Result<HashMap<String, Rc<Instrument>>>>()?;
 
         let instrument_groups = similar_groups
             .into_iter()
+            .filter(|group| !group.is_empty())
             .map(|group| {
                 let members = group
                     .into_iter()
                     .map(|iname| {
-                        Some(instruments.get(&iname).cloned().unwrap_or_else(|| {
-                            panic!("Cannot find member {} of instrument group", &iname)
-                        }))
+                        instruments
+                            .get(&iname)
+                            .cloned()
+                            .map(Some)
+                            .ok_or(crate::Error::StaticMsg(
+                                "Similar group member isn't a GUS patch",
+                            ))
+                            .with_instrument(&iname)
                     })
-                    .collect();
-                InstrumentGroup { members }
+                    .collect::<crate::Result<_>>()?;
+                Ok(InstrumentGroup { members })
             })
-            .collect::<Vec<_>>();
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        // the patch sets start with every instrument mapped to its group's leader
+        if let Some(lonely) = instruments.values().find(|instrument| {
+            !instrument_groups
+                .iter()
+                .flat_map(|g| g.members.iter().flatten())
+                .any(|member| Rc::ptr_eq(member, instrument))
+        }) {
+            return Err(crate::Error::StaticMsg(
+                "Instrument isn't in a similar group",
+            ))
+            .with_instrument(&lonely.patch_name);
+        }
 
-        let mut instruments: Box<[_]> = instruments.into_iter().map(|(_, i)| i).collect();
+        let mut instruments: Box<[_]> = instruments.into_values().collect();
 
-        // sort the instruments by their priority
+        // sort the instruments by their priority, then by number, so the patch sets don't depend on hash order
         instruments.sort_by_key(|instrument| {
             // dont feel like using ordered_float, so just convert it to a fixed-point number
             let res = (instrument.usage_score as f32 / instrument.patch_file_size as f32) * 1e7;
-            res as usize
+            (res as usize, cmp::Reverse(instrument.midi_id))
         });
         instruments.reverse();
 
-        Self {
+        Ok(Self {
             instruments,
             instrument_groups,
-        }
+        })
     }
 }
 
@@ -238,3 +277,64 @@ fn normalize_stats(stats: &mut [u16]) {
         *stat = r as u16;
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            gus_instr_patches: vec![(0, "acpiano".to_string()), (1, "britepno".to_string())]
+                .into_iter()
+                .collect(),
+            patch_file_sizes: vec![
+                ("acpiano".to_string(), 30000),
+                ("britepno".to_string(), 20000),
+            ]
+            .into_iter()
+            .collect(),
+            similar_groups: vec![vec!["acpiano".to_string(), "britepno".to_string()]],
+            instrument_stats: vec![10, 5],
+        }
+    }
+
+    #[test]
+    fn patchsets_fall_back_to_the_group_leader() {
+        let stats = InstrumentStats::try_from(config()).unwrap();
+        let small = patchset(256 * 1024, &stats).unwrap();
+        assert_eq!(small.get(&0), Some(&0));
+        assert_eq!(small.get(&1), Some(&1));
+
+        let mut out = vec![];
+        write_patchsets(&mut out, vec![small.clone(); 4], stats).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "0, 0, 0, 0, 0, acpiano\n1, 1, 1, 1, 1, britepno\n"
+        );
+    }
+
+    #[test]
+    fn broken_configs_name_the_instrument() {
+        let mut broken = config();
+        broken.patch_file_sizes.remove("britepno");
+        let err = InstrumentStats::try_from(broken).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "instrument britepno: Patch has no file size"
+        );
+
+        let mut broken = config();
+        broken.similar_groups[0].pop();
+        let err = InstrumentStats::try_from(broken).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "instrument britepno: Instrument isn't in a similar group"
+        );
+
+        let mut huge = config();
+        huge.patch_file_sizes
+            .insert("acpiano".to_string(), 300 * 1024);
+        let stats = InstrumentStats::try_from(huge).unwrap();
+        assert!(patchset(256 * 1024, &stats).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+// Apache 2.0 License
+
+use std::{
+    fmt,
+    io::Error as IoError,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    StaticMsg(&'static str),
+    /// A message naming the value that was wrong.
+    Msg(String),
+    Io(Arc<IoError>),
+    Yaml(Arc<serde_yaml::Error>),
+    PngDecode(Arc<png::DecodingError>),
+    PngEncode(Arc<png::EncodingError>),
+    Wav(Arc<hound::Error>),
+    Flac(Arc<claxon::Error>),
+    Vorbis(Arc<lewton::VorbisError>),
+    /// An error, and what was being worked on when it happened.
+    Context(Context, Box<Error>),
+}
+
+/// What an error happened to.
+#[derive(Debug, Clone)]
+pub enum Context {
+    File(PathBuf),
+    Lump(String),
+    Instrument(String),
+}
+
+pub type Result<T = ()> = std::result::Result<T, Error>;
+
+impl From<IoError> for Error {
+    #[inline]
+    fn from(i: IoError) -> Error {
+        Error::Io(Arc::new(i))
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    #[inline]
+    fn from(sy: serde_yaml::Error) -> Error {
+        Error::Yaml(Arc::new(sy))
+    }
+}
+
+impl From<png::DecodingError> for Error {
+    #[inline]
+    fn from(pd: png::DecodingError) -> Error {
+        Error::PngDecode(Arc::new(pd))
+    }
+}
+
+impl From<png::EncodingError> for Error {
+    #[inline]
+    fn from(pe: png::EncodingError) -> Error {
+        Error::PngEncode(Arc::new(pe))
+    }
+}
+
+impl From<hound::Error> for Error {
+    #[inline]
+    fn from(w: hound::Error) -> Error {
+        Error::Wav(Arc::new(w))
+    }
+}
+
+impl From<claxon::Error> for Error {
+    #[inline]
+    fn from(f: claxon::Error) -> Error {
+        Error::Flac(Arc::new(f))
+    }
+}
+
+impl From<lewton::VorbisError> for Error {
+    #[inline]
+    fn from(v: lewton::VorbisError) -> Error {
+        Error::Vorbis(Arc::new(v))
+    }
+}
+
+impl fmt::Display for Error {
+    /// Errors print their context first, so a chain reads like "genmidi/instr001.sbi: SBI file is too short".
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::StaticMsg(msg) => f.write_str(msg),
+            Error::Msg(msg) => f.write_str(msg),
+            Error::Io(e) => e.fmt(f),
+            Error::Yaml(e) => e.fmt(f),
+            Error::PngDecode(e) => e.fmt(f),
+            Error::PngEncode(e) => e.fmt(f),
+            Error::Wav(e) => e.fmt(f),
+            Error::Flac(e) => e.fmt(f),
+            Error::Vorbis(e) => e.fmt(f),
+            Error::Context(context, cause) => write!(f, "{}: {}", context, cause),
+        }
+    }
+}
+
+impl fmt::Display for Context {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Context::File(path) => write!(f, "{}", path.display()),
+            Context::Lump(name) => write!(f, "lump {}", name),
+            Context::Instrument(name) => write!(f, "instrument {}", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(&**e),
+            Error::Yaml(e) => Some(&**e),
+            Error::PngDecode(e) => Some(&**e),
+            Error::PngEncode(e) => Some(&**e),
+            Error::Wav(e) => Some(&**e),
+            Error::Flac(e) => Some(&**e),
+            Error::Vorbis(e) => Some(&**e),
+            Error::Context(_, cause) => Some(&**cause),
+            Error::StaticMsg(_) | Error::Msg(_) => None,
+        }
+    }
+}
+
+/// Adds context to the errors in results.
+pub trait ResultExt<T> {
+    /// The error happened while reading or writing a file.
+    fn with_file(self, path: &Path) -> Result<T>;
+    /// The error happened while reading or building a lump, or a map named after its marker lump.
+    fn with_lump(self, name: &str) -> Result<T>;
+    /// The error happened while loading a GENMIDI or DMXGUS instrument.
+    fn with_instrument(self, name: &str) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for std::result::Result<T, E> {
+    #[inline]
+    fn with_file(self, path: &Path) -> Result<T> {
+        self.map_err(|e| Error::Context(Context::File(path.to_path_buf()), Box::new(e.into())))
+    }
+
+    #[inline]
+    fn with_lump(self, name: &str) -> Result<T> {
+        self.map_err(|e| Error::Context(Context::Lump(name.to_string()), Box::new(e.into())))
+    }
+
+    #[inline]
+    fn with_instrument(self, name: &str) -> Result<T> {
+        self.map_err(|e| Error::Context(Context::Instrument(name.to_string()), Box::new(e.into())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{error::Error as _, fs::File, io::ErrorKind};
+
+    #[test]
+    fn context_is_printed_before_the_cause() {
+        let err = Err::<(), _>(Error::StaticMsg("SBI file is too short"))
+            .with_file(Path::new("genmidi/instr001.sbi"))
+            .with_instrument("Acoustic Grand Piano")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "instrument Acoustic Grand Piano: genmidi/instr001.sbi: SBI file is too short"
+        );
+        assert!(err.source().unwrap().source().is_some());
+    }
+
+    #[test]
+    fn io_errors_keep_their_cause() {
+        let err = File::open("no/such/file.sbi")
+            .with_file(Path::new("no/such/file.sbi"))
+            .unwrap_err();
+        assert!(err.to_string().starts_with("no/such/file.sbi: "));
+        match err {
+            Error::Context(_, cause) => match *cause {
+                Error::Io(io) => assert_eq!(io.kind(), ErrorKind::NotFound),
+                other => panic!("{:?}", other),
+            },
+            other => panic!("{:?}", other),
+        }
+    }
+}
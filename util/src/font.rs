@@ -0,0 +1,514 @@
+// Apache 2.0 License
+
+//! Draws the status bar numbers, HUD font and menu text from a TTF/OTF font or a bitmap font sheet. A spec lists the
+//! fonts, which characters become glyph patches, and which strings become whole graphics.
+
+use crate::{
+    blenderscript::postprocess::{darkest, quantize},
+    error::ResultExt,
+    picture::Picture,
+};
+use ab_glyph::{Font as _, FontVec, PxScale, ScaleFont};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+/// Coverage below this leaves a pixel transparent. Doom graphics have no partial transparency, so edges are hard.
+const COVERAGE_THRESHOLD: f32 = 0.5;
+
+/// The fonts, glyphs and strings to draw.
+#[derive(serde::Deserialize)]
+pub struct Spec {
+    pub fonts: BTreeMap<String, FontSpec>,
+    #[serde(default)]
+    pub glyphs: Vec<GlyphSet>,
+    #[serde(default)]
+    pub strings: Vec<Text>,
+}
+
+/// Where a font comes from, and how it's colored.
+#[derive(serde::Deserialize)]
+pub struct FontSpec {
+    /// A TTF or OTF file, or a PNG sheet of glyphs in a grid. Relative to the spec.
+    pub file: PathBuf,
+    /// For TTF/OTF fonts: the height of a line, in pixels.
+    #[serde(default)]
+    pub size: Option<f32>,
+    /// For sheets: the width and height of a cell. Cells go left to right, then top to bottom.
+    #[serde(default)]
+    pub cell: Option<[usize; 2]>,
+    /// For sheets: the character in the first cell.
+    #[serde(default = "default_first")]
+    pub first: char,
+    /// For sheets: trim the empty columns to the right of each glyph, instead of using the whole cell.
+    #[serde(default)]
+    pub proportional: bool,
+    /// Colors from the top of a line to the bottom. Each row is shaded between them, then matched to the palette.
+    #[serde(default = "default_ramp")]
+    pub ramp: Vec<[u8; 3]>,
+    /// A copy of each glyph drawn behind it, offset and in a flat color.
+    #[serde(default)]
+    pub shadow: Option<Shadow>,
+    /// Extra pixels between the glyphs of a string.
+    #[serde(default)]
+    pub spacing: i32,
+}
+
+/// A drop shadow.
+#[derive(serde::Deserialize)]
+pub struct Shadow {
+    #[serde(default = "default_shadow_offset")]
+    pub offset: [usize; 2],
+    /// Defaults to the palette's darkest color.
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+}
+
+/// A run of characters that each become a patch.
+#[derive(serde::Deserialize)]
+pub struct GlyphSet {
+    pub font: String,
+    pub chars: String,
+    /// The lump name, with `{char}` replaced by the character and `{code}` by its three digit code.
+    pub name: String,
+}
+
+/// A string drawn as a single patch.
+#[derive(serde::Deserialize)]
+pub struct Text {
+    pub font: String,
+    pub lump: String,
+    pub text: String,
+}
+
+#[inline]
+fn default_first() -> char {
+    '!'
+}
+
+#[inline]
+fn default_ramp() -> Vec<[u8; 3]> {
+    vec![[255, 255, 255]]
+}
+
+#[inline]
+fn default_shadow_offset() -> [usize; 2] {
+    [1, 1]
+}
+
+/// A loaded font.
+pub enum Font {
+    Outline {
+        font: FontVec,
+        size: f32,
+    },
+    Sheet {
+        sheet: Picture,
+        cell: [usize; 2],
+        first: char,
+        proportional: bool,
+    },
+}
+
+/// How much of each pixel a glyph or string covers, from 0 to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mask {
+    pub width: usize,
+    pub height: usize,
+    pub coverage: Vec<f32>,
+}
+
+impl Mask {
+    #[inline]
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            coverage: vec![0.0; width * height],
+        }
+    }
+
+    /// Add another mask with its left edge at `x`.
+    #[inline]
+    fn blit(&mut self, other: &Mask, x: i32) {
+        for oy in 0..other.height.min(self.height) {
+            for ox in 0..other.width {
+                let sx = x + ox as i32;
+                if sx < 0 || sx as usize >= self.width {
+                    continue;
+                }
+                let c = &mut self.coverage[(oy * self.width) + sx as usize];
+                *c = (*c + other.coverage[(oy * other.width) + ox]).min(1.0);
+            }
+        }
+    }
+}
+
+impl Font {
+    /// Load a font described by a spec, with its file relative to `root`.
+    #[inline]
+    pub fn load(spec: &FontSpec, root: &Path) -> crate::Result<Self> {
+        let path = root.join(&spec.file);
+        match (spec.size, spec.cell) {
+            (Some(size), None) => {
+                if size < 1.0 {
+                    return Err(crate::Error::StaticMsg(
+                        "Font size must be at least one pixel",
+                    ));
+                }
+                let font = FontVec::try_from_vec(fs::read(&path).with_file(&path)?)
+                    .map_err(|_| crate::Error::StaticMsg("Not a TTF or OTF font"))
+                    .with_file(&path)?;
+                Ok(Font::Outline { font, size })
+            }
+            (None, Some(cell)) => {
+                if cell[0] == 0 || cell[1] == 0 {
+                    return Err(crate::Error::StaticMsg(
+                        "Font cells must be at least one pixel",
+                    ));
+                }
+                Ok(Font::Sheet {
+                    sheet: Picture::load(&path)?,
+                    cell,
+                    first: spec.first,
+                    proportional: spec.proportional,
+                })
+            }
+            _ => Err(crate::Error::StaticMsg(
+                "A font needs either a size, for TTF/OTF fonts, or a cell size, for sheets",
+            )),
+        }
+    }
+
+    /// The height of every glyph, so they line up when drawn with the same offsets.
+    #[inline]
+    pub fn line_height(&self) -> usize {
+        match self {
+            Font::Outline { font, size } => {
+                let scaled = font.as_scaled(PxScale::from(*size));
+                (scaled.ascent() - scaled.descent()).ceil() as usize
+            }
+            Font::Sheet { cell, .. } => cell[1],
+        }
+    }
+
+    /// Draw a single character, as wide as its advance. Returns `None` if the font doesn't have it.
+    #[inline]
+    pub fn glyph(&self, c: char) -> Option<Mask> {
+        match self {
+            Font::Outline { font, size } => {
+                let scale = PxScale::from(*size);
+                let scaled = font.as_scaled(scale);
+                let id = font.glyph_id(c);
+                if id.0 == 0 {
+                    return None;
+                }
+
+                let height = self.line_height();
+                let advance = scaled.h_advance(id).round().max(0.0) as usize;
+                let outline = match font.outline_glyph(
+                    id.with_scale_and_position(scale, ab_glyph::point(0.0, scaled.ascent())),
+                ) {
+                    Some(outline) => outline,
+                    None => return Some(Mask::new(advance, height)),
+                };
+
+                // Glyphs that hang past their advance, like italics, are widened rather than cut off.
+                let bounds = outline.px_bounds();
+                let left = bounds.min.x.floor().min(0.0) as i32;
+                let width = advance.max(bounds.max.x.ceil() as usize) + (-left) as usize;
+                let mut mask = Mask::new(width, height);
+                outline.draw(|x, y, coverage| {
+                    let x = x as i32 + bounds.min.x.floor() as i32 - left;
+                    let y = y as i32 + bounds.min.y.floor() as i32;
+                    if x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height {
+                        mask.coverage[(y as usize * width) + x as usize] = coverage.min(1.0);
+                    }
+                });
+                Some(mask)
+            }
+            Font::Sheet {
+                sheet,
+                cell,
+                first,
+                proportional,
+            } => {
+                let index = (c as u32).checked_sub(*first as u32)? as usize;
+                let columns = sheet.width / cell[0];
+                if columns == 0 || index / columns >= sheet.height / cell[1] {
+                    return None;
+                }
+                let (left, top) = ((index % columns) * cell[0], (index / columns) * cell[1]);
+
+                let mut mask = Mask::new(cell[0], cell[1]);
+                for y in 0..cell[1] {
+                    for x in 0..cell[0] {
+                        mask.coverage[(y * cell[0]) + x] =
+                            sheet.get(left + x, top + y)[3] as f32 / 255.0;
+                    }
+                }
+
+                if *proportional {
+                    let right = (0..cell[0]).rev().find(|&x| {
+                        (0..cell[1]).any(|y| mask.coverage[(y * cell[0]) + x] >= COVERAGE_THRESHOLD)
+                    });
+                    // Empty cells, like a space, keep the whole cell as their width.
+                    if let Some(right) = right {
+                        let mut trimmed = Mask::new(right + 1, cell[1]);
+                        trimmed.blit(&mask, 0);
+                        mask = trimmed;
+                    }
+                }
+                Some(mask)
+            }
+        }
+    }
+
+    /// Draw a string on one line. Characters the font doesn't have are an error.
+    #[inline]
+    pub fn text(&self, text: &str, spacing: i32) -> crate::Result<Mask> {
+        let glyphs = text
+            .chars()
+            .map(|c| {
+                self.glyph(c)
+                    .ok_or_else(|| crate::Error::Msg(format!("Font has no glyph for {:?}", c)))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let mut positions = Vec::with_capacity(glyphs.len());
+        let mut x = 0i32;
+        let mut width = 0i32;
+        for (i, glyph) in glyphs.iter().enumerate() {
+            if i > 0 {
+                x += spacing;
+            }
+            positions.push(x);
+            width = width.max(x + glyph.width as i32);
+            x += glyph.width as i32;
+        }
+
+        let mut mask = Mask::new(width.max(0) as usize, self.line_height());
+        for (glyph, x) in glyphs.iter().zip(positions) {
+            mask.blit(glyph, x);
+        }
+        Ok(mask)
+    }
+}
+
+/// Color a mask with a ramp and shadow, and match it to the palette. The result has offsets of (0, 0), so
+/// glyphs drawn at the same point share a top line.
+#[inline]
+pub fn colorize(
+    mask: &Mask,
+    ramp: &[[u8; 3]],
+    shadow: Option<&Shadow>,
+    palette: &[[u8; 3]],
+) -> crate::Result<Picture> {
+    if ramp.is_empty() {
+        return Err(crate::Error::StaticMsg(
+            "A font's ramp needs at least one color",
+        ));
+    }
+
+    let (dx, dy) = shadow.map_or((0, 0), |s| (s.offset[0], s.offset[1]));
+    let mut picture = Picture::new(mask.width + dx, mask.height + dy);
+    let opaque = |x: usize, y: usize| mask.coverage[(y * mask.width) + x] >= COVERAGE_THRESHOLD;
+
+    if let Some(shadow) = shadow {
+        let [r, g, b] = match shadow.color {
+            Some(color) => color,
+            None => darkest(palette)?,
+        };
+        for y in 0..mask.height {
+            for x in 0..mask.width {
+                if opaque(x, y) {
+                    picture.set(x + dx, y + dy, [r, g, b, 255]);
+                }
+            }
+        }
+    }
+
+    for y in 0..mask.height {
+        let [r, g, b] = ramp_color(ramp, y, mask.height);
+        for x in 0..mask.width {
+            if opaque(x, y) {
+                picture.set(x, y, [r, g, b, 255]);
+            }
+        }
+    }
+
+    quantize(&mut picture, palette, false);
+    picture.offset = Some((0, 0));
+    Ok(picture)
+}
+
+/// The ramp's color for a row, blending between neighboring colors.
+#[inline]
+fn ramp_color(ramp: &[[u8; 3]], y: usize, height: usize) -> [u8; 3] {
+    if ramp.len() == 1 || height <= 1 {
+        return ramp[0];
+    }
+    let t = (y as f32 / (height - 1) as f32) * (ramp.len() - 1) as f32;
+    let i = (t.floor() as usize).min(ramp.len() - 2);
+    let f = t - i as f32;
+    let mut color = [0; 3];
+    for (c, out) in color.iter_mut().enumerate() {
+        *out = ((ramp[i][c] as f32 * (1.0 - f)) + (ramp[i + 1][c] as f32 * f)).round() as u8;
+    }
+    color
+}
+
+/// Fill in a glyph name template.
+#[inline]
+pub fn lump_name(template: &str, c: char) -> crate::Result<String> {
+    let name = template
+        .replace("{char}", &c.to_string())
+        .replace("{code}", &format!("{:03}", c as u32))
+        .to_ascii_uppercase();
+    if name.is_empty() || name.len() > 8 {
+        return Err(crate::Error::Msg(format!(
+            "Lump name {:?} must be 1 to 8 characters",
+            name
+        )));
+    }
+    Ok(name)
+}
+
+/// Draw everything in a spec, writing each patch to `<outdir>/<lump>.png`. Returns the lumps written.
+#[inline]
+pub fn render_fonts(
+    spec_path: &Path,
+    outdir: &Path,
+    palette: &[[u8; 3]],
+) -> crate::Result<Vec<String>> {
+    let spec: Spec =
+        serde_yaml::from_reader(BufReader::new(File::open(spec_path).with_file(spec_path)?))
+            .with_file(spec_path)?;
+    let root = spec_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let fonts = spec
+        .fonts
+        .iter()
+        .map(|(name, font)| {
+            Ok((
+                name.as_str(),
+                (font, Font::load(font, root).with_file(spec_path)?),
+            ))
+        })
+        .collect::<crate::Result<BTreeMap<_, _>>>()?;
+    let font = |name: &str| {
+        fonts
+            .get(name)
+            .ok_or_else(|| crate::Error::Msg(format!("No font named {:?}", name)))
+            .with_file(spec_path)
+    };
+
+    fs::create_dir_all(outdir).with_file(outdir)?;
+    let mut written = Vec::new();
+    let mut save = |lump: String, picture: Picture| -> crate::Result {
+        let path = outdir.join(format!("{}.png", lump.to_ascii_lowercase()));
+        picture.save(&path)?;
+        written.push(lump);
+        Ok(())
+    };
+
+    for set in &spec.glyphs {
+        let (spec_font, font) = font(&set.font)?;
+        for c in set.chars.chars() {
+            let lump = lump_name(&set.name, c).with_file(spec_path)?;
+            let mask = font
+                .glyph(c)
+                .ok_or_else(|| {
+                    crate::Error::Msg(format!("Font {:?} has no glyph for {:?}", set.font, c))
+                })
+                .with_file(spec_path)?;
+            save(
+                lump,
+                colorize(&mask, &spec_font.ramp, spec_font.shadow.as_ref(), palette)?,
+            )?;
+        }
+    }
+
+    for text in &spec.strings {
+        let (spec_font, font) = font(&text.font)?;
+        let lump = lump_name(&text.lump, ' ').with_file(spec_path)?;
+        let mask = font
+            .text(&text.text, spec_font.spacing)
+            .with_file(spec_path)?;
+        save(
+            lump,
+            colorize(&mask, &spec_font.ramp, spec_font.shadow.as_ref(), palette)?,
+        )?;
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PALETTE: [[u8; 3]; 4] = [[0, 0, 0], [255, 255, 255], [255, 0, 0], [0, 0, 255]];
+
+    /// A sheet with two 3x2 cells: 'A' is a full block, 'B' only uses its left column.
+    fn sheet(proportional: bool) -> Font {
+        let mut sheet = Picture::new(6, 2);
+        for y in 0..2 {
+            for x in 0..3 {
+                sheet.set(x, y, [255, 255, 255, 255]);
+            }
+            sheet.set(3, y, [255, 255, 255, 255]);
+        }
+        Font::Sheet {
+            sheet,
+            cell: [3, 2],
+            first: 'A',
+            proportional,
+        }
+    }
+
+    #[test]
+    fn sheet_glyphs() {
+        let font = sheet(false);
+        assert_eq!(font.glyph('A').unwrap().coverage, vec![1.0; 6]);
+        assert_eq!(font.glyph('B').unwrap().width, 3);
+        assert_eq!(font.glyph('C'), None);
+        assert_eq!(font.glyph('@'), None);
+
+        let font = sheet(true);
+        assert_eq!(font.glyph('B').unwrap().width, 1);
+        assert_eq!(font.text("BA", 1).unwrap().width, 5);
+    }
+
+    #[test]
+    fn ramp_and_shadow() {
+        let mask = sheet(true).glyph('B').unwrap();
+        let shadow = Shadow {
+            offset: [1, 1],
+            color: None,
+        };
+        let picture = colorize(
+            &mask,
+            &[[250, 10, 10], [10, 10, 250]],
+            Some(&shadow),
+            &PALETTE,
+        )
+        .unwrap();
+        assert_eq!((picture.width, picture.height), (2, 3));
+        assert_eq!(picture.offset, Some((0, 0)));
+        assert_eq!(picture.get(0, 0), [255, 0, 0, 255]);
+        assert_eq!(picture.get(0, 1), [0, 0, 255, 255]);
+        assert_eq!(picture.get(1, 1), [0, 0, 0, 255]);
+        assert_eq!(picture.get(1, 0)[3], 0);
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(lump_name("STCFN{code}", '!').unwrap(), "STCFN033");
+        assert_eq!(lump_name("sttnum{char}", '7').unwrap(), "STTNUM7");
+        assert!(lump_name("STYSNUM{code}", '1').is_err());
+    }
+}
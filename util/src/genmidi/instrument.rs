@@ -1,5 +1,6 @@
 // Apache 2.0 License
 
+use crate::error::ResultExt;
 use std::{
     fs::File,
     io::{prelude::*, BufReader},
@@ -15,6 +16,7 @@ pub struct Instrument {
     off1: isize,
     off2: isize,
     octave: Option<u8>,
+    dummy: bool,
 }
 
 impl Instrument {
@@ -35,9 +37,25 @@ impl Instrument {
             off1,
             off2,
             octave,
+            dummy: false,
         })
     }
 
+    /// Load the placeholder that fills the bank where there isn't an instrument yet.
+    #[inline]
+    pub fn load_dummy(path: &Path) -> crate::Result<Self> {
+        Ok(Self {
+            dummy: true,
+            ..Self::load(path, None, 0, 0, None)?
+        })
+    }
+
+    /// Whether this is the placeholder rather than a real instrument.
+    #[inline]
+    pub fn is_dummy(&self) -> bool {
+        self.dummy
+    }
+
     #[inline]
     pub fn voice1(&self) -> &Voice {
         &self.voice1
@@ -84,7 +102,7 @@ impl Voice {
     #[inline]
     fn load<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
         let mut v = Self::default();
-        let name = load_instrument(path, |name, value| {
+        let name = load_instrument(path.as_ref(), |name, value| {
             let field: &mut u8 = match name {
                 "m_am_vibrato_eg" => &mut v.m_am_vibrato_eg,
                 "c_am_vibrato_eg" => &mut v.c_am_vibrato_eg,
@@ -98,19 +116,23 @@ impl Voice {
                 "c_waveform" => &mut v.c_waveform,
                 "feedback_fm" => &mut v.feedback_fm,
                 name => {
-                    eprintln!("Unintelligible name: {}", name);
-                    return;
+                    return Err(crate::Error::Msg(format!(
+                        "Unintelligible SBI field name {}",
+                        name
+                    )))
                 }
             };
             *field = value;
-        })?;
+            Ok(())
+        })
+        .with_file(path.as_ref())?;
         v.name = name;
         Ok(v)
     }
 }
 
 #[inline]
-fn load_instrument<P: AsRef<Path>, F: FnMut(&str, u8)>(
+fn load_instrument<P: AsRef<Path>, F: FnMut(&str, u8) -> crate::Result>(
     path: P,
     mut operator: F,
 ) -> crate::Result<Vec<u8>> {
@@ -129,11 +151,12 @@ fn load_instrument<P: AsRef<Path>, F: FnMut(&str, u8)>(
     ];
 
     let mut data = vec![];
-    let p: &Path = path.as_ref();
-    let mut file =
-        BufReader::new(File::open(p).unwrap_or_else(|_| panic!("Could not find file {:?}", p)));
+    let mut file = BufReader::new(File::open(path)?);
     file.read_to_end(&mut data)?;
 
+    if data.len() < 36 + FIELDS.len() {
+        return Err(crate::Error::StaticMsg("SBI file is too short"));
+    }
     let instrument_data = data.split_off(36);
     let name_data = data.split_off(4);
 
@@ -141,9 +164,10 @@ fn load_instrument<P: AsRef<Path>, F: FnMut(&str, u8)>(
         return Err(crate::Error::StaticMsg("SBI file doesn't have SBI header"));
     }
 
-    FIELDS.iter().enumerate().for_each(|(i, field)| {
-        operator(*field, instrument_data[i].clone());
-    });
+    FIELDS
+        .iter()
+        .enumerate()
+        .try_for_each(|(i, field)| operator(field, instrument_data[i]))?;
 
     Ok(name_data)
 }
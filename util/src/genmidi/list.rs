@@ -1,7 +1,7 @@
 // Apache 2.0 License
 
 use super::*;
-use std::{array::IntoIter as ArrayIter, path::Path};
+use std::path::Path;
 
 macro_rules! instrument {
     ($path: expr, off1=$off1: expr, $basedir: expr) => {{
@@ -23,13 +23,13 @@ macro_rules! instrument {
         )
     }};
     ($basedir: expr) => {{
-        instrument!("dummy.sbi", $basedir)
+        Instrument::load_dummy(&($basedir).join("dummy.sbi"))
     }};
 }
 
 #[inline]
 pub fn iter_instruments(basedir: &Path) -> impl Iterator<Item = crate::Result<Instrument>> {
-    ArrayIter::new([
+    IntoIterator::into_iter([
         instrument!("instr001.sbi", basedir), // //001 - Acoustic Grand Piano
         instrument!("instr002.sbi", basedir), // //002 - Bright Acoustic Piano
         instrument!("instr003.sbi", basedir), // //003 - Electric Grand Piano
@@ -163,7 +163,7 @@ pub fn iter_instruments(basedir: &Path) -> impl Iterator<Item = crate::Result<In
 
 #[inline]
 pub fn iter_percussion(basedir: &Path) -> impl Iterator<Item = crate::Result<Instrument>> {
-    ArrayIter::new([
+    IntoIterator::into_iter([
         instrument!("perc35.sbi", note = ON4.a(), basedir), // //35 Acoustic Bass Drum
         instrument!("perc36.sbi", note = ON4.a(), basedir), // //36 Bass Drum 1
         instrument!("perc37.sbi", note = ON1.c(), basedir), // //37 Side Stick
@@ -7,48 +7,55 @@ mod midi;
 pub use instrument::{Instrument, Voice};
 pub use midi::*;
 
-use std::{
-    convert::TryInto,
-    io::{self, prelude::*},
-    iter,
-    path::Path,
-};
+use std::{convert::TryInto, io::prelude::*, iter, path::Path};
 
 const HEADER: &[u8; 8] = b"#OPL_II#";
 
+/// The instruments in a GENMIDI lump, in order: the 128 melodic instruments, then the percussion.
+#[derive(Debug, Clone)]
+pub struct Bank {
+    pub instruments: Vec<Instrument>,
+    /// The voice used as the second voice of instruments that only have one.
+    pub dummy: Instrument,
+}
+
+impl Bank {
+    /// Load the bank from a directory of SBI instruments.
+    #[inline]
+    pub fn load(basedir: &Path) -> crate::Result<Self> {
+        let instruments = list::iter_instruments(basedir)
+            .chain(list::iter_percussion(basedir))
+            .collect::<crate::Result<Vec<_>>>()?;
+        let dummy = Instrument::load_dummy(&basedir.join("dummy.sbi"))?;
+        Ok(Self { instruments, dummy })
+    }
+}
+
 /// Create the Genmidi Lump
 #[inline]
-pub fn generate_genmidi<P: AsRef<Path>>(basedir: P) -> crate::Result {
-    let stdout = io::stdout();
-    let mut cout = stdout.lock();
-    cout.write_all(HEADER)?;
+pub fn generate_genmidi<W: Write>(bank: &Bank, mut cout: W) -> crate::Result {
+    let Bank { instruments, dummy } = bank;
 
-    let instruments = list::iter_instruments(basedir.as_ref())
-        .chain(list::iter_percussion(basedir.as_ref()))
-        .collect::<crate::Result<Vec<_>>>()?;
-    let null_instrument = Instrument::load(&basedir.as_ref().join("dummy.sbi"), None, 0, 0, None)?;
+    cout.write_all(HEADER)?;
 
     //    eprintln!("Instruments: {:#?}", &instruments);
 
     instruments
         .iter()
-        .cloned()
-        .try_for_each::<_, crate::Result>(|i| {
-            encode_instrument(&mut cout, i, null_instrument.voice1())
-        })?;
-
-    instruments
-        .into_iter()
-        .try_for_each::<_, crate::Result>(|i| {
-            let name = i.voice1.name;
-            let namedata = name
-                .into_iter()
-                .chain(iter::repeat(0))
-                .take(32)
-                .collect::<Vec<u8>>();
-            cout.write_all(&namedata)?;
-            Ok(())
-        })?;
+        .try_for_each::<_, crate::Result>(|i| encode_instrument(&mut cout, i, dummy.voice1()))?;
+
+    instruments.iter().try_for_each::<_, crate::Result>(|i| {
+        let namedata = i
+            .voice1
+            .name
+            .iter()
+            .copied()
+            .chain(iter::repeat(0))
+            .take(32)
+            .collect::<Vec<u8>>();
+        cout.write_all(&namedata)?;
+        Ok(())
+    })?;
 
     cout.flush()?;
     Ok(())
@@ -57,7 +64,7 @@ pub fn generate_genmidi<P: AsRef<Path>>(basedir: P) -> crate::Result {
 #[inline]
 fn encode_instrument<W: Write>(
     w: &mut W,
-    instrument: Instrument,
+    instrument: &Instrument,
     null_voice: &Voice,
 ) -> crate::Result {
     const FLAG_TWO_VOICE: i16 = 0x0004;
@@ -79,14 +86,15 @@ fn encode_instrument<W: Write>(
     w.write_all(&[128])?;
     w.write_all(&[octave.unwrap_or(0)])?;
 
-    encode_voice(
-        w,
-        instrument.voice1(),
-        instrument.offset1().try_into().unwrap(),
-    )?;
+    let offset = |offset: isize| {
+        offset
+            .try_into()
+            .map_err(|_| crate::Error::StaticMsg("Instrument note offset doesn't fit in 16 bits"))
+    };
+    encode_voice(w, instrument.voice1(), offset(instrument.offset1())?)?;
 
     if let Some(voice2) = voice2 {
-        encode_voice(w, voice2, instrument.offset2().try_into().unwrap())?;
+        encode_voice(w, voice2, offset(instrument.offset2())?)?;
     } else {
         encode_voice(w, null_voice, 0)?;
     }
@@ -0,0 +1,25 @@
+// Apache 2.0 License
+
+//! The generators that build the lumps in klamath.wad. Each one takes its inputs as values, and writes the lump to
+//! any [`Write`](std::io::Write), so a `Vec<u8>` collects the bytes. `klamath-util` is a command line wrapper around
+//! them.
+
+pub mod blenderscript;
+pub mod bootstrap;
+pub mod colormap;
+pub mod dehacked;
+pub mod diffwad;
+pub mod dmxgus;
+pub mod error;
+pub mod font;
+pub mod genmidi;
+pub mod make;
+pub mod map;
+pub mod mus;
+pub mod pcspeaker;
+pub mod picture;
+pub mod playpal;
+pub mod sound;
+pub mod wad;
+
+pub use error::{Error, Result};
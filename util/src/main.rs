@@ -1,39 +1,28 @@
 // Apache 2.0 License
 
 use clap::{App, Arg, SubCommand};
-use std::{fs, io::Error as IoError, path::PathBuf, str::FromStr, sync::Arc};
-
-mod blenderscript;
-mod bootstrap;
-mod colormap;
-mod dmxgus;
-mod genmidi;
-mod playpal;
-
-#[derive(Debug, Clone)]
-pub enum Error {
-    StaticMsg(&'static str),
-    Io(Arc<IoError>),
-    Yaml(Arc<serde_yaml::Error>),
-}
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    thread,
+};
 
-impl From<IoError> for Error {
-    #[inline]
-    fn from(i: IoError) -> Error {
-        Error::Io(Arc::new(i))
-    }
-}
+use klamath::{
+    blenderscript, bootstrap, colormap, dehacked, diffwad, dmxgus, error::ResultExt, font, genmidi,
+    make, map, mus, pcspeaker, playpal, sound, Error, Result,
+};
 
-impl From<serde_yaml::Error> for Error {
-    #[inline]
-    fn from(sy: serde_yaml::Error) -> Error {
-        Error::Yaml(Arc::new(sy))
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("klamath-util: {}", e);
+        process::exit(1);
     }
 }
 
-pub type Result<T = ()> = std::result::Result<T, Error>;
-
-fn main() -> Result {
+fn run() -> Result {
     // set up clap architecture
     let matches = App::new("Klamath Util")
         .version("0.1")
@@ -85,36 +74,452 @@ fn main() -> Result {
                         .value_name("CONFIG"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("sound")
+                .about("Converts a WAV, FLAC or Ogg Vorbis file to a DMX sound lump")
+                .arg(
+                    Arg::with_name("source")
+                        .required(true)
+                        .index(1)
+                        .value_name("SOURCE"),
+                )
+                .arg(
+                    Arg::with_name("no-normalize")
+                        .long("no-normalize")
+                        .help("Keep the source's volume instead of making the loudest sample full volume"),
+                )
+                .arg(
+                    Arg::with_name("no-trim")
+                        .long("no-trim")
+                        .help("Keep silence at the start and end"),
+                )
+                .arg(
+                    Arg::with_name("gain")
+                        .long("gain")
+                        .takes_value(true)
+                        .value_name("DB")
+                        .allow_hyphen_values(true)
+                        .help("Volume change in decibels, after normalizing"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("pc-sound")
+                .about("Makes a PC speaker sound lump from a tone script, or by following a sound's pitch")
+                .arg(
+                    Arg::with_name("source")
+                        .required(true)
+                        .index(1)
+                        .value_name("SOURCE"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("mus")
+                .about("Converts a Standard MIDI File to a MUS lump")
+                .arg(Arg::with_name("midi").required(true).index(1).value_name("MIDI"))
+                .arg(
+                    Arg::with_name("genmidi")
+                        .long("genmidi")
+                        .takes_value(true)
+                        .value_name("BASEDIR")
+                        .default_value("genmidi")
+                        .help("The GENMIDI instruments, to warn about notes they can't play"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("fonts")
+                .about("Draws the glyphs and strings in a font spec as Doom graphics")
+                .arg(Arg::with_name("spec").required(true).index(1).value_name("SPEC"))
+                .arg(
+                    Arg::with_name("palette")
+                        .long("palette")
+                        .takes_value(true)
+                        .value_name("PLAYPAL")
+                        .default_value("lumps/playpal.lmp"),
+                )
+                .arg(
+                    Arg::with_name("outdir")
+                        .long("outdir")
+                        .takes_value(true)
+                        .value_name("DIR")
+                        .default_value("graphics"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("dehacked")
+                .about("Tools for working with DeHackEd patches")
+                .subcommand(
+                    SubCommand::with_name("check")
+                        .about("Reports mistakes in a DeHackEd patch, or in the patch a YAML source compiles to")
+                        .arg(
+                            Arg::with_name("patch")
+                                .required(true)
+                                .index(1)
+                                .value_name("PATCH"),
+                        )
+                        .arg(
+                            Arg::with_name("complevel")
+                                .long("complevel")
+                                .takes_value(true)
+                                .value_name("LEVEL")
+                                .default_value("vanilla")
+                                .help("vanilla, boom, mbf, mbf21 or a complevel number"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("compile")
+                        .about("Compiles a YAML DeHackEd source to a patch on stdout")
+                        .arg(
+                            Arg::with_name("source")
+                                .required(true)
+                                .index(1)
+                                .value_name("SOURCE"),
+                        )
+                        .arg(
+                            Arg::with_name("complevel")
+                                .long("complevel")
+                                .takes_value(true)
+                                .value_name("LEVEL")
+                                .default_value("vanilla")
+                                .help("The complevel the patch is checked against"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("diff")
+                        .about("Prints everything a DeHackEd patch or YAML source changes from vanilla Doom II")
+                        .arg(
+                            Arg::with_name("patch")
+                                .required(true)
+                                .index(1)
+                                .value_name("PATCH"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("mapinfo")
+                .about("Prints the monsters, ammo, health, keys and secrets in each map for every skill level")
+                .arg(
+                    Arg::with_name("wads")
+                        .required(true)
+                        .min_values(1)
+                        .value_name("WAD"),
+                )
+                .arg(
+                    Arg::with_name("dehacked")
+                        .long("dehacked")
+                        .takes_value(true)
+                        .value_name("PATCH")
+                        .help("A DeHackEd patch or YAML source that changes the monsters and pickups"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("lint-maps")
+                .about("Reports mistakes in maps, like textures and flats that the build doesn't ship")
+                .arg(
+                    Arg::with_name("wads")
+                        .required(true)
+                        .min_values(1)
+                        .value_name("WAD"),
+                )
+                .arg(
+                    Arg::with_name("wadinfo")
+                        .long("wadinfo")
+                        .takes_value(true)
+                        .value_name("WADINFO")
+                        .default_value("wadinfo.txt")
+                        .help("The DeuTeX wadinfo.txt whose [flats] maps can use"),
+                )
+                .arg(
+                    Arg::with_name("textures")
+                        .long("textures")
+                        .takes_value(true)
+                        .value_name("TEXTURES")
+                        .default_value("textures/texture1.txt")
+                        .help("The DeuTeX texture file whose textures maps can use"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("nodes")
+                .about("Rebuilds the nodes, reject table and blockmap of every map in a WAD, and writes it to stdout")
+                .arg(
+                    Arg::with_name("wad")
+                        .required(true)
+                        .index(1)
+                        .value_name("WAD"),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .possible_values(&["vanilla", "xnod", "znod"])
+                        .default_value("vanilla")
+                        .help("Use xnod or znod for maps too big for vanilla nodes"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("render-map")
+                .about("Draws a map from above, as an SVG or PNG depending on OUTPUT's extension")
+                .arg(
+                    Arg::with_name("wad")
+                        .required(true)
+                        .index(1)
+                        .value_name("WAD"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .required(true)
+                        .index(2)
+                        .value_name("OUTPUT"),
+                )
+                .arg(
+                    Arg::with_name("map")
+                        .long("map")
+                        .takes_value(true)
+                        .value_name("MAP")
+                        .help("Which map to draw, if the WAD has more than one"),
+                )
+                .arg(
+                    Arg::with_name("size")
+                        .long("size")
+                        .takes_value(true)
+                        .value_name("PIXELS")
+                        .default_value("1024")
+                        .help("The length of the longer side of a PNG"),
+                )
+                .arg(
+                    Arg::with_name("dehacked")
+                        .long("dehacked")
+                        .takes_value(true)
+                        .value_name("PATCH")
+                        .help("A DeHackEd patch or YAML source that changes which things are monsters"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("udmf")
+                .about("Converts maps between Doom format and UDMF TEXTMAP sources")
+                .subcommand(
+                    SubCommand::with_name("export")
+                        .about("Prints a map from a WAD as a UDMF TEXTMAP")
+                        .arg(
+                            Arg::with_name("wad")
+                                .required(true)
+                                .index(1)
+                                .value_name("WAD"),
+                        )
+                        .arg(
+                            Arg::with_name("map")
+                                .long("map")
+                                .takes_value(true)
+                                .value_name("MAP")
+                                .help("Which map to export, if the WAD has more than one"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("import")
+                        .about("Converts a UDMF TEXTMAP to a Doom format map without nodes, and writes it to stdout")
+                        .arg(
+                            Arg::with_name("textmap")
+                                .required(true)
+                                .index(1)
+                                .value_name("TEXTMAP"),
+                        )
+                        .arg(
+                            Arg::with_name("name")
+                                .long("name")
+                                .takes_value(true)
+                                .value_name("MAP")
+                                .default_value("MAP01")
+                                .help("The map's marker lump"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("make")
+                .about("Makes everything in the project manifest that's out of date, or just the TARGETs")
+                .arg(
+                    Arg::with_name("targets")
+                        .multiple(true)
+                        .value_name("TARGET"),
+                )
+                .arg(
+                    Arg::with_name("manifest")
+                        .long("manifest")
+                        .takes_value(true)
+                        .value_name("MANIFEST")
+                        .default_value("klamath.yml")
+                        .help("The project manifest, which paths in it are relative to"),
+                )
+                .arg(
+                    Arg::with_name("jobs")
+                        .short("j")
+                        .long("jobs")
+                        .takes_value(true)
+                        .value_name("JOBS")
+                        .help("How many targets to make at once"),
+                )
+                .arg(
+                    Arg::with_name("blender")
+                        .long("blender")
+                        .help("Render sprites and graphics with Blender, even where the manifest uses placeholders"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Makes a WAD again from scratch, and checks it's bit-identical to a build manifest")
+                .arg(
+                    Arg::with_name("build")
+                        .required(true)
+                        .index(1)
+                        .value_name("BUILD_MANIFEST"),
+                )
+                .arg(
+                    Arg::with_name("manifest")
+                        .long("manifest")
+                        .takes_value(true)
+                        .value_name("MANIFEST")
+                        .default_value("klamath.yml")
+                        .help("The project manifest, which paths in it are relative to"),
+                )
+                .arg(
+                    Arg::with_name("jobs")
+                        .short("j")
+                        .long("jobs")
+                        .takes_value(true)
+                        .value_name("JOBS")
+                        .help("How many targets to make at once"),
+                )
+                .arg(
+                    Arg::with_name("blender")
+                        .long("blender")
+                        .help("Render sprites and graphics with Blender, even where the manifest uses placeholders"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("diff-wad")
+                .about("Makes a PWAD with the lumps that were added or changed between two builds, and lists them")
+                .arg(Arg::with_name("old").required(true).index(1).value_name("OLD"))
+                .arg(Arg::with_name("new").required(true).index(2).value_name("NEW"))
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("PATCH")
+                        .default_value("patch.wad")
+                        .help("Where to write the PWAD"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("blenderscript")
+                .about("Renders frames of a Blender model, or full sets of sprites with --sprites")
                 .arg(
                     Arg::with_name("model")
                         .index(1)
-                        .required(true)
+                        .required_unless_one(&["sprites", "screens"])
                         .value_name("MODEL"),
                 )
                 .arg(
                     Arg::with_name("start")
                         .index(2)
-                        .required(true)
+                        .required_unless_one(&["sprites", "screens"])
                         .value_name("START"),
                 )
                 .arg(
                     Arg::with_name("end")
                         .index(3)
-                        .required(true)
+                        .required_unless_one(&["sprites", "screens"])
                         .value_name("END"),
                 )
-                .arg(Arg::with_name("outfiles").multiple(true).min_values(1)),
+                .arg(Arg::with_name("outfiles").multiple(true).min_values(1))
+                .arg(
+                    Arg::with_name("sprites")
+                        .long("sprites")
+                        .takes_value(true)
+                        .min_values(1)
+                        .value_name("SPEC")
+                        .conflicts_with_all(&["model", "start", "end", "outfiles", "settings"])
+                        .requires("outdir"),
+                )
+                .arg(
+                    Arg::with_name("screens")
+                        .long("screens")
+                        .takes_value(true)
+                        .min_values(1)
+                        .value_name("SPEC")
+                        .help("Renders fullscreen graphics like TITLEPIC, squashed to 320x200")
+                        .conflicts_with_all(&["model", "start", "end", "outfiles", "settings", "sprites"])
+                        .requires_all(&["outdir", "palette"]),
+                )
+                .arg(
+                    Arg::with_name("outdir")
+                        .long("outdir")
+                        .takes_value(true)
+                        .value_name("DIR"),
+                )
+                .arg(
+                    Arg::with_name("palette")
+                        .long("palette")
+                        .takes_value(true)
+                        .value_name("PLAYPAL"),
+                )
+                .arg(
+                    Arg::with_name("settings")
+                        .long("settings")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("YAML file with the engine, resolution, samples and camera to use"),
+                )
+                .arg(
+                    Arg::with_name("engine")
+                        .long("engine")
+                        .takes_value(true)
+                        .value_name("ENGINE"),
+                )
+                .arg(
+                    Arg::with_name("resolution")
+                        .long("resolution")
+                        .takes_value(true)
+                        .value_name("WIDTHxHEIGHT"),
+                )
+                .arg(
+                    Arg::with_name("samples")
+                        .long("samples")
+                        .takes_value(true)
+                        .value_name("SAMPLES"),
+                )
+                .arg(
+                    Arg::with_name("camera")
+                        .long("camera")
+                        .takes_value(true)
+                        .value_name("CAMERA"),
+                )
+                .arg(
+                    Arg::with_name("backend")
+                        .long("backend")
+                        .takes_value(true)
+                        .possible_values(&["blender", "placeholder"])
+                        .default_value("blender")
+                        .help("Use \"placeholder\" to test the pipeline without Blender installed"),
+                )
+                .arg(
+                    Arg::with_name("jobs")
+                        .short("j")
+                        .long("jobs")
+                        .takes_value(true)
+                        .value_name("JOBS")
+                        .help("How many models to render at once"),
+                ),
         )
         .get_matches();
 
-    if let Some(_) = matches.subcommand_matches("bootstrap") {
-        bootstrap::write_bootstrap()?;
+    if matches.subcommand_matches("bootstrap").is_some() {
+        bootstrap::write_bootstrap(&stdin_file()?, io::stdout().lock())?;
         return Ok(());
     } else if let Some(matches) = matches.subcommand_matches("playpal") {
-        let p: Option<PathBuf> = matches.value_of_os("palbase").map(|p| p.into());
-        playpal::generate_palette(p.as_deref())?;
+        let base = match matches.value_of_os("palbase") {
+            Some(p) => Some(fs::read(p).with_file(p.as_ref())?),
+            None => None,
+        };
+        playpal::generate_palette(base.as_deref(), io::stdout().lock())?;
         return Ok(());
     } else if let Some(matches) = matches.subcommand_matches("rm") {
         for file in matches.values_of_os("files").unwrap() {
@@ -135,39 +540,275 @@ fn main() -> Result {
             matches.value_of("b"),
         ) {
             [
-                u8::from_str(r).expect("R is not a number"),
-                u8::from_str(g).expect("G is not a number"),
-                u8::from_str(b).expect("B is not a number"),
+                number::<u8>("R", r)?,
+                number::<u8>("G", g)?,
+                number::<u8>("B", b)?,
             ]
         } else {
             [0, 0, 0]
         };
 
-        colormap::generate_colormap(rgb)?;
+        colormap::generate_colormap(&stdin_file()?, rgb, io::stdout().lock())?;
 
         return Ok(());
     } else if let Some(matches) = matches.subcommand_matches("genmidi") {
         let basedir = matches.value_of_os("basedir").unwrap();
-        genmidi::generate_genmidi(basedir)?;
+        let bank = genmidi::Bank::load(basedir.as_ref())?;
+        genmidi::generate_genmidi(&bank, io::stdout().lock())?;
 
         return Ok(());
     } else if let Some(matches) = matches.subcommand_matches("dmxgus") {
         let config = matches.value_of_os("config").unwrap();
-        return dmxgus::generate_dmxgus(config.as_ref());
+        let gus = dmxgus::Config::load(config.as_ref())?;
+        return dmxgus::generate_dmxgus(gus, io::stdout().lock()).with_file(config.as_ref());
+    } else if let Some(matches) = matches.subcommand_matches("sound") {
+        let source = matches.value_of_os("source").unwrap();
+        let settings = sound::Settings {
+            normalize: !matches.is_present("no-normalize"),
+            trim: !matches.is_present("no-trim"),
+            gain: match matches.value_of("gain") {
+                None => 0.0,
+                Some(gain) => number("DB", gain)?,
+            },
+        };
+        return sound::generate_sound(source.as_ref(), settings, io::stdout().lock());
+    } else if let Some(matches) = matches.subcommand_matches("pc-sound") {
+        let source = matches.value_of_os("source").unwrap();
+        return pcspeaker::generate_pc_sound(source.as_ref(), io::stdout().lock());
+    } else if let Some(matches) = matches.subcommand_matches("mus") {
+        let midi = matches.value_of_os("midi").unwrap();
+        let bank = genmidi::Bank::load(matches.value_of_os("genmidi").unwrap().as_ref())?;
+        mus::generate_mus(midi.as_ref(), Some(&bank), io::stdout().lock())?
+            .iter()
+            .for_each(|warning| eprintln!("{}", warning));
+        return Ok(());
+    } else if let Some(matches) = matches.subcommand_matches("fonts") {
+        let spec = matches.value_of_os("spec").unwrap();
+        let palette =
+            blenderscript::load_palette(matches.value_of_os("palette").unwrap().as_ref())?;
+        let outdir = matches.value_of_os("outdir").unwrap();
+        for lump in font::render_fonts(spec.as_ref(), outdir.as_ref(), &palette)? {
+            println!("{}", lump);
+        }
+        return Ok(());
+    } else if let Some(matches) = matches.subcommand_matches("dehacked") {
+        if let Some(matches) = matches.subcommand_matches("check") {
+            let patch = matches.value_of_os("patch").unwrap();
+            let complevel = dehacked::Complevel::from_str(matches.value_of("complevel").unwrap())?;
+            return dehacked::check(patch.as_ref(), complevel, io::stderr().lock());
+        }
+        if let Some(matches) = matches.subcommand_matches("compile") {
+            let source = matches.value_of_os("source").unwrap();
+            let complevel = dehacked::Complevel::from_str(matches.value_of("complevel").unwrap())?;
+            let patch = dehacked::Source::load(source.as_ref())?;
+            return dehacked::compile(&patch, complevel, io::stdout().lock())
+                .with_file(source.as_ref());
+        }
+        if let Some(matches) = matches.subcommand_matches("diff") {
+            let patch = matches.value_of_os("patch").unwrap();
+            return dehacked::diff(patch.as_ref(), io::stdout().lock());
+        }
+
+        return Err(Error::StaticMsg("dehacked needs a subcommand"));
+    } else if let Some(matches) = matches.subcommand_matches("mapinfo") {
+        let wads: Vec<PathBuf> = matches
+            .values_of_os("wads")
+            .unwrap()
+            .map(PathBuf::from)
+            .collect();
+        let dehacked: Option<PathBuf> = matches.value_of_os("dehacked").map(|p| p.into());
+        return map::mapinfo(&wads, dehacked.as_deref(), io::stdout().lock());
+    } else if let Some(matches) = matches.subcommand_matches("lint-maps") {
+        let wads: Vec<PathBuf> = matches
+            .values_of_os("wads")
+            .unwrap()
+            .map(PathBuf::from)
+            .collect();
+        let wadinfo = matches.value_of_os("wadinfo").unwrap();
+        let textures = matches.value_of_os("textures").unwrap();
+        return map::lint_maps(
+            &wads,
+            wadinfo.as_ref(),
+            textures.as_ref(),
+            io::stderr().lock(),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("nodes") {
+        let wad = matches.value_of_os("wad").unwrap();
+        let format = map::NodeFormat::from_str(matches.value_of("format").unwrap())?;
+        let wad = Path::new(wad);
+        let wad = map::build_nodes(map::load_wad(wad)?, format).with_file(wad)?;
+        wad.write(io::stdout().lock())?;
+        return Ok(());
+    } else if let Some(matches) = matches.subcommand_matches("render-map") {
+        let wad = matches.value_of_os("wad").unwrap();
+        let output = matches.value_of_os("output").unwrap();
+        let size = match usize::from_str(matches.value_of("size").unwrap()) {
+            Ok(size) if size > 0 => size,
+            _ => return Err(Error::StaticMsg("Size must be a positive number")),
+        };
+        let dehacked: Option<PathBuf> = matches.value_of_os("dehacked").map(|p| p.into());
+        return map::render_map(
+            wad.as_ref(),
+            matches.value_of("map"),
+            output.as_ref(),
+            size,
+            dehacked.as_deref(),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("udmf") {
+        if let Some(matches) = matches.subcommand_matches("export") {
+            let wad = matches.value_of_os("wad").unwrap();
+            return map::export_udmf(wad.as_ref(), matches.value_of("map"), io::stdout().lock());
+        }
+        if let Some(matches) = matches.subcommand_matches("import") {
+            let textmap = matches.value_of_os("textmap").unwrap();
+            return map::import_udmf(
+                textmap.as_ref(),
+                matches.value_of("name").unwrap(),
+                io::stdout().lock(),
+            );
+        }
+
+        return Err(Error::StaticMsg("udmf needs a subcommand"));
+    } else if let Some(matches) = matches.subcommand_matches("make") {
+        let targets: Vec<PathBuf> = matches
+            .values_of_os("targets")
+            .map(|targets| targets.map(PathBuf::from).collect())
+            .unwrap_or_default();
+        let manifest = matches.value_of_os("manifest").unwrap();
+        let jobs = match matches.value_of("jobs") {
+            None => thread::available_parallelism().map_or(1, |n| n.get()),
+            Some(jobs) => number::<usize>("JOBS", jobs)?,
+        };
+        let summary = make::make(
+            manifest.as_ref(),
+            &targets,
+            jobs,
+            matches.is_present("blender"),
+            io::stdout(),
+        )?;
+        println!(
+            "{} made, {} already up to date",
+            summary.made, summary.up_to_date
+        );
+        return Ok(());
+    } else if let Some(matches) = matches.subcommand_matches("verify") {
+        let build = matches.value_of_os("build").unwrap();
+        let manifest = matches.value_of_os("manifest").unwrap();
+        let jobs = match matches.value_of("jobs") {
+            None => thread::available_parallelism().map_or(1, |n| n.get()),
+            Some(jobs) => number::<usize>("JOBS", jobs)?,
+        };
+        return make::verify(
+            manifest.as_ref(),
+            build.as_ref(),
+            jobs,
+            matches.is_present("blender"),
+            io::stdout(),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("diff-wad") {
+        let old = matches.value_of_os("old").unwrap();
+        let new = matches.value_of_os("new").unwrap();
+        let output = matches.value_of_os("output").unwrap();
+        return diffwad::diff_wad(
+            old.as_ref(),
+            new.as_ref(),
+            output.as_ref(),
+            io::stdout().lock(),
+        );
     } else if let Some(matches) = matches.subcommand_matches("blenderscript") {
-        let model = matches.value_of_os("model").unwrap();
-        let start = usize::from_str(matches.value_of("start").unwrap()).unwrap();
-        let end = usize::from_str(matches.value_of("end").unwrap()).unwrap();
-        if (end - start + 1) != matches.occurrences_of("outfiles") as usize {
-            return Err(Error::StaticMsg("Frames do not match outfiles"));
+        let resolution = match matches
+            .value_of("resolution")
+            .map(|res| res.split_once('x'))
+        {
+            None => None,
+            Some(Some((x, y))) => Some([number("WIDTH", x)?, number("HEIGHT", y)?]),
+            Some(None) => return Err(Error::StaticMsg("Resolution must look like 320x240")),
+        };
+        let samples = matches
+            .value_of("samples")
+            .map(|samples| number("SAMPLES", samples))
+            .transpose()?;
+        let renderer: &dyn blenderscript::Renderer = match matches.value_of("backend") {
+            Some("placeholder") => &blenderscript::Placeholder,
+            _ => &blenderscript::Blender,
+        };
+        let flags = blenderscript::RenderSettings {
+            engine: matches.value_of("engine").map(String::from),
+            resolution,
+            samples,
+            camera: matches.value_of("camera").map(String::from),
+        };
+
+        let jobs = || match matches.value_of("jobs") {
+            None => Ok(thread::available_parallelism().map_or(1, |n| n.get())),
+            Some(jobs) => number::<usize>("JOBS", jobs),
+        };
+
+        if let Some(specs) = matches.values_of_os("sprites") {
+            let specs: Vec<PathBuf> = specs.map(PathBuf::from).collect();
+            let outdir = matches.value_of_os("outdir").unwrap();
+            let palette: Option<PathBuf> = matches.value_of_os("palette").map(|p| p.into());
+            return blenderscript::run_parallel(&specs, jobs()?, |spec| {
+                blenderscript::render_sprites(
+                    renderer,
+                    spec,
+                    outdir.as_ref(),
+                    palette.as_deref(),
+                    &flags,
+                )
+            });
         }
+
+        if let Some(specs) = matches.values_of_os("screens") {
+            let specs: Vec<PathBuf> = specs.map(PathBuf::from).collect();
+            let outdir = matches.value_of_os("outdir").unwrap();
+            let palette = matches.value_of_os("palette").unwrap();
+            return blenderscript::run_parallel(&specs, jobs()?, |spec| {
+                blenderscript::render_screens(
+                    renderer,
+                    spec,
+                    outdir.as_ref(),
+                    palette.as_ref(),
+                    &flags,
+                )
+            });
+        }
+
+        let settings = match matches.value_of_os("settings") {
+            Some(settings) => flags.or(&blenderscript::RenderSettings::load(settings.as_ref())?),
+            None => flags,
+        };
+        let model = matches.value_of_os("model").unwrap();
+        let start = number::<usize>("START", matches.value_of("start").unwrap())?;
+        let end = number::<usize>("END", matches.value_of("end").unwrap())?;
+        blenderscript::check_frames(start, end, matches.occurrences_of("outfiles") as usize)?;
         return blenderscript::render_blender(
+            renderer,
             model.as_ref(),
             start,
             end,
             matches.values_of_os("outfiles").unwrap(),
+            &settings,
         );
     }
 
     Err(Error::StaticMsg("Did not receive any arguments."))
 }
+
+/// Read all of stdin, for generators that take a palette from it. It needs to be redirected from a file.
+#[inline]
+fn stdin_file() -> Result<Vec<u8>> {
+    if atty::is(atty::Stream::Stdin) {
+        return Err(Error::StaticMsg("Stdin needs to be a file"));
+    }
+    let mut bytes = vec![];
+    io::stdin().lock().read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Parse a number from the command line, naming the argument if it isn't one.
+#[inline]
+fn number<T: FromStr>(name: &str, value: &str) -> Result<T> {
+    T::from_str(value)
+        .map_err(|_| Error::Msg(format!("{} {:?} isn't a number in range", name, value)))
+}
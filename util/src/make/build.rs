@@ -0,0 +1,336 @@
+// Apache 2.0 License
+
+use super::{digest, graph, load, make_targets, tool_digest, Generator, Manifest};
+use crate::{error::ResultExt, map::MAP_LUMPS, wad::Wad};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    io::{BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+/// Everything in a WAD and what made it. Two builds with the same WAD hash are the same build.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BuildManifest {
+    pub wad: FileRecord,
+    /// The klamath-util that made the WAD. It's only for information, since builds of it on other machines differ.
+    pub tool: ToolRecord,
+    /// The target that made the WAD, and every target it was made from.
+    pub targets: BTreeMap<PathBuf, TargetRecord>,
+    /// Every lump, in directory order.
+    pub lumps: Vec<LumpRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FileRecord {
+    pub path: PathBuf,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ToolRecord {
+    pub version: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TargetRecord {
+    pub generator: Generator,
+    /// Settings built in to the generator, like the palette biases.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub constants: BTreeMap<String, serde_yaml::Value>,
+    /// The hash of every input. Inputs can be the outputs of other targets.
+    pub inputs: BTreeMap<PathBuf, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LumpRecord {
+    pub name: String,
+    pub size: usize,
+    pub sha256: String,
+    /// The file the lump was built from, if one could be found in the WAD target's inputs. Markers have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// The target that made the file, if it isn't checked in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<PathBuf>,
+}
+
+impl BuildManifest {
+    /// Describe a WAD that's already been made, and the targets it was made from.
+    #[inline]
+    pub fn new(manifest: &Manifest, root: &Path, wad: &Path) -> crate::Result<Self> {
+        let targets = graph(manifest);
+        let wad_target = targets
+            .iter()
+            .position(|t| t.output == wad)
+            .ok_or_else(|| {
+                crate::Error::Msg(format!("{} isn't a target in the manifest", wad.display()))
+            })?;
+
+        // the WAD's target and everything upstream of it
+        let mut upstream = vec![false; targets.len()];
+        let mut stack = vec![wad_target];
+        while let Some(i) = stack.pop() {
+            if !upstream[i] {
+                upstream[i] = true;
+                stack.extend(&targets[i].deps);
+            }
+        }
+        let records = targets
+            .iter()
+            .zip(&upstream)
+            .filter(|(_, upstream)| **upstream)
+            .map(|(target, _)| {
+                let inputs = target
+                    .generator
+                    .inputs()
+                    .into_iter()
+                    .map(|input| {
+                        let hash = digest(&root.join(&input)).with_file(&input)?;
+                        Ok((input, hash))
+                    })
+                    .collect::<crate::Result<_>>()?;
+                let record = TargetRecord {
+                    generator: target.generator.clone(),
+                    constants: target.generator.constants()?,
+                    inputs,
+                };
+                Ok((target.output.to_path_buf(), record))
+            })
+            .collect::<crate::Result<BTreeMap<_, _>>>()?;
+
+        let bytes = fs::read(root.join(wad)).with_file(wad)?;
+        let files = lump_files(root, &targets[wad_target].generator.inputs())?;
+        let made_by = |file: &Path| {
+            targets
+                .iter()
+                .find(|t| file.starts_with(t.output))
+                .map(|t| t.output.to_path_buf())
+        };
+
+        // map lumps come from the same file as their marker
+        let mut map_file = None;
+        let lumps = Wad::parse(&bytes)
+            .with_file(wad)?
+            .lumps
+            .into_iter()
+            .map(|lump| {
+                let file = match files.get(&lump.name) {
+                    Some(file) => Some(file.clone()),
+                    None if MAP_LUMPS.contains(&lump.name.as_str()) => map_file.clone(),
+                    None => None,
+                };
+                map_file = file.clone();
+                LumpRecord {
+                    target: file.as_deref().and_then(made_by),
+                    file,
+                    size: lump.data.len(),
+                    sha256: hex_digest(&lump.data),
+                    name: lump.name,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            wad: FileRecord {
+                path: wad.to_path_buf(),
+                size: bytes.len() as u64,
+                sha256: hex_digest(&bytes),
+            },
+            tool: ToolRecord {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                sha256: tool_digest()?,
+            },
+            targets: records,
+            lumps,
+        })
+    }
+
+    #[inline]
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        serde_yaml::from_reader(BufReader::new(File::open(path).with_file(path)?)).with_file(path)
+    }
+
+    /// How this build differs from another one, lump by lump. Empty if the WADs are the same.
+    #[inline]
+    pub fn differences(&self, other: &BuildManifest) -> Vec<String> {
+        if self.wad.sha256 == other.wad.sha256 {
+            return vec![];
+        }
+
+        // lumps are matched by name, so one lump added or removed doesn't make every lump after it look different
+        let mut differences = vec![];
+        let old: HashMap<(&str, usize), &LumpRecord> = occurrences(&self.lumps).collect();
+        let new: HashMap<(&str, usize), &LumpRecord> = occurrences(&other.lumps).collect();
+        for (key, lump) in occurrences(&other.lumps) {
+            match old.get(&key) {
+                None => differences.push(format!(
+                    "lump {} is new{}",
+                    lump_label(key),
+                    describe_source(lump)
+                )),
+                Some(old) if old.sha256 != lump.sha256 => differences.push(format!(
+                    "lump {} changed{}",
+                    lump_label(key),
+                    describe_source(lump)
+                )),
+                Some(_) => {}
+            }
+        }
+        occurrences(&self.lumps)
+            .filter(|(key, _)| !new.contains_key(key))
+            .for_each(|(key, _)| differences.push(format!("lump {} is missing", lump_label(key))));
+        if differences.is_empty()
+            && !occurrences(&self.lumps)
+                .map(|(key, _)| key)
+                .eq(occurrences(&other.lumps).map(|(key, _)| key))
+        {
+            differences.push("the lumps are in a different order".to_string());
+        }
+
+        // inputs that changed explain why lumps did
+        for (output, new) in &other.targets {
+            let old = self.targets.get(output);
+            for (input, hash) in &new.inputs {
+                if old.and_then(|old| old.inputs.get(input)) != Some(hash) {
+                    differences.push(format!(
+                        "input {} of {} changed",
+                        input.display(),
+                        output.display()
+                    ));
+                }
+            }
+        }
+
+        if differences.is_empty() {
+            differences.push("the WAD changed, but every lump is the same".to_string());
+        }
+        differences
+    }
+}
+
+/// Rebuild the WAD a build manifest describes, and check it's bit-identical to that build. Everything is made again
+/// in place, even if it's up to date. A build with Blender renders needs `blender` to be rebuilt the same way. What's
+/// made, and then the verdict or every difference from the recorded build, are written to `log`.
+#[inline]
+pub fn verify<W: Write + Send>(
+    manifest_path: &Path,
+    recorded: &Path,
+    jobs: usize,
+    blender: bool,
+    mut log: W,
+) -> crate::Result {
+    let recorded = BuildManifest::load(recorded)?;
+    let (manifest, root) = load(manifest_path)?;
+    let target = manifest
+        .targets
+        .iter()
+        .find(|(_, generator)| {
+            matches!(generator, Generator::BuildManifest { wad } if *wad == recorded.wad.path)
+        })
+        .map(|(output, _)| output.clone())
+        .ok_or_else(|| {
+            crate::Error::Msg(format!(
+                "No build manifest target in {} describes {}",
+                manifest_path.display(),
+                recorded.wad.path.display()
+            ))
+        })?;
+
+    make_targets(
+        manifest_path,
+        std::slice::from_ref(&target),
+        jobs,
+        true,
+        blender,
+        &mut log,
+    )?;
+    let rebuilt = BuildManifest::load(&root.join(&target))?;
+
+    let differences = recorded.differences(&rebuilt);
+    if differences.is_empty() {
+        writeln!(
+            log,
+            "{} is bit-identical to the recorded build, {}",
+            rebuilt.wad.path.display(),
+            rebuilt.wad.sha256
+        )?;
+        return Ok(());
+    }
+    differences
+        .iter()
+        .try_for_each(|d| writeln!(log, "{}", d))?;
+    Err(crate::Error::Msg(format!(
+        "{} isn't the same as the recorded build",
+        rebuilt.wad.path.display()
+    )))
+}
+
+/// Each lump with how many lumps before it have the same name, which is what tells apart the THINGS of two maps.
+#[inline]
+fn occurrences(lumps: &[LumpRecord]) -> impl Iterator<Item = ((&str, usize), &LumpRecord)> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    lumps.iter().map(move |lump| {
+        let count = seen.entry(&lump.name).or_default();
+        let key = (lump.name.as_str(), *count);
+        *count += 1;
+        (key, lump)
+    })
+}
+
+/// A lump's name, and which one it is if there's more than one with the name.
+#[inline]
+fn lump_label((name, occurrence): (&str, usize)) -> String {
+    match occurrence {
+        0 => name.to_string(),
+        n => format!("{} #{}", name, n + 1),
+    }
+}
+
+#[inline]
+fn describe_source(lump: &LumpRecord) -> String {
+    match (&lump.file, &lump.target) {
+        (Some(file), Some(target)) if file != target => {
+            format!(" ({}, made by {})", file.display(), target.display())
+        }
+        (Some(file), _) => format!(" ({})", file.display()),
+        (None, _) => String::new(),
+    }
+}
+
+/// The files lumps could come from, by lump name. Directories are searched one level deep, like DeuTeX does.
+#[inline]
+fn lump_files(root: &Path, inputs: &[PathBuf]) -> crate::Result<HashMap<String, PathBuf>> {
+    let mut files = HashMap::new();
+    for input in inputs {
+        let path = root.join(input);
+        let mut candidates = if path.is_dir() {
+            fs::read_dir(&path)
+                .with_file(input)?
+                .map(|entry| Ok(input.join(entry?.file_name())))
+                .collect::<crate::Result<Vec<_>>>()?
+        } else {
+            vec![input.clone()]
+        };
+        candidates.sort();
+        for file in candidates {
+            // DeuTeX writes backslashes in sprite names as carets
+            let name = file
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_ascii_uppercase()
+                .replace('^', "\\");
+            files.entry(name).or_insert(file);
+        }
+    }
+    Ok(files)
+}
+
+#[inline]
+fn hex_digest(bytes: &[u8]) -> String {
+    super::hex(&Sha256::digest(bytes))
+}
@@ -0,0 +1,457 @@
+// Apache 2.0 License
+
+use super::{build::BuildManifest, Manifest};
+use crate::{
+    blenderscript::{self, RenderSettings, Renderer},
+    bootstrap, colormap, dehacked, dmxgus,
+    error::ResultExt,
+    font, genmidi, map, mus, pcspeaker, playpal, sound,
+};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+    thread,
+};
+
+/// How a target is made. Paths are relative to the manifest.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "generator", rename_all = "snake_case")]
+pub enum Generator {
+    /// PLAYPAL, from a base palette or the built in one.
+    Playpal {
+        #[serde(default)]
+        base: Option<PathBuf>,
+    },
+    /// The minimal IWAD DeuTeX needs to build another one.
+    Bootstrap { palette: PathBuf },
+    /// COLORMAP, fading the palette to a dark color.
+    Colormap {
+        palette: PathBuf,
+        #[serde(default)]
+        dark_color: [u8; 3],
+    },
+    /// DMXGUS, from the patch sizes and groups in a config.
+    Dmxgus { config: PathBuf },
+    /// GENMIDI, from a directory of SBI instruments.
+    Genmidi { instruments: PathBuf },
+    /// A DeHackEd patch compiled from a YAML source.
+    Dehacked {
+        source: PathBuf,
+        #[serde(default = "default_complevel")]
+        complevel: String,
+    },
+    /// A directory with a WAD for every map source in `maps`, with nodes built.
+    Maps {
+        maps: PathBuf,
+        #[serde(default = "default_node_format")]
+        format: String,
+    },
+    /// A directory of sprites rendered from Blender models.
+    Sprites {
+        specs: Vec<PathBuf>,
+        #[serde(default)]
+        palette: Option<PathBuf>,
+        #[serde(default)]
+        placeholder: bool,
+    },
+    /// A directory of DMX sounds, converted from every WAV, FLAC and Ogg Vorbis file in `sources`, and a PC speaker
+    /// sound for each DS* one. The optional config sets how they're converted.
+    Sounds {
+        sources: PathBuf,
+        #[serde(default)]
+        config: Option<PathBuf>,
+    },
+    /// A directory of MUS songs, converted from every MIDI file in `sources`. Notes the GENMIDI instruments in
+    /// `genmidi` can't play are warned about.
+    Music { sources: PathBuf, genmidi: PathBuf },
+    /// A directory of graphics drawn from fonts: status bar numbers, the HUD font, and menu and intermission text.
+    /// `fonts` is a spec, with the fonts it uses in the same directory. `screens` are specs for fullscreen graphics
+    /// rendered from Blender scenes, like TITLEPIC.
+    Graphics {
+        #[serde(default)]
+        fonts: Option<PathBuf>,
+        #[serde(default)]
+        screens: Vec<PathBuf>,
+        palette: PathBuf,
+        #[serde(default)]
+        placeholder: bool,
+    },
+    /// A file copied as it is.
+    Copy { source: PathBuf },
+    /// A WAD built by DeuTeX. DeuTeX reads the lumps named in `wadinfo` from directories next to it, so every one it
+    /// uses should be in `inputs`.
+    Deutex {
+        wadinfo: PathBuf,
+        inputs: Vec<PathBuf>,
+        #[serde(default = "default_deutex")]
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// A record of every lump in a WAD and what made it, so a build can be told apart from others and checked with
+    /// `klamath-util verify`.
+    BuildManifest { wad: PathBuf },
+}
+
+#[inline]
+fn default_complevel() -> String {
+    "vanilla".to_string()
+}
+
+#[inline]
+fn default_node_format() -> String {
+    "vanilla".to_string()
+}
+
+#[inline]
+fn default_deutex() -> String {
+    "deutex".to_string()
+}
+
+impl Generator {
+    /// Every file or directory the generator reads.
+    #[inline]
+    pub fn inputs(&self) -> Vec<PathBuf> {
+        match self {
+            Generator::Playpal { base } => base.iter().cloned().collect(),
+            Generator::Bootstrap { palette } | Generator::Colormap { palette, .. } => {
+                vec![palette.clone()]
+            }
+            Generator::Dmxgus { config } => vec![config.clone()],
+            Generator::Genmidi { instruments } => vec![instruments.clone()],
+            Generator::Dehacked { source, .. } | Generator::Copy { source } => vec![source.clone()],
+            Generator::BuildManifest { wad } => vec![wad.clone()],
+            Generator::Maps { maps, .. } => vec![maps.clone()],
+            Generator::Music { sources, genmidi } => vec![sources.clone(), genmidi.clone()],
+            Generator::Graphics {
+                fonts,
+                screens,
+                palette,
+                ..
+            } => fonts
+                .iter()
+                .map(|fonts| {
+                    fonts
+                        .parent()
+                        .unwrap_or_else(|| Path::new("."))
+                        .to_path_buf()
+                })
+                // scenes are next to their specs, with the same name
+                .chain(
+                    screens
+                        .iter()
+                        .flat_map(|spec| vec![spec.clone(), spec.with_extension("blend")]),
+                )
+                .chain(std::iter::once(palette.clone()))
+                .collect(),
+            Generator::Sounds { sources, config } => std::iter::once(sources.clone())
+                .chain(config.clone())
+                .collect(),
+            Generator::Sprites { specs, palette, .. } => specs
+                .iter()
+                // models are next to their specs, with the same name
+                .flat_map(|spec| vec![spec.clone(), spec.with_extension("blend")])
+                .chain(palette.iter().cloned())
+                .collect(),
+            Generator::Deutex {
+                wadinfo, inputs, ..
+            } => std::iter::once(wadinfo.clone())
+                .chain(inputs.iter().cloned())
+                .collect(),
+        }
+    }
+
+    /// Render with Blender, even if the manifest asks for placeholders.
+    #[inline]
+    pub fn use_blender(&mut self) {
+        if let Generator::Sprites { placeholder, .. } | Generator::Graphics { placeholder, .. } =
+            self
+        {
+            *placeholder = false;
+        }
+    }
+
+    /// Whether the output is a directory instead of a file.
+    #[inline]
+    pub fn makes_directory(&self) -> bool {
+        matches!(
+            self,
+            Generator::Graphics { .. }
+                | Generator::Maps { .. }
+                | Generator::Music { .. }
+                | Generator::Sounds { .. }
+                | Generator::Sprites { .. }
+        )
+    }
+
+    /// Settings built in to the generator rather than given in the manifest, for build manifests.
+    #[inline]
+    pub fn constants(&self) -> crate::Result<BTreeMap<String, serde_yaml::Value>> {
+        let mut constants = BTreeMap::new();
+        match self {
+            Generator::Playpal { .. } => {
+                #[derive(serde::Serialize)]
+                struct Bias {
+                    color: Vec<f64>,
+                    bias: f64,
+                }
+
+                // f32s are widened to f64s to be written, so print them as short as they were written here
+                let short = |f: f32| f.to_string().parse::<f64>().unwrap_or_default();
+                let biases: Vec<Bias> = playpal::palette_biases()
+                    .map(|(color, bias)| Bias {
+                        color: color.iter().copied().map(short).collect(),
+                        bias: short(bias),
+                    })
+                    .collect();
+                constants.insert("palette_biases".to_string(), serde_yaml::to_value(biases)?);
+            }
+            Generator::Dmxgus { .. } => {
+                constants.insert(
+                    "gus_memory_kb".to_string(),
+                    serde_yaml::to_value(dmxgus::GUS_MEMORY_KB)?,
+                );
+            }
+            _ => {}
+        }
+        Ok(constants)
+    }
+
+    /// Make the output, and return the warnings from making it. `root` is the directory the manifest is in.
+    #[inline]
+    pub fn run(
+        &self,
+        manifest: &Manifest,
+        root: &Path,
+        output: &Path,
+    ) -> crate::Result<Vec<String>> {
+        let path = |p: &Path| root.join(p);
+        let out = path(output);
+        let mut warnings = vec![];
+
+        let result = match self {
+            Generator::Playpal { base } => {
+                let base = base.as_deref().map(|base| read(&path(base))).transpose()?;
+                write_file(&out, |w| playpal::generate_palette(base.as_deref(), w))
+            }
+            Generator::Bootstrap { palette } => {
+                let palette = read(&path(palette))?;
+                write_file(&out, |w| bootstrap::write_bootstrap(&palette, w))
+            }
+            Generator::Colormap {
+                palette,
+                dark_color,
+            } => {
+                let palette = read(&path(palette))?;
+                write_file(&out, |w| {
+                    colormap::generate_colormap(&palette, *dark_color, w)
+                })
+            }
+            Generator::Dmxgus { config } => {
+                let config = path(config);
+                let gus = dmxgus::Config::load(&config)?;
+                write_file(&out, |w| dmxgus::generate_dmxgus(gus, w).with_file(&config))
+            }
+            Generator::Genmidi { instruments } => {
+                let bank = genmidi::Bank::load(&path(instruments))?;
+                write_file(&out, |w| genmidi::generate_genmidi(&bank, w))
+            }
+            Generator::Dehacked { source, complevel } => {
+                let complevel = dehacked::Complevel::from_str(complevel)?;
+                let source = path(source);
+                let patch = dehacked::Source::load(&source)?;
+                write_file(&out, |w| {
+                    dehacked::compile(&patch, complevel, w).with_file(&source)
+                })
+            }
+            Generator::Maps { maps, format } => {
+                let format = map::NodeFormat::from_str(format)?;
+                fs::create_dir_all(&out).with_file(&out)?;
+                let mut sources = fs::read_dir(path(maps))
+                    .with_file(&path(maps))?
+                    .map(|entry| Ok(entry?.path()))
+                    .collect::<crate::Result<Vec<_>>>()?;
+                sources.sort();
+                sources
+                    .iter()
+                    .filter(|source| {
+                        source.extension().is_some_and(|e| {
+                            e.eq_ignore_ascii_case("udmf") || e.eq_ignore_ascii_case("wad")
+                        })
+                    })
+                    .try_for_each(|source| {
+                        let name = source.file_stem().unwrap_or_default();
+                        let level = out.join(name).with_extension("wad");
+                        let wad =
+                            map::build_nodes(map::load_wad(source)?, format).with_file(source)?;
+                        write_file(&level, |w| Ok(wad.write(w)?))
+                    })
+            }
+            Generator::Music { sources, genmidi } => {
+                let bank = genmidi::Bank::load(&path(genmidi))?;
+                fs::create_dir_all(&out).with_file(&out)?;
+                mus::sources(&path(sources))?
+                    .iter()
+                    .try_for_each(|(name, source)| {
+                        let song = out.join(name.to_ascii_lowercase()).with_extension("mus");
+                        write_file(&song, |w| {
+                            warnings.extend(mus::generate_mus(source, Some(&bank), w)?);
+                            Ok(())
+                        })
+                    })
+            }
+            Generator::Graphics {
+                fonts,
+                screens,
+                palette,
+                placeholder,
+            } => {
+                fs::create_dir_all(&out).with_file(&out)?;
+                let palette = path(palette);
+                if let Some(fonts) = fonts {
+                    let palette = blenderscript::load_palette(&palette)?;
+                    font::render_fonts(&path(fonts), &out, &palette)?;
+                }
+
+                let screens: Vec<PathBuf> = screens.iter().map(|s| path(s)).collect();
+                let renderer: &dyn Renderer = if *placeholder {
+                    &blenderscript::Placeholder
+                } else {
+                    &blenderscript::Blender
+                };
+                let jobs = thread::available_parallelism().map_or(1, |n| n.get());
+                blenderscript::run_parallel(&screens, jobs, |spec| {
+                    blenderscript::render_screens(
+                        renderer,
+                        spec,
+                        &out,
+                        &palette,
+                        &RenderSettings::default(),
+                    )
+                })
+            }
+            Generator::Sounds { sources, config } => {
+                let config = match config {
+                    Some(config) => sound::Config::load(&path(config))?,
+                    None => sound::Config::default(),
+                };
+                fs::create_dir_all(&out).with_file(&out)?;
+                // DeuTeX takes sounds that are already lumps as .lmp files
+                let lump = |name: &str| out.join(name.to_ascii_lowercase()).with_extension("lmp");
+                let sources = path(sources);
+                sound::sources(&sources)?
+                    .iter()
+                    .try_for_each(|(name, source)| {
+                        write_file(&lump(name), |w| {
+                            sound::generate_sound(source, config.settings(name), w)
+                        })
+                    })?;
+                pcspeaker::sources(&sources)?
+                    .iter()
+                    .try_for_each(|(name, source)| {
+                        write_file(&lump(name), |w| pcspeaker::generate_pc_sound(source, w))
+                    })
+            }
+            Generator::Sprites {
+                specs,
+                palette,
+                placeholder,
+            } => {
+                fs::create_dir_all(&out).with_file(&out)?;
+                let specs: Vec<PathBuf> = specs.iter().map(|s| path(s)).collect();
+                let palette = palette.as_deref().map(path);
+                let renderer: &dyn Renderer = if *placeholder {
+                    &blenderscript::Placeholder
+                } else {
+                    &blenderscript::Blender
+                };
+                let jobs = thread::available_parallelism().map_or(1, |n| n.get());
+                blenderscript::run_parallel(&specs, jobs, |spec| {
+                    blenderscript::render_sprites(
+                        renderer,
+                        spec,
+                        &out,
+                        palette.as_deref(),
+                        &RenderSettings::default(),
+                    )
+                })
+            }
+            Generator::Copy { source } => {
+                let source = read(&path(source))?;
+                write_file(&out, |w| Ok(w.write_all(&source)?))
+            }
+            Generator::Deutex {
+                wadinfo,
+                program,
+                args,
+                ..
+            } => {
+                // DeuTeX won't overwrite a WAD
+                if out.exists() {
+                    fs::remove_file(&out).with_file(&out)?;
+                }
+                if let Some(parent) = out.parent() {
+                    fs::create_dir_all(parent).with_file(parent)?;
+                }
+                let status = Command::new(program)
+                    .current_dir(root)
+                    .args(args)
+                    .arg("-build")
+                    .arg(wadinfo)
+                    .arg(output)
+                    .status()
+                    .map_err(|e| crate::Error::Msg(format!("Couldn't run {}: {}", program, e)))?;
+                if !status.success() {
+                    return Err(crate::Error::Msg(format!(
+                        "{} failed with {}",
+                        program, status
+                    )));
+                }
+                Ok(())
+            }
+            Generator::BuildManifest { wad } => {
+                let build = BuildManifest::new(manifest, root, wad)?;
+                write_file(&out, |w| Ok(serde_yaml::to_writer(w, &build)?))
+            }
+        };
+        result.map(|()| warnings)
+    }
+}
+
+#[inline]
+fn read(path: &Path) -> crate::Result<Vec<u8>> {
+    fs::read(path).with_file(path)
+}
+
+/// Write a file through a temporary file next to it, so a failed generator never leaves half an output behind.
+#[inline]
+fn write_file<F: FnOnce(&mut BufWriter<&mut File>) -> crate::Result>(
+    path: &Path,
+    generate: F,
+) -> crate::Result {
+    let parent = match path.parent() {
+        Some(parent) if parent != Path::new("") => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(parent).with_file(parent)?;
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = parent.join(format!(".{}.tmp", name));
+    let result = File::create(&temp)
+        .map_err(crate::Error::from)
+        .and_then(|mut file| {
+            let mut writer = BufWriter::new(&mut file);
+            generate(&mut writer)?;
+            writer.flush()?;
+            Ok(())
+        });
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(e).with_file(path);
+    }
+    fs::rename(&temp, path).with_file(path)
+}
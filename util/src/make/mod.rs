@@ -0,0 +1,671 @@
+// Apache 2.0 License
+
+mod build;
+mod generator;
+
+pub use build::{verify, BuildManifest};
+pub use generator::Generator;
+
+use crate::error::ResultExt;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Condvar, Mutex, OnceLock},
+    thread,
+};
+
+/// Where the hashes of the last build are kept, next to the manifest.
+const STATE_FILE: &str = ".klamath-make.yml";
+
+/// The project manifest, which names every output and the generator that makes it.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Manifest {
+    pub targets: BTreeMap<PathBuf, Generator>,
+}
+
+/// What an output was last made from.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Record {
+    /// Hash of klamath-util, the generator's settings and the contents of its inputs.
+    inputs: String,
+    /// Hash of the output, so outputs changed by hand are made again.
+    output: String,
+}
+
+/// How many targets were made and how many were already up to date.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub made: usize,
+    pub up_to_date: usize,
+}
+
+struct Target<'a> {
+    output: &'a Path,
+    generator: &'a Generator,
+    /// What the target is made again for: the generator's inputs, and for a build manifest, everything upstream of
+    /// its WAD too.
+    inputs: Vec<PathBuf>,
+    /// Targets whose outputs this one reads.
+    deps: Vec<usize>,
+}
+
+#[derive(Default)]
+struct Progress {
+    started: Vec<bool>,
+    finished: Vec<bool>,
+    failure: Option<crate::Error>,
+    records: BTreeMap<PathBuf, Record>,
+    summary: Summary,
+}
+
+/// Make the requested outputs, or everything in the manifest, and whatever they depend on. Targets that don't depend
+/// on each other are made on up to `jobs` threads at once. With `blender`, sprites and graphics the manifest makes
+/// placeholders for are rendered with Blender. Each target made, and its warnings, are written to `log` as it's made.
+#[inline]
+pub fn make<W: Write + Send>(
+    manifest: &Path,
+    requested: &[PathBuf],
+    jobs: usize,
+    blender: bool,
+    log: W,
+) -> crate::Result<Summary> {
+    make_targets(manifest, requested, jobs, false, blender, log)
+}
+
+/// Load a manifest, and find the directory its paths are relative to.
+#[inline]
+fn load(manifest_path: &Path) -> crate::Result<(Manifest, PathBuf)> {
+    let manifest: Manifest = serde_yaml::from_reader(BufReader::new(
+        File::open(manifest_path).with_file(manifest_path)?,
+    ))
+    .with_file(manifest_path)?;
+    let root = match manifest_path.parent() {
+        Some(parent) if parent != Path::new("") => parent,
+        _ => Path::new("."),
+    };
+    Ok((manifest, root.to_path_buf()))
+}
+
+/// Make targets that are out of date, or every one that's selected if `force` is set.
+#[inline]
+fn make_targets<W: Write + Send>(
+    manifest_path: &Path,
+    requested: &[PathBuf],
+    jobs: usize,
+    force: bool,
+    blender: bool,
+    log: W,
+) -> crate::Result<Summary> {
+    let (mut manifest, root) = load(manifest_path)?;
+    let root = root.as_path();
+    // the settings are part of what a target is made from, so switching renderers makes its targets again
+    if blender {
+        manifest
+            .targets
+            .values_mut()
+            .for_each(Generator::use_blender);
+    }
+
+    let targets = graph(&manifest);
+    let selected = select(&targets, requested)?;
+    check_cycles(&targets)?;
+
+    let state_path = root.join(STATE_FILE);
+    let records: BTreeMap<PathBuf, Record> = match File::open(&state_path) {
+        Ok(file) => serde_yaml::from_reader(BufReader::new(file)).with_file(&state_path)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => return Err(e).with_file(&state_path),
+    };
+    let tool = tool_digest()?;
+
+    let progress = Mutex::new(Progress {
+        started: vec![false; targets.len()],
+        finished: vec![false; targets.len()],
+        records,
+        ..Progress::default()
+    });
+    let wake = Condvar::new();
+    let log = Mutex::new(log);
+
+    thread::scope(|s| {
+        (0..jobs.max(1)).for_each(|_| {
+            s.spawn(|| loop {
+                let i = {
+                    let mut progress = progress.lock().unwrap();
+                    loop {
+                        if progress.failure.is_some() {
+                            return;
+                        }
+                        let ready = selected.iter().copied().find(|&i| {
+                            !progress.started[i]
+                                && targets[i].deps.iter().all(|&d| progress.finished[d])
+                        });
+                        match ready {
+                            Some(i) => {
+                                progress.started[i] = true;
+                                break i;
+                            }
+                            None if selected.iter().all(|&i| progress.started[i]) => return,
+                            None => progress = wake.wait(progress).unwrap(),
+                        }
+                    }
+                };
+
+                let target = &targets[i];
+                let last = match force {
+                    true => None,
+                    false => progress.lock().unwrap().records.get(target.output).cloned(),
+                };
+                let result = make_target(&manifest, root, target, &tool, last.as_ref(), &log);
+
+                let mut progress = progress.lock().unwrap();
+                match result.with_file(target.output) {
+                    Ok((record, made)) => {
+                        progress.finished[i] = true;
+                        progress.records.insert(target.output.to_path_buf(), record);
+                        if made {
+                            progress.summary.made += 1;
+                        } else {
+                            progress.summary.up_to_date += 1;
+                        }
+                    }
+                    Err(e) => {
+                        progress.failure.get_or_insert(e);
+                    }
+                }
+                wake.notify_all();
+            });
+        })
+    });
+
+    // record what was made even if something failed, so it isn't made again
+    let progress = progress.into_inner().unwrap();
+    let state = BufWriter::new(File::create(&state_path).with_file(&state_path)?);
+    serde_yaml::to_writer(state, &progress.records).with_file(&state_path)?;
+
+    match progress.failure {
+        Some(e) => Err(e),
+        None => Ok(progress.summary),
+    }
+}
+
+/// Hash of klamath-util itself. Outputs are made again when it changes, since its generators might have.
+#[inline]
+fn tool_digest() -> crate::Result<String> {
+    static TOOL: OnceLock<String> = OnceLock::new();
+    if let Some(tool) = TOOL.get() {
+        return Ok(tool.clone());
+    }
+    let exe = env::current_exe()?;
+    let tool = digest(&exe).with_file(&exe)?;
+    Ok(TOOL.get_or_init(|| tool).clone())
+}
+
+/// Find which targets read other targets' outputs. Inputs can be outputs themselves, files in an output directory, or
+/// directories with outputs in them.
+#[inline]
+fn graph(manifest: &Manifest) -> Vec<Target<'_>> {
+    let outputs: Vec<&Path> = manifest.targets.keys().map(PathBuf::as_path).collect();
+    let mut targets: Vec<Target<'_>> = manifest
+        .targets
+        .iter()
+        .map(|(output, generator)| {
+            let inputs = generator.inputs();
+            let deps = outputs
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| **other != output.as_path())
+                .filter(|(_, other)| {
+                    inputs
+                        .iter()
+                        .any(|input| input.starts_with(other) || other.starts_with(input))
+                })
+                .map(|(j, _)| j)
+                .collect();
+            Target {
+                output,
+                generator,
+                inputs,
+                deps,
+            }
+        })
+        .collect();
+
+    // a build manifest lists the inputs of everything upstream, so it's made again when any of them change
+    for i in 0..targets.len() {
+        if !matches!(targets[i].generator, Generator::BuildManifest { .. }) {
+            continue;
+        }
+        let mut seen = vec![false; targets.len()];
+        let mut stack = targets[i].deps.clone();
+        while let Some(j) = stack.pop() {
+            if !seen[j] {
+                seen[j] = true;
+                stack.extend(&targets[j].deps);
+                let upstream = targets[j].generator.inputs();
+                targets[i].inputs.extend(upstream);
+            }
+        }
+    }
+    targets
+}
+
+/// The requested targets and everything they depend on, or every target if none were asked for.
+#[inline]
+fn select(targets: &[Target<'_>], requested: &[PathBuf]) -> crate::Result<Vec<usize>> {
+    if requested.is_empty() {
+        return Ok((0..targets.len()).collect());
+    }
+
+    let mut wanted = vec![false; targets.len()];
+    let mut stack = requested
+        .iter()
+        .map(|request| {
+            targets
+                .iter()
+                .position(|t| t.output == request.as_path())
+                .ok_or_else(|| {
+                    crate::Error::Msg(format!(
+                        "{} isn't a target in the manifest",
+                        request.display()
+                    ))
+                })
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+    while let Some(i) = stack.pop() {
+        if !wanted[i] {
+            wanted[i] = true;
+            stack.extend(&targets[i].deps);
+        }
+    }
+
+    Ok((0..targets.len()).filter(|&i| wanted[i]).collect())
+}
+
+#[inline]
+fn check_cycles(targets: &[Target<'_>]) -> crate::Result {
+    // 0 is unvisited, 1 is being visited and 2 is done
+    fn visit(targets: &[Target<'_>], marks: &mut [u8], i: usize) -> crate::Result {
+        match marks[i] {
+            1 => {
+                return Err(crate::Error::StaticMsg("Target depends on its own output"))
+                    .with_file(targets[i].output)
+            }
+            2 => return Ok(()),
+            _ => {}
+        }
+        marks[i] = 1;
+        targets[i]
+            .deps
+            .iter()
+            .try_for_each(|&d| visit(targets, marks, d))?;
+        marks[i] = 2;
+        Ok(())
+    }
+
+    let mut marks = vec![0; targets.len()];
+    (0..targets.len()).try_for_each(|i| visit(targets, &mut marks, i))
+}
+
+/// Make a target unless its inputs and output are the same as last time, and log it and its warnings if it's made.
+/// Returns whether it was made.
+#[inline]
+fn make_target<W: Write>(
+    manifest: &Manifest,
+    root: &Path,
+    target: &Target<'_>,
+    tool: &str,
+    last: Option<&Record>,
+    log: &Mutex<W>,
+) -> crate::Result<(Record, bool)> {
+    let mut hasher = Sha256::new();
+    hasher.update(tool);
+    hasher.update(serde_yaml::to_string(target.generator)?);
+    target.inputs.iter().try_for_each(|input| {
+        hasher.update(input.to_string_lossy().as_bytes());
+        hash_path(&root.join(input), &mut hasher).with_file(input)
+    })?;
+    let inputs = hex(&hasher.finalize());
+
+    let output = root.join(target.output);
+    if let Some(last) = last.filter(|last| last.inputs == inputs) {
+        if output.exists() && digest(&output)? == last.output {
+            return Ok((last.clone(), false));
+        }
+    }
+
+    writeln!(log.lock().unwrap(), "making {}", target.output.display())?;
+    if target.generator.makes_directory() {
+        fs::create_dir_all(&output)?;
+    }
+    let warnings = target.generator.run(manifest, root, target.output)?;
+    let mut log = log.lock().unwrap();
+    warnings
+        .iter()
+        .try_for_each(|warning| writeln!(log, "{}", warning))?;
+
+    let output = digest(&output)?;
+    Ok((Record { inputs, output }, true))
+}
+
+/// Hash a file or a directory.
+#[inline]
+pub fn digest(path: &Path) -> crate::Result<String> {
+    let mut hasher = Sha256::new();
+    hash_path(path, &mut hasher)?;
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Add a file's contents to a hash, or the names and contents of everything in a directory.
+#[inline]
+fn hash_path(path: &Path, hasher: &mut Sha256) -> crate::Result {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<crate::Result<Vec<_>>>()?;
+        entries.sort();
+        entries.iter().try_for_each(|entry| {
+            let name = entry.file_name().unwrap_or_default();
+            hasher.update(name.to_string_lossy().as_bytes());
+            hash_path(entry, hasher)
+        })
+    } else {
+        let mut file = File::open(path)?;
+        let len = io::copy(&mut file, hasher)?;
+        hasher.update(len.to_le_bytes());
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        files.iter().for_each(|(name, contents)| {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        });
+        dir
+    }
+
+    const MANIFEST: &str = "targets:\n  \
+                            out/b.txt: { generator: copy, source: a.txt }\n  \
+                            dist/c.txt: { generator: copy, source: out/b.txt }\n  \
+                            dist/d.txt: { generator: copy, source: d.txt }\n";
+
+    #[test]
+    fn targets_are_made_in_order_and_only_when_stale() {
+        let dir = project(&[("klamath.yml", MANIFEST), ("a.txt", "a"), ("d.txt", "d")]);
+        let manifest = dir.path().join("klamath.yml");
+
+        let summary = make_targets(&manifest, &[], 4, false, false, io::sink()).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                made: 3,
+                up_to_date: 0
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dist/c.txt")).unwrap(),
+            "a"
+        );
+
+        let summary = make_targets(&manifest, &[], 4, false, false, io::sink()).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                made: 0,
+                up_to_date: 3
+            }
+        );
+
+        // a change only remakes what depends on it, and an output changed by hand is made again
+        fs::write(dir.path().join("a.txt"), "aa").unwrap();
+        fs::write(dir.path().join("dist/d.txt"), "edited").unwrap();
+        let mut log = vec![];
+        let summary = make_targets(&manifest, &[], 1, false, false, &mut log).unwrap();
+        let mut made: Vec<_> = std::str::from_utf8(&log).unwrap().lines().collect();
+        made.sort_unstable();
+        assert_eq!(
+            made,
+            ["making dist/c.txt", "making dist/d.txt", "making out/b.txt"]
+        );
+        assert_eq!(
+            summary,
+            Summary {
+                made: 3,
+                up_to_date: 0
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dist/c.txt")).unwrap(),
+            "aa"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("dist/d.txt")).unwrap(),
+            "d"
+        );
+    }
+
+    #[test]
+    fn requested_targets_bring_their_dependencies() {
+        let dir = project(&[("klamath.yml", MANIFEST), ("a.txt", "a"), ("d.txt", "d")]);
+        let manifest = dir.path().join("klamath.yml");
+
+        let summary = make_targets(
+            &manifest,
+            &[PathBuf::from("dist/c.txt")],
+            2,
+            false,
+            false,
+            io::sink(),
+        )
+        .unwrap();
+        assert_eq!(summary.made, 2);
+        assert!(!dir.path().join("dist/d.txt").exists());
+
+        assert!(make_targets(
+            &manifest,
+            &[PathBuf::from("nope.txt")],
+            2,
+            false,
+            false,
+            io::sink()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn build_manifests_describe_every_lump_and_verify_rebuilds() {
+        let manifest = "targets:\n  \
+                        lumps/playpal.lmp: { generator: playpal }\n  \
+                        boot.wad: { generator: bootstrap, palette: lumps/playpal.lmp }\n  \
+                        boot.yml: { generator: build_manifest, wad: boot.wad }\n";
+        let dir = project(&[("klamath.yml", manifest)]);
+        let manifest = dir.path().join("klamath.yml");
+        assert_eq!(
+            make_targets(&manifest, &[], 2, false, false, io::sink())
+                .unwrap()
+                .made,
+            3
+        );
+
+        let recorded_path = dir.path().join("boot.yml");
+        let recorded = BuildManifest::load(&recorded_path).unwrap();
+        let names: Vec<&str> = recorded.lumps.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["PLAYPAL", "TEXTURE1", "PNAMES"]);
+        assert_eq!(
+            recorded.lumps[0].file.as_deref(),
+            Some(Path::new("lumps/playpal.lmp"))
+        );
+        assert_eq!(recorded.lumps[0].target, recorded.lumps[0].file);
+        assert_eq!(recorded.lumps[1].file, None);
+        assert_eq!(recorded.targets.len(), 2);
+        assert!(recorded.targets[Path::new("lumps/playpal.lmp")]
+            .constants
+            .contains_key("palette_biases"));
+
+        verify(&manifest, &recorded_path, 2, false, io::sink()).unwrap();
+
+        let mut other = recorded.clone();
+        other.wad.sha256 = "different".to_string();
+        other.lumps[0].sha256 = "different".to_string();
+        assert_eq!(
+            other.differences(&recorded),
+            ["lump PLAYPAL changed (lumps/playpal.lmp)"]
+        );
+
+        // lumps are matched by name and occurrence, not by place
+        let lump = |name: &str, sha256: &str| build::LumpRecord {
+            name: name.to_string(),
+            size: 0,
+            sha256: sha256.to_string(),
+            file: None,
+            target: None,
+        };
+        let mut old = recorded.clone();
+        old.lumps = vec![
+            lump("MAP01", "m"),
+            lump("THINGS", "a"),
+            lump("MAP02", "m"),
+            lump("THINGS", "b"),
+        ];
+        let mut new = other.clone();
+        new.lumps = vec![
+            lump("PLAYPAL", "p"),
+            lump("MAP01", "m"),
+            lump("THINGS", "a"),
+            lump("MAP02", "m"),
+            lump("THINGS", "c"),
+        ];
+        assert_eq!(
+            old.differences(&new),
+            ["lump PLAYPAL is new", "lump THINGS #2 changed"]
+        );
+        new.lumps.remove(0);
+        new.lumps.swap(0, 2);
+        new.lumps[3].sha256 = "b".to_string();
+        assert_eq!(
+            old.differences(&new),
+            ["the lumps are in a different order"]
+        );
+
+        let other_path = dir.path().join("other.yml");
+        fs::write(&other_path, serde_yaml::to_string(&other).unwrap()).unwrap();
+        assert!(verify(&manifest, &other_path, 2, false, io::sink()).is_err());
+    }
+
+    #[test]
+    fn switching_to_blender_makes_renders_again() {
+        let manifest = "targets:\n  \
+                        lumps/playpal.lmp: { generator: playpal }\n  \
+                        graphics: { generator: graphics, palette: lumps/playpal.lmp, placeholder: true }\n";
+        let dir = project(&[("klamath.yml", manifest)]);
+        let manifest = dir.path().join("klamath.yml");
+        assert_eq!(
+            make_targets(&manifest, &[], 2, false, false, io::sink())
+                .unwrap()
+                .made,
+            2
+        );
+        assert_eq!(
+            make_targets(&manifest, &[], 2, false, false, io::sink())
+                .unwrap()
+                .made,
+            0
+        );
+        assert_eq!(
+            make_targets(&manifest, &[], 2, false, true, io::sink())
+                .unwrap()
+                .made,
+            1
+        );
+    }
+
+    #[test]
+    fn placeholder_screens_need_no_blender() {
+        let manifest = "targets:\n  \
+                        lumps/playpal.lmp: { generator: playpal }\n  \
+                        graphics:\n    \
+                        generator: graphics\n    \
+                        screens: [models/title.yml]\n    \
+                        palette: lumps/playpal.lmp\n    \
+                        placeholder: true\n";
+        let spec = "model: title.blend\nscreens:\n  - { lump: TITLEPIC, frame: 1 }\n  - { lump: HELP1, frame: 2 }\n";
+        let dir = project(&[
+            ("klamath.yml", manifest),
+            ("models/title.yml", spec),
+            ("models/title.blend", "not a scene"),
+        ]);
+        make_targets(
+            &dir.path().join("klamath.yml"),
+            &[],
+            2,
+            false,
+            false,
+            io::sink(),
+        )
+        .unwrap();
+        assert!(dir.path().join("graphics/titlepic.png").exists());
+        assert!(dir.path().join("graphics/help1.png").exists());
+    }
+
+    #[test]
+    fn failures_and_cycles_are_errors() {
+        let dir = project(&[("klamath.yml", MANIFEST), ("d.txt", "d")]);
+        let err = make_targets(
+            &dir.path().join("klamath.yml"),
+            &[],
+            2,
+            false,
+            false,
+            io::sink(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("out/b.txt: a.txt: "), "{}", err);
+
+        let missing = "targets:\n  a.wad: { generator: deutex, wadinfo: wadinfo.txt, inputs: [], program: no-such-deutex }\n";
+        let dir = project(&[("klamath.yml", missing), ("wadinfo.txt", "")]);
+        let err = make_targets(
+            &dir.path().join("klamath.yml"),
+            &[],
+            2,
+            false,
+            false,
+            io::sink(),
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("a.wad: Couldn't run no-such-deutex: "),
+            "{}",
+            err
+        );
+
+        let cycle = "targets:\n  a.txt: { generator: copy, source: b.txt }\n  \
+                     b.txt: { generator: copy, source: a.txt }\n";
+        let dir = project(&[("klamath.yml", cycle)]);
+        assert!(make_targets(
+            &dir.path().join("klamath.yml"),
+            &[],
+            2,
+            false,
+            false,
+            io::sink()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,128 @@
+// Apache 2.0 License
+
+use super::Map;
+use std::collections::HashMap;
+
+/// Blocks are 128 units on a side.
+const BLOCK_SIZE: i64 = 128;
+/// The blockmap starts a little below and left of the map, so nothing is on its edge.
+const MARGIN: i64 = 8;
+/// Lists in the blockmap are found by 16 bit offsets, counted in words from the start of the lump.
+const MAX_WORDS: usize = 0x1_0000;
+
+/// Build the BLOCKMAP lump, which lists the linedefs that pass through each block of the map for collision
+/// detection. Blocks with the same linedefs share a list.
+#[inline]
+pub fn blockmap(map: &Map) -> crate::Result<Vec<u8>> {
+    let xs = map.vertexes.iter().map(|v| i64::from(v.x));
+    let ys = map.vertexes.iter().map(|v| i64::from(v.y));
+    let origin_x = xs.clone().min().unwrap_or(0) - MARGIN;
+    let origin_y = ys.clone().min().unwrap_or(0) - MARGIN;
+    let columns = (xs.max().unwrap_or(0) - origin_x) / BLOCK_SIZE + 1;
+    let rows = (ys.max().unwrap_or(0) - origin_y) / BLOCK_SIZE + 1;
+
+    let mut blocks = vec![vec![]; (columns * rows) as usize];
+    map.linedefs.iter().enumerate().for_each(|(i, line)| {
+        let point = |v: u16| {
+            let v = &map.vertexes[v as usize];
+            (i64::from(v.x) - origin_x, i64::from(v.y) - origin_y)
+        };
+        let (a, b) = (point(line.start), point(line.end));
+
+        for row in a.1.min(b.1) / BLOCK_SIZE..=a.1.max(b.1) / BLOCK_SIZE {
+            for column in a.0.min(b.0) / BLOCK_SIZE..=a.0.max(b.0) / BLOCK_SIZE {
+                if crosses(a, b, column * BLOCK_SIZE, row * BLOCK_SIZE) {
+                    blocks[(row * columns + column) as usize].push(i as u16);
+                }
+            }
+        }
+    });
+
+    let mut words: Vec<u16> = vec![
+        origin_x as u16,
+        origin_y as u16,
+        columns as u16,
+        rows as u16,
+    ];
+    let mut offsets = vec![0; blocks.len()];
+    let mut lists = vec![];
+    let mut shared = HashMap::new();
+    let start = words.len() + blocks.len();
+    blocks.iter().enumerate().for_each(|(i, block)| {
+        offsets[i] = *shared.entry(block).or_insert_with(|| {
+            let offset = start + lists.len();
+            // every list starts with a zero, which vanilla Doom skips over
+            lists.push(0);
+            lists.extend(block);
+            lists.push(0xFFFF);
+            offset
+        });
+    });
+    if start + lists.len() > MAX_WORDS {
+        return Err(crate::Error::StaticMsg(
+            "Map is too big for a vanilla blockmap",
+        ));
+    }
+    words.extend(offsets.iter().map(|&o| o as u16));
+    words.extend(lists);
+
+    Ok(words.iter().flat_map(|w| w.to_le_bytes()).collect())
+}
+
+/// Whether a line from a to b passes through the block with its bottom left corner here. The line is already known
+/// to overlap the block's rows and columns, so it only misses if every corner is on the same side of it.
+#[inline]
+fn crosses(a: (i64, i64), b: (i64, i64), x: i64, y: i64) -> bool {
+    let side =
+        |(cx, cy): (i64, i64)| ((cx - a.0) * (b.1 - a.1) - (cy - a.1) * (b.0 - a.0)).signum();
+    let corners = [
+        (x, y),
+        (x + BLOCK_SIZE, y),
+        (x, y + BLOCK_SIZE),
+        (x + BLOCK_SIZE, y + BLOCK_SIZE),
+    ];
+    let sides: Vec<i64> = corners.iter().map(|&c| side(c)).collect();
+    !(sides.iter().all(|&s| s > 0) || sides.iter().all(|&s| s < 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        map::tests::square_room,
+        wad::{tests::pwad, Wad},
+    };
+
+    #[test]
+    fn lines_are_listed_in_their_blocks() {
+        let map = Map::from_wad(&Wad::parse(&pwad(&square_room(&[]))).unwrap())
+            .unwrap()
+            .remove(0);
+        let lump = blockmap(&map).unwrap();
+        let words: Vec<u16> = lump
+            .chunks_exact(2)
+            .map(|w| u16::from_le_bytes([w[0], w[1]]))
+            .collect();
+
+        // the room is 128 units wide, which with the margin takes two blocks each way
+        assert_eq!(&words[..4], &[(-8i16) as u16, (-8i16) as u16, 2, 2]);
+        let list = |block: usize| {
+            let start = words[4 + block] as usize;
+            let end = start + words[start..].iter().position(|&w| w == 0xFFFF).unwrap();
+            words[start + 1..end].to_vec()
+        };
+        // lines go 0 -> 1 -> 2 -> 3 around (0, 0), (0, 128), (128, 128) and (128, 0)
+        assert_eq!(list(0), vec![0, 3]);
+        assert_eq!(list(1), vec![2, 3]);
+        assert_eq!(list(2), vec![0, 1]);
+        assert_eq!(list(3), vec![1, 2]);
+    }
+
+    #[test]
+    fn lines_miss_blocks_they_only_pass_near() {
+        // a diagonal through the bottom left block doesn't reach the top right one
+        assert!(crosses((0, 0), (200, 200), 0, 0));
+        assert!(crosses((0, 0), (200, 200), 128, 128));
+        assert!(!crosses((0, 100), (100, 0), 128, 128));
+    }
+}
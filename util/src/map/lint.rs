@@ -0,0 +1,433 @@
+// Apache 2.0 License
+
+use super::{Linedef, Map};
+use crate::{dehacked::Severity, error::ResultExt};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The flat Doom draws as sky. It isn't a real flat, so it doesn't have to be shipped.
+const SKY_FLAT: &str = "F_SKY1";
+
+/// Thing types for the player 1 to 4 starts.
+static PLAYER_STARTS: &[i16] = &[1, 2, 3, 4];
+/// The thing type for deathmatch starts.
+const DEATHMATCH_START: i16 = 11;
+/// Doom refuses to start a deathmatch game with fewer starts than this.
+const MIN_DEATHMATCH_STARTS: usize = 4;
+/// The line specials that act on the sector behind the line, or end the level, rather than on sectors with the
+/// line's tag: manual doors, exits and the scrolling wall. Every other special needs a tagged sector.
+static UNTAGGED_SPECIALS: &[i16] = &[1, 11, 26, 27, 28, 31, 32, 33, 34, 48, 51, 52, 117, 118, 124];
+
+/// Something wrong, or possibly wrong, with a map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}", severity, self.message)
+    }
+}
+
+/// The flats and textures the build ships, which are all a map can use.
+#[derive(Debug, Clone, Default)]
+pub struct Resources {
+    pub flats: HashSet<String>,
+    pub textures: HashSet<String>,
+}
+
+impl Resources {
+    /// Read the flats from the [flats] section of DeuTeX's wadinfo.txt, and the textures from a DeuTeX texture file.
+    #[inline]
+    pub fn load(wadinfo: &Path, textures: &Path) -> crate::Result<Self> {
+        Ok(Self {
+            flats: wadinfo_section(&fs::read_to_string(wadinfo).with_file(wadinfo)?, "flats"),
+            textures: texture_names(&fs::read_to_string(textures).with_file(textures)?),
+        })
+    }
+}
+
+/// The entries in one section of a wadinfo.txt. Each entry is a lump name, possibly followed by where to find it.
+#[inline]
+fn wadinfo_section(text: &str, section: &str) -> HashSet<String> {
+    let mut current = None;
+    text.lines()
+        .map(|line| line.split(';').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            if line.starts_with('[') && line.ends_with(']') {
+                current = Some(line[1..line.len() - 1].trim().to_ascii_lowercase());
+                return None;
+            }
+            if current.as_deref() != Some(section) {
+                return None;
+            }
+            line.split(|c: char| c.is_whitespace() || c == '=')
+                .next()
+                .map(str::to_ascii_uppercase)
+        })
+        .collect()
+}
+
+/// The names of the textures in a DeuTeX texture file. Lines starting with '*' are the patches in a texture.
+#[inline]
+fn texture_names(text: &str) -> HashSet<String> {
+    text.lines()
+        .map(|line| line.split(';').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('*'))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_ascii_uppercase)
+        .collect()
+}
+
+/// Check every map in some WADs, and write everything that looks wrong to `out`. Fails if any problem is an error
+/// rather than a warning.
+#[inline]
+pub fn lint_maps<W: Write>(
+    wads: &[PathBuf],
+    wadinfo: &Path,
+    textures: &Path,
+    mut out: W,
+) -> crate::Result {
+    let resources = Resources::load(wadinfo, textures)?;
+    let mut errors = false;
+
+    for wad in wads {
+        for map in Map::load(wad)? {
+            lint_map(&map, &resources).iter().try_for_each(|d| {
+                errors |= d.severity == Severity::Error;
+                writeln!(out, "{}:{}: {}", wad.display(), map.name, d)
+            })?;
+        }
+    }
+
+    if errors {
+        Err(crate::Error::StaticMsg("Maps have errors"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Everything that looks wrong with a map.
+#[inline]
+pub fn lint_map(map: &Map, resources: &Resources) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut report = |severity, message| diagnostics.push(Diagnostic { severity, message });
+
+    // the geometry checks need every reference to point at something; maps that are loaded always do, but lint
+    // maps made in code too
+    let mut broken = false;
+    map.broken_references().into_iter().for_each(|message| {
+        report(Severity::Error, message);
+        broken = true;
+    });
+    map.linedefs.iter().enumerate().for_each(|(i, line)| {
+        if line.front.is_none() {
+            report(Severity::Error, format!("linedef {} has no front side", i));
+            broken = true;
+        }
+    });
+
+    map.sectors.iter().enumerate().for_each(|(i, sector)| {
+        [&sector.floor_flat, &sector.ceiling_flat]
+            .iter()
+            .filter(|flat| flat.as_str() != SKY_FLAT && !resources.flats.contains(flat.as_str()))
+            .for_each(|flat| {
+                report(
+                    Severity::Error,
+                    format!("sector {} uses flat {}, which isn't in [flats]", i, flat),
+                )
+            });
+    });
+
+    map.sidedefs.iter().enumerate().for_each(|(i, side)| {
+        [&side.upper, &side.lower, &side.middle]
+            .iter()
+            .filter(|texture| {
+                texture.as_str() != "-" && !resources.textures.contains(texture.as_str())
+            })
+            .for_each(|texture| {
+                report(
+                    Severity::Error,
+                    format!(
+                        "sidedef {} uses texture {}, which isn't defined",
+                        i, texture
+                    ),
+                )
+            });
+    });
+
+    if !broken {
+        unclosed_sectors(map)
+            .into_iter()
+            .for_each(|i| report(Severity::Error, format!("sector {} is not closed", i)));
+        map.linedefs.iter().enumerate().for_each(|(i, line)| {
+            missing_textures(map, line).into_iter().for_each(|part| {
+                report(
+                    Severity::Error,
+                    format!("linedef {} is missing its {} texture", i, part),
+                )
+            })
+        });
+    }
+
+    // only player 1's start is needed to play the map alone, and the others only matter for multiplayer
+    PLAYER_STARTS
+        .iter()
+        .filter(|&&start| !map.things.iter().any(|t| t.kind == start))
+        .for_each(|&start| {
+            let severity = if start == PLAYER_STARTS[0] {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            report(severity, format!("there is no player {} start", start))
+        });
+    let deathmatch_starts = map
+        .things
+        .iter()
+        .filter(|t| t.kind == DEATHMATCH_START)
+        .count();
+    if deathmatch_starts < MIN_DEATHMATCH_STARTS {
+        report(
+            Severity::Warning,
+            format!(
+                "there are {} deathmatch starts, but deathmatch needs at least {}",
+                deathmatch_starts, MIN_DEATHMATCH_STARTS
+            ),
+        );
+    }
+
+    let tags: HashSet<i16> = map.sectors.iter().map(|s| s.tag).collect();
+    map.linedefs
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.special != 0 && !UNTAGGED_SPECIALS.contains(&line.special))
+        .filter(|(_, line)| line.tag != 0 && !tags.contains(&line.tag))
+        .for_each(|(i, line)| {
+            report(
+                Severity::Error,
+                format!(
+                    "linedef {} has tag {}, but no sector has that tag",
+                    i, line.tag
+                ),
+            )
+        });
+
+    diagnostics
+}
+
+/// Sectors whose lines don't form closed loops. Every vertex in a closed sector is where an even number of the
+/// sector's lines meet.
+#[inline]
+fn unclosed_sectors(map: &Map) -> Vec<usize> {
+    let mut ends: HashMap<(usize, u16), usize> = HashMap::new();
+    map.linedefs.iter().for_each(|line| {
+        [line.front, line.back]
+            .iter()
+            .flatten()
+            .map(|&s| map.sidedefs[s as usize].sector as usize)
+            .for_each(|sector| {
+                *ends.entry((sector, line.start)).or_default() += 1;
+                *ends.entry((sector, line.end)).or_default() += 1;
+            });
+    });
+
+    let mut unclosed: Vec<usize> = ends
+        .into_iter()
+        .filter(|(_, count)| count % 2 == 1)
+        .map(|((sector, _), _)| sector)
+        .collect();
+    unclosed.sort_unstable();
+    unclosed.dedup();
+    unclosed
+}
+
+/// The parts of a line that show but have no texture. One sided lines need a middle texture, and two sided lines
+/// need an upper or lower texture on each side that looks at a step in the ceiling or floor.
+#[inline]
+fn missing_textures(map: &Map, line: &Linedef) -> Vec<&'static str> {
+    let side = |s: u16| &map.sidedefs[s as usize];
+    let sector = |s: u16| &map.sectors[side(s).sector as usize];
+    let front = match line.front {
+        Some(front) => front,
+        None => return vec![],
+    };
+
+    let back = match line.back {
+        Some(back) => back,
+        None if side(front).middle == "-" => return vec!["front middle"],
+        None => return vec![],
+    };
+
+    let mut missing = vec![];
+    for &(this, other, name) in &[(front, back, "front"), (back, front, "back")] {
+        let (near, far) = (sector(this), sector(other));
+        // Doom doesn't draw upper textures between two skies
+        let sky = near.ceiling_flat == SKY_FLAT && far.ceiling_flat == SKY_FLAT;
+        if far.ceiling_height < near.ceiling_height && !sky && side(this).upper == "-" {
+            missing.push(if name == "front" {
+                "front upper"
+            } else {
+                "back upper"
+            });
+        }
+        if far.floor_height > near.floor_height && side(this).lower == "-" {
+            missing.push(if name == "front" {
+                "front lower"
+            } else {
+                "back lower"
+            });
+        }
+    }
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        map::tests::square_room,
+        wad::{tests::pwad, Wad},
+    };
+
+    fn resources() -> Resources {
+        Resources {
+            flats: wadinfo_section(
+                "; flats\n[lumps]\nPLAYPAL\n\n[flats]\nfloor4_8\nCEIL3_5 = ceil.png\n",
+                "flats",
+            ),
+            textures: texture_names("; textures\nSTARTAN3 128 128\n*\tSTARTAN3 0 0\n"),
+        }
+    }
+
+    fn room(things: &[(i16, i16)]) -> Map {
+        Map::from_wad(&Wad::parse(&pwad(&square_room(things))).unwrap())
+            .unwrap()
+            .remove(0)
+    }
+
+    fn messages(map: &Map, resources: &Resources) -> Vec<String> {
+        lint_map(map, resources)
+            .iter()
+            .map(|d| d.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn resource_lists_are_read() {
+        let resources = resources();
+        assert_eq!(resources.flats.len(), 2);
+        assert!(resources.flats.contains("FLOOR4_8"));
+        assert!(resources.flats.contains("CEIL3_5"));
+        assert_eq!(resources.textures.len(), 1);
+        assert!(resources.textures.contains("STARTAN3"));
+    }
+
+    #[test]
+    fn a_good_room_passes() {
+        let starts = [
+            (1, 7),
+            (2, 7),
+            (3, 7),
+            (4, 7),
+            (11, 7),
+            (11, 7),
+            (11, 7),
+            (11, 7),
+        ];
+        assert!(lint_map(&room(&starts), &resources()).is_empty());
+    }
+
+    #[test]
+    fn problems_are_found() {
+        let mut map = room(&[(1, 7), (11, 7)]);
+        map.sectors[0].floor_flat = "NUKAGE1".to_string();
+        map.sidedefs[1].middle = "-".to_string();
+        // a lift with a tag nothing has, a manual door with a leftover tag, and a tag with no special
+        map.linedefs[2].special = 62;
+        map.linedefs[2].tag = 5;
+        map.linedefs[1].special = 1;
+        map.linedefs[1].tag = 6;
+        map.linedefs[3].tag = 7;
+
+        let messages = messages(&map, &resources());
+        assert!(
+            messages.contains(&"sector 0 uses flat NUKAGE1, which isn't in [flats]".to_string())
+        );
+        assert!(messages.contains(&"linedef 1 is missing its front middle texture".to_string()));
+        assert!(messages.contains(&"there is no player 2 start".to_string()));
+        assert!(messages.contains(&"there is no player 4 start".to_string()));
+        assert!(messages.contains(
+            &"there are 1 deathmatch starts, but deathmatch needs at least 4".to_string()
+        ));
+        assert!(messages.contains(&"linedef 2 has tag 5, but no sector has that tag".to_string()));
+        assert!(!messages
+            .iter()
+            .any(|m| m.contains("tag 6") || m.contains("tag 7")));
+    }
+
+    #[test]
+    fn single_player_maps_only_get_warnings_for_multiplayer_starts() {
+        let diagnostics = lint_map(&room(&[(1, 7)]), &resources());
+        assert_eq!(diagnostics.len(), 4);
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+
+        let diagnostics = lint_map(&room(&[(2, 7)]), &resources());
+        assert!(diagnostics.contains(&Diagnostic {
+            severity: Severity::Error,
+            message: "there is no player 1 start".to_string(),
+        }));
+    }
+
+    #[test]
+    fn gaps_and_steps_are_found() {
+        let mut map = room(&[]);
+        map.linedefs.pop();
+        assert_eq!(unclosed_sectors(&map), vec![0]);
+
+        // split the room in two, with a higher floor and lower ceiling behind the new line
+        let mut map = room(&[]);
+        let mut raised = map.sectors[0].clone();
+        raised.floor_height = 16;
+        raised.ceiling_height = 96;
+        map.sectors.push(raised);
+        let mut back = map.sidedefs[0].clone();
+        back.sector = 1;
+        map.sidedefs.push(back);
+        map.linedefs.push(Linedef {
+            start: 0,
+            end: 2,
+            flags: 4,
+            special: 0,
+            tag: 0,
+            front: Some(0),
+            back: Some(4),
+        });
+        assert_eq!(
+            missing_textures(&map, &map.linedefs[4]),
+            vec!["front upper", "front lower"]
+        );
+    }
+
+    #[test]
+    fn broken_references_are_reported_not_followed() {
+        let mut map = room(&[]);
+        map.linedefs[0].end = 40;
+        map.sidedefs[3].sector = 7;
+        let messages = messages(&map, &resources());
+        assert!(messages.contains(&"linedef 0 has a vertex that doesn't exist".to_string()));
+        assert!(messages.contains(&"sidedef 3 has a sector that doesn't exist".to_string()));
+    }
+}
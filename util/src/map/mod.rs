@@ -0,0 +1,501 @@
+// Apache 2.0 License
+
+mod blockmap;
+mod lint;
+mod nodes;
+mod render;
+mod stats;
+mod udmf;
+
+pub use lint::lint_maps;
+pub use nodes::{build_nodes, NodeFormat};
+pub use render::render_map;
+pub use stats::mapinfo;
+pub use udmf::{export_udmf, import_udmf};
+
+use crate::{
+    error::ResultExt,
+    wad::{lump_name, Lump, Wad},
+};
+use std::{fs, path::Path};
+
+/// Lumps that belong to a map, after its marker. Only the first few are needed to play it; the rest are built from
+/// them by a node builder.
+pub static MAP_LUMPS: &[&str] = &[
+    "THINGS", "LINEDEFS", "SIDEDEFS", "VERTEXES", "SEGS", "SSECTORS", "NODES", "SECTORS", "REJECT",
+    "BLOCKMAP", "BEHAVIOR",
+];
+
+/// Thing flags that say which skills a thing appears on.
+pub const MTF_EASY: i16 = 0x0001;
+pub const MTF_NORMAL: i16 = 0x0002;
+pub const MTF_HARD: i16 = 0x0004;
+/// The thing only appears in multiplayer games.
+pub const MTF_NOTSINGLE: i16 = 0x0010;
+
+/// A map in Doom format.
+#[derive(Debug, Clone, Default)]
+pub struct Map {
+    /// The name of the marker lump, like MAP01.
+    pub name: String,
+    pub things: Vec<Thing>,
+    pub linedefs: Vec<Linedef>,
+    pub sidedefs: Vec<Sidedef>,
+    pub vertexes: Vec<Vertex>,
+    pub sectors: Vec<Sector>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Thing {
+    pub x: i16,
+    pub y: i16,
+    pub angle: i16,
+    /// The editor number, which DeHackEd calls "ID #".
+    pub kind: i16,
+    pub flags: i16,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Linedef {
+    pub start: u16,
+    pub end: u16,
+    pub flags: i16,
+    pub special: i16,
+    pub tag: i16,
+    pub front: Option<u16>,
+    pub back: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sidedef {
+    pub x_offset: i16,
+    pub y_offset: i16,
+    /// Texture names, with "-" for no texture.
+    pub upper: String,
+    pub lower: String,
+    pub middle: String,
+    pub sector: u16,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Vertex {
+    pub x: i16,
+    pub y: i16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sector {
+    pub floor_height: i16,
+    pub ceiling_height: i16,
+    pub floor_flat: String,
+    pub ceiling_flat: String,
+    pub light: i16,
+    pub special: i16,
+    pub tag: i16,
+}
+
+impl Map {
+    /// Load every map in a WAD, or the map in a UDMF source.
+    #[inline]
+    pub fn load(path: &Path) -> crate::Result<Vec<Self>> {
+        Self::from_wad(&load_wad(path)?).with_file(path)
+    }
+
+    /// Find every map in a WAD. A map is a marker lump followed by THINGS and the rest of its lumps.
+    #[inline]
+    pub fn from_wad(wad: &Wad) -> crate::Result<Vec<Self>> {
+        let mut maps = vec![];
+        let mut lumps = wad.lumps.iter().peekable();
+
+        while let Some(marker) = lumps.next() {
+            if lumps.peek().is_none_or(|l| l.name != "THINGS") {
+                continue;
+            }
+
+            let mut map = Map {
+                name: marker.name.clone(),
+                ..Map::default()
+            };
+            let mut found = vec![];
+            while let Some(lump) = lumps.next_if(|l| MAP_LUMPS.contains(&l.name.as_str())) {
+                let (data, name) = (&lump.data, lump.name.as_str());
+                let parsed = match name {
+                    "THINGS" => records(data, 10, thing).map(|r| map.things = r),
+                    "LINEDEFS" => records(data, 14, linedef).map(|r| map.linedefs = r),
+                    "SIDEDEFS" => records(data, 30, sidedef).map(|r| map.sidedefs = r),
+                    "VERTEXES" => records(data, 4, vertex).map(|r| map.vertexes = r),
+                    "SECTORS" => records(data, 26, sector).map(|r| map.sectors = r),
+                    "BEHAVIOR" => Err(crate::Error::StaticMsg(
+                        "Hexen format maps aren't supported, only Doom format",
+                    )),
+                    _ => Ok(()),
+                };
+                parsed.with_lump(name).with_lump(&map.name)?;
+                found.push(lump.name.as_str());
+            }
+
+            if let Some(missing) = ["LINEDEFS", "SIDEDEFS", "VERTEXES", "SECTORS"]
+                .iter()
+                .find(|name| !found.contains(name))
+            {
+                return Err(crate::Error::Msg(format!("Map has no {} lump", missing)))
+                    .with_lump(&map.name);
+            }
+            // everything that reads a map indexes it with these, so a map isn't loaded without them all being valid
+            if let Some(broken) = map.broken_references().into_iter().next() {
+                return Err(crate::Error::Msg(broken)).with_lump(&map.name);
+            }
+
+            maps.push(map);
+        }
+
+        Ok(maps)
+    }
+
+    /// Every linedef vertex and sidedef, and every sidedef sector, that doesn't exist.
+    #[inline]
+    pub fn broken_references(&self) -> Vec<String> {
+        let mut broken = vec![];
+        self.linedefs.iter().enumerate().for_each(|(i, line)| {
+            if [line.start, line.end]
+                .iter()
+                .any(|&v| v as usize >= self.vertexes.len())
+            {
+                broken.push(format!("linedef {} has a vertex that doesn't exist", i));
+            }
+            if [line.front, line.back]
+                .iter()
+                .flatten()
+                .any(|&s| s as usize >= self.sidedefs.len())
+            {
+                broken.push(format!("linedef {} has a sidedef that doesn't exist", i));
+            }
+        });
+        self.sidedefs.iter().enumerate().for_each(|(i, side)| {
+            if side.sector as usize >= self.sectors.len() {
+                broken.push(format!("sidedef {} has a sector that doesn't exist", i));
+            }
+        });
+        broken
+    }
+
+    /// The map's marker and the lumps needed to play it, without nodes.
+    #[inline]
+    pub fn lumps(&self) -> Vec<Lump> {
+        let mut things = vec![];
+        self.things.iter().for_each(|t| {
+            put_i16s(&mut things, &[t.x, t.y, t.angle, t.kind, t.flags]);
+        });
+        let mut linedefs = vec![];
+        self.linedefs.iter().for_each(|l| {
+            let side = |side: Option<u16>| side.unwrap_or(0xFFFF) as i16;
+            let (start, end) = (l.start as i16, l.end as i16);
+            put_i16s(&mut linedefs, &[start, end, l.flags, l.special, l.tag]);
+            put_i16s(&mut linedefs, &[side(l.front), side(l.back)]);
+        });
+        let mut sidedefs = vec![];
+        self.sidedefs.iter().for_each(|s| {
+            put_i16s(&mut sidedefs, &[s.x_offset, s.y_offset]);
+            [&s.upper, &s.lower, &s.middle]
+                .iter()
+                .for_each(|name| put_name(&mut sidedefs, name));
+            put_i16s(&mut sidedefs, &[s.sector as i16]);
+        });
+        let mut vertexes = vec![];
+        self.vertexes
+            .iter()
+            .for_each(|v| put_i16s(&mut vertexes, &[v.x, v.y]));
+        let mut sectors = vec![];
+        self.sectors.iter().for_each(|s| {
+            put_i16s(&mut sectors, &[s.floor_height, s.ceiling_height]);
+            put_name(&mut sectors, &s.floor_flat);
+            put_name(&mut sectors, &s.ceiling_flat);
+            put_i16s(&mut sectors, &[s.light, s.special, s.tag]);
+        });
+
+        [
+            (self.name.as_str(), vec![]),
+            ("THINGS", things),
+            ("LINEDEFS", linedefs),
+            ("SIDEDEFS", sidedefs),
+            ("VERTEXES", vertexes),
+            ("SECTORS", sectors),
+        ]
+        .iter()
+        .cloned()
+        .map(|(name, data)| Lump {
+            name: name.to_string(),
+            data,
+        })
+        .collect()
+    }
+}
+
+/// Load a WAD. A UDMF source, with the .udmf extension, is converted to a WAD with one Doom format map in it, named
+/// after the file.
+#[inline]
+pub fn load_wad(path: &Path) -> crate::Result<Wad> {
+    if path
+        .extension()
+        .is_none_or(|e| !e.eq_ignore_ascii_case("udmf"))
+    {
+        return Wad::load(path);
+    }
+
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_ascii_uppercase())
+        .unwrap_or_default();
+    if name.is_empty() || name.len() > 8 {
+        return Err(crate::Error::Msg(format!(
+            "UDMF sources must be named after their map, and {:?} can't be a lump name",
+            name
+        )))
+        .with_file(path);
+    }
+    let map =
+        udmf::parse_textmap(&fs::read_to_string(path).with_file(path)?, &name).with_file(path)?;
+    Ok(Wad { lumps: map.lumps() })
+}
+
+impl Thing {
+    /// Whether the thing appears in single player on a skill from 1 to 5.
+    #[inline]
+    pub fn on_skill(&self, skill: usize) -> bool {
+        let bit = match skill {
+            1 | 2 => MTF_EASY,
+            3 => MTF_NORMAL,
+            _ => MTF_HARD,
+        };
+        self.flags & bit != 0 && self.flags & MTF_NOTSINGLE == 0
+    }
+}
+
+/// Split a lump into fixed size records and parse each one.
+#[inline]
+fn records<T>(data: &[u8], size: usize, parse: fn(&[u8]) -> T) -> crate::Result<Vec<T>> {
+    if !data.len().is_multiple_of(size) {
+        return Err(crate::Error::StaticMsg(
+            "Map lump ends partway through a record",
+        ));
+    }
+    Ok(data.chunks_exact(size).map(parse).collect())
+}
+
+#[inline]
+fn thing(r: &[u8]) -> Thing {
+    Thing {
+        x: i16_at(r, 0),
+        y: i16_at(r, 2),
+        angle: i16_at(r, 4),
+        kind: i16_at(r, 6),
+        flags: i16_at(r, 8),
+    }
+}
+
+#[inline]
+fn linedef(r: &[u8]) -> Linedef {
+    // 0xFFFF means there's no sidedef
+    let side = |pos| Some(u16_at(r, pos)).filter(|&s| s != 0xFFFF);
+    Linedef {
+        start: u16_at(r, 0),
+        end: u16_at(r, 2),
+        flags: i16_at(r, 4),
+        special: i16_at(r, 6),
+        tag: i16_at(r, 8),
+        front: side(10),
+        back: side(12),
+    }
+}
+
+#[inline]
+fn sidedef(r: &[u8]) -> Sidedef {
+    Sidedef {
+        x_offset: i16_at(r, 0),
+        y_offset: i16_at(r, 2),
+        upper: lump_name(&r[4..12]),
+        lower: lump_name(&r[12..20]),
+        middle: lump_name(&r[20..28]),
+        sector: u16_at(r, 28),
+    }
+}
+
+#[inline]
+fn vertex(r: &[u8]) -> Vertex {
+    Vertex {
+        x: i16_at(r, 0),
+        y: i16_at(r, 2),
+    }
+}
+
+#[inline]
+fn sector(r: &[u8]) -> Sector {
+    Sector {
+        floor_height: i16_at(r, 0),
+        ceiling_height: i16_at(r, 2),
+        floor_flat: lump_name(&r[4..12]),
+        ceiling_flat: lump_name(&r[12..20]),
+        light: i16_at(r, 20),
+        special: i16_at(r, 22),
+        tag: i16_at(r, 24),
+    }
+}
+
+#[inline]
+fn put_i16s(out: &mut Vec<u8>, words: &[i16]) {
+    words.iter().for_each(|w| out.extend(w.to_le_bytes()));
+}
+
+/// Write a lump name padded to eight bytes with zeroes.
+#[inline]
+fn put_name(out: &mut Vec<u8>, name: &str) {
+    let mut padded = [0; 8];
+    let len = name.len().min(8);
+    padded[..len].copy_from_slice(&name.as_bytes()[..len]);
+    out.extend(padded);
+}
+
+#[inline]
+fn u16_at(bytes: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([bytes[pos], bytes[pos + 1]])
+}
+
+#[inline]
+fn i16_at(bytes: &[u8], pos: usize) -> i16 {
+    i16::from_le_bytes([bytes[pos], bytes[pos + 1]])
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::wad::tests::pwad;
+
+    fn words(words: &[i16]) -> Vec<u8> {
+        words
+            .iter()
+            .flat_map(|w| w.to_le_bytes().to_vec())
+            .collect()
+    }
+
+    fn name(name: &str) -> Vec<u8> {
+        let mut padded = vec![0; 8];
+        padded[..name.len()].copy_from_slice(name.as_bytes());
+        padded
+    }
+
+    /// A square room with the given things in it, as the lumps of a map.
+    pub fn square_room(things: &[(i16, i16)]) -> Vec<(&'static str, Vec<u8>)> {
+        let things = things
+            .iter()
+            .flat_map(|&(kind, flags)| words(&[0, 0, 90, kind, flags]))
+            .collect();
+        let linedefs = (0..4)
+            .flat_map(|i| words(&[i, (i + 1) % 4, 1, 0, 0, i, -1]))
+            .collect();
+        let sidedefs = (0..4)
+            .flat_map(|_| {
+                let mut side = words(&[0, 0]);
+                side.extend(name("-"));
+                side.extend(name("-"));
+                side.extend(name("STARTAN3"));
+                side.extend(words(&[0]));
+                side
+            })
+            .collect();
+        let vertexes = words(&[0, 0, 0, 128, 128, 128, 128, 0]);
+        let mut sectors = words(&[0, 128]);
+        sectors.extend(name("FLOOR4_8"));
+        sectors.extend(name("CEIL3_5"));
+        sectors.extend(words(&[160, 9, 0]));
+
+        vec![
+            ("MAP01", vec![]),
+            ("THINGS", things),
+            ("LINEDEFS", linedefs),
+            ("SIDEDEFS", sidedefs),
+            ("VERTEXES", vertexes),
+            ("SECTORS", sectors),
+        ]
+    }
+
+    #[test]
+    fn maps_are_parsed() {
+        let mut lumps = square_room(&[(1, 7), (3004, 4)]);
+        lumps.push(("ENDOOM", vec![0; 4]));
+        let maps = Map::from_wad(&Wad::parse(&pwad(&lumps)).unwrap()).unwrap();
+
+        assert_eq!(maps.len(), 1);
+        let map = &maps[0];
+        assert_eq!(map.name, "MAP01");
+        assert_eq!(
+            map.things[1],
+            Thing {
+                x: 0,
+                y: 0,
+                angle: 90,
+                kind: 3004,
+                flags: 4
+            }
+        );
+        assert_eq!(map.linedefs.len(), 4);
+        assert_eq!(map.linedefs[3].end, 0);
+        assert_eq!(map.linedefs[3].back, None);
+        assert_eq!(map.sidedefs[0].middle, "STARTAN3");
+        assert_eq!(map.vertexes[2], Vertex { x: 128, y: 128 });
+        assert_eq!(map.sectors[0].floor_flat, "FLOOR4_8");
+        assert_eq!(map.sectors[0].special, 9);
+    }
+
+    #[test]
+    fn broken_maps_are_rejected() {
+        let mut lumps = square_room(&[]);
+        lumps[1].1.push(0);
+        assert!(Map::from_wad(&Wad::parse(&pwad(&lumps)).unwrap()).is_err());
+
+        let mut lumps = square_room(&[]);
+        lumps.remove(5);
+        assert!(Map::from_wad(&Wad::parse(&pwad(&lumps)).unwrap()).is_err());
+
+        let map = Map::from_wad(&Wad::parse(&pwad(&square_room(&[]))).unwrap())
+            .unwrap()
+            .remove(0);
+        let broken = |change: fn(&mut Map)| {
+            let mut map = map.clone();
+            change(&mut map);
+            Map::from_wad(&Wad { lumps: map.lumps() })
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            broken(|map| map.linedefs[0].start = 40),
+            "lump MAP01: linedef 0 has a vertex that doesn't exist"
+        );
+        assert_eq!(
+            broken(|map| map.linedefs[2].back = Some(4)),
+            "lump MAP01: linedef 2 has a sidedef that doesn't exist"
+        );
+        assert_eq!(
+            broken(|map| map.sidedefs[3].sector = 1),
+            "lump MAP01: sidedef 3 has a sector that doesn't exist"
+        );
+    }
+
+    #[test]
+    fn maps_are_written_as_they_were_read() {
+        let lumps = square_room(&[(1, 7), (3004, 4)]);
+        let wad = Wad::parse(&pwad(&lumps)).unwrap();
+        let map = Map::from_wad(&wad).unwrap().remove(0);
+        assert_eq!(map.lumps(), wad.lumps);
+    }
+
+    #[test]
+    fn the_real_map_loads() {
+        let maps = Map::load(Path::new("../maps/map01.udmf")).unwrap();
+        assert_eq!(maps.len(), 1);
+        assert_eq!(maps[0].name, "MAP01");
+        assert!(maps[0]
+            .linedefs
+            .iter()
+            .all(|l| (l.start as usize) < maps[0].vertexes.len()));
+    }
+}
@@ -0,0 +1,711 @@
+// Apache 2.0 License
+
+use super::{blockmap::blockmap, Map, MAP_LUMPS};
+use crate::{
+    error::ResultExt,
+    wad::{Lump, Wad},
+};
+use flate2::{write::ZlibEncoder, Compression};
+use std::{collections::HashMap, f64::consts::PI, io::Write, str::FromStr};
+
+/// How far from a partition line a point can be and still count as on it.
+const EPSILON: f64 = 1.0 / 256.0;
+/// How many unbalanced segs a split is worth when picking a partition. Splits make more segs, which vanilla has a
+/// limit on, so they're avoided more than an unbalanced tree is.
+const SPLIT_COST: usize = 8;
+
+/// In vanilla nodes, references are 16 bits and the top bit of a child marks it as a subsector.
+const VANILLA_SUBSECTOR: u32 = 0x8000;
+const VANILLA_MAX_CHILD: usize = 0x7FFF;
+const VANILLA_MAX_INDEX: usize = 0xFFFF;
+/// Extended nodes use 32 bits, with the top bit for subsectors.
+const EXTENDED_SUBSECTOR: u32 = 0x8000_0000;
+
+/// How the node builder writes its output.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NodeFormat {
+    /// SEGS, SSECTORS and NODES lumps that any Doom engine can load. Vertexes made by splitting segs are rounded to
+    /// whole units.
+    Vanilla,
+    /// ZDoom's extended nodes, with everything in the NODES lump. They have no vanilla limits, and keep split
+    /// vertexes in fixed point.
+    Xnod,
+    /// Extended nodes compressed with zlib.
+    Znod,
+}
+
+impl FromStr for NodeFormat {
+    type Err = crate::Error;
+
+    #[inline]
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "vanilla" => Ok(NodeFormat::Vanilla),
+            "xnod" => Ok(NodeFormat::Xnod),
+            "znod" => Ok(NodeFormat::Znod),
+            _ => Err(crate::Error::StaticMsg(
+                "Node format must be vanilla, xnod or znod",
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A partition line, as a point on it and its direction. Partitions always lie along a linedef, so these are in
+/// whole map units.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Partition {
+    pub x: i64,
+    pub y: i64,
+    pub dx: i64,
+    pub dy: i64,
+}
+
+/// A piece of one side of a linedef.
+#[derive(Debug, Clone)]
+pub struct Seg {
+    pub start: usize,
+    pub end: usize,
+    pub linedef: usize,
+    /// 0 for the front of the linedef, 1 for the back.
+    pub side: u8,
+    /// How far along the linedef, from the start of this side, the seg starts.
+    pub offset: f64,
+    /// The line the seg lies on, pointing the same way.
+    pub line: Partition,
+}
+
+/// The smallest box around everything on one side of a node, in whole units.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Bbox {
+    pub top: i64,
+    pub bottom: i64,
+    pub left: i64,
+    pub right: i64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Child {
+    Node(usize),
+    Subsector(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub partition: Partition,
+    /// The boxes around the right and left children.
+    pub bbox: [Bbox; 2],
+    /// The right child, which is in front of the partition, and the left child, which is behind it.
+    pub children: [Child; 2],
+}
+
+/// A built BSP tree. Segs are in subsector order, and the root is the last node.
+#[derive(Debug, Clone, Default)]
+pub struct Nodes {
+    /// The map's vertexes, followed by the ones made by splitting segs.
+    pub vertexes: Vec<Point>,
+    /// How many of the vertexes came from the map.
+    pub original_vertexes: usize,
+    pub segs: Vec<Seg>,
+    /// The first seg and the number of segs in each subsector.
+    pub subsectors: Vec<(usize, usize)>,
+    pub nodes: Vec<Node>,
+}
+
+/// Where a seg is compared to a partition line.
+enum Place {
+    Right,
+    Left,
+    /// The seg crosses the line, and the distances of its ends from the line are these.
+    Split(f64, f64),
+}
+
+impl Nodes {
+    /// Build a BSP tree for a map. Each partition is picked to split as few segs as it can while keeping the tree
+    /// balanced, and a set of segs becomes a subsector once none of them has another behind it.
+    #[inline]
+    pub fn build(map: &Map) -> Self {
+        let mut nodes = Nodes {
+            vertexes: map
+                .vertexes
+                .iter()
+                .map(|v| Point {
+                    x: f64::from(v.x),
+                    y: f64::from(v.y),
+                })
+                .collect(),
+            original_vertexes: map.vertexes.len(),
+            ..Nodes::default()
+        };
+        let mut vertex_lookup = HashMap::new();
+        nodes.vertexes.iter().enumerate().rev().for_each(|(i, &p)| {
+            vertex_lookup.insert(fixed_key(p), i);
+        });
+
+        let segs = map
+            .linedefs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, line)| {
+                let start = &map.vertexes[line.start as usize];
+                let end = &map.vertexes[line.end as usize];
+                let forward = Partition {
+                    x: i64::from(start.x),
+                    y: i64::from(start.y),
+                    dx: i64::from(end.x) - i64::from(start.x),
+                    dy: i64::from(end.y) - i64::from(start.y),
+                };
+                let backward = Partition {
+                    x: i64::from(end.x),
+                    y: i64::from(end.y),
+                    dx: -forward.dx,
+                    dy: -forward.dy,
+                };
+
+                let front = line.front.map(|_| Seg {
+                    start: line.start as usize,
+                    end: line.end as usize,
+                    linedef: i,
+                    side: 0,
+                    offset: 0.0,
+                    line: forward,
+                });
+                let back = line.back.map(|_| Seg {
+                    start: line.end as usize,
+                    end: line.start as usize,
+                    linedef: i,
+                    side: 1,
+                    offset: 0.0,
+                    line: backward,
+                });
+                front.into_iter().chain(back)
+            })
+            // a linedef with both ends on the same vertex has nothing to draw
+            .filter(|seg| seg.line.dx != 0 || seg.line.dy != 0)
+            .collect();
+
+        nodes.subdivide(segs, &mut vertex_lookup);
+        nodes
+    }
+
+    /// Split segs until each set of them is convex, and return where the result went.
+    fn subdivide(&mut self, segs: Vec<Seg>, lookup: &mut HashMap<(i64, i64), usize>) -> Child {
+        let partition = match self.choose_partition(&segs) {
+            Some(partition) => partition,
+            None => {
+                self.subsectors.push((self.segs.len(), segs.len()));
+                self.segs.extend(segs);
+                return Child::Subsector(self.subsectors.len() - 1);
+            }
+        };
+
+        let mut right = vec![];
+        let mut left = vec![];
+        for seg in segs {
+            match self.place(&seg, partition) {
+                Place::Right => right.push(seg),
+                Place::Left => left.push(seg),
+                Place::Split(start_distance, end_distance) => {
+                    let (start, end) = (self.vertexes[seg.start], self.vertexes[seg.end]);
+                    let t = start_distance / (start_distance - end_distance);
+                    let point = Point {
+                        x: start.x + t * (end.x - start.x),
+                        y: start.y + t * (end.y - start.y),
+                    };
+                    let vertex = *lookup.entry(fixed_key(point)).or_insert_with(|| {
+                        self.vertexes.push(point);
+                        self.vertexes.len() - 1
+                    });
+
+                    let first = Seg {
+                        end: vertex,
+                        ..seg.clone()
+                    };
+                    let second = Seg {
+                        start: vertex,
+                        offset: seg.offset + distance(start, point),
+                        ..seg
+                    };
+                    if start_distance < 0.0 {
+                        right.push(first);
+                        left.push(second);
+                    } else {
+                        left.push(first);
+                        right.push(second);
+                    }
+                }
+            }
+        }
+
+        let bbox = [self.bbox(&right), self.bbox(&left)];
+        let children = [self.subdivide(right, lookup), self.subdivide(left, lookup)];
+        self.nodes.push(Node {
+            partition,
+            bbox,
+            children,
+        });
+        Child::Node(self.nodes.len() - 1)
+    }
+
+    /// The best line to split segs along, or nothing if they're already convex.
+    fn choose_partition(&self, segs: &[Seg]) -> Option<Partition> {
+        let mut best: Option<(usize, Partition)> = None;
+        let mut tried = HashMap::new();
+
+        for candidate in segs {
+            if tried.insert(candidate.line, ()).is_some() {
+                continue;
+            }
+
+            let (mut right, mut left, mut splits) = (0, 0, 0);
+            for seg in segs {
+                match self.place(seg, candidate.line) {
+                    Place::Right => right += 1,
+                    Place::Left => left += 1,
+                    Place::Split(..) => {
+                        splits += 1;
+                        right += 1;
+                        left += 1;
+                    }
+                }
+            }
+
+            // the candidate itself is always on the right, so this only skips lines with nothing behind them
+            if left == 0 {
+                continue;
+            }
+            let cost = splits * SPLIT_COST + (right as isize - left as isize).unsigned_abs();
+            if best.is_none_or(|(best_cost, _)| cost < best_cost) {
+                best = Some((cost, candidate.line));
+            }
+        }
+
+        best.map(|(_, partition)| partition)
+    }
+
+    fn place(&self, seg: &Seg, partition: Partition) -> Place {
+        let start = side(self.vertexes[seg.start], partition);
+        let end = side(self.vertexes[seg.end], partition);
+
+        if start == 0.0 && end == 0.0 {
+            // segs on the partition go with the side they face
+            let same_way = seg.line.dx * partition.dx + seg.line.dy * partition.dy > 0;
+            if same_way {
+                Place::Right
+            } else {
+                Place::Left
+            }
+        } else if start <= 0.0 && end <= 0.0 {
+            Place::Right
+        } else if start >= 0.0 && end >= 0.0 {
+            Place::Left
+        } else {
+            Place::Split(start, end)
+        }
+    }
+
+    fn bbox(&self, segs: &[Seg]) -> Bbox {
+        let mut points = segs
+            .iter()
+            .flat_map(|seg| [self.vertexes[seg.start], self.vertexes[seg.end]]);
+        let first = match points.next() {
+            Some(first) => first,
+            None => {
+                return Bbox {
+                    top: 0,
+                    bottom: 0,
+                    left: 0,
+                    right: 0,
+                }
+            }
+        };
+
+        let (mut min, mut max) = (first, first);
+        points.for_each(|p| {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+        });
+        Bbox {
+            top: max.y.ceil() as i64,
+            bottom: min.y.floor() as i64,
+            left: min.x.floor() as i64,
+            right: max.x.ceil() as i64,
+        }
+    }
+
+    /// The VERTEXES, SEGS, SSECTORS and NODES lumps for vanilla Doom.
+    #[inline]
+    pub fn vanilla_lumps(&self) -> crate::Result<[Vec<u8>; 4]> {
+        if self.vertexes.len() > VANILLA_MAX_INDEX + 1
+            || self.segs.len() > VANILLA_MAX_INDEX + 1
+            || self.subsectors.len() > VANILLA_MAX_CHILD + 1
+            || self.nodes.len() > VANILLA_MAX_CHILD + 1
+        {
+            return Err(crate::Error::StaticMsg(
+                "Map is too big for vanilla nodes; use xnod or znod",
+            ));
+        }
+
+        let mut vertexes = vec![];
+        self.vertexes.iter().for_each(|v| {
+            put_i16(&mut vertexes, v.x.round() as i64);
+            put_i16(&mut vertexes, v.y.round() as i64);
+        });
+
+        let mut segs = vec![];
+        self.segs.iter().for_each(|seg| {
+            put_u16(&mut segs, seg.start as u32);
+            put_u16(&mut segs, seg.end as u32);
+            put_u16(&mut segs, angle(seg.line));
+            put_u16(&mut segs, seg.linedef as u32);
+            put_u16(&mut segs, u32::from(seg.side));
+            put_i16(&mut segs, seg.offset.round() as i64);
+        });
+
+        let mut subsectors = vec![];
+        self.subsectors.iter().for_each(|&(first, count)| {
+            put_u16(&mut subsectors, count as u32);
+            put_u16(&mut subsectors, first as u32);
+        });
+
+        let mut nodes = vec![];
+        self.nodes.iter().for_each(|node| {
+            put_node(&mut nodes, node);
+            node.children.iter().for_each(|child| {
+                let child = match *child {
+                    Child::Node(i) => i as u32,
+                    Child::Subsector(i) => i as u32 | VANILLA_SUBSECTOR,
+                };
+                put_u16(&mut nodes, child);
+            });
+        });
+
+        Ok([vertexes, segs, subsectors, nodes])
+    }
+
+    /// The NODES lump for extended nodes, without the XNOD or ZNOD at the start. The map's VERTEXES lump is left as
+    /// it is, and SEGS and SSECTORS are left empty.
+    #[inline]
+    pub fn extended_lump(&self) -> Vec<u8> {
+        let mut out = vec![];
+        put_u32(&mut out, self.original_vertexes as u32);
+        put_u32(
+            &mut out,
+            (self.vertexes.len() - self.original_vertexes) as u32,
+        );
+        self.vertexes[self.original_vertexes..]
+            .iter()
+            .for_each(|v| {
+                put_u32(&mut out, (v.x * 65536.0).round() as i32 as u32);
+                put_u32(&mut out, (v.y * 65536.0).round() as i32 as u32);
+            });
+
+        put_u32(&mut out, self.subsectors.len() as u32);
+        self.subsectors
+            .iter()
+            .for_each(|&(_, count)| put_u32(&mut out, count as u32));
+
+        put_u32(&mut out, self.segs.len() as u32);
+        self.segs.iter().for_each(|seg| {
+            put_u32(&mut out, seg.start as u32);
+            put_u32(&mut out, seg.end as u32);
+            put_u16(&mut out, seg.linedef as u32);
+            out.push(seg.side);
+        });
+
+        put_u32(&mut out, self.nodes.len() as u32);
+        self.nodes.iter().for_each(|node| {
+            put_node(&mut out, node);
+            node.children.iter().for_each(|child| {
+                let child = match *child {
+                    Child::Node(i) => i as u32,
+                    Child::Subsector(i) => i as u32 | EXTENDED_SUBSECTOR,
+                };
+                put_u32(&mut out, child);
+            });
+        });
+
+        out
+    }
+}
+
+/// Rebuild the nodes, reject table and blockmap for every map in a WAD. Other lumps are copied as they are.
+#[inline]
+pub fn build_nodes(wad: Wad, format: NodeFormat) -> crate::Result<Wad> {
+    let mut maps = Map::from_wad(&wad)?.into_iter();
+    let mut lumps = vec![];
+    let mut old = wad.lumps.into_iter().peekable();
+
+    while let Some(lump) = old.next() {
+        let is_marker = old.peek().is_some_and(|l| l.name == "THINGS");
+        lumps.push(lump);
+        if !is_marker {
+            continue;
+        }
+
+        let mut map_lumps = HashMap::new();
+        while let Some(lump) = old.next_if(|l| MAP_LUMPS.contains(&l.name.as_str())) {
+            map_lumps.insert(lump.name, lump.data);
+        }
+        // maps are found the same way here as in Map::from_wad, so there's one for every marker
+        let map = maps.next().unwrap();
+        let mut take = |name: &str| map_lumps.remove(name).unwrap_or_default();
+
+        let nodes = Nodes::build(&map);
+        let [vertexes, segs, subsectors, nodes] = match format {
+            NodeFormat::Vanilla => nodes.vanilla_lumps().with_lump(&map.name)?,
+            NodeFormat::Xnod => {
+                let mut lump = b"XNOD".to_vec();
+                lump.extend(nodes.extended_lump());
+                [take("VERTEXES"), vec![], vec![], lump]
+            }
+            NodeFormat::Znod => {
+                let mut encoder = ZlibEncoder::new(b"ZNOD".to_vec(), Compression::best());
+                encoder.write_all(&nodes.extended_lump())?;
+                [take("VERTEXES"), vec![], vec![], encoder.finish()?]
+            }
+        };
+
+        let reject = vec![0; (map.sectors.len() * map.sectors.len()).div_ceil(8)];
+        let built = [
+            ("THINGS", take("THINGS")),
+            ("LINEDEFS", take("LINEDEFS")),
+            ("SIDEDEFS", take("SIDEDEFS")),
+            ("VERTEXES", vertexes),
+            ("SEGS", segs),
+            ("SSECTORS", subsectors),
+            ("NODES", nodes),
+            ("SECTORS", take("SECTORS")),
+            ("REJECT", reject),
+            ("BLOCKMAP", blockmap(&map).with_lump(&map.name)?),
+        ];
+        lumps.extend(built.iter().cloned().map(|(name, data)| Lump {
+            name: name.to_string(),
+            data,
+        }));
+    }
+
+    Ok(Wad { lumps })
+}
+
+/// How far a point is from a line, negative on the right (in front) and positive on the left (behind). Points very
+/// close to the line are on it.
+#[inline]
+pub fn side(p: Point, line: Partition) -> f64 {
+    let (dx, dy) = (line.dx as f64, line.dy as f64);
+    let d = ((p.y - line.y as f64) * dx - (p.x - line.x as f64) * dy) / dx.hypot(dy);
+    if d.abs() < EPSILON {
+        0.0
+    } else {
+        d
+    }
+}
+
+#[inline]
+fn distance(a: Point, b: Point) -> f64 {
+    (b.x - a.x).hypot(b.y - a.y)
+}
+
+/// Vertexes closer together than a fixed point unit are the same vertex.
+#[inline]
+fn fixed_key(p: Point) -> (i64, i64) {
+    (
+        (p.x * 65536.0).round() as i64,
+        (p.y * 65536.0).round() as i64,
+    )
+}
+
+/// The direction of a line as a binary angle, where a full turn is 65536.
+#[inline]
+fn angle(line: Partition) -> u32 {
+    let turns = (line.dy as f64).atan2(line.dx as f64) / (2.0 * PI);
+    ((turns * 65536.0).round() as i64).rem_euclid(65536) as u32
+}
+
+/// The partition line and bounding boxes of a node, which are the same in every format.
+#[inline]
+fn put_node(out: &mut Vec<u8>, node: &Node) {
+    let p = node.partition;
+    [p.x, p.y, p.dx, p.dy].iter().for_each(|&v| put_i16(out, v));
+    node.bbox.iter().for_each(|b| {
+        [b.top, b.bottom, b.left, b.right]
+            .iter()
+            .for_each(|&v| put_i16(out, v))
+    });
+}
+
+#[inline]
+fn put_i16(out: &mut Vec<u8>, value: i64) {
+    out.extend_from_slice(&(value as i16).to_le_bytes());
+}
+
+#[inline]
+fn put_u16(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&(value as u16).to_le_bytes());
+}
+
+#[inline]
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        map::tests::square_room,
+        wad::{tests::pwad, Wad},
+    };
+    use flate2::read::ZlibDecoder;
+    use std::{io::Read, path::Path};
+
+    fn room() -> Map {
+        Map::from_wad(&Wad::parse(&pwad(&square_room(&[]))).unwrap())
+            .unwrap()
+            .remove(0)
+    }
+
+    /// Check that every seg is on the right side of every partition above it.
+    fn check_tree(nodes: &Nodes, child: Child, partitions: &mut Vec<(Partition, bool)>) {
+        match child {
+            Child::Node(i) => {
+                let node = &nodes.nodes[i];
+                partitions.push((node.partition, false));
+                check_tree(nodes, node.children[0], partitions);
+                partitions.pop();
+                partitions.push((node.partition, true));
+                check_tree(nodes, node.children[1], partitions);
+                partitions.pop();
+            }
+            Child::Subsector(i) => {
+                let (first, count) = nodes.subsectors[i];
+                assert!(count > 0);
+                for seg in &nodes.segs[first..first + count] {
+                    for &(partition, left) in partitions.iter() {
+                        for &v in &[seg.start, seg.end] {
+                            let d = side(nodes.vertexes[v], partition);
+                            assert!(
+                                if left { d >= 0.0 } else { d <= 0.0 },
+                                "seg of linedef {} is on the wrong side",
+                                seg.linedef
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn convex_rooms_are_one_subsector() {
+        let nodes = Nodes::build(&room());
+        assert!(nodes.nodes.is_empty());
+        assert_eq!(nodes.subsectors, vec![(0, 4)]);
+        assert_eq!(nodes.vertexes.len(), 4);
+    }
+
+    #[test]
+    fn the_real_map_is_split_correctly() {
+        let map = Map::load(Path::new("../maps/map01.udmf"))
+            .unwrap()
+            .remove(0);
+        let nodes = Nodes::build(&map);
+        assert!(!nodes.nodes.is_empty());
+        check_tree(&nodes, Child::Node(nodes.nodes.len() - 1), &mut vec![]);
+
+        // the segs of each side of each linedef add up to the whole linedef
+        let mut lengths: HashMap<(usize, u8), f64> = HashMap::new();
+        nodes.segs.iter().for_each(|seg| {
+            *lengths.entry((seg.linedef, seg.side)).or_default() +=
+                distance(nodes.vertexes[seg.start], nodes.vertexes[seg.end]);
+        });
+        map.linedefs.iter().enumerate().for_each(|(i, line)| {
+            let (a, b) = (
+                &map.vertexes[line.start as usize],
+                &map.vertexes[line.end as usize],
+            );
+            let length = f64::from(b.x - a.x).hypot(f64::from(b.y - a.y));
+            assert!((lengths[&(i, 0)] - length).abs() < 0.01);
+            if line.back.is_some() {
+                assert!((lengths[&(i, 1)] - length).abs() < 0.01);
+            }
+        });
+    }
+
+    #[test]
+    fn vanilla_lumps_have_the_right_sizes() {
+        let map = Map::load(Path::new("../maps/map01.udmf"))
+            .unwrap()
+            .remove(0);
+        let nodes = Nodes::build(&map);
+        let [vertexes, segs, subsectors, node_lump] = nodes.vanilla_lumps().unwrap();
+        assert_eq!(vertexes.len(), nodes.vertexes.len() * 4);
+        assert_eq!(segs.len(), nodes.segs.len() * 12);
+        assert_eq!(subsectors.len(), nodes.subsectors.len() * 4);
+        assert_eq!(node_lump.len(), nodes.nodes.len() * 28);
+        assert_eq!(&vertexes[..map.vertexes.len() * 4], &map_vertexes(&map)[..]);
+    }
+
+    fn map_vertexes(map: &Map) -> Vec<u8> {
+        let mut out = vec![];
+        map.vertexes.iter().for_each(|v| {
+            put_i16(&mut out, i64::from(v.x));
+            put_i16(&mut out, i64::from(v.y));
+        });
+        out
+    }
+
+    #[test]
+    fn extended_nodes_compress_the_same_data() {
+        let map = Map::load(Path::new("../maps/map01.udmf"))
+            .unwrap()
+            .remove(0);
+        let nodes = Nodes::build(&map);
+        let lump = nodes.extended_lump();
+        assert_eq!(
+            u32::from_le_bytes([lump[0], lump[1], lump[2], lump[3]]) as usize,
+            map.vertexes.len()
+        );
+
+        let mut encoder = ZlibEncoder::new(vec![], Compression::best());
+        encoder.write_all(&lump).unwrap();
+        let mut decoded = vec![];
+        ZlibDecoder::new(&encoder.finish().unwrap()[..])
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, lump);
+    }
+
+    #[test]
+    fn wads_are_rebuilt_in_place() {
+        let wad = Wad::parse(&pwad(&square_room(&[]))).unwrap();
+        let built = build_nodes(wad.clone(), NodeFormat::Vanilla).unwrap();
+        let names: Vec<&str> = built.lumps.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names[0], "MAP01");
+        assert_eq!(&names[1..], &MAP_LUMPS[..10]);
+        assert_eq!(built.lumps[1].data, wad.lumps[1].data);
+        assert!(!built.lumps[5].data.is_empty());
+    }
+
+    #[test]
+    fn angles_are_binary() {
+        let line = |dx, dy| Partition { x: 0, y: 0, dx, dy };
+        assert_eq!(angle(line(1, 0)), 0);
+        assert_eq!(angle(line(0, 1)), 0x4000);
+        assert_eq!(angle(line(-1, 0)), 0x8000);
+        assert_eq!(angle(line(0, -1)), 0xC000);
+    }
+
+    #[test]
+    fn formats_are_parsed() {
+        assert_eq!(NodeFormat::from_str("ZNOD").unwrap(), NodeFormat::Znod);
+        assert!(NodeFormat::from_str("gl").is_err());
+    }
+}
@@ -0,0 +1,513 @@
+// Apache 2.0 License
+
+use super::{
+    stats::{pickup, Pickup, MF_COUNTKILL, SECRET},
+    Map,
+};
+use crate::{
+    dehacked::{Balance, Patch},
+    picture::Picture,
+};
+use std::{collections::BTreeMap, ffi::OsStr, fmt::Write as _, fs, path::Path};
+
+/// The linedef flag that makes a line show as one sided on the automap.
+const ML_SECRET: i16 = 0x0020;
+
+/// Thing types for player starts, including deathmatch starts.
+static PLAYER_STARTS: &[i16] = &[1, 2, 3, 4, 11];
+
+/// How big things are drawn, in map units. This is the radius of the player.
+const THING_RADIUS: f64 = 16.0;
+/// Space left around the map, in map units.
+const MARGIN: f64 = 32.0;
+
+static BACKGROUND: [u8; 3] = [0xFF, 0xFF, 0xFF];
+static TAG_COLOR: [u8; 3] = [0x10, 0x50, 0xD0];
+
+/// What kind of line a linedef is drawn as, from least to most important. More important lines are drawn on top.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LineKind {
+    TwoSided,
+    OneSided,
+    /// A line with an action, like a door or a switch.
+    Special,
+    /// A line that hides itself on the automap, or that borders a secret sector.
+    Secret,
+}
+
+impl LineKind {
+    #[inline]
+    fn color(self) -> [u8; 3] {
+        match self {
+            LineKind::TwoSided => [0xA0, 0xA0, 0xA0],
+            LineKind::OneSided => [0x20, 0x20, 0x20],
+            LineKind::Special => [0xE0, 0x70, 0x00],
+            LineKind::Secret => [0xA0, 0x20, 0xC0],
+        }
+    }
+}
+
+/// What kind of thing a thing is drawn as.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ThingKind {
+    Player,
+    Monster,
+    Weapon,
+    Ammo,
+    /// Health and armor.
+    Health,
+    Key,
+    Other,
+}
+
+impl ThingKind {
+    #[inline]
+    fn color(self) -> [u8; 3] {
+        match self {
+            ThingKind::Player => [0x20, 0xA0, 0x20],
+            ThingKind::Monster => [0xD0, 0x10, 0x10],
+            ThingKind::Weapon => [0x80, 0x50, 0x20],
+            ThingKind::Ammo => [0xC0, 0xA0, 0x00],
+            ThingKind::Health => [0x10, 0x90, 0xD0],
+            ThingKind::Key => [0xF0, 0x40, 0xA0],
+            ThingKind::Other => [0x70, 0x70, 0x70],
+        }
+    }
+
+    #[inline]
+    fn of(kind: i16, balance: &Balance) -> Self {
+        if PLAYER_STARTS.contains(&kind) {
+            return ThingKind::Player;
+        }
+        let thing = match balance.thing(kind) {
+            Some(thing) => thing,
+            None => return ThingKind::Other,
+        };
+        if thing.flags & MF_COUNTKILL != 0 {
+            return ThingKind::Monster;
+        }
+        match pickup(thing.name) {
+            Some(Pickup::Weapon(_)) => ThingKind::Weapon,
+            Some(Pickup::Ammo(..)) | Some(Pickup::Backpack) => ThingKind::Ammo,
+            Some(Pickup::Health(_))
+            | Some(Pickup::Soulsphere)
+            | Some(Pickup::Megasphere)
+            | Some(Pickup::Armor(_))
+            | Some(Pickup::GreenArmor)
+            | Some(Pickup::BlueArmor) => ThingKind::Health,
+            Some(Pickup::Key(_)) => ThingKind::Key,
+            None => ThingKind::Other,
+        }
+    }
+}
+
+/// A line from one point to another.
+type Line = ((f64, f64), (f64, f64), LineKind);
+
+/// Everything to draw for a map, in map units, with y going up like it does in the map.
+#[derive(Debug, Clone, Default)]
+pub struct Drawing {
+    pub min: (f64, f64),
+    pub max: (f64, f64),
+    /// Lines, from least to most important.
+    pub lines: Vec<Line>,
+    pub things: Vec<((f64, f64), ThingKind)>,
+    /// Tag numbers, in the middle of tagged sectors and tagged lines.
+    pub tags: Vec<((f64, f64), i16)>,
+}
+
+impl Drawing {
+    /// Lay out a map's lines and things. The map's references have to be valid, which they are for every loaded map.
+    #[inline]
+    pub fn new(map: &Map, balance: &Balance) -> Self {
+        let vertex = |v: u16| {
+            let v = &map.vertexes[v as usize];
+            (f64::from(v.x), f64::from(v.y))
+        };
+        let sector_of = |side: Option<u16>| {
+            side.and_then(|s| map.sidedefs.get(s as usize))
+                .map(|s| s.sector as usize)
+        };
+
+        let mut lines: Vec<_> = map
+            .linedefs
+            .iter()
+            .map(|line| {
+                let secret = line.flags & ML_SECRET != 0
+                    || [line.front, line.back].iter().any(|&side| {
+                        sector_of(side)
+                            .and_then(|s| map.sectors.get(s))
+                            .is_some_and(|s| s.special == SECRET)
+                    });
+                let kind = if secret {
+                    LineKind::Secret
+                } else if line.special != 0 {
+                    LineKind::Special
+                } else if line.back.is_some() {
+                    LineKind::TwoSided
+                } else {
+                    LineKind::OneSided
+                };
+                (vertex(line.start), vertex(line.end), kind)
+            })
+            .collect();
+        lines.sort_by_key(|&(_, _, kind)| kind);
+
+        // tags go in the middle of a sector's lines, which is close enough to its middle for a label
+        let mut middles: BTreeMap<usize, (f64, f64, f64)> = BTreeMap::new();
+        map.linedefs.iter().for_each(|line| {
+            let (a, b) = (vertex(line.start), vertex(line.end));
+            [line.front, line.back]
+                .iter()
+                .filter_map(|&side| sector_of(side))
+                .for_each(|sector| {
+                    let middle = middles.entry(sector).or_default();
+                    middle.0 += (a.0 + b.0) / 2.0;
+                    middle.1 += (a.1 + b.1) / 2.0;
+                    middle.2 += 1.0;
+                });
+        });
+        let mut tags: Vec<_> = middles
+            .into_iter()
+            .filter_map(|(sector, (x, y, count))| {
+                let tag = map.sectors.get(sector)?.tag;
+                Some(((x / count, y / count), tag)).filter(|_| tag != 0)
+            })
+            .collect();
+        tags.extend(map.linedefs.iter().filter(|l| l.tag != 0).map(|line| {
+            let (a, b) = (vertex(line.start), vertex(line.end));
+            (((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0), line.tag)
+        }));
+
+        let things = map
+            .things
+            .iter()
+            .map(|t| {
+                (
+                    (f64::from(t.x), f64::from(t.y)),
+                    ThingKind::of(t.kind, balance),
+                )
+            })
+            .collect();
+
+        let xs = map.vertexes.iter().map(|v| f64::from(v.x));
+        let ys = map.vertexes.iter().map(|v| f64::from(v.y));
+        let low =
+            |values: &mut dyn Iterator<Item = f64>| values.reduce(f64::min).unwrap_or(0.0) - MARGIN;
+        let high =
+            |values: &mut dyn Iterator<Item = f64>| values.reduce(f64::max).unwrap_or(0.0) + MARGIN;
+        Self {
+            min: (low(&mut xs.clone()), low(&mut ys.clone())),
+            max: (high(&mut xs.clone()), high(&mut ys.clone())),
+            lines,
+            things,
+            tags,
+        }
+    }
+
+    /// Draw as an SVG, flipping y so the map isn't upside down.
+    #[inline]
+    pub fn svg(&self) -> String {
+        let (width, height) = (self.max.0 - self.min.0, self.max.1 - self.min.1);
+        let point = |(x, y): (f64, f64)| (x - self.min.0, self.max.1 - y);
+        let color = |[r, g, b]: [u8; 3]| format!("#{:02x}{:02x}{:02x}", r, g, b);
+        let stroke = (width.max(height) / 512.0).max(1.0);
+
+        let mut svg = String::new();
+        // writing to a string can't fail
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\">",
+            width, height
+        );
+        let _ = writeln!(
+            svg,
+            "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            width,
+            height,
+            color(BACKGROUND)
+        );
+        self.lines.iter().for_each(|&(a, b, kind)| {
+            let (a, b) = (point(a), point(b));
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                a.0,
+                a.1,
+                b.0,
+                b.1,
+                color(kind.color()),
+                stroke
+            );
+        });
+        self.things.iter().for_each(|&(p, kind)| {
+            let p = point(p);
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+                p.0,
+                p.1,
+                THING_RADIUS,
+                color(kind.color())
+            );
+        });
+        self.tags.iter().for_each(|&(p, tag)| {
+            let p = point(p);
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" text-anchor=\"middle\" fill=\"{}\">{}</text>",
+                p.0,
+                p.1,
+                THING_RADIUS * 2.0,
+                color(TAG_COLOR),
+                tag
+            );
+        });
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Draw as a picture whose longer side is this many pixels.
+    #[inline]
+    pub fn picture(&self, size: usize) -> Picture {
+        let (width, height) = (self.max.0 - self.min.0, self.max.1 - self.min.1);
+        let scale = size as f64 / width.max(height);
+        let mut picture = Picture::new(
+            ((width * scale).ceil() as usize).max(1),
+            ((height * scale).ceil() as usize).max(1),
+        );
+        picture
+            .pixels
+            .iter_mut()
+            .for_each(|p| *p = opaque(BACKGROUND));
+
+        let point = |(x, y): (f64, f64)| {
+            (
+                ((x - self.min.0) * scale) as i64,
+                ((self.max.1 - y) * scale) as i64,
+            )
+        };
+
+        self.lines
+            .iter()
+            .for_each(|&(a, b, kind)| draw_line(&mut picture, point(a), point(b), kind.color()));
+        let radius = ((THING_RADIUS * scale) as i64).max(2);
+        self.things.iter().for_each(|&(p, kind)| {
+            let (x, y) = point(p);
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    if dx * dx + dy * dy <= radius * radius {
+                        plot(&mut picture, x + dx, y + dy, kind.color());
+                    }
+                }
+            }
+        });
+        self.tags.iter().for_each(|&(p, tag)| {
+            let text = tag.to_string();
+            let (x, y) = point(p);
+            let x = x - (text.len() as i64 * 4 * 2) / 2;
+            text.bytes().enumerate().for_each(|(i, digit)| {
+                draw_digit(&mut picture, x + i as i64 * 8, y - 5, digit, TAG_COLOR)
+            });
+        });
+
+        picture
+    }
+}
+
+/// Draw one map from a WAD to an SVG or PNG, depending on the output's extension. The map can be left out if the WAD
+/// only has one.
+#[inline]
+pub fn render_map(
+    wad: &Path,
+    map: Option<&str>,
+    output: &Path,
+    size: usize,
+    dehacked: Option<&Path>,
+) -> crate::Result {
+    let maps = Map::load(wad)?;
+    let map = match map {
+        Some(name) => maps.iter().find(|m| m.name.eq_ignore_ascii_case(name)),
+        None if maps.len() == 1 => maps.first(),
+        None => {
+            return Err(crate::Error::StaticMsg(
+                "WAD has more than one map; pick one with --map",
+            ))
+        }
+    }
+    .ok_or(crate::Error::StaticMsg("Map is not in the WAD"))?;
+
+    let balance = match dehacked {
+        Some(path) => Balance::patched(&Patch::load_or_compile(path)?),
+        None => Balance::vanilla(),
+    };
+    let drawing = Drawing::new(map, &balance);
+
+    match output.extension().and_then(OsStr::to_str) {
+        Some(ext) if ext.eq_ignore_ascii_case("svg") => fs::write(output, drawing.svg())?,
+        Some(ext) if ext.eq_ignore_ascii_case("png") => drawing.picture(size).save(output)?,
+        _ => return Err(crate::Error::StaticMsg("Output must end in .svg or .png")),
+    }
+    Ok(())
+}
+
+#[inline]
+fn opaque([r, g, b]: [u8; 3]) -> [u8; 4] {
+    [r, g, b, 0xFF]
+}
+
+#[inline]
+fn plot(picture: &mut Picture, x: i64, y: i64, color: [u8; 3]) {
+    if x >= 0 && y >= 0 && (x as usize) < picture.width && (y as usize) < picture.height {
+        picture.set(x as usize, y as usize, opaque(color));
+    }
+}
+
+/// Bresenham's line algorithm.
+#[inline]
+fn draw_line(
+    picture: &mut Picture,
+    (mut x, mut y): (i64, i64),
+    (x1, y1): (i64, i64),
+    color: [u8; 3],
+) {
+    let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+    let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+    let mut error = dx + dy;
+    loop {
+        plot(picture, x, y, color);
+        if x == x1 && y == y1 {
+            break;
+        }
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Digits 0 to 9 in a 3x5 font, a row per three bits.
+static DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Draw a digit, or a minus sign, at twice the size of the font.
+#[inline]
+fn draw_digit(picture: &mut Picture, x: i64, y: i64, digit: u8, color: [u8; 3]) {
+    let rows = match digit {
+        b'0'..=b'9' => DIGITS[(digit - b'0') as usize],
+        _ => [0, 0, 0b111, 0, 0],
+    };
+    for (row, bits) in rows.iter().enumerate() {
+        for column in 0..3 {
+            if bits & (0b100 >> column) != 0 {
+                for (dx, dy) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    plot(picture, x + column * 2 + dx, y + row as i64 * 2 + dy, color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        map::tests::square_room,
+        wad::{tests::pwad, Wad},
+    };
+
+    fn room(things: &[(i16, i16)]) -> Map {
+        Map::from_wad(&Wad::parse(&pwad(&square_room(things))).unwrap())
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn lines_and_things_are_sorted_into_kinds() {
+        let mut map = room(&[(1, 7), (3004, 7), (2006, 7), (2012, 7), (5, 7), (2035, 7)]);
+        // the test room is a secret, which would hide every other kind of line
+        map.sectors[0].special = 0;
+        map.sectors[0].tag = 3;
+        map.linedefs[1].special = 1;
+        map.linedefs[2].flags |= ML_SECRET;
+
+        let drawing = Drawing::new(&map, &Balance::vanilla());
+        let kinds: Vec<LineKind> = drawing.lines.iter().map(|l| l.2).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LineKind::OneSided,
+                LineKind::OneSided,
+                LineKind::Special,
+                LineKind::Secret
+            ]
+        );
+        let kinds: Vec<ThingKind> = drawing.things.iter().map(|t| t.1).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ThingKind::Player,
+                ThingKind::Monster,
+                ThingKind::Weapon,
+                ThingKind::Health,
+                ThingKind::Key,
+                ThingKind::Other
+            ]
+        );
+        assert_eq!(drawing.tags, vec![((64.0, 64.0), 3)]);
+        assert_eq!(drawing.min, (-MARGIN, -MARGIN));
+        assert_eq!(drawing.max, (128.0 + MARGIN, 128.0 + MARGIN));
+    }
+
+    #[test]
+    fn drawings_are_rendered() {
+        let drawing = Drawing::new(&room(&[(1, 7)]), &Balance::vanilla());
+
+        let svg = drawing.svg();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<line").count(), 4);
+        assert_eq!(svg.matches("<circle").count(), 1);
+
+        let picture = drawing.picture(192);
+        assert_eq!((picture.width, picture.height), (192, 192));
+        // the room's corner, 32 units in from the top left, is a wall
+        assert_eq!(picture.get(32, 32), opaque(LineKind::Secret.color()));
+        // and the player start is in the middle of the room, at the origin
+        assert_eq!(picture.get(34, 158), opaque(ThingKind::Player.color()));
+        assert_eq!(picture.get(96, 96), opaque(BACKGROUND));
+    }
+
+    #[test]
+    fn broken_maps_are_errors() {
+        // the first linedef starts at vertex 40, and there are only 4
+        let mut lumps = square_room(&[(1, 7)]);
+        lumps[2].1[..2].copy_from_slice(&40i16.to_le_bytes());
+        let dir = tempfile::tempdir().unwrap();
+        let wad = dir.path().join("broken.wad");
+        fs::write(&wad, pwad(&lumps)).unwrap();
+
+        let err = render_map(&wad, None, &dir.path().join("broken.svg"), 256, None).unwrap_err();
+        assert!(
+            err.to_string()
+                .ends_with("MAP01: linedef 0 has a vertex that doesn't exist"),
+            "{}",
+            err
+        );
+        assert!(!dir.path().join("broken.svg").exists());
+    }
+}
@@ -0,0 +1,314 @@
+// Apache 2.0 License
+
+use super::Map;
+use crate::dehacked::{Balance, Patch};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The flag for things that count towards the kill percentage, which is what makes a thing a monster.
+pub(super) const MF_COUNTKILL: u32 = 0x0040_0000;
+
+/// The sector special for secrets.
+pub(super) const SECRET: i16 = 9;
+
+/// A line of the table mapinfo prints, with how to get its value out of each skill's stats.
+type Row = (&'static str, fn(&Stats) -> i64);
+
+/// The skill levels, as the menu abbreviates them.
+static SKILLS: &[&str] = &["ITYTD", "HNTR", "HMP", "UV", "NM"];
+
+/// What picking a thing up gives the player. Pickups depend on the thing's place in the table, so a patch that
+/// renumbers a thing in maps keeps what it gives.
+#[derive(Debug, Copy, Clone)]
+pub(super) enum Pickup {
+    /// A number of clips of an ammo type. Zero clips means half of one, which is what dropped clips give.
+    Ammo(usize, i64),
+    /// A weapon, which comes with two clips of its ammo, or one if a monster dropped it.
+    Weapon(&'static str),
+    /// One clip of each ammo type.
+    Backpack,
+    Health(i64),
+    Soulsphere,
+    /// Health and blue armor.
+    Megasphere,
+    /// Armor points, on top of whatever armor the player has.
+    Armor(i64),
+    /// Armor of one of the classes the patch can change, which is 100 points per class.
+    GreenArmor,
+    BlueArmor,
+    Key(&'static str),
+}
+
+static PICKUPS: &[(&str, Pickup)] = &[
+    ("MT_CLIP", Pickup::Ammo(0, 1)),
+    ("MT_MISC17", Pickup::Ammo(0, 5)),
+    ("MT_MISC22", Pickup::Ammo(1, 1)),
+    ("MT_MISC23", Pickup::Ammo(1, 5)),
+    ("MT_MISC20", Pickup::Ammo(2, 1)),
+    ("MT_MISC21", Pickup::Ammo(2, 5)),
+    ("MT_MISC18", Pickup::Ammo(3, 1)),
+    ("MT_MISC19", Pickup::Ammo(3, 5)),
+    ("MT_MISC24", Pickup::Backpack),
+    ("MT_SHOTGUN", Pickup::Weapon("shotgun")),
+    ("MT_SUPERSHOTGUN", Pickup::Weapon("supershotgun")),
+    ("MT_CHAINGUN", Pickup::Weapon("chaingun")),
+    ("MT_MISC26", Pickup::Weapon("chainsaw")),
+    ("MT_MISC27", Pickup::Weapon("missile")),
+    ("MT_MISC28", Pickup::Weapon("plasma")),
+    ("MT_MISC25", Pickup::Weapon("bfg")),
+    ("MT_MISC2", Pickup::Health(1)),
+    ("MT_MISC10", Pickup::Health(10)),
+    ("MT_MISC11", Pickup::Health(25)),
+    // the berserk pack only tops health up to 100, but limits are ignored here like everywhere else
+    ("MT_MISC13", Pickup::Health(100)),
+    ("MT_MISC12", Pickup::Soulsphere),
+    ("MT_MEGA", Pickup::Megasphere),
+    ("MT_MISC3", Pickup::Armor(1)),
+    ("MT_MISC0", Pickup::GreenArmor),
+    ("MT_MISC1", Pickup::BlueArmor),
+    ("MT_MISC4", Pickup::Key("blue card")),
+    ("MT_MISC5", Pickup::Key("red card")),
+    ("MT_MISC6", Pickup::Key("yellow card")),
+    ("MT_MISC7", Pickup::Key("yellow skull")),
+    ("MT_MISC8", Pickup::Key("red skull")),
+    ("MT_MISC9", Pickup::Key("blue skull")),
+];
+
+/// What monsters drop when they die. The drop is hardcoded to the monster's place in the table.
+static DROPS: &[(&str, &str)] = &[
+    ("MT_WOLFSS", "MT_CLIP"),
+    ("MT_POSSESSED", "MT_CLIP"),
+    ("MT_SHOTGUY", "MT_SHOTGUN"),
+    ("MT_CHAINGUY", "MT_CHAINGUN"),
+];
+
+/// How much a map has to fight and to fight with, on one skill level.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub monsters: usize,
+    /// The health of every monster added up, which is how much damage it takes to clear the map.
+    pub monster_hp: i64,
+    /// Every bit of ammo, including what monsters drop, in the order of the ammo table.
+    pub ammo: [i64; 4],
+    /// Every bit of health from pickups, ignoring the limits on how much the player can have.
+    pub health: i64,
+    /// Every armor point from pickups, ignoring limits like health.
+    pub armor: i64,
+    pub keys: Vec<&'static str>,
+}
+
+impl Stats {
+    /// Add up the things in a map that appear in single player on a skill from 1 to 5.
+    #[inline]
+    pub fn count(map: &Map, balance: &Balance, skill: usize) -> Self {
+        let mut stats = Stats::default();
+
+        map.things
+            .iter()
+            .filter(|t| t.on_skill(skill))
+            .filter_map(|t| balance.thing(t.kind))
+            .for_each(|thing| {
+                if thing.flags & MF_COUNTKILL != 0 {
+                    stats.monsters += 1;
+                    stats.monster_hp += thing.health;
+                    if let Some((_, drop)) = DROPS.iter().find(|(name, _)| *name == thing.name) {
+                        stats.pick_up(drop, balance, true);
+                    }
+                }
+                stats.pick_up(thing.name, balance, false);
+            });
+
+        // the easiest and hardest skills give double ammo
+        if skill == 1 || skill == 5 {
+            stats.ammo.iter_mut().for_each(|ammo| *ammo *= 2);
+        }
+
+        stats
+    }
+
+    #[inline]
+    fn pick_up(&mut self, thing: &str, balance: &Balance, dropped: bool) {
+        let pickup = match pickup(thing) {
+            Some(pickup) => pickup,
+            None => return,
+        };
+
+        match pickup {
+            Pickup::Ammo(ammo, clips) => {
+                let clips = if dropped { 0 } else { clips };
+                self.add_ammo(balance, ammo, clips);
+            }
+            Pickup::Weapon(weapon) => {
+                if let Some((ammo, _)) = balance.weapon_clip(weapon) {
+                    self.add_ammo(balance, ammo, if dropped { 1 } else { 2 });
+                }
+            }
+            Pickup::Backpack => {
+                (0..self.ammo.len()).for_each(|ammo| self.add_ammo(balance, ammo, 1))
+            }
+            Pickup::Health(health) => self.health += health,
+            Pickup::Soulsphere => self.health += balance.soulsphere_health,
+            Pickup::Megasphere => {
+                // the megasphere always gives class 2 armor; patches only change its health
+                self.health += balance.megasphere_health;
+                self.armor += 200;
+            }
+            Pickup::Armor(armor) => self.armor += armor,
+            Pickup::GreenArmor => self.armor += balance.green_armor_class * 100,
+            Pickup::BlueArmor => self.armor += balance.blue_armor_class * 100,
+            Pickup::Key(key) => self.keys.push(key),
+        }
+    }
+
+    #[inline]
+    fn add_ammo(&mut self, balance: &Balance, ammo: usize, clips: i64) {
+        let clip = balance.clip_ammo.get(ammo).copied().unwrap_or(0);
+        if let Some(total) = self.ammo.get_mut(ammo) {
+            *total += if clips == 0 { clip / 2 } else { clip * clips };
+        }
+    }
+}
+
+/// What picking up a thing gives, by the thing's name in the source.
+#[inline]
+pub(super) fn pickup(thing: &str) -> Option<Pickup> {
+    PICKUPS
+        .iter()
+        .find(|(name, _)| *name == thing)
+        .map(|(_, pickup)| *pickup)
+}
+
+/// How many secret sectors a map has.
+#[inline]
+pub fn secrets(map: &Map) -> usize {
+    map.sectors.iter().filter(|s| s.special == SECRET).count()
+}
+
+/// Write the monsters, ammo, health, armor, keys and secrets in every map of some WADs, for each skill level. Monsters and
+/// pickups are the ones a DeHackEd patch or YAML source makes, if there is one.
+#[inline]
+pub fn mapinfo<W: Write>(wads: &[PathBuf], dehacked: Option<&Path>, mut out: W) -> crate::Result {
+    let balance = match dehacked {
+        Some(path) => Balance::patched(&Patch::load_or_compile(path)?),
+        None => Balance::vanilla(),
+    };
+
+    for wad in wads {
+        for map in Map::load(wad)? {
+            let stats: Vec<Stats> = (1..=SKILLS.len())
+                .map(|skill| Stats::count(&map, &balance, skill))
+                .collect();
+
+            writeln!(out, "{} ({})", map.name, wad.display())?;
+            let rows: &[Row] = &[
+                ("Monsters", |s| s.monsters as i64),
+                ("Monster HP", |s| s.monster_hp),
+                ("Bullets", |s| s.ammo[0]),
+                ("Shells", |s| s.ammo[1]),
+                ("Cells", |s| s.ammo[2]),
+                ("Rockets", |s| s.ammo[3]),
+                ("Health", |s| s.health),
+                ("Armor", |s| s.armor),
+                ("Keys", |s| s.keys.len() as i64),
+            ];
+            let skills: String = SKILLS.iter().map(|s| format!("{:>8}", s)).collect();
+            writeln!(out, "  {:12}{}", "Skill", skills)?;
+            for (name, value) in rows {
+                let values: String = stats.iter().map(|s| format!("{:>8}", value(s))).collect();
+                writeln!(out, "  {:12}{}", name, values)?;
+            }
+
+            let mut keys: Vec<&str> = stats.iter().flat_map(|s| s.keys.iter().copied()).collect();
+            keys.sort_unstable();
+            keys.dedup();
+            if !keys.is_empty() {
+                writeln!(out, "  Keys: {}", keys.join(", "))?;
+            }
+            writeln!(out, "  Secrets: {}", secrets(&map))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dehacked::parse_patch,
+        map::tests::square_room,
+        wad::{tests::pwad, Wad},
+    };
+
+    fn room(things: &[(i16, i16)]) -> Map {
+        Map::from_wad(&Wad::parse(&pwad(&square_room(things))).unwrap())
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn things_are_counted_per_skill() {
+        let map = room(&[
+            // a zombieman on every skill, a shotgun guy on hard and an imp only in multiplayer
+            (3004, 7),
+            (9, 4),
+            (3001, 7 | 16),
+            // a box of shells on easy, a medikit and a blue card everywhere
+            (2049, 1),
+            (2012, 7),
+            (5, 7),
+        ]);
+        let balance = Balance::vanilla();
+
+        let easy = Stats::count(&map, &balance, 2);
+        assert_eq!(easy.monsters, 1);
+        assert_eq!(easy.monster_hp, 20);
+        // half a clip from the zombieman, and a box of shells
+        assert_eq!(easy.ammo, [5, 20, 0, 0]);
+        assert_eq!(easy.health, 25);
+        assert_eq!(easy.keys, vec!["blue card"]);
+
+        let hard = Stats::count(&map, &balance, 4);
+        assert_eq!(hard.monsters, 2);
+        assert_eq!(hard.monster_hp, 50);
+        // the dropped shotgun has one clip of shells
+        assert_eq!(hard.ammo, [5, 4, 0, 0]);
+
+        let nightmare = Stats::count(&map, &balance, 5);
+        assert_eq!(nightmare.ammo, [10, 8, 0, 0]);
+
+        assert_eq!(secrets(&map), 1);
+    }
+
+    #[test]
+    fn patched_things_are_counted() {
+        let map = room(&[(3004, 7), (2006, 7)]);
+        let patch = parse_patch(
+            "Patch File for DeHackEd v3.0\n\nDoom version = 21\nPatch format = 6\n\n\
+             Thing 2 (Trooper)\nHit points = 100\n\n\
+             Weapon 6 (BFG 9000)\nAmmo type = 1\n",
+        );
+        let stats = Stats::count(&map, &Balance::patched(&patch), 3);
+        assert_eq!(stats.monster_hp, 100);
+        // the BFG now gives two clips of shells
+        assert_eq!(stats.ammo, [5, 8, 0, 0]);
+    }
+
+    #[test]
+    fn berserk_and_armor_are_counted() {
+        // a berserk pack, green armor, blue armor, an armor bonus and a megasphere
+        let map = room(&[(2023, 7), (2018, 7), (2019, 7), (2015, 7), (83, 7)]);
+        let stats = Stats::count(&map, &Balance::vanilla(), 3);
+        assert_eq!(stats.health, 100 + 200);
+        assert_eq!(stats.armor, 100 + 200 + 1 + 200);
+
+        let patch = parse_patch(
+            "Patch File for DeHackEd v3.0\n\nDoom version = 21\nPatch format = 6\n\n\
+             Misc 0\nGreen Armor Class = 2\n",
+        );
+        let stats = Stats::count(&map, &Balance::patched(&patch), 3);
+        assert_eq!(stats.armor, 200 + 200 + 1 + 200);
+    }
+}
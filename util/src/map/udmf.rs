@@ -0,0 +1,780 @@
+// Apache 2.0 License
+
+use super::{
+    Linedef, Map, Sector, Sidedef, Thing, Vertex, MTF_EASY, MTF_HARD, MTF_NORMAL, MTF_NOTSINGLE,
+};
+use crate::{error::ResultExt, wad::Wad};
+use std::{fs, io::Write, path::Path};
+
+/// Thing flags UDMF has names for, besides the skills and single player. Boom added the ones for things left out of
+/// deathmatch and coop, and MBF added friendly monsters.
+const MTF_AMBUSH: i16 = 0x0008;
+const MTF_NOTDM: i16 = 0x0020;
+const MTF_NOTCOOP: i16 = 0x0040;
+const MTF_FRIEND: i16 = 0x0080;
+
+/// The linedef flags UDMF has names for in the Doom namespace, with Boom's passuse.
+static LINEDEF_FLAGS: &[(&str, i16)] = &[
+    ("blocking", 0x0001),
+    ("blockmonsters", 0x0002),
+    ("twosided", 0x0004),
+    ("dontpegtop", 0x0008),
+    ("dontpegbottom", 0x0010),
+    ("secret", 0x0020),
+    ("blocksound", 0x0040),
+    ("dontdraw", 0x0080),
+    ("mapped", 0x0100),
+    ("passuse", 0x0200),
+];
+
+/// A value in a TEXTMAP.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+}
+
+/// A field's lowercase name, its value and the line it's on.
+type Field = (String, Value, usize);
+
+/// A thing, linedef, sidedef, vertex or sector in a TEXTMAP.
+#[derive(Debug, Clone)]
+struct Block {
+    kind: String,
+    line: usize,
+    fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Value(Value),
+    Symbol(char),
+}
+
+/// Convert a UDMF TEXTMAP in the Doom namespace to a Doom format map. Anything Doom format has no room for, like
+/// fractional coordinates or fields from other namespaces, is an error.
+#[inline]
+pub fn parse_textmap(text: &str, name: &str) -> crate::Result<Map> {
+    let tokens = tokenize(text)?;
+    let (globals, blocks) = parse_blocks(&tokens)?;
+
+    let namespace = globals.iter().find(|(key, ..)| key == "namespace");
+    match namespace {
+        Some((_, Value::Str(ns), _)) if ns.eq_ignore_ascii_case("doom") => {}
+        Some((_, _, line)) => {
+            return Err(fail(
+                *line,
+                "only the \"doom\" namespace can be converted to Doom format",
+            ))
+        }
+        None => return Err(fail(1, "there is no namespace")),
+    }
+    if let Some((key, _, line)) = globals.iter().find(|(key, ..)| key != "namespace") {
+        return Err(fail(*line, &format!("unknown global field {}", key)));
+    }
+
+    let mut map = Map {
+        name: name.to_ascii_uppercase(),
+        ..Map::default()
+    };
+    for block in &blocks {
+        let mut fields = Fields::new(block);
+        match block.kind.as_str() {
+            "thing" => map.things.push(thing(&mut fields)?),
+            "vertex" => map.vertexes.push(Vertex {
+                x: fields.coordinate("x")?,
+                y: fields.coordinate("y")?,
+            }),
+            "linedef" => map.linedefs.push(linedef(&mut fields)?),
+            "sidedef" => map.sidedefs.push(Sidedef {
+                x_offset: fields.short("offsetx", Some(0))?,
+                y_offset: fields.short("offsety", Some(0))?,
+                upper: fields.texture("texturetop", Some("-"))?,
+                lower: fields.texture("texturebottom", Some("-"))?,
+                middle: fields.texture("texturemiddle", Some("-"))?,
+                sector: fields.reference("sector")?,
+            }),
+            "sector" => map.sectors.push(Sector {
+                floor_height: fields.short("heightfloor", Some(0))?,
+                ceiling_height: fields.short("heightceiling", Some(0))?,
+                floor_flat: fields.texture("texturefloor", None)?,
+                ceiling_flat: fields.texture("textureceiling", None)?,
+                light: fields.short("lightlevel", Some(160))?,
+                special: fields.short("special", Some(0))?,
+                tag: fields.short("id", Some(0))?,
+            }),
+            _ => {
+                return Err(fail(
+                    block.line,
+                    &format!("{} blocks can't be converted to Doom format", block.kind),
+                ))
+            }
+        }
+        fields.finish()?;
+    }
+
+    Ok(map)
+}
+
+#[inline]
+fn thing(fields: &mut Fields) -> crate::Result<Thing> {
+    let x = fields.coordinate("x")?;
+    let y = fields.coordinate("y")?;
+    if fields.float("height", Some(0.0))? != 0.0 {
+        return Err(fields.fail("height", "things can't have a height in Doom format"));
+    }
+    let angle = fields.short("angle", Some(0))?;
+    let kind = fields.short("type", None)?;
+
+    // Doom format has one flag for the two easiest skills and one for the two hardest
+    let skills = [1, 2, 3, 4, 5].map(|skill| fields.flag(&format!("skill{}", skill)));
+    if skills[0] != skills[1] || skills[3] != skills[4] {
+        return Err(fields.fail(
+            "skill1",
+            "Doom format can't tell skills 1 and 2, or 4 and 5, apart",
+        ));
+    }
+    let mut flags = 0;
+    [
+        (skills[0], MTF_EASY),
+        (skills[2], MTF_NORMAL),
+        (skills[3], MTF_HARD),
+    ]
+    .iter()
+    .filter(|(set, _)| *set)
+    .for_each(|(_, bit)| flags |= bit);
+    if fields.flag("ambush") {
+        flags |= MTF_AMBUSH;
+    }
+    if !fields.flag("single") {
+        flags |= MTF_NOTSINGLE;
+    }
+    if !fields.flag("dm") {
+        flags |= MTF_NOTDM;
+    }
+    if !fields.flag("coop") {
+        flags |= MTF_NOTCOOP;
+    }
+    if fields.flag("friend") {
+        flags |= MTF_FRIEND;
+    }
+
+    Ok(Thing {
+        x,
+        y,
+        angle,
+        kind,
+        flags,
+    })
+}
+
+#[inline]
+fn linedef(fields: &mut Fields) -> crate::Result<Linedef> {
+    let mut flags = 0;
+    LINEDEF_FLAGS
+        .iter()
+        .filter(|(name, _)| fields.flag(name))
+        .for_each(|(_, bit)| flags |= bit);
+
+    // Doom calls the tag an id, and -1 means there isn't one
+    let tag = match fields.int("id", Some(-1))? {
+        -1 => 0,
+        _ => fields.short("id", None)?,
+    };
+
+    Ok(Linedef {
+        start: fields.reference("v1")?,
+        end: fields.reference("v2")?,
+        flags,
+        special: fields.short("special", Some(0))?,
+        tag,
+        front: fields.index("sidefront", None)?,
+        back: fields.index("sideback", Some(-1))?,
+    })
+}
+
+/// Reads the fields of a block, and keeps track of which ones were used so anything left over can be reported.
+struct Fields<'a> {
+    block: &'a Block,
+    used: Vec<bool>,
+}
+
+impl<'a> Fields<'a> {
+    #[inline]
+    fn new(block: &'a Block) -> Self {
+        Self {
+            block,
+            used: vec![false; block.fields.len()],
+        }
+    }
+
+    #[inline]
+    fn get(&mut self, name: &str) -> Option<&'a Field> {
+        let i = self
+            .block
+            .fields
+            .iter()
+            .rposition(|(key, ..)| key == name)?;
+        self.used[i] = true;
+        Some(&self.block.fields[i])
+    }
+
+    #[inline]
+    fn fail(&self, name: &str, message: &str) -> crate::Error {
+        let line = self
+            .block
+            .fields
+            .iter()
+            .find(|(key, ..)| key == name)
+            .map_or(self.block.line, |(.., line)| *line);
+        fail(line, message)
+    }
+
+    #[inline]
+    fn int(&mut self, name: &str, default: Option<i64>) -> crate::Result<i64> {
+        match (self.get(name), default) {
+            (Some((_, Value::Int(value), _)), _) => Ok(*value),
+            (Some((_, _, line)), _) => Err(fail(*line, &format!("{} must be an integer", name))),
+            (None, Some(default)) => Ok(default),
+            (None, None) => {
+                Err(self.fail(name, &format!("{} {} is missing", self.block.kind, name)))
+            }
+        }
+    }
+
+    #[inline]
+    fn float(&mut self, name: &str, default: Option<f64>) -> crate::Result<f64> {
+        match (self.get(name), default) {
+            (Some((_, Value::Float(value), _)), _) => Ok(*value),
+            (Some((_, Value::Int(value), _)), _) => Ok(*value as f64),
+            (Some((_, _, line)), _) => Err(fail(*line, &format!("{} must be a number", name))),
+            (None, Some(default)) => Ok(default),
+            (None, None) => {
+                Err(self.fail(name, &format!("{} {} is missing", self.block.kind, name)))
+            }
+        }
+    }
+
+    /// A number that Doom format stores as a 16 bit integer.
+    #[inline]
+    fn short(&mut self, name: &str, default: Option<i64>) -> crate::Result<i16> {
+        let value = self.int(name, default)?;
+        if value < i64::from(i16::MIN) || value > i64::from(i16::MAX) {
+            return Err(self.fail(
+                name,
+                &format!("{} {} is too big for Doom format", name, value),
+            ));
+        }
+        Ok(value as i16)
+    }
+
+    /// A coordinate, which has to be a whole number in Doom format.
+    #[inline]
+    fn coordinate(&mut self, name: &str) -> crate::Result<i16> {
+        let value = self.float(name, None)?;
+        if value.fract() != 0.0 || value < f64::from(i16::MIN) || value > f64::from(i16::MAX) {
+            return Err(self.fail(
+                name,
+                &format!(
+                    "{} {} isn't a whole number that fits in Doom format",
+                    name, value
+                ),
+            ));
+        }
+        Ok(value as i16)
+    }
+
+    /// A reference to a vertex, sidedef or sector. -1, or 0xFFFF in Doom format, means none.
+    #[inline]
+    fn index(&mut self, name: &str, default: Option<i64>) -> crate::Result<Option<u16>> {
+        match self.int(name, default)? {
+            -1 => Ok(None),
+            value if (0..0xFFFF).contains(&value) => Ok(Some(value as u16)),
+            value => Err(self.fail(
+                name,
+                &format!("{} {} is too big for Doom format", name, value),
+            )),
+        }
+    }
+
+    /// A reference that has to be there.
+    #[inline]
+    fn reference(&mut self, name: &str) -> crate::Result<u16> {
+        self.index(name, None)?
+            .ok_or_else(|| self.fail(name, &format!("{} {} is missing", self.block.kind, name)))
+    }
+
+    /// A texture or flat name, which Doom format limits to eight characters.
+    #[inline]
+    fn texture(&mut self, name: &str, default: Option<&str>) -> crate::Result<String> {
+        let value = match (self.get(name), default) {
+            (Some((_, Value::Str(value), _)), _) => value.to_ascii_uppercase(),
+            (Some((_, _, line)), _) => {
+                return Err(fail(*line, &format!("{} must be a string", name)))
+            }
+            (None, Some(default)) => default.to_string(),
+            (None, None) => {
+                return Err(self.fail(name, &format!("{} {} is missing", self.block.kind, name)))
+            }
+        };
+        if value.len() > 8 || !value.is_ascii() {
+            return Err(self.fail(name, &format!("{} is too long for Doom format", value)));
+        }
+        Ok(value)
+    }
+
+    /// A flag, which is false unless it's set.
+    #[inline]
+    fn flag(&mut self, name: &str) -> bool {
+        matches!(self.get(name), Some((_, Value::Bool(true), _)))
+    }
+
+    /// Fail if the block has fields that weren't read, since Doom format has nowhere to put them. Comments are only
+    /// for editors, so they can be dropped.
+    #[inline]
+    fn finish(self) -> crate::Result {
+        match self
+            .block
+            .fields
+            .iter()
+            .zip(&self.used)
+            .find(|((key, ..), used)| !**used && key != "comment")
+        {
+            Some(((key, _, line), _)) => Err(fail(
+                *line,
+                &format!(
+                    "{} {} can't be converted to Doom format",
+                    self.block.kind, key
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Report a problem with a TEXTMAP.
+#[inline]
+fn fail(line: usize, message: &str) -> crate::Error {
+    crate::Error::Msg(format!("TEXTMAP:{}: {}", line, message))
+}
+
+#[inline]
+fn tokenize(text: &str) -> crate::Result<Vec<(Token, usize)>> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            last = c;
+                        }
+                        None => return Err(fail(line, "comment never ends")),
+                    }
+                }
+            }
+            '{' | '}' | '=' | ';' => tokens.push((Token::Symbol(c), line)),
+            '"' => {
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => string.extend(chars.next()),
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            string.push(c);
+                        }
+                        None => return Err(fail(line, "string never ends")),
+                    }
+                }
+                tokens.push((Token::Value(Value::Str(string)), line));
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '+' || c == '.' => {
+                let mut word = c.to_string();
+                while let Some(c) =
+                    chars.next_if(|c| c.is_ascii_alphanumeric() || "_-+.".contains(*c))
+                {
+                    word.push(c);
+                }
+                tokens.push((word_token(&word, line)?, line));
+            }
+            c => return Err(fail(line, &format!("unexpected {:?}", c))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// A keyword, number or identifier.
+#[inline]
+fn word_token(word: &str, line: usize) -> crate::Result<Token> {
+    let lower = word.to_ascii_lowercase();
+    if lower == "true" || lower == "false" {
+        return Ok(Token::Value(Value::Bool(lower == "true")));
+    }
+    if word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        return Ok(Token::Ident(lower));
+    }
+
+    let (sign, digits) = match word.strip_prefix('-') {
+        Some(digits) => (-1, digits),
+        None => (1, word.strip_prefix('+').unwrap_or(word)),
+    };
+    let int = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => digits.parse::<i64>().ok(),
+    };
+    if let Some(int) = int {
+        return Ok(Token::Value(Value::Int(sign * int)));
+    }
+    word.parse::<f64>()
+        .map(|float| Token::Value(Value::Float(float)))
+        .map_err(|_| fail(line, &format!("{} isn't a number", word)))
+}
+
+/// Split tokens into global assignments, like the namespace, and blocks.
+#[inline]
+fn parse_blocks(tokens: &[(Token, usize)]) -> crate::Result<(Vec<Field>, Vec<Block>)> {
+    let mut globals = vec![];
+    let mut blocks = vec![];
+    let mut tokens = tokens.iter().peekable();
+
+    while let Some((token, line)) = tokens.next() {
+        let name = match token {
+            Token::Ident(name) => name.clone(),
+            _ => return Err(fail(*line, "expected a field or block name")),
+        };
+        match tokens.next() {
+            Some((Token::Symbol('='), _)) => globals.push(assignment(name, &mut tokens, *line)?),
+            Some((Token::Symbol('{'), _)) => {
+                let mut block = Block {
+                    kind: name,
+                    line: *line,
+                    fields: vec![],
+                };
+                loop {
+                    match tokens.next() {
+                        Some((Token::Symbol('}'), _)) => break,
+                        Some((Token::Ident(key), line)) => {
+                            match tokens.next() {
+                                Some((Token::Symbol('='), _)) => {}
+                                _ => return Err(fail(*line, &format!("expected = after {}", key))),
+                            }
+                            block
+                                .fields
+                                .push(assignment(key.clone(), &mut tokens, *line)?);
+                        }
+                        Some((_, line)) => return Err(fail(*line, "expected a field name")),
+                        None => return Err(fail(block.line, "block never ends")),
+                    }
+                }
+                blocks.push(block);
+            }
+            _ => return Err(fail(*line, &format!("expected = or {{ after {}", name))),
+        }
+    }
+
+    Ok((globals, blocks))
+}
+
+/// The value and semicolon after a field's '='.
+#[inline]
+fn assignment<'a, I: Iterator<Item = &'a (Token, usize)>>(
+    key: String,
+    tokens: &mut I,
+    line: usize,
+) -> crate::Result<Field> {
+    let value = match tokens.next() {
+        Some((Token::Value(value), _)) => value.clone(),
+        _ => return Err(fail(line, &format!("{} needs a value", key))),
+    };
+    match tokens.next() {
+        Some((Token::Symbol(';'), _)) => Ok((key, value, line)),
+        _ => Err(fail(line, &format!("expected ; after {}", key))),
+    }
+}
+
+/// Convert a Doom format map to a UDMF TEXTMAP in the Doom namespace. Fields with default values are left out, so
+/// the text stays short and diffs stay small.
+#[inline]
+pub fn write_textmap(map: &Map) -> crate::Result<String> {
+    let mut out = String::from("namespace = \"doom\";\n");
+    let mut block = |kind: &str, i: usize, fields: Vec<(&str, String)>| {
+        out.push_str(&format!("\n{} // {}\n{{\n", kind, i));
+        fields
+            .iter()
+            .for_each(|(key, value)| out.push_str(&format!("{} = {};\n", key, value)));
+        out.push_str("}\n");
+    };
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+
+    for (i, thing) in map.things.iter().enumerate() {
+        let known = MTF_EASY
+            | MTF_NORMAL
+            | MTF_HARD
+            | MTF_AMBUSH
+            | MTF_NOTSINGLE
+            | MTF_NOTDM
+            | MTF_NOTCOOP
+            | MTF_FRIEND;
+        if thing.flags & !known != 0 {
+            return Err(crate::Error::Msg(format!(
+                "{} thing {} has flags {:#x}, which UDMF has no names for",
+                map.name, i, thing.flags
+            )));
+        }
+
+        let mut fields = vec![
+            ("x", format!("{:.1}", f64::from(thing.x))),
+            ("y", format!("{:.1}", f64::from(thing.y))),
+        ];
+        if thing.angle != 0 {
+            fields.push(("angle", thing.angle.to_string()));
+        }
+        fields.push(("type", thing.kind.to_string()));
+        let set = |bit: i16| thing.flags & bit != 0;
+        [
+            ("skill1", set(MTF_EASY)),
+            ("skill2", set(MTF_EASY)),
+            ("skill3", set(MTF_NORMAL)),
+            ("skill4", set(MTF_HARD)),
+            ("skill5", set(MTF_HARD)),
+            ("ambush", set(MTF_AMBUSH)),
+            ("single", !set(MTF_NOTSINGLE)),
+            ("dm", !set(MTF_NOTDM)),
+            ("coop", !set(MTF_NOTCOOP)),
+            ("friend", set(MTF_FRIEND)),
+        ]
+        .iter()
+        .filter(|(_, on)| *on)
+        .for_each(|(name, _)| fields.push((name, "true".to_string())));
+        block("thing", i, fields);
+    }
+
+    for (i, vertex) in map.vertexes.iter().enumerate() {
+        block(
+            "vertex",
+            i,
+            vec![
+                ("x", format!("{:.1}", f64::from(vertex.x))),
+                ("y", format!("{:.1}", f64::from(vertex.y))),
+            ],
+        );
+    }
+
+    for (i, line) in map.linedefs.iter().enumerate() {
+        let known = LINEDEF_FLAGS.iter().fold(0, |bits, (_, bit)| bits | bit);
+        if line.flags & !known != 0 {
+            return Err(crate::Error::Msg(format!(
+                "{} linedef {} has flags {:#x}, which UDMF has no names for",
+                map.name, i, line.flags
+            )));
+        }
+
+        let mut fields = vec![("v1", line.start.to_string()), ("v2", line.end.to_string())];
+        if let Some(front) = line.front {
+            fields.push(("sidefront", front.to_string()));
+        }
+        if let Some(back) = line.back {
+            fields.push(("sideback", back.to_string()));
+        }
+        if line.special != 0 {
+            fields.push(("special", line.special.to_string()));
+        }
+        if line.tag != 0 {
+            fields.push(("id", line.tag.to_string()));
+        }
+        LINEDEF_FLAGS
+            .iter()
+            .filter(|(_, bit)| line.flags & bit != 0)
+            .for_each(|(name, _)| fields.push((name, "true".to_string())));
+        block("linedef", i, fields);
+    }
+
+    for (i, side) in map.sidedefs.iter().enumerate() {
+        let mut fields = vec![];
+        if side.x_offset != 0 {
+            fields.push(("offsetx", side.x_offset.to_string()));
+        }
+        if side.y_offset != 0 {
+            fields.push(("offsety", side.y_offset.to_string()));
+        }
+        [
+            ("texturetop", &side.upper),
+            ("texturebottom", &side.lower),
+            ("texturemiddle", &side.middle),
+        ]
+        .iter()
+        .filter(|(_, texture)| texture.as_str() != "-")
+        .for_each(|(name, texture)| fields.push((name, quote(texture))));
+        fields.push(("sector", side.sector.to_string()));
+        block("sidedef", i, fields);
+    }
+
+    for (i, sector) in map.sectors.iter().enumerate() {
+        let mut fields = vec![];
+        if sector.floor_height != 0 {
+            fields.push(("heightfloor", sector.floor_height.to_string()));
+        }
+        if sector.ceiling_height != 0 {
+            fields.push(("heightceiling", sector.ceiling_height.to_string()));
+        }
+        fields.push(("texturefloor", quote(&sector.floor_flat)));
+        fields.push(("textureceiling", quote(&sector.ceiling_flat)));
+        if sector.light != 160 {
+            fields.push(("lightlevel", sector.light.to_string()));
+        }
+        if sector.special != 0 {
+            fields.push(("special", sector.special.to_string()));
+        }
+        if sector.tag != 0 {
+            fields.push(("id", sector.tag.to_string()));
+        }
+        block("sector", i, fields);
+    }
+
+    Ok(out)
+}
+
+/// Write a map from a WAD as a UDMF TEXTMAP.
+#[inline]
+pub fn export_udmf<W: Write>(path: &Path, name: Option<&str>, mut out: W) -> crate::Result {
+    let maps = Map::load(path)?;
+    let map = match name {
+        Some(name) => maps.iter().find(|m| m.name.eq_ignore_ascii_case(name)),
+        None if maps.len() == 1 => maps.first(),
+        None => {
+            return Err(crate::Error::Msg(format!(
+                "WAD has more than one map; choose one of {} with --map",
+                maps.iter()
+                    .map(|m| m.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )))
+        }
+    };
+    let map = map.ok_or(crate::Error::StaticMsg("Map not found"))?;
+
+    out.write_all(write_textmap(map)?.as_bytes())?;
+    Ok(())
+}
+
+/// Convert a UDMF TEXTMAP to a WAD with one Doom format map in it, without nodes.
+#[inline]
+pub fn import_udmf<W: Write>(path: &Path, name: &str, out: W) -> crate::Result {
+    if name.is_empty() || name.len() > 8 {
+        return Err(crate::Error::StaticMsg("Map names are 1 to 8 characters"));
+    }
+    let map = parse_textmap(&fs::read_to_string(path).with_file(path)?, name).with_file(path)?;
+
+    Wad { lumps: map.lumps() }.write(out)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{map::tests::square_room, wad::tests::pwad};
+
+    fn room() -> Map {
+        Map::from_wad(&Wad::parse(&pwad(&square_room(&[(1, 7), (3004, 4 | 8 | 16)]))).unwrap())
+            .unwrap()
+            .remove(0)
+    }
+
+    fn same(a: &Map, b: &Map) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.things, b.things);
+        assert_eq!(a.linedefs, b.linedefs);
+        assert_eq!(a.sidedefs, b.sidedefs);
+        assert_eq!(a.vertexes, b.vertexes);
+        assert_eq!(a.sectors, b.sectors);
+    }
+
+    #[test]
+    fn maps_survive_a_round_trip() {
+        let map = room();
+        let text = write_textmap(&map).unwrap();
+        assert!(text.starts_with("namespace = \"doom\";\n"));
+        assert!(text.contains("thing // 1\n{\nx = 0.0;\ny = 0.0;\nangle = 90;\ntype = 3004;\nskill4 = true;\nskill5 = true;\nambush = true;\ndm = true;\ncoop = true;\n}\n"));
+        same(&parse_textmap(&text, "map01").unwrap(), &map);
+    }
+
+    #[test]
+    fn the_real_map_survives_a_round_trip() {
+        let map = Map::load(Path::new("../maps/map01.udmf"))
+            .unwrap()
+            .remove(0);
+        same(
+            &parse_textmap(&write_textmap(&map).unwrap(), "MAP01").unwrap(),
+            &map,
+        );
+    }
+
+    #[test]
+    fn udmf_syntax_is_understood() {
+        let text = "// a comment\nNamespace = \"Doom\";\n/* another\ncomment */\n\
+                    vertex { x = -64; y = 1.28e2; }\n\
+                    sector { texturefloor = \"floor4_8\"; textureceiling = \"F_SKY1\"; id = 0x10; comment = \"a \\\"room\\\"\"; }\n";
+        let map = parse_textmap(text, "MAP02").unwrap();
+        assert_eq!(map.vertexes, vec![Vertex { x: -64, y: 128 }]);
+        assert_eq!(map.sectors[0].floor_flat, "FLOOR4_8");
+        assert_eq!(map.sectors[0].light, 160);
+        assert_eq!(map.sectors[0].tag, 16);
+    }
+
+    #[test]
+    fn unrepresentable_features_are_rejected() {
+        let reject = |body: &str| {
+            let text = format!("namespace = \"doom\";\n{}\n", body);
+            assert!(parse_textmap(&text, "MAP01").is_err(), "{}", body);
+        };
+        reject("vertex { x = 0.5; y = 0; }");
+        reject("vertex { x = 40000; y = 0; }");
+        reject("vertex { x = 0; y = 0; zfloor = 8.0; }");
+        reject("thing { x = 0; y = 0; type = 1; skill1 = true; }");
+        reject("thing { x = 0; y = 0; type = 1; height = 16.0; }");
+        reject("sidedef { sector = 0; texturemiddle = \"LONGTEXTURE\"; }");
+        reject("linedef { v1 = 0; v2 = 1; sidefront = 0; arg0 = 3; }");
+        reject("linedef { v1 = 0; v2 = 1; }");
+        reject("linedef { v1 = -1; v2 = 1; sidefront = 0; }");
+        reject("slope { }");
+        assert!(parse_textmap("namespace = \"zdoom\";\n", "MAP01").is_err());
+        assert_eq!(
+            parse_textmap(
+                "namespace = \"doom\";\nvertex { x = 0.5; y = 0; }\n",
+                "MAP01"
+            )
+            .unwrap_err()
+            .to_string(),
+            "TEXTMAP:2: x 0.5 isn't a whole number that fits in Doom format"
+        );
+
+        let mut map = room();
+        map.linedefs[0].flags = 0x1000;
+        assert_eq!(
+            write_textmap(&map).unwrap_err().to_string(),
+            "MAP01 linedef 0 has flags 0x1000, which UDMF has no names for"
+        );
+    }
+}
@@ -0,0 +1,488 @@
+// Apache 2.0 License
+
+//! Music, converted from Standard MIDI Files to the DMX MUS lumps vanilla plays.
+
+use crate::{error::ResultExt, genmidi::Bank};
+use midly::{Format, MetaMessage, MidiMessage, Smf, Timing, TrackEventKind};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+const HEADER: &[u8; 4] = b"MUS\x1A";
+/// How many MUS tics there are in a second.
+pub const TIC_RATE: f64 = 140.0;
+/// The MUS channel percussion is played on.
+pub const PERCUSSION_CHANNEL: u8 = 15;
+/// The MIDI channel percussion is played on, counting from 0.
+const MIDI_PERCUSSION_CHANNEL: u8 = 9;
+/// The percussion keys GENMIDI has instruments for.
+const PERCUSSION_KEYS: std::ops::RangeInclusive<u8> = 35..=81;
+/// MUS lists percussion in its instruments as the key plus this.
+const PERCUSSION_INSTRUMENT: u16 = 100;
+/// How many notes an OPL2 chip can play at once. Instruments with two voices use two.
+const OPL_VOICES: usize = 9;
+/// The microseconds per beat of a MIDI file that doesn't set a tempo: 120 beats a minute.
+const DEFAULT_TEMPO: f64 = 500_000.0;
+/// The source file extensions that are converted.
+pub static EXTENSIONS: &[&str] = &["mid", "midi"];
+
+/// MIDI controllers with a MUS controller event, and its number.
+static CONTROLLERS: &[(u8, u8)] = &[
+    (0, 1),  // bank select
+    (1, 2),  // modulation
+    (7, 3),  // volume
+    (10, 4), // pan
+    (11, 5), // expression
+    (91, 6), // reverb
+    (93, 7), // chorus
+    (64, 8), // sustain pedal
+    (67, 9), // soft pedal
+];
+
+/// MIDI channel mode messages with a MUS system event, and its number.
+static SYSTEM_EVENTS: &[(u8, u8)] = &[
+    (120, 10), // all sounds off
+    (123, 11), // all notes off
+    (126, 12), // mono
+    (127, 13), // poly
+    (121, 14), // reset all controllers
+];
+
+/// MUS events, by their number in the high bits of an event's first byte.
+const RELEASE_NOTE: u8 = 0;
+const PLAY_NOTE: u8 = 1;
+const PITCH_BEND: u8 = 2;
+const SYSTEM_EVENT: u8 = 3;
+const CONTROLLER: u8 = 4;
+const SCORE_END: u8 = 6;
+/// The controller number that changes a channel's instrument.
+const CHANGE_INSTRUMENT: u8 = 0;
+
+/// An event on the MUS timeline.
+struct Event {
+    tic: u64,
+    bytes: Vec<u8>,
+}
+
+/// What a MIDI channel plays on in MUS.
+#[derive(Default, Clone, Copy)]
+struct Channel {
+    mus: Option<u8>,
+    program: u8,
+    volume: Option<u8>,
+}
+
+/// Convert a Standard MIDI File to MUS. Every track is merged into one score, and percussion is moved to MUS channel
+/// 15. Returns warnings about what MUS or the GENMIDI bank can't play, if a bank is given.
+#[inline]
+pub fn midi_to_mus<W: Write>(
+    midi: &[u8],
+    bank: Option<&Bank>,
+    mut out: W,
+) -> crate::Result<Vec<String>> {
+    let smf = Smf::parse(midi)
+        .map_err(|e| crate::Error::Msg(format!("MIDI file doesn't parse: {}", e)))?;
+    if smf.header.format == Format::Sequential {
+        return Err(crate::Error::StaticMsg(
+            "MIDI file has independent songs in each track, which can't be merged into one",
+        ));
+    }
+
+    // every event with the MIDI tick it's at; sorting is stable, so events at the same tick keep the track order
+    let mut events = vec![];
+    for track in &smf.tracks {
+        let mut tick = 0u64;
+        for event in track {
+            tick += u64::from(event.delta.as_int());
+            events.push((tick, event.kind));
+        }
+    }
+    events.sort_by_key(|(tick, _)| *tick);
+
+    let mut warnings = BTreeSet::new();
+    let mut channels = [Channel::default(); 16];
+    let mut next_channel = 0;
+    let mut score: Vec<Event> = vec![];
+    let mut playing = BTreeSet::new();
+    let mut most_playing = 0;
+    let mut instruments = BTreeSet::new();
+    let mut dummy_instruments = BTreeSet::new();
+
+    let (mut tempo, mut last_tick, mut seconds) = (DEFAULT_TEMPO, 0, 0.0);
+    let mut end = 0;
+    for (tick, kind) in events {
+        seconds += (tick - last_tick) as f64
+            * match smf.header.timing {
+                Timing::Metrical(per_beat) => tempo / 1_000_000.0 / f64::from(per_beat.as_int()),
+                Timing::Timecode(fps, per_frame) => {
+                    1.0 / (f64::from(fps.as_f32()) * f64::from(per_frame))
+                }
+            };
+        last_tick = tick;
+        let tic = (seconds * TIC_RATE).round() as u64;
+        end = end.max(tic);
+
+        let (channel, message) = match kind {
+            TrackEventKind::Midi { channel, message } => (channel.as_int(), message),
+            TrackEventKind::Meta(MetaMessage::Tempo(t)) => {
+                tempo = f64::from(t.as_int());
+                continue;
+            }
+            TrackEventKind::SysEx(_) | TrackEventKind::Escape(_) => {
+                warnings.insert(
+                    "system exclusive messages aren't in MUS and were left out".to_string(),
+                );
+                continue;
+            }
+            TrackEventKind::Meta(_) => continue,
+        };
+
+        let state = &mut channels[usize::from(channel)];
+        let mus = match state.mus {
+            Some(mus) => mus,
+            None if channel == MIDI_PERCUSSION_CHANNEL => PERCUSSION_CHANNEL,
+            None => {
+                // MIDI has 15 channels besides percussion, and so does MUS
+                let mus = next_channel;
+                next_channel += 1;
+                mus
+            }
+        };
+        state.mus = Some(mus);
+
+        let mut event = |kind: u8, bytes: &[u8]| {
+            let mut data = vec![kind << 4 | mus];
+            data.extend_from_slice(bytes);
+            score.push(Event { tic, bytes: data });
+        };
+        match message {
+            MidiMessage::NoteOn { key, vel } if vel.as_int() > 0 => {
+                let (key, vel) = (key.as_int(), vel.as_int());
+                if state.volume == Some(vel) {
+                    event(PLAY_NOTE, &[key]);
+                } else {
+                    event(PLAY_NOTE, &[key | 0x80, vel]);
+                    state.volume = Some(vel);
+                }
+
+                let instrument = if mus == PERCUSSION_CHANNEL {
+                    if !PERCUSSION_KEYS.contains(&key) {
+                        warnings.insert(format!(
+                            "percussion key {} has no GENMIDI instrument and is silent",
+                            key
+                        ));
+                        continue;
+                    }
+                    PERCUSSION_INSTRUMENT + u16::from(key)
+                } else {
+                    u16::from(state.program)
+                };
+                playing.insert((mus, key));
+                most_playing = most_playing.max(playing.len());
+                instruments.insert(instrument);
+                let slot = if mus == PERCUSSION_CHANNEL {
+                    128 + usize::from(key - PERCUSSION_KEYS.start())
+                } else {
+                    usize::from(state.program)
+                };
+                if bank
+                    .and_then(|b| b.instruments.get(slot))
+                    .is_some_and(|i| i.is_dummy())
+                {
+                    dummy_instruments.insert(instrument);
+                }
+            }
+            MidiMessage::NoteOn { key, .. } | MidiMessage::NoteOff { key, .. } => {
+                event(RELEASE_NOTE, &[key.as_int()]);
+                playing.remove(&(mus, key.as_int()));
+            }
+            MidiMessage::PitchBend { bend } => {
+                // MUS bends are 8 bits, with 128 in the middle
+                event(PITCH_BEND, &[(bend.0.as_int() >> 6) as u8]);
+            }
+            MidiMessage::ProgramChange { program } => {
+                if mus != PERCUSSION_CHANNEL {
+                    state.program = program.as_int();
+                    event(CONTROLLER, &[CHANGE_INSTRUMENT, program.as_int()]);
+                }
+            }
+            MidiMessage::Controller { controller, value } => {
+                let controller = controller.as_int();
+                if let Some((_, mus_controller)) =
+                    CONTROLLERS.iter().find(|(c, _)| *c == controller)
+                {
+                    event(CONTROLLER, &[*mus_controller, value.as_int()]);
+                } else if let Some((_, system)) =
+                    SYSTEM_EVENTS.iter().find(|(c, _)| *c == controller)
+                {
+                    event(SYSTEM_EVENT, &[*system]);
+                    if matches!(controller, 120 | 123) {
+                        playing.retain(|(channel, _)| *channel != mus);
+                    }
+                } else {
+                    warnings.insert(format!(
+                        "controller {} isn't in MUS and was left out",
+                        controller
+                    ));
+                }
+            }
+            MidiMessage::Aftertouch { .. } | MidiMessage::ChannelAftertouch { .. } => {
+                warnings.insert("aftertouch isn't in MUS and was left out".to_string());
+            }
+        }
+    }
+
+    if most_playing > OPL_VOICES {
+        warnings.insert(format!(
+            "up to {} notes play at once, but OPL2 cards only play {}",
+            most_playing, OPL_VOICES
+        ));
+    }
+    warnings.extend(dummy_instruments.iter().map(|instrument| {
+        if *instrument >= PERCUSSION_INSTRUMENT {
+            format!(
+                "percussion key {} plays dummy.sbi in GENMIDI",
+                instrument - PERCUSSION_INSTRUMENT
+            )
+        } else {
+            format!("instrument {} plays dummy.sbi in GENMIDI", instrument + 1)
+        }
+    }));
+
+    score.push(Event {
+        tic: end,
+        bytes: vec![SCORE_END << 4],
+    });
+    let mut bytes = vec![];
+    for (i, event) in score.iter().enumerate() {
+        let delay = score.get(i + 1).map_or(0, |next| next.tic - event.tic);
+        let mut data = event.bytes.clone();
+        if delay > 0 {
+            // the high bit of the first byte says a delay follows
+            data[0] |= 0x80;
+        }
+        bytes.extend(data);
+        if delay > 0 {
+            write_delay(delay, &mut bytes);
+        }
+    }
+
+    let too_big = |what| crate::Error::Msg(format!("The song has too many {} for MUS", what));
+    let score_len = u16::try_from(bytes.len()).map_err(|_| too_big("events"))?;
+    let count = u16::try_from(instruments.len()).map_err(|_| too_big("instruments"))?;
+    let score_start =
+        u16::try_from(16 + instruments.len() * 2).map_err(|_| too_big("instruments"))?;
+
+    out.write_all(HEADER)?;
+    out.write_all(&score_len.to_le_bytes())?;
+    out.write_all(&score_start.to_le_bytes())?;
+    out.write_all(&u16::from(next_channel).to_le_bytes())?;
+    // secondary channels, which DMX doesn't use
+    out.write_all(&0u16.to_le_bytes())?;
+    out.write_all(&count.to_le_bytes())?;
+    out.write_all(&0u16.to_le_bytes())?;
+    instruments
+        .iter()
+        .try_for_each(|instrument| out.write_all(&instrument.to_le_bytes()))?;
+    out.write_all(&bytes)?;
+    Ok(warnings.into_iter().collect())
+}
+
+/// Write a delay in tics, seven bits to a byte with the most significant first. The high bit is set on every byte
+/// but the last.
+#[inline]
+fn write_delay(delay: u64, out: &mut Vec<u8>) {
+    let mut groups = vec![(delay & 0x7F) as u8];
+    let mut rest = delay >> 7;
+    while rest > 0 {
+        groups.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    out.extend(groups.iter().rev());
+}
+
+/// Convert a MIDI file to MUS. Returns the warnings, each starting with the file's path.
+#[inline]
+pub fn generate_mus<W: Write>(
+    source: &Path,
+    bank: Option<&Bank>,
+    out: W,
+) -> crate::Result<Vec<String>> {
+    let midi = fs::read(source).with_file(source)?;
+    Ok(midi_to_mus(&midi, bank, out)
+        .with_file(source)?
+        .iter()
+        .map(|warning| format!("{}: {}", source.display(), warning))
+        .collect())
+}
+
+/// Every MIDI file in a directory, with the lump it becomes. Lumps are named after the files, so `d_runnin.mid` is
+/// D_RUNNIN.
+#[inline]
+pub fn sources(dir: &Path) -> crate::Result<Vec<(String, PathBuf)>> {
+    let mut sources = BTreeMap::new();
+    for entry in fs::read_dir(dir).with_file(dir)? {
+        let path = entry.with_file(dir)?.path();
+        let is_midi = path
+            .extension()
+            .is_some_and(|e| EXTENSIONS.iter().any(|ext| e.eq_ignore_ascii_case(ext)));
+        if !is_midi {
+            continue;
+        }
+        let name = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_ascii_uppercase();
+        if name.len() > 8 {
+            return Err(crate::Error::Msg(format!(
+                "{} is too long for a lump name",
+                name
+            )))
+            .with_file(&path);
+        }
+        if let Some(other) = sources.insert(name.clone(), path.clone()) {
+            return Err(crate::Error::Msg(format!(
+                "{} and {} would both be {}",
+                other.display(),
+                path.display(),
+                name
+            )));
+        }
+    }
+    Ok(sources.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A format 1 MIDI file at 96 ticks a beat, with a track for each list of events.
+    fn midi(tracks: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = b"MThd\0\0\0\x06\0\x01".to_vec();
+        bytes.extend((tracks.len() as u16).to_be_bytes());
+        bytes.extend(96u16.to_be_bytes());
+        for track in tracks {
+            bytes.extend(b"MTrk");
+            bytes.extend((track.len() as u32 + 4).to_be_bytes());
+            bytes.extend(*track);
+            bytes.extend([0, 0xFF, 0x2F, 0]);
+        }
+        bytes
+    }
+
+    fn convert(midi: &[u8], bank: Option<&Bank>) -> (Vec<u8>, Vec<String>) {
+        let mut out = vec![];
+        let warnings = midi_to_mus(midi, bank, &mut out).unwrap();
+        (out, warnings)
+    }
+
+    #[test]
+    fn notes_are_converted_to_mus_tics() {
+        // a beat of middle C, then a beat of E at the same volume; a beat at 120 BPM is 70 tics
+        let (mus, warnings) = convert(
+            &midi(&[&[
+                0, 0xC0, 30, // program 30
+                0, 0x90, 60, 100, // C on
+                96, 0x80, 60, 0, // C off
+                0, 0x90, 64, 100, // E on
+                96, 0x90, 64, 0, // E off, as a note on with no velocity
+            ]]),
+            None,
+        );
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(&mus[..4], HEADER);
+        // 16 byte header, one instrument
+        assert_eq!(&mus[4..18], &[15, 0, 18, 0, 1, 0, 0, 0, 1, 0, 0, 0, 30, 0]);
+        assert_eq!(
+            &mus[18..],
+            &[
+                0x40,
+                0,
+                30, // instrument 30
+                0x90,
+                60 | 0x80,
+                100,
+                70, // play C at 100, then wait
+                0x00,
+                60, // release C
+                0x90,
+                64,
+                70, // play E at the same volume, then wait
+                0x00,
+                64,   // release E
+                0x60, // end
+            ]
+        );
+    }
+
+    #[test]
+    fn tracks_are_merged_and_percussion_is_channel_15() {
+        let (mus, _) = convert(
+            &midi(&[
+                &[0, 0xFF, 0x51, 3, 0x03, 0xD0, 0x90], // 250000 us a beat, twice as fast
+                &[0, 0x99, 36, 127, 48, 0x89, 36, 0],  // kick drum on channel 10
+                &[24, 0x93, 67, 64, 24, 0x83, 67, 0],  // G on channel 4
+            ]),
+            None,
+        );
+        // channel 4 is the first melodic one, so it's MUS channel 0
+        assert_eq!(&mus[4..16], &[13, 0, 20, 0, 1, 0, 0, 0, 2, 0, 0, 0]);
+        // G plays the default instrument, and the kick is listed as key 36
+        assert_eq!(&mus[16..20], &[0, 0, 136, 0]);
+        assert_eq!(
+            &mus[20..],
+            &[
+                0x9F,
+                36 | 0x80,
+                127,
+                9, // kick, then 24 ticks at 96 a beat and 240 BPM: 8.75 tics
+                0x90,
+                67 | 0x80,
+                64,
+                9, // G, until 48 ticks, which is 17.5 tics
+                0x0F,
+                36, // kick off
+                0x00,
+                67, // G off
+                0x60,
+            ]
+        );
+    }
+
+    #[test]
+    fn long_delays_take_more_bytes() {
+        let mut out = vec![];
+        write_delay(0x3FFF, &mut out);
+        write_delay(200, &mut out);
+        assert_eq!(out, [0xFF, 0x7F, 0x81, 0x48]);
+    }
+
+    #[test]
+    fn what_cant_be_played_is_warned_about() {
+        let bank = Bank::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("../genmidi")).unwrap();
+        let mut track = vec![];
+        // ten notes at once, a controller MUS doesn't have, a percussion key GENMIDI doesn't have and one it has as
+        // dummy.sbi
+        for key in 60..70 {
+            track.extend([0, 0x90, key, 100]);
+        }
+        track.extend([0, 0xB0, 74, 10]);
+        track.extend([0, 0x99, 30, 100]);
+        track.extend([0, 0x99, 56, 100]);
+        let (_, warnings) = convert(&midi(&[&track]), Some(&bank));
+        assert_eq!(
+            warnings,
+            [
+                "controller 74 isn't in MUS and was left out",
+                "percussion key 30 has no GENMIDI instrument and is silent",
+                "percussion key 56 plays dummy.sbi in GENMIDI",
+                "up to 11 notes play at once, but OPL2 cards only play 9",
+            ]
+        );
+    }
+}
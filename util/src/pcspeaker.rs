@@ -0,0 +1,392 @@
+// Apache 2.0 License
+
+//! PC speaker sounds, the DP* lumps played instead of the DS* ones by engines without digital sound. They're a tone
+//! for every 140th of a second, from a table of 127 frequencies.
+
+use crate::{
+    error::ResultExt,
+    sound::{self, Audio},
+};
+use std::{
+    convert::TryFrom,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// How many tones are played a second.
+pub const TIC_RATE: u32 = 140;
+/// The clock the PC speaker's timer divides to make a tone.
+pub const PIT_FREQUENCY: f64 = 1_193_181.0;
+/// The timer divisor of every tone DMX can play. Tone 0 is silence.
+pub static DIVISORS: [u16; 128] = [
+    0, 6818, 6628, 6449, 6279, 6087, 5906, 5736, 5575, 5423, 5279, 5120, 4971, 4830, 4697, 4554,
+    4435, 4307, 4186, 4058, 3950, 3836, 3728, 3615, 3519, 3418, 3323, 3224, 3131, 3043, 2960, 2875,
+    2794, 2711, 2633, 2560, 2485, 2415, 2348, 2281, 2213, 2153, 2089, 2032, 1975, 1918, 1864, 1810,
+    1757, 1709, 1659, 1612, 1565, 1521, 1478, 1435, 1395, 1355, 1316, 1280, 1242, 1207, 1173, 1140,
+    1107, 1075, 1045, 1015, 986, 959, 931, 905, 879, 854, 829, 806, 783, 760, 739, 718, 697, 677,
+    658, 640, 621, 604, 586, 570, 553, 538, 522, 507, 493, 479, 465, 452, 439, 427, 415, 403, 391,
+    380, 369, 359, 348, 339, 329, 319, 310, 302, 293, 285, 276, 269, 261, 253, 246, 239, 232, 226,
+    219, 213, 207, 201, 195, 190, 184, 179,
+];
+/// The rate sounds are resampled to before their pitch is tracked, which is enough for the highest tone.
+const ANALYSIS_RATE: u32 = 22050;
+/// How many tics of audio the pitch of each tic is found from, so the lowest tone fits in it twice.
+const WINDOW_TICS: usize = 2;
+/// How far below the loudest tic a tic can be before it's silent, in decibels.
+const SILENCE: f32 = -30.0;
+/// How clearly a tic has to repeat to have a pitch, as a threshold for YIN's normalized difference.
+const PERIODICITY: f32 = 0.2;
+/// Tone scripts are named after their lumps, like `dppistol.txt`.
+pub const SCRIPT_EXTENSION: &str = "txt";
+
+/// The frequency a tone plays at.
+#[inline]
+pub fn frequency(tone: u8) -> Option<f64> {
+    match DIVISORS.get(usize::from(tone)) {
+        Some(0) | None => None,
+        Some(divisor) => Some(PIT_FREQUENCY / f64::from(*divisor)),
+    }
+}
+
+/// The tone closest to a frequency, by pitch. Frequencies past either end of the table get the tone at that end.
+#[inline]
+pub fn tone(frequency: f64) -> u8 {
+    let pitch = |f: f64| f.max(1.0).log2();
+    (1..DIVISORS.len() as u8)
+        .min_by(|a, b| {
+            let distance = |tone| (pitch(self::frequency(tone).unwrap()) - pitch(frequency)).abs();
+            distance(*a).total_cmp(&distance(*b))
+        })
+        .unwrap_or(0)
+}
+
+/// Follow the pitch of a sound, and play the closest tone for each tic. Tics without a clear pitch, like noise, get
+/// a tone from how often the sound crosses zero instead. Silence at the start and end is left out.
+#[inline]
+pub fn track(audio: &Audio) -> Vec<u8> {
+    let samples = sound::resample(&audio.samples, audio.rate, ANALYSIS_RATE);
+    let tic = (ANALYSIS_RATE / TIC_RATE) as usize;
+    let tics = samples.len().div_ceil(tic);
+    // the last tics are found from the end of the sound, so their windows are as long as the others
+    let window = |i: usize| {
+        let start = (i * tic).min(samples.len().saturating_sub(tic * WINDOW_TICS));
+        &samples[start..(start + tic * WINDOW_TICS).min(samples.len())]
+    };
+
+    let loudness: Vec<f32> = (0..tics)
+        .map(|i| {
+            let start = i * tic;
+            rms(&samples[start..(start + tic).min(samples.len())])
+        })
+        .collect();
+    let loudest = loudness.iter().copied().fold(0.0, f32::max);
+    let quiet = loudest * 10f32.powf(SILENCE / 20.0);
+
+    let mut tones: Vec<u8> = (0..tics)
+        .map(|i| {
+            if loudest == 0.0 || loudness[i] < quiet {
+                return 0;
+            }
+            let window = window(i);
+            let frequency = pitch(window, f64::from(ANALYSIS_RATE))
+                .unwrap_or_else(|| zero_crossing_rate(window, f64::from(ANALYSIS_RATE)));
+            tone(frequency)
+        })
+        .collect();
+
+    let end = tones.iter().rposition(|t| *t != 0).map_or(0, |end| end + 1);
+    tones.truncate(end);
+    let start = tones.iter().position(|t| *t != 0).unwrap_or(0);
+    tones.drain(..start);
+    tones
+}
+
+#[inline]
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// The fundamental frequency of a window, with YIN, if it repeats clearly enough to have one.
+#[inline]
+fn pitch(window: &[f32], rate: f64) -> Option<f64> {
+    let highest = frequency(DIVISORS.len() as u8 - 1)?;
+    let lowest = frequency(1)?;
+    let min_lag = ((rate / highest).floor() as usize).max(2);
+    let max_lag = ((rate / lowest).ceil() as usize).min(window.len() / 2);
+    if min_lag >= max_lag {
+        return None;
+    }
+
+    let width = window.len() - max_lag;
+    let difference = |lag: usize| -> f32 {
+        (0..width)
+            .map(|j| (window[j] - window[j + lag]).powi(2))
+            .sum()
+    };
+    // the cumulative mean normalized difference, which is 1 on average and dips near 0 at the period
+    let mut normalized = vec![1.0; max_lag + 1];
+    let mut total = 0.0;
+    for (lag, normalized) in normalized.iter_mut().enumerate().skip(1) {
+        let d = difference(lag);
+        total += d;
+        *normalized = if total > 0.0 {
+            d * lag as f32 / total
+        } else {
+            1.0
+        };
+    }
+
+    let mut lag = (min_lag..max_lag).find(|lag| normalized[*lag] < PERIODICITY)?;
+    while lag + 1 < max_lag && normalized[lag + 1] < normalized[lag] {
+        lag += 1;
+    }
+
+    // fit a parabola through the dip for a period between samples
+    let (before, at, after) = (normalized[lag - 1], normalized[lag], normalized[lag + 1]);
+    let curve = before - 2.0 * at + after;
+    let shift = if curve > 0.0 {
+        (0.5 * (before - after) / curve).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    };
+    Some(rate / (lag as f64 + f64::from(shift)))
+}
+
+/// How often a window crosses zero, as the frequency of a tone that would cross it as often.
+#[inline]
+fn zero_crossing_rate(window: &[f32], rate: f64) -> f64 {
+    let crossings = window
+        .windows(2)
+        .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+        .count();
+    crossings as f64 * rate / (2.0 * window.len() as f64)
+}
+
+/// Read a hand-written tone script. Each line is a tone and how many tics it plays for, one if it's left out. A tone
+/// is a note like `A4` or `C#5`, a frequency in hertz like `440hz`, `tone 64` for a tone number, or `rest`. A `#` at
+/// the start of a line or after a space starts a comment.
+#[inline]
+pub fn parse_script(script: &str) -> crate::Result<Vec<u8>> {
+    let mut tones = vec![];
+    for (i, line) in script.lines().enumerate() {
+        let line = match line
+            .find(" #")
+            .or_else(|| line.strip_prefix('#').map(|_| 0))
+        {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let mut words = line.split_whitespace();
+        let first = match words.next() {
+            Some(word) => word,
+            None => continue,
+        };
+        let error = |what: &str| crate::Error::Msg(format!("line {}: {}", i + 1, what));
+
+        let tone = if first.eq_ignore_ascii_case("rest") {
+            0
+        } else if first.eq_ignore_ascii_case("tone") {
+            let number = words.next().ok_or_else(|| error("tone needs a number"))?;
+            match number.parse::<u8>() {
+                Ok(tone) if usize::from(tone) < DIVISORS.len() => tone,
+                _ => return Err(error(&format!("{:?} isn't a tone from 0 to 127", number))),
+            }
+        } else if let Some(hz) = first
+            .strip_suffix("hz")
+            .or_else(|| first.strip_suffix("Hz"))
+        {
+            match hz.parse::<f64>() {
+                Ok(hz) if hz > 0.0 => tone(hz),
+                _ => return Err(error(&format!("{:?} isn't a frequency", first))),
+            }
+        } else {
+            tone(note(first).ok_or_else(|| error(&format!("{:?} isn't a note", first)))?)
+        };
+
+        let tics = match words.next() {
+            None => 1,
+            Some(tics) => tics
+                .parse::<usize>()
+                .map_err(|_| error(&format!("{:?} isn't a number of tics", tics)))?,
+        };
+        if let Some(extra) = words.next() {
+            return Err(error(&format!("didn't expect {:?}", extra)));
+        }
+        tones.extend(std::iter::repeat_n(tone, tics));
+    }
+    Ok(tones)
+}
+
+/// The frequency of a note like `A4`, `C#5` or `Bb3`, with A4 at 440 Hz.
+#[inline]
+fn note(name: &str) -> Option<f64> {
+    let mut chars = name.chars();
+    let semitone = match chars.next()?.to_ascii_uppercase() {
+        'C' => -9,
+        'D' => -7,
+        'E' => -5,
+        'F' => -4,
+        'G' => -2,
+        'A' => 0,
+        'B' => 2,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    let (accidental, octave) = match rest.strip_prefix('#') {
+        Some(octave) => (1, octave),
+        None => match rest.strip_prefix('b') {
+            Some(octave) => (-1, octave),
+            None => (0, rest),
+        },
+    };
+    let octave: i32 = octave.parse().ok()?;
+    let semitones = semitone + accidental + (octave - 4) * 12;
+    Some(440.0 * 2f64.powf(f64::from(semitones) / 12.0))
+}
+
+/// Write a PC speaker sound lump.
+#[inline]
+pub fn write_pc_sound<W: Write>(tones: &[u8], mut out: W) -> crate::Result {
+    let len = u16::try_from(tones.len())
+        .map_err(|_| crate::Error::StaticMsg("PC speaker sound is too long"))?;
+    out.write_all(&0u16.to_le_bytes())?;
+    out.write_all(&len.to_le_bytes())?;
+    out.write_all(tones)?;
+    Ok(())
+}
+
+/// Make a PC speaker sound from a tone script, or by tracking the pitch of a digital sound source.
+#[inline]
+pub fn generate_pc_sound<W: Write>(source: &Path, out: W) -> crate::Result {
+    let is_script = source
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case(SCRIPT_EXTENSION));
+    let tones = if is_script {
+        parse_script(&fs::read_to_string(source).with_file(source)?).with_file(source)?
+    } else {
+        track(&Audio::load(source)?)
+    };
+    write_pc_sound(&tones, out)
+}
+
+/// The PC speaker lump for a digital sound lump, like DPPISTOL for DSPISTOL. Only DS* sounds have one.
+#[inline]
+pub fn lump_name(sound: &str) -> Option<String> {
+    sound.strip_prefix("DS").map(|name| format!("DP{}", name))
+}
+
+/// What every PC speaker lump in a directory of sound sources is made from: a tone script if there is one, or else
+/// the DS* sound with the same name.
+#[inline]
+pub fn sources(dir: &Path) -> crate::Result<Vec<(String, PathBuf)>> {
+    let mut sources: Vec<(String, PathBuf)> = sound::sources(dir)?
+        .into_iter()
+        .filter_map(|(name, path)| Some((lump_name(&name)?, path)))
+        .collect();
+    for entry in fs::read_dir(dir).with_file(dir)? {
+        let path = entry.with_file(dir)?.path();
+        let name = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_ascii_uppercase();
+        let is_script = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case(SCRIPT_EXTENSION));
+        if !is_script || !name.starts_with("DP") {
+            continue;
+        }
+        match sources.iter_mut().find(|(lump, _)| *lump == name) {
+            Some(source) => source.1 = path,
+            None => sources.push((name, path)),
+        }
+    }
+    sources.sort();
+    Ok(sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn sine(frequency: f64, seconds: f64) -> Audio {
+        let rate = 11025;
+        Audio {
+            rate,
+            samples: (0..(f64::from(rate) * seconds) as usize)
+                .map(|i| (2.0 * PI * frequency * i as f64 / f64::from(rate)).sin() as f32)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn every_tone_is_its_own_closest() {
+        for tone in 1..DIVISORS.len() as u8 {
+            assert_eq!(super::tone(frequency(tone).unwrap()), tone);
+        }
+        assert_eq!(frequency(0), None);
+        assert_eq!(super::tone(20.0), 1);
+        assert_eq!(super::tone(20000.0), 127);
+    }
+
+    #[test]
+    fn tones_follow_the_pitch() {
+        let mut audio = sine(440.0, 0.25);
+        audio.samples.extend(sine(1000.0, 0.25).samples);
+        let tones = track(&audio);
+        assert!((69..=71).contains(&tones.len()), "{}", tones.len());
+        // the tics where the window straddles both tones can go either way
+        assert!(tones[..30].iter().all(|t| *t == tone(440.0)), "{:?}", tones);
+        assert!(
+            tones[40..].iter().all(|t| *t == tone(1000.0)),
+            "{:?}",
+            tones
+        );
+    }
+
+    #[test]
+    fn silence_is_trimmed_and_rested() {
+        let mut audio = sine(0.0, 0.1);
+        audio.samples.extend(sine(440.0, 0.1).samples);
+        audio.samples.extend(sine(0.0, 0.1).samples);
+        audio.samples.extend(sine(440.0, 0.1).samples);
+        audio.samples.extend(sine(0.0, 0.1).samples);
+        let tones = track(&audio);
+        assert_ne!(tones[0], 0);
+        assert_ne!(tones[tones.len() - 1], 0);
+        assert!(tones[18..24].iter().all(|t| *t == 0), "{:?}", tones);
+        assert!(track(&sine(0.0, 0.1)).is_empty());
+    }
+
+    #[test]
+    fn scripts_are_parsed() {
+        let script = "# pistol\nA4 2\nC#5\nrest 2 # a comment\n1000hz\ntone 64 1\n\n";
+        assert_eq!(
+            parse_script(script).unwrap(),
+            [
+                tone(440.0),
+                tone(440.0),
+                tone(554.37),
+                0,
+                0,
+                tone(1000.0),
+                64
+            ]
+        );
+        assert!(parse_script("H4").is_err());
+        assert!(parse_script("tone 128").is_err());
+        assert!(parse_script("A4 2 3").is_err());
+    }
+
+    #[test]
+    fn pc_sounds_have_a_header() {
+        let mut out = vec![];
+        write_pc_sound(&[1, 2, 0, 3], &mut out).unwrap();
+        assert_eq!(out, [0, 0, 4, 0, 1, 2, 0, 3]);
+    }
+}
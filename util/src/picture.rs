@@ -0,0 +1,349 @@
+// Apache 2.0 License
+
+use crate::error::ResultExt;
+use png::{chunk::ChunkType, BitDepth, ColorType, Decoder, Encoder, Transformations};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+/// The chunk that ZDoom, SLADE and DeuTeX use to store a graphic's offsets.
+const GRAB: ChunkType = ChunkType(*b"grAb");
+
+/// Pixels with less alpha than this are treated as fully transparent, since Doom graphics can't be partially
+/// see-through.
+pub const ALPHA_THRESHOLD: u8 = 128;
+
+/// An RGBA image, along with the Doom offsets it should be written with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Picture {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 4]>,
+    pub offset: Option<(i32, i32)>,
+}
+
+/// The opaque area of a picture, with inclusive bounds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Bounds {
+    pub left: usize,
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+}
+
+impl Picture {
+    /// Create a fully transparent picture.
+    #[inline]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0; 4]; width * height],
+            offset: None,
+        }
+    }
+
+    /// Load a PNG file, converting it to RGBA.
+    #[inline]
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let mut decoder = Decoder::new(BufReader::new(File::open(path).with_file(path)?));
+        decoder.set_transformations(Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().with_file(path)?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).with_file(path)?;
+        buf.truncate(info.buffer_size());
+
+        let (width, height) = (info.width as usize, info.height as usize);
+        let pixels = match info.color_type {
+            ColorType::Rgba => buf
+                .chunks_exact(4)
+                .map(|p| [p[0], p[1], p[2], p[3]])
+                .collect(),
+            ColorType::Rgb => buf
+                .chunks_exact(3)
+                .map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            ColorType::GrayscaleAlpha => buf
+                .chunks_exact(2)
+                .map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            ColorType::Grayscale => buf.iter().map(|p| [*p, *p, *p, 255]).collect(),
+            ColorType::Indexed => {
+                return Err(crate::Error::StaticMsg("PNG palette was not expanded"))
+            }
+        };
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+            offset: None,
+        })
+    }
+
+    /// Write the picture out as an RGBA PNG, storing the offset in a grAb chunk if there is one.
+    #[inline]
+    pub fn save(&self, path: &Path) -> crate::Result {
+        let mut encoder = Encoder::new(
+            BufWriter::new(File::create(path).with_file(path)?),
+            self.width as u32,
+            self.height as u32,
+        );
+        encoder.set_color(ColorType::Rgba);
+        encoder.set_depth(BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        if let Some((x, y)) = self.offset {
+            let mut grab = [0; 8];
+            grab[..4].copy_from_slice(&x.to_be_bytes());
+            grab[4..].copy_from_slice(&y.to_be_bytes());
+            writer.write_chunk(GRAB, &grab)?;
+        }
+
+        let data: Vec<u8> = self.pixels.iter().flatten().copied().collect();
+        writer.write_image_data(&data)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> [u8; 4] {
+        self.pixels[(y * self.width) + x]
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, pixel: [u8; 4]) {
+        self.pixels[(y * self.width) + x] = pixel;
+    }
+
+    /// Find the smallest rectangle containing every pixel that stays opaque once quantized, so faint edges don't
+    /// widen it. Returns `None` if the picture is empty.
+    #[inline]
+    pub fn bounds(&self) -> Option<Bounds> {
+        let mut bounds: Option<Bounds> = None;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.get(x, y)[3] < ALPHA_THRESHOLD {
+                    continue;
+                }
+
+                bounds = Some(match bounds {
+                    None => Bounds {
+                        left: x,
+                        top: y,
+                        right: x,
+                        bottom: y,
+                    },
+                    Some(b) => Bounds {
+                        left: b.left.min(x),
+                        top: b.top.min(y),
+                        right: b.right.max(x),
+                        bottom: b.bottom.max(y),
+                    },
+                });
+            }
+        }
+
+        bounds
+    }
+
+    /// Cut the picture down to the given bounds, moving the offset along with it.
+    #[inline]
+    pub fn crop(&self, bounds: Bounds) -> Picture {
+        let width = bounds.right - bounds.left + 1;
+        let height = bounds.bottom - bounds.top + 1;
+        let mut cropped = Picture::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                cropped.set(x, y, self.get(x + bounds.left, y + bounds.top));
+            }
+        }
+
+        cropped.offset = self
+            .offset
+            .map(|(x, y)| (x - bounds.left as i32, y - bounds.top as i32));
+        cropped
+    }
+
+    /// Resize the picture to the given height, keeping the aspect ratio. Each new pixel is the average of the pixels
+    /// it covers, weighted by their alpha so transparent pixels don't darken the edges.
+    #[inline]
+    pub fn scale_to_height(&self, height: usize) -> Picture {
+        let factor = height as f32 / self.height as f32;
+        let width = ((self.width as f32 * factor).round() as usize).max(1);
+        self.resize(width, height)
+    }
+
+    /// Resize the picture to the given width and height, which may change its aspect ratio. Pixels are averaged the
+    /// same way as in [`scale_to_height`](Self::scale_to_height).
+    #[inline]
+    pub fn resize(&self, width: usize, height: usize) -> Picture {
+        let mut scaled = Picture::new(width, height);
+
+        let span = |i: usize, from: usize, to: usize| {
+            let start = (i * from) / to;
+            let end = (((i + 1) * from) / to).max(start + 1);
+            start..end
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = [0.0f32; 4];
+                let mut count = 0.0f32;
+
+                for sy in span(y, self.height, height) {
+                    for sx in span(x, self.width, width) {
+                        let [r, g, b, a] = self.get(sx, sy);
+                        let a = a as f32;
+                        sum[0] += r as f32 * a;
+                        sum[1] += g as f32 * a;
+                        sum[2] += b as f32 * a;
+                        sum[3] += a;
+                        count += 1.0;
+                    }
+                }
+
+                if sum[3] > 0.0 {
+                    scaled.set(
+                        x,
+                        y,
+                        [
+                            (sum[0] / sum[3]).round() as u8,
+                            (sum[1] / sum[3]).round() as u8,
+                            (sum[2] / sum[3]).round() as u8,
+                            (sum[3] / count).round() as u8,
+                        ],
+                    );
+                }
+            }
+        }
+
+        scaled.offset = self.offset.map(|(x, y)| {
+            (
+                (x as f32 * width as f32 / self.width as f32).round() as i32,
+                (y as f32 * height as f32 / self.height as f32).round() as i32,
+            )
+        });
+        scaled
+    }
+
+    /// Surround every opaque area with a one pixel border of the given color. The picture grows by a pixel on each
+    /// side so the border always fits.
+    #[inline]
+    pub fn outline(&self, color: [u8; 3]) -> Picture {
+        let mut outlined = Picture::new(self.width + 2, self.height + 2);
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                outlined.set(x + 1, y + 1, self.get(x, y));
+            }
+        }
+
+        let [r, g, b] = color;
+        for y in 0..outlined.height {
+            for x in 0..outlined.width {
+                if outlined.get(x, y)[3] != 0 {
+                    continue;
+                }
+
+                let touches_opaque = [(0, 1), (2, 1), (1, 0), (1, 2)].iter().any(|(dx, dy)| {
+                    // bounds are checked against the original picture, which sits one pixel in
+                    match ((x + dx).checked_sub(2), (y + dy).checked_sub(2)) {
+                        (Some(sx), Some(sy)) if sx < self.width && sy < self.height => {
+                            self.get(sx, sy)[3] != 0
+                        }
+                        _ => false,
+                    }
+                });
+
+                if touches_opaque {
+                    outlined.set(x, y, [r, g, b, 255]);
+                }
+            }
+        }
+
+        outlined.offset = self.offset.map(|(x, y)| (x + 1, y + 1));
+        outlined
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+
+    #[test]
+    fn faint_pixels_are_outside_bounds() {
+        let mut picture = Picture::new(4, 4);
+        picture.set(1, 1, RED);
+        picture.set(3, 3, [255, 0, 0, ALPHA_THRESHOLD - 1]);
+        let bounds = Bounds {
+            left: 1,
+            top: 1,
+            right: 1,
+            bottom: 1,
+        };
+        assert_eq!(picture.bounds(), Some(bounds));
+        assert_eq!(Picture::new(2, 2).bounds(), None);
+    }
+
+    #[test]
+    fn crop_moves_offset() {
+        let mut picture = Picture::new(4, 4);
+        picture.set(2, 3, RED);
+        picture.offset = Some((2, 4));
+        let cropped = picture.crop(Bounds {
+            left: 1,
+            top: 2,
+            right: 2,
+            bottom: 3,
+        });
+        assert_eq!((cropped.width, cropped.height), (2, 2));
+        assert_eq!(cropped.get(1, 1), RED);
+        assert_eq!(cropped.offset, Some((1, 2)));
+    }
+
+    #[test]
+    fn resize_weights_by_alpha() {
+        let mut picture = Picture::new(2, 1);
+        picture.set(0, 0, RED);
+        picture.set(1, 0, [0, 0, 255, 0]);
+        picture.offset = Some((2, 1));
+        // the transparent pixel's color is ignored, but its alpha still counts
+        let resized = picture.resize(1, 1);
+        assert_eq!(resized.get(0, 0), [255, 0, 0, 128]);
+        assert_eq!(resized.offset, Some((1, 1)));
+    }
+
+    #[test]
+    fn scale_keeps_aspect() {
+        let mut picture = Picture::new(4, 2);
+        picture.pixels = vec![RED; 8];
+        picture.offset = Some((2, 2));
+        let scaled = picture.scale_to_height(1);
+        assert_eq!((scaled.width, scaled.height), (2, 1));
+        assert_eq!(scaled.pixels, vec![RED; 2]);
+        assert_eq!(scaled.offset, Some((1, 1)));
+    }
+
+    #[test]
+    fn outline_surrounds_and_moves_offset() {
+        let mut picture = Picture::new(1, 1);
+        picture.set(0, 0, RED);
+        picture.offset = Some((0, 1));
+        let outlined = picture.outline([0, 0, 0]);
+        assert_eq!((outlined.width, outlined.height), (3, 3));
+        assert_eq!(outlined.get(1, 1), RED);
+        for (x, y) in [(1, 0), (0, 1), (2, 1), (1, 2)] {
+            assert_eq!(outlined.get(x, y), [0, 0, 0, 255]);
+        }
+        // corners only touch diagonally
+        assert_eq!(outlined.get(0, 0)[3], 0);
+        assert_eq!(outlined.offset, Some((1, 2)));
+    }
+}
@@ -1,48 +1,44 @@
 // Apache 2.0 License
 
-use std::{
-    array::IntoIter as ArrayIter,
-    fs::File,
-    io::{self, prelude::*, BufReader},
-    path::Path,
-};
-
-/// Generate the color pallete
+use std::io::prelude::*;
+
+/// Bytes in one palette: 256 colors of red, green and blue.
+pub const PALETTE_SIZE: usize = 256 * 3;
+
+/// Generate the color pallete, from the bytes of a base palette or the built in one.
 #[inline]
-pub fn generate_palette<P: AsRef<Path>>(inpal: Option<P>) -> crate::Result {
-    let palette: Vec<[f32; 3]> = match inpal {
+pub fn generate_palette<W: Write>(base: Option<&[u8]>, mut out: W) -> crate::Result {
+    let palette: Vec<[f32; 3]> = match base {
         None => default_palette().collect(),
-        Some(inpal) => load_palette(inpal.as_ref())?,
+        Some(base) => parse_palette(base)?,
     };
     let expanded_palette: Vec<[u8; 3]> = palette
         .iter()
         .copied()
-        .chain((0..8).flat_map(|i| {
-            bias_palette(
-                palette.iter().copied(),
-                [1.0, 0.0, 0.0],
-                (i as f32 + 1.0) * 0.9 / 8.0,
-            )
-        }))
-        .chain((0..4).flat_map(|i| {
-            bias_palette(
-                palette.iter().copied(),
-                [0.839, 0.729, 0.271],
-                (i as f32 + 1.0) * 0.5 / 4.0,
-            )
-        }))
-        .chain(bias_palette(
-            palette.iter().copied(),
-            [0.0, 1.0, 0.0],
-            0.125,
-        ))
+        .chain(
+            palette_biases()
+                .flat_map(|(target, bias)| bias_palette(palette.iter().copied(), target, bias)),
+        )
         .map(|[r, g, b]| [saturate_byte(r), saturate_byte(g), saturate_byte(b)])
         .collect();
 
-    let stdout = io::stdout();
-    let mut cout = stdout.lock();
+    write_palette(&mut out, expanded_palette)
+}
+
+/// The palettes after the base one, as the color they fade towards, how many of them there are and how far the last
+/// one fades: red for taking damage, gold for picking things up and green for the radiation suit.
+pub static BIASES: &[([f32; 3], usize, f32)] = &[
+    ([1.0, 0.0, 0.0], 8, 0.9),
+    ([0.839, 0.729, 0.271], 4, 0.5),
+    ([0.0, 1.0, 0.0], 1, 0.125),
+];
 
-    write_palette(&mut cout, expanded_palette)
+/// The color and bias of every palette after the base one.
+#[inline]
+pub fn palette_biases() -> impl Iterator<Item = ([f32; 3], f32)> {
+    BIASES.iter().flat_map(|&(target, count, last)| {
+        (0..count).map(move |i| (target, (i as f32 + 1.0) * last / count as f32))
+    })
 }
 
 /// Take a palette and bias it in a certain direction.
@@ -71,28 +67,33 @@ fn write_palette<W: Write>(w: &mut W, palette: impl IntoIterator<Item = [u8; 3]>
     palette
         .into_iter()
         //        .inspect(|rgb| eprintln!("Writing rgb: {:?}", rgb))
-        .flat_map(|rgb| ArrayIter::new(rgb))
-        .map(|b| w.write_all(&[b]))
-        .collect::<io::Result<()>>()?;
+        .flat_map(IntoIterator::into_iter)
+        .try_for_each(|b| w.write_all(&[b]))?;
     w.flush()?;
     Ok(())
 }
 
 #[inline]
-fn load_palette(path: &Path) -> crate::Result<Vec<[f32; 3]>> {
-    let mut bytes = vec![];
-    let mut f = BufReader::new(File::open(path)?);
-    f.read_to_end(&mut bytes)?;
-
-    let mut result = Vec::with_capacity(bytes.len() / 3);
-    for i in (0..bytes.len()).step_by(3) {
-        result.push([
-            bytes[i] as f32 / 255.0,
-            bytes[i + 1] as f32 / 255.0,
-            bytes[i + 2] as f32 / 255.0,
-        ]);
+fn parse_palette(bytes: &[u8]) -> crate::Result<Vec<[f32; 3]>> {
+    // the other palettes are made from the base, so a whole PLAYPAL won't do
+    if bytes.len() != PALETTE_SIZE {
+        return Err(crate::Error::Msg(format!(
+            "Base palette is {} bytes, but needs to be {} for 256 colors",
+            bytes.len(),
+            PALETTE_SIZE
+        )));
     }
-    Ok(result)
+
+    Ok(bytes
+        .chunks(3)
+        .map(|rgb| {
+            [
+                rgb[0] as f32 / 255.0,
+                rgb[1] as f32 / 255.0,
+                rgb[2] as f32 / 255.0,
+            ]
+        })
+        .collect())
 }
 
 #[inline]
@@ -144,11 +145,85 @@ fn make_palette_range(r: f32, g: f32, b: f32, n: usize) -> impl Iterator<Item =
 
 #[inline]
 fn saturate_byte(b: f32) -> u8 {
-    if b >= 1.0 {
-        255
-    } else if b <= 0.0 {
-        0
-    } else {
-        (b * 255.0) as u8
+    // `as` truncates, so a color read as 200 / 255 could come back as 199; NaN comes out as 0
+    (b.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn unit() -> impl Strategy<Value = f32> {
+        0.0f32..=1.0
+    }
+
+    fn color() -> impl Strategy<Value = [f32; 3]> {
+        [unit(), unit(), unit()]
+    }
+
+    proptest! {
+        #[test]
+        fn bytes_survive_the_trip_through_floats(b in any::<u8>()) {
+            prop_assert_eq!(saturate_byte(b as f32 / 255.0), b);
+        }
+
+        #[test]
+        fn saturation_is_monotonic(a in any::<f32>(), b in any::<f32>()) {
+            prop_assume!(!a.is_nan() && !b.is_nan());
+            let (low, high) = if a <= b { (a, b) } else { (b, a) };
+            prop_assert!(saturate_byte(low) <= saturate_byte(high));
+        }
+
+        #[test]
+        fn biased_colors_stay_between_the_color_and_the_target(
+            rgb in color(),
+            target in color(),
+            bias in unit(),
+        ) {
+            let biased = bias_palette(vec![rgb], target, bias).next().unwrap();
+            for i in 0..3 {
+                let (low, high) = (rgb[i].min(target[i]), rgb[i].max(target[i]));
+                prop_assert!(biased[i] >= low - 1e-6 && biased[i] <= high + 1e-6);
+            }
+            prop_assert_eq!(bias_palette(vec![rgb], target, 0.0).next().unwrap(), rgb);
+        }
+
+        #[test]
+        fn ranges_darken_from_the_color(rgb in color(), n in 0usize..300) {
+            let range: Vec<[f32; 3]> = make_palette_range(rgb[0], rgb[1], rgb[2], n).collect();
+            prop_assert_eq!(range.len(), n);
+            if let Some(first) = range.first() {
+                prop_assert_eq!(*first, rgb);
+            }
+            for pair in range.windows(2) {
+                for (darker, brighter) in pair[1].iter().zip(&pair[0]) {
+                    prop_assert!(darker <= brighter && *darker >= 0.0);
+                }
+            }
+        }
+
+        #[test]
+        fn base_palettes_must_be_256_colors(bytes in proptest::collection::vec(any::<u8>(), 0..2000)) {
+            let palette = parse_palette(&bytes);
+            prop_assert_eq!(palette.is_ok(), bytes.len() == PALETTE_SIZE);
+        }
+
+        #[test]
+        fn the_first_palette_is_the_base(base in proptest::collection::vec(any::<u8>(), PALETTE_SIZE)) {
+            let mut out = vec![];
+            generate_palette(Some(&base), &mut out).unwrap();
+            prop_assert_eq!(out.len(), PALETTE_SIZE * 14);
+            prop_assert_eq!(&out[..PALETTE_SIZE], &base[..]);
+        }
+    }
+
+    #[test]
+    fn colors_are_rounded() {
+        // the second shade of white is 46 / 47 * 255 = 249.57, which truncating made 249; the golden files changed
+        // when this was fixed
+        let mut out = vec![];
+        generate_palette(None, &mut out).unwrap();
+        assert_eq!(out[6..9], [250, 250, 250]);
     }
 }
@@ -0,0 +1,411 @@
+// Apache 2.0 License
+
+//! Sound effects, converted from WAV, FLAC or Ogg Vorbis sources to DMX digital sound lumps.
+
+use crate::error::ResultExt;
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    f64::consts::PI,
+    fs::File,
+    io::{BufReader, Cursor, Write},
+    path::{Path, PathBuf},
+};
+
+/// The sample rate vanilla plays every sound at.
+pub const SAMPLE_RATE: u32 = 11025;
+/// The format number at the start of a DMX digital sound.
+const DMX_FORMAT: u16 = 3;
+/// DMX skips this many samples at each end of a sound, so they're padding.
+pub const DMX_PADDING: usize = 16;
+/// How many zero crossings of the sinc function are used on each side of a sample when resampling.
+const RESAMPLE_TAPS: f64 = 8.0;
+/// The source file extensions that are converted.
+pub static EXTENSIONS: &[&str] = &["wav", "flac", "ogg"];
+
+/// Decoded audio, mixed down to mono, with samples from -1.0 to 1.0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Audio {
+    pub rate: u32,
+    pub samples: Vec<f32>,
+}
+
+/// How every sound is converted, and the sounds that are converted differently.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub defaults: Settings,
+    /// Settings for single sounds, by lump name.
+    #[serde(default)]
+    pub sounds: BTreeMap<String, Settings>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+pub struct Settings {
+    /// Scale the sound so its loudest sample is at full volume.
+    #[serde(default = "default_true")]
+    pub normalize: bool,
+    /// Cut off the start and end of the sound while it would be silent at 8 bits.
+    #[serde(default = "default_true")]
+    pub trim: bool,
+    /// Volume change in decibels, after normalizing.
+    #[serde(default)]
+    pub gain: f32,
+}
+
+#[inline]
+fn default_true() -> bool {
+    true
+}
+
+impl Default for Settings {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            normalize: true,
+            trim: true,
+            gain: 0.0,
+        }
+    }
+}
+
+impl Config {
+    #[inline]
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        serde_yaml::from_reader(BufReader::new(File::open(path).with_file(path)?)).with_file(path)
+    }
+
+    /// The settings for a sound, by lump name.
+    #[inline]
+    pub fn settings(&self, name: &str) -> Settings {
+        self.sounds.get(name).copied().unwrap_or(self.defaults)
+    }
+}
+
+impl Audio {
+    #[inline]
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        Self::decode(&std::fs::read(path).with_file(path)?).with_file(path)
+    }
+
+    /// Decode a WAV, FLAC or Ogg Vorbis file, whichever it starts like.
+    #[inline]
+    pub fn decode(bytes: &[u8]) -> crate::Result<Self> {
+        match bytes.get(..4) {
+            Some(b"RIFF") => Self::decode_wav(bytes),
+            Some(b"fLaC") => Self::decode_flac(bytes),
+            Some(b"OggS") => Self::decode_ogg(bytes),
+            _ => Err(crate::Error::StaticMsg(
+                "Sound isn't a WAV, FLAC or Ogg Vorbis file",
+            )),
+        }
+    }
+
+    #[inline]
+    fn decode_wav(bytes: &[u8]) -> crate::Result<Self> {
+        let reader = hound::WavReader::new(Cursor::new(bytes))?;
+        let spec = reader.spec();
+        let samples = match spec.sample_format {
+            hound::SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<_, _>>()?,
+            hound::SampleFormat::Int => {
+                let scale = int_scale(spec.bits_per_sample.into());
+                reader
+                    .into_samples::<i32>()
+                    .map(|s| s.map(|s| s as f32 * scale))
+                    .collect::<Result<_, _>>()?
+            }
+        };
+        Ok(Self::mixed(spec.sample_rate, spec.channels.into(), samples))
+    }
+
+    #[inline]
+    fn decode_flac(bytes: &[u8]) -> crate::Result<Self> {
+        let mut reader = claxon::FlacReader::new(Cursor::new(bytes))?;
+        let info = reader.streaminfo();
+        let scale = int_scale(info.bits_per_sample);
+        let samples = reader
+            .samples()
+            .map(|s| s.map(|s| s as f32 * scale))
+            .collect::<Result<_, _>>()?;
+        Ok(Self::mixed(info.sample_rate, info.channels, samples))
+    }
+
+    #[inline]
+    fn decode_ogg(bytes: &[u8]) -> crate::Result<Self> {
+        let mut reader = lewton::inside_ogg::OggStreamReader::new(Cursor::new(bytes))?;
+        let scale = int_scale(16);
+        let mut samples = vec![];
+        while let Some(packet) = reader.read_dec_packet_itl()? {
+            samples.extend(packet.into_iter().map(|s| f32::from(s) * scale));
+        }
+        Ok(Self::mixed(
+            reader.ident_hdr.audio_sample_rate,
+            reader.ident_hdr.audio_channels.into(),
+            samples,
+        ))
+    }
+
+    /// Average interleaved channels together.
+    #[inline]
+    fn mixed(rate: u32, channels: u32, samples: Vec<f32>) -> Self {
+        let channels = channels.max(1) as usize;
+        let samples = if channels == 1 {
+            samples
+        } else {
+            samples
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+                .collect()
+        };
+        Self { rate, samples }
+    }
+}
+
+/// What an integer sample is multiplied by to be from -1.0 to 1.0.
+#[inline]
+fn int_scale(bits: u32) -> f32 {
+    1.0 / (1u64 << (bits.clamp(1, 32) - 1)) as f32
+}
+
+/// Convert audio to the samples of a vanilla sound: 11025 Hz, 8-bit unsigned.
+#[inline]
+pub fn convert(audio: &Audio, settings: Settings) -> Vec<u8> {
+    let mut samples = resample(&audio.samples, audio.rate, SAMPLE_RATE);
+
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    let mut scale = 10f32.powf(settings.gain / 20.0);
+    if settings.normalize && peak > 0.0 {
+        scale /= peak;
+    }
+    samples.iter_mut().for_each(|s| *s *= scale);
+
+    let mut bytes: Vec<u8> = samples.iter().copied().map(to_u8).collect();
+    if settings.trim {
+        let start = bytes.iter().position(|b| *b != 128).unwrap_or(bytes.len());
+        let end = bytes
+            .iter()
+            .rposition(|b| *b != 128)
+            .map_or(start, |end| end + 1);
+        bytes.truncate(end);
+        bytes.drain(..start);
+    }
+    bytes
+}
+
+#[inline]
+fn to_u8(sample: f32) -> u8 {
+    // silence is 127.5, which rounds to 128
+    (sample.clamp(-1.0, 1.0) * 127.5 + 127.5).round() as u8
+}
+
+/// Change the sample rate with a windowed sinc filter, which also filters out what's too high for the new rate.
+#[inline]
+pub fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let step = f64::from(from) / f64::from(to);
+    // the filter is stretched when downsampling so it cuts off at the new Nyquist frequency
+    let cutoff = (1.0 / step).min(1.0);
+    let radius = (RESAMPLE_TAPS / cutoff).ceil() as isize;
+    let len = (samples.len() as f64 / step).round() as usize;
+    (0..len)
+        .map(|i| {
+            let center = i as f64 * step;
+            let first = (center.floor() as isize - radius + 1).max(0);
+            let last = (center.floor() as isize + radius).min(samples.len() as isize - 1);
+            let (mut sum, mut weights) = (0.0, 0.0);
+            for j in first..=last {
+                let x = (j as f64 - center) * cutoff;
+                let weight = sinc(x) * sinc(x / RESAMPLE_TAPS);
+                sum += f64::from(samples[j as usize]) * weight;
+                weights += weight;
+            }
+            if weights == 0.0 {
+                0.0
+            } else {
+                (sum / weights) as f32
+            }
+        })
+        .collect()
+}
+
+#[inline]
+fn sinc(x: f64) -> f64 {
+    if x.abs() >= RESAMPLE_TAPS {
+        0.0
+    } else if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Write a DMX digital sound. The first and last samples are repeated into the padding DMX skips, so the sound
+/// doesn't click.
+#[inline]
+pub fn write_dmx<W: Write>(samples: &[u8], rate: u32, mut out: W) -> crate::Result {
+    let rate = u16::try_from(rate)
+        .map_err(|_| crate::Error::Msg(format!("Sample rate {} is too high for DMX", rate)))?;
+    let len = u32::try_from(samples.len() + DMX_PADDING * 2)
+        .map_err(|_| crate::Error::StaticMsg("Sound is too long for DMX"))?;
+    let (first, last) = (
+        samples.first().copied().unwrap_or(128),
+        samples.last().copied().unwrap_or(128),
+    );
+
+    out.write_all(&DMX_FORMAT.to_le_bytes())?;
+    out.write_all(&rate.to_le_bytes())?;
+    out.write_all(&len.to_le_bytes())?;
+    out.write_all(&[first; DMX_PADDING])?;
+    out.write_all(samples)?;
+    out.write_all(&[last; DMX_PADDING])?;
+    Ok(())
+}
+
+/// Convert a source file to a DMX sound.
+#[inline]
+pub fn generate_sound<W: Write>(source: &Path, settings: Settings, out: W) -> crate::Result {
+    let audio = Audio::load(source)?;
+    write_dmx(&convert(&audio, settings), SAMPLE_RATE, out)
+}
+
+/// Every sound source in a directory, with the lump it becomes. Lumps are named after the files, so `dspistol.flac`
+/// is DSPISTOL.
+#[inline]
+pub fn sources(dir: &Path) -> crate::Result<Vec<(String, PathBuf)>> {
+    let mut sources = std::fs::read_dir(dir)
+        .with_file(dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<crate::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|e| EXTENSIONS.iter().any(|ext| e.eq_ignore_ascii_case(ext)))
+        })
+        .map(|path| {
+            let name = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_ascii_uppercase();
+            (name, path)
+        })
+        .collect::<Vec<_>>();
+    sources.sort();
+
+    if let Some(pair) = sources.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        return Err(crate::Error::Msg(format!(
+            "{} and {} would both be {}",
+            pair[0].1.display(),
+            pair[1].1.display(),
+            pair[0].0
+        )));
+    }
+    if let Some((name, path)) = sources.iter().find(|(name, _)| name.len() > 8) {
+        return Err(crate::Error::Msg(format!(
+            "{} is too long for a lump name",
+            name
+        )))
+        .with_file(path);
+    }
+    Ok(sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(rate: u32, frequency: f64, seconds: f64) -> Vec<f32> {
+        (0..(f64::from(rate) * seconds) as usize)
+            .map(|i| (2.0 * PI * frequency * i as f64 / f64::from(rate)).sin() as f32 * 0.5)
+            .collect()
+    }
+
+    fn wav(rate: u32, channels: u16, samples: &[i16]) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate: rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut out = Cursor::new(vec![]);
+        let mut writer = hound::WavWriter::new(&mut out, spec).unwrap();
+        samples
+            .iter()
+            .for_each(|s| writer.write_sample(*s).unwrap());
+        writer.finalize().unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn wavs_are_mixed_to_mono() {
+        let audio = Audio::decode(&wav(22050, 2, &[16384, -16384, 16384, 0])).unwrap();
+        assert_eq!(audio.rate, 22050);
+        assert_eq!(audio.samples, [0.0, 0.25]);
+    }
+
+    #[test]
+    fn unknown_formats_are_rejected() {
+        assert!(Audio::decode(b"MThd\0\0\0\x06").is_err());
+    }
+
+    #[test]
+    fn resampling_keeps_the_length_and_the_tone() {
+        let samples = resample(&sine(44100, 440.0, 0.4), 44100, SAMPLE_RATE);
+        assert_eq!(samples.len(), 4410);
+        let expected = sine(SAMPLE_RATE, 440.0, 0.4);
+        // away from the ends, where the filter runs out of samples
+        for (actual, expected) in samples.iter().zip(&expected).skip(64).take(4000) {
+            assert!((actual - expected).abs() < 0.01, "{} {}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn resampling_filters_out_what_the_new_rate_cant_hold() {
+        // 8 kHz is above the 5512 Hz Nyquist frequency of 11025 Hz
+        let samples = resample(&sine(44100, 8000.0, 0.5), 44100, SAMPLE_RATE);
+        let peak = samples[64..samples.len() - 64]
+            .iter()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(peak < 0.02, "{}", peak);
+    }
+
+    #[test]
+    fn sounds_are_normalized_and_trimmed() {
+        let mut samples = vec![0.0; 100];
+        samples.extend(sine(SAMPLE_RATE, 440.0, 0.1));
+        samples.extend(vec![0.0; 100]);
+        let audio = Audio {
+            rate: SAMPLE_RATE,
+            samples,
+        };
+
+        let bytes = convert(&audio, Settings::default());
+        assert!(bytes.len() <= 1103, "{}", bytes.len());
+        assert_ne!(bytes[0], 128);
+        assert_ne!(bytes[bytes.len() - 1], 128);
+        assert_eq!(bytes.iter().max(), Some(&255));
+        assert_eq!(bytes.iter().min(), Some(&0));
+
+        let untouched = Settings {
+            normalize: false,
+            trim: false,
+            gain: 0.0,
+        };
+        let bytes = convert(&audio, untouched);
+        assert_eq!(bytes.len(), audio.samples.len());
+        assert_eq!(bytes.iter().max(), Some(&191));
+    }
+
+    #[test]
+    fn dmx_sounds_are_padded() {
+        let mut out = vec![];
+        write_dmx(&[1, 2, 3], SAMPLE_RATE, &mut out).unwrap();
+        assert_eq!(&out[..8], &[3, 0, 0x11, 0x2b, 35, 0, 0, 0]);
+        assert_eq!(&out[8..24], &[1; DMX_PADDING]);
+        assert_eq!(&out[24..27], &[1, 2, 3]);
+        assert_eq!(&out[27..], &[3; DMX_PADDING]);
+    }
+}
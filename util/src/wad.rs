@@ -0,0 +1,156 @@
+// Apache 2.0 License
+
+use crate::error::ResultExt;
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+/// Size of the header at the start of a WAD: the magic number, the lump count and the directory offset.
+const HEADER_SIZE: usize = 12;
+/// Size of one directory entry: the lump offset, its size and its name.
+const DIRECTORY_ENTRY_SIZE: usize = 16;
+
+/// A WAD file, with its lumps in directory order.
+#[derive(Debug, Clone)]
+pub struct Wad {
+    pub lumps: Vec<Lump>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lump {
+    /// The name, without the padding. Lump names are case insensitive, so they're kept in upper case.
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+impl Wad {
+    #[inline]
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        Self::parse(&fs::read(path).with_file(path)?).with_file(path)
+    }
+
+    #[inline]
+    pub fn parse(bytes: &[u8]) -> crate::Result<Self> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(crate::Error::StaticMsg("WAD is too short to have a header"));
+        }
+
+        if &bytes[..4] != b"IWAD" && &bytes[..4] != b"PWAD" {
+            return Err(crate::Error::StaticMsg(
+                "WAD doesn't start with IWAD or PWAD",
+            ));
+        }
+        let count = read_u32(bytes, 4) as usize;
+        let directory = read_u32(bytes, 8) as usize;
+
+        let lumps = (0..count)
+            .map(|i| {
+                let entry = directory + i * DIRECTORY_ENTRY_SIZE;
+                let entry = bytes.get(entry..entry + DIRECTORY_ENTRY_SIZE).ok_or(
+                    crate::Error::StaticMsg("WAD directory runs past the end of the file"),
+                )?;
+
+                let pos = read_u32(entry, 0) as usize;
+                let len = read_u32(entry, 4) as usize;
+                let data = bytes.get(pos..pos + len).ok_or(crate::Error::StaticMsg(
+                    "WAD lump runs past the end of the file",
+                ))?;
+
+                Ok(Lump {
+                    name: lump_name(&entry[8..16]),
+                    data: data.to_vec(),
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Self { lumps })
+    }
+
+    /// Write the WAD as a PWAD, with the lumps first and the directory after them.
+    #[inline]
+    pub fn write<W: Write>(&self, mut out: W) -> io::Result<()> {
+        let data_len: usize = self.lumps.iter().map(|l| l.data.len()).sum();
+        out.write_all(b"PWAD")?;
+        out.write_all(&(self.lumps.len() as u32).to_le_bytes())?;
+        out.write_all(&((HEADER_SIZE + data_len) as u32).to_le_bytes())?;
+        self.lumps.iter().try_for_each(|l| out.write_all(&l.data))?;
+
+        let mut pos = HEADER_SIZE;
+        self.lumps.iter().try_for_each(|lump| {
+            out.write_all(&(pos as u32).to_le_bytes())?;
+            out.write_all(&(lump.data.len() as u32).to_le_bytes())?;
+            let mut name = [0; 8];
+            lump.name
+                .bytes()
+                .take(8)
+                .enumerate()
+                .for_each(|(i, b)| name[i] = b);
+            pos += lump.data.len();
+            out.write_all(&name)
+        })
+    }
+}
+
+/// Decode a lump, texture or flat name, which is padded to eight bytes with zeroes.
+#[inline]
+pub fn lump_name(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .take_while(|&&b| b != 0)
+        .map(|&b| b.to_ascii_uppercase() as char)
+        .collect()
+}
+
+#[inline]
+fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    let mut word = [0; 4];
+    word.copy_from_slice(&bytes[pos..pos + 4]);
+    u32::from_le_bytes(word)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Build the bytes of a PWAD, for testing anything that reads them.
+    pub fn pwad(lumps: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let data_len: usize = lumps.iter().map(|(_, data)| data.len()).sum();
+        let mut bytes = b"PWAD".to_vec();
+        bytes.extend_from_slice(&(lumps.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&((HEADER_SIZE + data_len) as u32).to_le_bytes());
+        lumps
+            .iter()
+            .for_each(|(_, data)| bytes.extend_from_slice(data));
+
+        let mut pos = HEADER_SIZE;
+        lumps.iter().for_each(|(name, data)| {
+            bytes.extend_from_slice(&(pos as u32).to_le_bytes());
+            bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            let mut padded = [0; 8];
+            padded[..name.len()].copy_from_slice(name.as_bytes());
+            bytes.extend_from_slice(&padded);
+            pos += data.len();
+        });
+        bytes
+    }
+
+    #[test]
+    fn lumps_are_read_in_order() {
+        let wad = Wad::parse(&pwad(&[("MAP01", vec![]), ("things", vec![1, 2, 3])])).unwrap();
+        assert_eq!(wad.lumps.len(), 2);
+        assert_eq!(wad.lumps[0].name, "MAP01");
+        assert_eq!(wad.lumps[1].name, "THINGS");
+        assert_eq!(wad.lumps[1].data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn truncated_wads_are_rejected() {
+        let mut bytes = pwad(&[("MAP01", vec![0; 10])]);
+        assert!(Wad::parse(&bytes[..8]).is_err());
+        bytes.truncate(bytes.len() - 4);
+        assert!(Wad::parse(&bytes).is_err());
+        assert!(Wad::parse(b"JUNK\0\0\0\0\0\0\0\0").is_err());
+    }
+}
@@ -0,0 +1,289 @@
+// Apache 2.0 License
+
+//! Differences between a golden lump and a new one, described by what the bytes mean.
+
+use klamath::wad::Wad;
+use std::{
+    cmp,
+    fmt::{Debug, Write},
+};
+
+const PALETTE_SIZE: usize = 256 * 3;
+const COLORMAP_SIZE: usize = 256;
+const GENMIDI_HEADER: usize = 8;
+const GENMIDI_INSTRUMENT_SIZE: usize = 36;
+const GENMIDI_NAME_SIZE: usize = 32;
+const GENMIDI_INSTRUMENTS: usize = 128;
+/// The note of the first GENMIDI percussion instrument.
+const FIRST_PERCUSSION: usize = 35;
+
+/// The fields of a GENMIDI voice, in the order they're written.
+static VOICE_FIELDS: &[&str] = &[
+    "m_am_vibrato_eg",
+    "m_attack_decay",
+    "m_sustain_release",
+    "m_waveform",
+    "m_ksl",
+    "m_volume",
+    "feedback_fm",
+    "c_am_vibrato_eg",
+    "c_attack_decay",
+    "c_sustain_release",
+    "c_waveform",
+    "c_ksl",
+    "c_volume",
+    "unused",
+];
+
+/// PLAYPAL: 14 palettes of 256 colors.
+pub fn palettes(expected: &[u8], actual: &[u8]) -> Vec<String> {
+    let mut differences = vec![];
+    length(
+        "palette count",
+        expected,
+        actual,
+        PALETTE_SIZE,
+        &mut differences,
+    );
+    for (i, (old, new)) in expected.chunks(3).zip(actual.chunks(3)).enumerate() {
+        if old != new {
+            differences.push(format!(
+                "palette {} entry {} changed from {} to {}",
+                i * 3 / PALETTE_SIZE,
+                i % 256,
+                color(old),
+                color(new)
+            ));
+        }
+    }
+    or_bytes(differences, expected, actual)
+}
+
+/// COLORMAP: 34 maps from palette indexes to palette indexes.
+pub fn colormaps(expected: &[u8], actual: &[u8]) -> Vec<String> {
+    let mut differences = vec![];
+    length(
+        "colormap count",
+        expected,
+        actual,
+        COLORMAP_SIZE,
+        &mut differences,
+    );
+    for (i, (old, new)) in expected.iter().zip(actual).enumerate() {
+        if old != new {
+            differences.push(format!(
+                "colormap {} entry {} changed from {} to {}",
+                i / COLORMAP_SIZE,
+                i % COLORMAP_SIZE,
+                old,
+                new
+            ));
+        }
+    }
+    or_bytes(differences, expected, actual)
+}
+
+/// GENMIDI: the header, then every instrument, then every instrument's name.
+pub fn genmidi(expected: &[u8], actual: &[u8]) -> Vec<String> {
+    let count = |lump: &[u8]| {
+        lump.len().saturating_sub(GENMIDI_HEADER) / (GENMIDI_INSTRUMENT_SIZE + GENMIDI_NAME_SIZE)
+    };
+    let (old_count, new_count) = (count(expected), count(actual));
+    let mut differences = vec![];
+    if old_count != new_count {
+        differences.push(format!(
+            "instrument count changed from {} to {}",
+            old_count, new_count
+        ));
+        return or_bytes(differences, expected, actual);
+    }
+
+    let instrument = |lump: &[u8], i: usize| {
+        let start = GENMIDI_HEADER + i * GENMIDI_INSTRUMENT_SIZE;
+        lump[start..start + GENMIDI_INSTRUMENT_SIZE].to_vec()
+    };
+    let name = |lump: &[u8], i: usize| {
+        let start = GENMIDI_HEADER + old_count * GENMIDI_INSTRUMENT_SIZE + i * GENMIDI_NAME_SIZE;
+        let name = &lump[start..start + GENMIDI_NAME_SIZE];
+        let end = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+        String::from_utf8_lossy(&name[..end]).into_owned()
+    };
+
+    if expected[..GENMIDI_HEADER] != actual[..GENMIDI_HEADER] {
+        differences.push("header changed".to_string());
+    }
+    for i in 0..old_count {
+        let label = if i < GENMIDI_INSTRUMENTS {
+            format!("instrument {}", i + 1)
+        } else {
+            format!("percussion {}", i - GENMIDI_INSTRUMENTS + FIRST_PERCUSSION)
+        };
+        let (old, new) = (instrument(expected, i), instrument(actual, i));
+
+        let u16_at = |data: &[u8], at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+        field(
+            &label,
+            "flags",
+            u16_at(&old, 0),
+            u16_at(&new, 0),
+            &mut differences,
+        );
+        field(&label, "fine_tuning", old[2], new[2], &mut differences);
+        field(&label, "note", old[3], new[3], &mut differences);
+        for voice in 0..2 {
+            let start = 4 + voice * 16;
+            let label = format!("{} voice {}", label, voice + 1);
+            for (j, name) in VOICE_FIELDS.iter().enumerate() {
+                field(
+                    &label,
+                    name,
+                    old[start + j],
+                    new[start + j],
+                    &mut differences,
+                );
+            }
+            let i16_at = |data: &[u8]| i16::from_le_bytes([data[start + 14], data[start + 15]]);
+            field(
+                &label,
+                "offset",
+                i16_at(&old),
+                i16_at(&new),
+                &mut differences,
+            );
+        }
+        field(
+            &label,
+            "name",
+            name(expected, i),
+            name(actual, i),
+            &mut differences,
+        );
+    }
+    or_bytes(differences, expected, actual)
+}
+
+/// DMXGUS: a line for each instrument, starting with its MIDI number.
+pub fn dmxgus(expected: &[u8], actual: &[u8]) -> Vec<String> {
+    let (expected_text, actual_text) = (
+        String::from_utf8_lossy(expected),
+        String::from_utf8_lossy(actual),
+    );
+    let (old, new): (Vec<&str>, Vec<&str>) = (
+        expected_text.lines().collect(),
+        actual_text.lines().collect(),
+    );
+    let describe = |line: &str| match line.split(',').next() {
+        Some(id) if id.trim().parse::<u16>().is_ok() => format!("instrument {}", id.trim()),
+        _ => "line".to_string(),
+    };
+
+    let mut differences = vec![];
+    for i in 0..cmp::max(old.len(), new.len()) {
+        match (old.get(i), new.get(i)) {
+            (Some(o), Some(n)) if o != n => differences.push(format!(
+                "line {} ({}) changed from {:?} to {:?}",
+                i + 1,
+                describe(o),
+                o,
+                n
+            )),
+            (Some(o), None) => {
+                differences.push(format!("line {} ({}) was removed", i + 1, describe(o)))
+            }
+            (None, Some(n)) => {
+                differences.push(format!("line {} ({}) was added", i + 1, describe(n)))
+            }
+            _ => {}
+        }
+    }
+    or_bytes(differences, expected, actual)
+}
+
+/// A WAD, lump by lump. Palettes are compared color by color.
+pub fn wads(expected: &[u8], actual: &[u8]) -> Vec<String> {
+    let (old, new) = match (Wad::parse(expected), Wad::parse(actual)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(e), _) => return vec![format!("golden WAD doesn't load: {}", e)],
+        (_, Err(e)) => return vec![format!("new WAD doesn't load: {}", e)],
+    };
+
+    let mut differences = vec![];
+    let names = |wad: &Wad| wad.lumps.iter().map(|l| l.name.clone()).collect::<Vec<_>>();
+    field(
+        "WAD",
+        "lumps",
+        names(&old).join(" "),
+        names(&new).join(" "),
+        &mut differences,
+    );
+    for (old, new) in old.lumps.iter().zip(&new.lumps) {
+        if old.name != new.name || old.data == new.data {
+            continue;
+        }
+        let lump = if old.name == "PLAYPAL" {
+            palettes(&old.data, &new.data)
+        } else {
+            or_bytes(vec![], &old.data, &new.data)
+        };
+        differences.extend(
+            lump.into_iter()
+                .map(|d| format!("lump {}: {}", old.name, d)),
+        );
+    }
+    or_bytes(differences, expected, actual)
+}
+
+/// Note a change in the number of records in a lump.
+fn length(what: &str, expected: &[u8], actual: &[u8], size: usize, differences: &mut Vec<String>) {
+    if expected.len() != actual.len() {
+        differences.push(format!(
+            "{} changed from {} to {} ({} bytes to {})",
+            what,
+            expected.len() as f64 / size as f64,
+            actual.len() as f64 / size as f64,
+            expected.len(),
+            actual.len()
+        ));
+    }
+}
+
+/// Note a change in one field of a record.
+fn field<T: PartialEq + Debug>(
+    record: &str,
+    name: &str,
+    old: T,
+    new: T,
+    differences: &mut Vec<String>,
+) {
+    if old != new {
+        differences.push(format!(
+            "{} {} changed from {:?} to {:?}",
+            record, name, old, new
+        ));
+    }
+}
+
+fn color(rgb: &[u8]) -> String {
+    rgb.iter().fold("#".to_string(), |mut s, b| {
+        let _ = write!(s, "{:02x}", b);
+        s
+    })
+}
+
+/// If nothing was found that means something, say where the bytes differ.
+fn or_bytes(differences: Vec<String>, expected: &[u8], actual: &[u8]) -> Vec<String> {
+    if !differences.is_empty() || expected == actual {
+        return differences;
+    }
+    let at = expected
+        .iter()
+        .zip(actual)
+        .position(|(old, new)| old != new)
+        .unwrap_or_else(|| cmp::min(expected.len(), actual.len()));
+    vec![format!(
+        "bytes differ from offset {} ({} bytes to {})",
+        at,
+        expected.len(),
+        actual.len()
+    )]
+}
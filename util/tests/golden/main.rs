@@ -0,0 +1,172 @@
+// Apache 2.0 License
+
+//! Builds every lump from the inputs checked in to the repository and compares it byte for byte with the golden
+//! output in this directory. Run `cargo test --test golden -- --update` after changing a generator or its inputs on
+//! purpose, and check the new golden files in. Other arguments only run the cases whose names contain them.
+
+mod diff;
+
+use klamath::{bootstrap, colormap, dmxgus, genmidi, playpal};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// How many differences are printed for each case.
+const MAX_DIFFERENCES: usize = 20;
+
+struct Case {
+    name: &'static str,
+    /// The golden file, in this directory.
+    golden: &'static str,
+    generate: fn() -> klamath::Result<Vec<u8>>,
+    diff: fn(&[u8], &[u8]) -> Vec<String>,
+}
+
+static CASES: &[Case] = &[
+    Case {
+        name: "playpal",
+        golden: "playpal.lmp",
+        generate: playpal,
+        diff: diff::palettes,
+    },
+    Case {
+        name: "playpal-builtin",
+        golden: "playpal-builtin.lmp",
+        generate: || {
+            let mut out = vec![];
+            playpal::generate_palette(None, &mut out)?;
+            Ok(out)
+        },
+        diff: diff::palettes,
+    },
+    Case {
+        name: "colormap",
+        golden: "colormap.lmp",
+        generate: || {
+            let mut out = vec![];
+            colormap::generate_colormap(&playpal()?, [0, 0, 0], &mut out)?;
+            Ok(out)
+        },
+        diff: diff::colormaps,
+    },
+    Case {
+        name: "genmidi",
+        golden: "genmidi.lmp",
+        generate: || {
+            let mut out = vec![];
+            genmidi::generate_genmidi(&genmidi::Bank::load(&repo("genmidi"))?, &mut out)?;
+            Ok(out)
+        },
+        diff: diff::genmidi,
+    },
+    Case {
+        name: "dmxgus",
+        golden: "dmxgus.lmp",
+        generate: || {
+            let mut out = vec![];
+            dmxgus::generate_dmxgus(dmxgus::Config::load(&repo("dmxgus/dmxgus.yml"))?, &mut out)?;
+            Ok(out)
+        },
+        diff: diff::dmxgus,
+    },
+    Case {
+        name: "bootstrap",
+        golden: "bootstrap.wad",
+        generate: || {
+            let mut out = vec![];
+            bootstrap::write_bootstrap(&playpal()?, &mut out)?;
+            Ok(out)
+        },
+        diff: diff::wads,
+    },
+];
+
+/// A path in the repository, which is the directory above this crate.
+fn repo(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(path)
+}
+
+/// PLAYPAL from the checked in base palette. COLORMAP and the bootstrap WAD are made from it.
+fn playpal() -> klamath::Result<Vec<u8>> {
+    let base = fs::read(repo("playpal/playpal.lmp"))?;
+    let mut out = vec![];
+    playpal::generate_palette(Some(&base), &mut out)?;
+    Ok(out)
+}
+
+fn main() {
+    let mut update = false;
+    let mut filters = vec![];
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--update" => update = true,
+            // flags cargo passes to every test binary
+            arg if arg.starts_with('-') => {}
+            _ => filters.push(arg),
+        }
+    }
+
+    let golden_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let mut failed = 0;
+    let cases = CASES.iter().filter(|case| {
+        filters.is_empty() || filters.iter().any(|f| case.name.contains(f.as_str()))
+    });
+    for case in cases {
+        let golden = golden_dir.join(case.golden);
+        let actual = match (case.generate)() {
+            Ok(actual) => actual,
+            Err(e) => {
+                println!("golden {} ... FAILED\n    {}", case.name, e);
+                failed += 1;
+                continue;
+            }
+        };
+
+        if update {
+            if let Err(e) = fs::write(&golden, &actual) {
+                println!(
+                    "golden {} ... FAILED\n    {}: {}",
+                    case.name,
+                    golden.display(),
+                    e
+                );
+                failed += 1;
+            } else {
+                println!("golden {} ... updated", case.name);
+            }
+            continue;
+        }
+
+        let differences = match fs::read(&golden) {
+            Ok(expected) if expected == actual => {
+                println!("golden {} ... ok", case.name);
+                continue;
+            }
+            Ok(expected) => (case.diff)(&expected, &actual),
+            Err(e) => vec![format!(
+                "{}: {}; run with --update to make it",
+                golden.display(),
+                e
+            )],
+        };
+        println!("golden {} ... FAILED", case.name);
+        differences
+            .iter()
+            .take(MAX_DIFFERENCES)
+            .for_each(|d| println!("    {}", d));
+        if differences.len() > MAX_DIFFERENCES {
+            println!("    and {} more", differences.len() - MAX_DIFFERENCES);
+        }
+        failed += 1;
+    }
+
+    if failed > 0 {
+        println!(
+            "\n{} golden output(s) changed; if that was on purpose, run cargo test --test golden -- --update",
+            failed
+        );
+        process::exit(1);
+    }
+}